@@ -17,7 +17,7 @@ fn main() -> Result<()> {
 
     let rec = set_record_fields(&mut slow5)?;
     slow5.add_record(&rec)?;
-    slow5.close();
+    slow5.close()?;
 
     println!("Success!");
     tmp_dir.close()?;
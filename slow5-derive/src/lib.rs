@@ -1,17 +1,106 @@
 use proc_macro::TokenStream;
-use proc_macro_error::{abort_call_site, proc_macro_error};
+use proc_macro_error::{abort, abort_call_site, proc_macro_error};
 use quote::quote;
-use syn::{parse_macro_input, Data, DataStruct, DeriveInput, Fields, Ident};
+use syn::{
+    parse_macro_input, Data, DataStruct, DeriveInput, Field, Fields, GenericArgument, Generics,
+    Ident, Lit, Meta, NestedMeta, PathArguments, Type,
+};
 
-#[proc_macro_derive(FieldExt)]
+/// Options set on a struct field with `#[slow5(...)]`
+#[derive(Default)]
+struct FieldAttrs {
+    rename: Option<String>,
+    skip: bool,
+    labels: Option<Vec<String>>,
+}
+
+impl FieldAttrs {
+    fn parse(field: &Field) -> Self {
+        let mut attrs = FieldAttrs::default();
+        for attr in field.attrs.iter().filter(|a| a.path.is_ident("slow5")) {
+            let Ok(Meta::List(list)) = attr.parse_meta() else {
+                abort!(attr, "expected #[slow5(...)]")
+            };
+            for nested in list.nested.iter() {
+                match nested {
+                    NestedMeta::Meta(Meta::Path(p)) if p.is_ident("skip") => attrs.skip = true,
+                    NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("rename") => {
+                        let Lit::Str(ref name) = nv.lit else {
+                            abort!(nv.lit, "expected a string literal")
+                        };
+                        attrs.rename = Some(name.value());
+                    }
+                    NestedMeta::Meta(Meta::List(ml)) if ml.path.is_ident("labels") => {
+                        let labels = ml
+                            .nested
+                            .iter()
+                            .map(|label| match label {
+                                NestedMeta::Lit(Lit::Str(label)) => label.value(),
+                                _ => abort!(label, "expected a string literal"),
+                            })
+                            .collect();
+                        attrs.labels = Some(labels);
+                    }
+                    _ => abort!(
+                        nested,
+                        "unknown slow5 attribute, expected `rename`, `skip`, or `labels`"
+                    ),
+                }
+            }
+        }
+        attrs
+    }
+
+    /// Name of the auxiliary field in the SLOW5 file
+    fn aux_name(&self, field: &Field) -> String {
+        match self.rename {
+            Some(ref name) => name.clone(),
+            None => field.ident.as_ref().unwrap().to_string(),
+        }
+    }
+}
+
+/// If the type is `wrapper<T>`, return `T`
+fn generic_inner<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let Type::Path(ref tp) = ty else { return None };
+    let segment = tp.path.segments.last()?;
+    if segment.ident != wrapper {
+        return None;
+    }
+    let PathArguments::AngleBracketed(ref args) = segment.arguments else {
+        return None;
+    };
+    match args.args.first() {
+        Some(GenericArgument::Type(inner)) if args.args.len() == 1 => Some(inner),
+        _ => None,
+    }
+}
+
+/// If the type is `Option<T>`, return `T`
+fn option_inner(ty: &Type) -> Option<&Type> {
+    generic_inner(ty, "Option")
+}
+
+/// If the type is `Vec<T>` or `&[T]`, return `T`
+fn array_elem(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Reference(r) => match *r.elem {
+            Type::Slice(ref slice) => Some(&slice.elem),
+            _ => None,
+        },
+        _ => generic_inner(ty, "Vec"),
+    }
+}
+
+#[proc_macro_derive(FieldExt, attributes(slow5))]
 #[proc_macro_error]
 pub fn derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = input.ident;
     let Data::Struct(ds) = input.data else { abort_call_site!("#[derive(FieldExt)] not allowed for enums or DataStructs")};
 
-    let rec_aux = derive_record_auxiliary(&name, &ds);
-    let hdr_init = derive_header_init(&name, &ds);
+    let rec_aux = derive_record_auxiliary(&name, &input.generics, &ds);
+    let hdr_init = derive_header_init(&name, &input.generics, &ds);
 
     let expanded = quote! {
         #rec_aux
@@ -21,47 +110,152 @@ pub fn derive(input: TokenStream) -> TokenStream {
     TokenStream::from(expanded)
 }
 
-fn derive_record_auxiliary(name: &Ident, ds: &DataStruct) -> proc_macro2::TokenStream {
+fn derive_record_auxiliary(
+    name: &Ident,
+    generics: &Generics,
+    ds: &DataStruct,
+) -> proc_macro2::TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     let Fields::Named(ref fields) = ds.fields else { abort_call_site!("#[derive(FieldExt)] only for named fields") };
-    let fs = fields.named.iter().map(|f| {
+    let fs = fields.named.iter().filter_map(|f| {
+        let attrs = FieldAttrs::parse(f);
+        if attrs.skip {
+            return None;
+        }
         let fname = f.ident.as_ref().unwrap();
-        let sfname = format!("{fname}");
+        let sfname = attrs.aux_name(f);
         // let set_fname = format_ident!("set_{fname}");
         let ty = &f.ty;
-        quote! {
-            fn #fname(rec: &slow5::typed::record::RecordT<#name>) -> Result<#ty, slow5::Slow5Error> {
-                rec.get_aux_field(#sfname)
+        let getter = match option_inner(ty) {
+            Some(inner) => quote! { rec.get_aux_field_opt::<#inner>(#sfname) },
+            None => quote! { rec.get_aux_field(#sfname) },
+        };
+        Some(quote! {
+            fn #fname(rec: &slow5_typed::RecordT<Self>) -> Result<#ty, slow5_typed::Slow5Error> {
+                #getter
             }
 
-            // fn #set_fname(rb: &mut slow5::RecordT<#name>, val: #ty) -> Result<(), slow5::Slow5Error> {
+            // fn #set_fname(rb: &mut slow5_typed::RecordT<#name>, val: #ty) -> Result<(), slow5::Slow5Error> {
             //     todo!()
             // }
+        })
+    });
+    // Skipped fields aren't in the record, so they are set with Default
+    let loaders = fields.named.iter().map(|f| {
+        let fname = f.ident.as_ref().unwrap();
+        if FieldAttrs::parse(f).skip {
+            quote! { #fname: Default::default(), }
+        } else {
+            quote! { #fname: Self::#fname(rec)?, }
         }
     });
     let impl_record_aux = quote! {
-        impl #name {
+        impl #impl_generics #name #ty_generics #where_clause {
             #(#fs)*
+
+            /// Load every auxiliary field of the record into a new value
+            pub fn from_record(
+                rec: &slow5_typed::RecordT<Self>,
+            ) -> Result<Self, slow5_typed::Slow5Error> {
+                Ok(Self {
+                    #(#loaders)*
+                })
+            }
         }
     };
     impl_record_aux
 }
 
-fn derive_header_init(name: &Ident, ds: &DataStruct) -> proc_macro2::TokenStream {
+fn derive_header_init(
+    name: &Ident,
+    generics: &Generics,
+    ds: &DataStruct,
+) -> proc_macro2::TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     let Fields::Named(ref fields) = ds.fields else { abort_call_site!("#[derive(FieldExt)] only for named fields") };
-    let fs = fields.named.iter().map(|f| {
-        let fname = f.ident.as_ref().unwrap();
-        let sfname = fname.to_string();
-        let ty = &f.ty;
-        quote! {
-            header.add_aux_field_t::<&'static str, #ty>(#sfname).unwrap();
+    let fs = fields.named.iter().filter_map(|f| {
+        let attrs = FieldAttrs::parse(f);
+        if attrs.skip {
+            return None;
+        }
+        let sfname = attrs.aux_name(f);
+        // Option<T> fields are registered with the type of T
+        let ty = option_inner(&f.ty).unwrap_or(&f.ty);
+        let registration = if let Some(labels) = attrs.labels {
+            quote! {
+                header.add_aux_enum_field(#sfname, vec![#(#labels),*]).unwrap();
+            }
+        } else if let Some(elem) = array_elem(ty) {
+            quote! {
+                header.add_aux_array_field_t::<&'static str, #elem>(#sfname).unwrap();
+            }
+        } else {
+            quote! {
+                header.add_aux_field_t::<&'static str, #ty>(#sfname).unwrap();
+            }
+        };
+        Some(registration)
+    });
+
+    let schema = fields.named.iter().filter_map(|f| {
+        let attrs = FieldAttrs::parse(f);
+        if attrs.skip {
+            return None;
         }
+        let sfname = attrs.aux_name(f);
+        let ty = option_inner(&f.ty).unwrap_or(&f.ty);
+        let field_type = if let Some(labels) = attrs.labels {
+            quote! { slow5_typed::FieldType::from(vec![#(#labels),*]) }
+        } else if let Some(elem) = array_elem(ty) {
+            quote! {
+                <#elem as slow5_typed::AuxFieldType>::array_field_type()
+                    .expect("SLOW5 doesn't support arrays of this type")
+            }
+        } else {
+            quote! { <#ty as slow5_typed::AuxFieldType>::field_type() }
+        };
+        Some(quote! { (#sfname, #field_type), })
+    });
+
+    let setters = fields.named.iter().filter_map(|f| {
+        let attrs = FieldAttrs::parse(f);
+        if attrs.skip {
+            return None;
+        }
+        let fname = f.ident.as_ref().unwrap();
+        let sfname = attrs.aux_name(f);
+        let setter = if option_inner(&f.ty).is_some() {
+            quote! {
+                if let Some(ref value) = self.#fname {
+                    writer.set_aux_field(rec, #sfname, value)?;
+                }
+            }
+        } else {
+            quote! {
+                writer.set_aux_field(rec, #sfname, &self.#fname)?;
+            }
+        };
+        Some(setter)
     });
 
     quote! {
-        impl slow5::typed::FieldExt for #name {
-            fn set_header_aux_fields(header: &slow5::typed::Header<Self>) {
+        impl #impl_generics slow5_typed::FieldExt for #name #ty_generics #where_clause {
+            fn set_header_aux_fields(header: &slow5_typed::Header<Self>) {
                 #(#fs)*
             }
+
+            fn set_record_aux_fields(
+                &self,
+                writer: &mut slow5_typed::FileWriter<Self>,
+                rec: &mut slow5_typed::RecordT<Self>,
+            ) -> Result<(), slow5_typed::Slow5Error> {
+                #(#setters)*
+                Ok(())
+            }
+
+            fn aux_schema() -> Vec<(&'static str, slow5_typed::FieldType)> {
+                vec![#(#schema)*]
+            }
         }
     }
 }
@@ -0,0 +1,251 @@
+//! Python bindings for reading and writing SLOW5 files, with signals as numpy
+//! arrays.
+use std::path::PathBuf;
+
+use numpy::{IntoPyArray, PyArray1, PyReadonlyArray1};
+use pyo3::{
+    create_exception,
+    exceptions::{PyException, PyValueError},
+    prelude::*,
+};
+use slow5::{HeaderExt, RecordExt};
+
+create_exception!(
+    slow5,
+    Slow5Error,
+    PyException,
+    "Error from reading or writing a SLOW5 file"
+);
+
+fn to_py_err(err: slow5::Slow5Error) -> PyErr {
+    Slow5Error::new_err(err.to_string())
+}
+
+/// Read records from a SLOW5 or BLOW5 file
+///
+/// Iterating over the reader reads the records that haven't been read yet, in
+/// the order they are in the file.
+#[pyclass(name = "FileReader")]
+struct PyFileReader {
+    inner: slow5::FileReader,
+}
+
+#[pymethods]
+impl PyFileReader {
+    #[new]
+    fn new(path: PathBuf) -> PyResult<Self> {
+        let inner = slow5::FileReader::open(path).map_err(to_py_err)?;
+        Ok(Self { inner })
+    }
+
+    /// Get a record by read id
+    fn get_record(&self, read_id: &str) -> PyResult<PyRecord> {
+        let inner = self.inner.get_record(read_id).map_err(to_py_err)?;
+        Ok(PyRecord { inner })
+    }
+
+    /// Read ids of every record, in the order of the index
+    fn read_ids(&self) -> PyResult<Vec<String>> {
+        let read_ids = self.inner.iter_read_ids().map_err(to_py_err)?;
+        Ok(read_ids
+            .map(|read_id| String::from_utf8_lossy(read_id).into_owned())
+            .collect())
+    }
+
+    /// Value of an attribute of a read group
+    #[pyo3(signature = (key, read_group = 0))]
+    fn get_attribute(&self, key: &str, read_group: u32) -> PyResult<String> {
+        let value = self
+            .inner
+            .get_attribute(key, read_group)
+            .map_err(to_py_err)?;
+        Ok(String::from_utf8_lossy(value).into_owned())
+    }
+
+    /// Number of read groups
+    #[getter]
+    fn num_read_groups(&self) -> u32 {
+        self.inner.num_read_groups()
+    }
+
+    /// Names of the auxiliary fields
+    #[getter]
+    fn aux_names(&self) -> Vec<String> {
+        self.inner
+            .aux_names_iter()
+            .map(|name| String::from_utf8_lossy(name).into_owned())
+            .collect()
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self) -> PyResult<Option<PyRecord>> {
+        match self.inner.records().next() {
+            Some(Ok(inner)) => Ok(Some(PyRecord { inner })),
+            Some(Err(err)) => Err(to_py_err(err)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Read with its signal
+#[pyclass(name = "Record")]
+struct PyRecord {
+    inner: slow5::Record,
+}
+
+#[pymethods]
+impl PyRecord {
+    #[new]
+    #[pyo3(signature = (read_id, signal, digitisation, offset, range, sampling_rate, read_group = 0))]
+    fn new(
+        read_id: &str,
+        signal: PyReadonlyArray1<'_, i16>,
+        digitisation: f64,
+        offset: f64,
+        range: f64,
+        sampling_rate: f64,
+        read_group: u32,
+    ) -> PyResult<Self> {
+        let inner = slow5::Record::builder()
+            .read_id(read_id)
+            .read_group(read_group)
+            .digitisation(digitisation)
+            .offset(offset)
+            .range(range)
+            .sampling_rate(sampling_rate)
+            .raw_signal(signal.as_slice()?)
+            .build()
+            .map_err(|err| PyValueError::new_err(err.to_string()))?;
+        Ok(Self { inner })
+    }
+
+    #[getter]
+    fn read_id(&self) -> String {
+        String::from_utf8_lossy(self.inner.read_id()).into_owned()
+    }
+
+    #[getter]
+    fn read_group(&self) -> u32 {
+        self.inner.read_group()
+    }
+
+    #[getter]
+    fn digitisation(&self) -> f64 {
+        self.inner.digitisation()
+    }
+
+    #[getter]
+    fn offset(&self) -> f64 {
+        self.inner.offset()
+    }
+
+    #[getter]
+    fn range(&self) -> f64 {
+        self.inner.range()
+    }
+
+    #[getter]
+    fn sampling_rate(&self) -> f64 {
+        self.inner.sampling_rate()
+    }
+
+    /// Raw signal as an int16 array
+    #[getter]
+    fn signal<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<i16>> {
+        let signal = self.inner.raw_signal_iter().collect::<Vec<_>>();
+        signal.into_pyarray_bound(py)
+    }
+
+    /// Signal in picoamps as a float64 array
+    #[getter]
+    fn picoamps<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+        let picoamps = self.inner.picoamps_signal_iter().collect::<Vec<_>>();
+        picoamps.into_pyarray_bound(py)
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.len_signal() as usize
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Record(read_id={:?}, len_signal={})",
+            self.read_id(),
+            self.inner.len_signal()
+        )
+    }
+}
+
+/// Write records to a SLOW5 or BLOW5 file, the format is from the file
+/// extension
+///
+/// With a template reader, the file has the same attributes and auxiliary
+/// fields as the reader. The file is closed when leaving a `with` block.
+#[pyclass(name = "FileWriter")]
+struct PyFileWriter {
+    inner: Option<slow5::FileWriter>,
+}
+
+impl PyFileWriter {
+    fn writer(&mut self) -> PyResult<&mut slow5::FileWriter> {
+        self.inner
+            .as_mut()
+            .ok_or_else(|| PyValueError::new_err("FileWriter is closed"))
+    }
+}
+
+#[pymethods]
+impl PyFileWriter {
+    #[new]
+    #[pyo3(signature = (path, template = None))]
+    fn new(path: PathBuf, template: Option<PyRef<'_, PyFileReader>>) -> PyResult<Self> {
+        let writer = match template {
+            Some(reader) => slow5::FileWriter::create_from(&reader.inner, path),
+            None => slow5::FileWriter::create(path),
+        }
+        .map_err(to_py_err)?;
+        Ok(Self {
+            inner: Some(writer),
+        })
+    }
+
+    /// Write a record
+    fn write(&mut self, record: PyRef<'_, PyRecord>) -> PyResult<()> {
+        self.writer()?.add_record(&record.inner).map_err(to_py_err)
+    }
+
+    /// Write the end of the file and close it
+    fn close(&mut self) -> PyResult<()> {
+        match self.inner.take() {
+            Some(writer) => writer.close().map_err(to_py_err),
+            None => Ok(()),
+        }
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &mut self,
+        _exc_type: &Bound<'_, PyAny>,
+        _exc_value: &Bound<'_, PyAny>,
+        _traceback: &Bound<'_, PyAny>,
+    ) -> PyResult<bool> {
+        self.close()?;
+        Ok(false)
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "slow5")]
+fn slow5_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyFileReader>()?;
+    m.add_class::<PyRecord>()?;
+    m.add_class::<PyFileWriter>()?;
+    m.add("Slow5Error", m.py().get_type_bound::<Slow5Error>())?;
+    Ok(())
+}
@@ -0,0 +1,266 @@
+//! Command line tools for SLOW5/BLOW5 files
+use std::{
+    fs,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use clap::{Parser, Subcommand, ValueEnum};
+use slow5::{
+    export::{to_tsv, ExportOptions},
+    stats::FileStats,
+    FileReader, FileWriter, RecordCompression, SignalCompression, SplitMode, SubsetOptions,
+};
+
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Print the records of a file as TSV
+    View {
+        input: PathBuf,
+        /// Leave out the signal
+        #[arg(long)]
+        no_signal: bool,
+        /// Print the signal in picoamps instead of raw values
+        #[arg(long, conflicts_with = "no_signal")]
+        picoamps: bool,
+        /// Only print these auxiliary fields, comma separated
+        #[arg(long, value_delimiter = ',')]
+        aux: Option<Vec<String>>,
+        /// Separate columns with commas
+        #[arg(long)]
+        csv: bool,
+    },
+    /// Print the number of records and signal lengths of a file
+    Stats {
+        input: PathBuf,
+        /// Read records on this many threads, requires an index
+        #[arg(short, long, default_value_t = 1)]
+        threads: usize,
+    },
+    /// Copy the records with the given read ids into a new file
+    Subset {
+        input: PathBuf,
+        /// File with one read id per line, "-" for stdin
+        #[arg(short, long)]
+        read_ids: PathBuf,
+        #[arg(short, long)]
+        output: PathBuf,
+        /// Fetch records on this many threads
+        #[arg(short, long, default_value_t = 1)]
+        threads: usize,
+    },
+    /// Concatenate files with the same header into one file
+    Merge {
+        #[arg(required = true)]
+        inputs: Vec<PathBuf>,
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+    /// Split the records of a file into multiple files
+    Split {
+        input: PathBuf,
+        /// Directory for the output files, created if it doesn't exist
+        #[arg(short, long)]
+        out_dir: PathBuf,
+        /// One file per read group
+        #[arg(long, group = "mode")]
+        read_group: bool,
+        /// New file every n records
+        #[arg(long, group = "mode")]
+        records: Option<usize>,
+        /// Divide records evenly into n files, requires an index
+        #[arg(long, group = "mode")]
+        files: Option<usize>,
+    },
+    /// Write a BLOW5 file with different compression
+    Recompress {
+        input: PathBuf,
+        #[arg(short, long)]
+        output: PathBuf,
+        #[arg(long, value_enum, default_value_t = RecordMethod::Zstd)]
+        record: RecordMethod,
+        #[arg(long, value_enum, default_value_t = SignalMethod::SvbZd)]
+        signal: SignalMethod,
+        /// Compress records on this many threads
+        #[arg(short, long, default_value_t = 1)]
+        threads: usize,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum RecordMethod {
+    None,
+    Zlib,
+    Zstd,
+}
+
+impl From<RecordMethod> for RecordCompression {
+    fn from(method: RecordMethod) -> Self {
+        match method {
+            RecordMethod::None => RecordCompression::None,
+            RecordMethod::Zlib => RecordCompression::Zlib,
+            RecordMethod::Zstd => RecordCompression::ZStd,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum SignalMethod {
+    None,
+    SvbZd,
+    ExZd,
+}
+
+impl From<SignalMethod> for SignalCompression {
+    fn from(method: SignalMethod) -> Self {
+        match method {
+            SignalMethod::None => SignalCompression::None,
+            SignalMethod::SvbZd => SignalCompression::StreamVByte,
+            SignalMethod::ExZd => SignalCompression::ExZd,
+        }
+    }
+}
+
+fn open(path: &Path) -> anyhow::Result<FileReader> {
+    FileReader::open(path).with_context(|| format!("failed to open {}", path.display()))
+}
+
+// Read ids, one per line, skipping empty lines
+fn read_ids(path: &Path) -> anyhow::Result<Vec<String>> {
+    let input: Box<dyn BufRead> = if path == Path::new("-") {
+        Box::new(io::stdin().lock())
+    } else {
+        let file =
+            fs::File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+        Box::new(BufReader::new(file))
+    };
+    let mut read_ids = Vec::new();
+    for line in input.lines() {
+        let line = line?;
+        let read_id = line.trim();
+        if !read_id.is_empty() {
+            read_ids.push(read_id.to_string());
+        }
+    }
+    Ok(read_ids)
+}
+
+fn print_stats<W: Write>(mut out: W, stats: &FileStats) -> io::Result<()> {
+    let (major, minor, patch) = stats.version();
+    writeln!(out, "format\t{:?}", stats.format())?;
+    writeln!(out, "version\t{major}.{minor}.{patch}")?;
+    writeln!(out, "record_compression\t{:?}", stats.record_compression())?;
+    writeln!(out, "signal_compression\t{:?}", stats.signal_compression())?;
+    writeln!(out, "records\t{}", stats.records())?;
+    writeln!(out, "total_samples\t{}", stats.total_samples())?;
+    for (rg, count) in stats.read_group_counts().iter().enumerate() {
+        writeln!(out, "read_group_{rg}\t{count}")?;
+    }
+    let or_missing = |len: Option<String>| len.unwrap_or_else(|| ".".to_string());
+    let min = stats.min_signal_len().map(|len| len.to_string());
+    let max = stats.max_signal_len().map(|len| len.to_string());
+    let mean = stats.mean_signal_len().map(|len| format!("{len:.2}"));
+    writeln!(out, "min_signal_len\t{}", or_missing(min))?;
+    writeln!(out, "max_signal_len\t{}", or_missing(max))?;
+    writeln!(out, "mean_signal_len\t{}", or_missing(mean))?;
+    Ok(())
+}
+
+fn run(command: Command) -> anyhow::Result<()> {
+    match command {
+        Command::View {
+            input,
+            no_signal,
+            picoamps,
+            aux,
+            csv,
+        } => {
+            let mut reader = open(&input)?;
+            let mut opts = ExportOptions::default();
+            opts.signal(!no_signal).picoamps(picoamps);
+            if let Some(aux) = aux {
+                opts.aux_fields(aux);
+            }
+            if csv {
+                opts.delimiter(b',');
+            }
+            let mut out = BufWriter::new(io::stdout().lock());
+            to_tsv(&mut reader, &mut out, &opts)?;
+            out.flush()?;
+        }
+        Command::Stats { input, threads } => {
+            let mut reader = open(&input)?;
+            let stats = if threads > 1 {
+                slow5::stats::summarize_parallel(&reader, threads)?
+            } else {
+                slow5::stats::summarize(&mut reader)?
+            };
+            print_stats(io::stdout().lock(), &stats)?;
+        }
+        Command::Subset {
+            input,
+            read_ids: read_ids_path,
+            output,
+            threads,
+        } => {
+            let reader = open(&input)?;
+            let read_ids = read_ids(&read_ids_path)?;
+            let mut writer = FileWriter::create_from(&reader, &output)
+                .with_context(|| format!("failed to create {}", output.display()))?;
+            let mut opts = SubsetOptions::default();
+            opts.threads(threads);
+            let report = opts.subset(&reader, &mut writer, read_ids)?;
+            writer.close()?;
+            for read_id in report.missing() {
+                eprintln!("read id not found: {}", String::from_utf8_lossy(read_id));
+            }
+            eprintln!("{} records written", report.written());
+        }
+        Command::Merge { inputs, output } => {
+            let n = slow5::cat(&inputs, &output)?;
+            eprintln!("{n} records written");
+        }
+        Command::Split {
+            input,
+            out_dir,
+            read_group,
+            records,
+            files,
+        } => {
+            let mode = match (read_group, records, files) {
+                (_, Some(n), _) => SplitMode::ByCount(n),
+                (_, _, Some(n)) => SplitMode::ByFileCount(n),
+                (true, _, _) => SplitMode::ByReadGroup,
+                _ => anyhow::bail!("one of --read-group, --records or --files is required"),
+            };
+            let mut reader = open(&input)?;
+            fs::create_dir_all(&out_dir)?;
+            for path in slow5::split(&mut reader, &out_dir, mode)? {
+                println!("{}", path.display());
+            }
+        }
+        Command::Recompress {
+            input,
+            output,
+            record,
+            signal,
+            threads,
+        } => {
+            let n = slow5::recompress(input, output, record.into(), signal.into(), threads)?;
+            eprintln!("{n} records written");
+        }
+    }
+    Ok(())
+}
+
+fn main() -> anyhow::Result<()> {
+    run(Cli::parse().command)
+}
@@ -0,0 +1,115 @@
+use std::process::{Command, Output};
+
+use assert_fs::{
+    prelude::{FileWriteStr, PathChild},
+    TempDir,
+};
+use slow5::{FileReader, RecordCompression, RecordExt, SignalCompression};
+
+fn run(args: &[&str]) -> Output {
+    let output = Command::new(env!("CARGO_BIN_EXE_slow5-rs-tools"))
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output
+}
+
+#[test]
+fn test_view_and_stats() {
+    let output = run(&["view", "--no-signal", "../examples/example.slow5"]);
+    let tsv = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(tsv.lines().count(), 6);
+    assert!(tsv.lines().next().unwrap().starts_with("read_id\t"));
+
+    let output = run(&["stats", "../examples/example.slow5"]);
+    let stats = String::from_utf8(output.stdout).unwrap();
+    assert!(stats.lines().any(|line| line == "records\t5"));
+}
+
+#[test]
+fn test_split_subset_merge() -> anyhow::Result<()> {
+    let tmp_dir = TempDir::new()?;
+    let out_dir = tmp_dir.child("parts");
+    let output = run(&[
+        "split",
+        "../examples/example.slow5",
+        "-o",
+        out_dir.to_str().unwrap(),
+        "--records",
+        "2",
+    ]);
+    let parts = String::from_utf8(output.stdout)?;
+    let parts = parts.lines().collect::<Vec<_>>();
+    assert_eq!(parts.len(), 3);
+
+    let merged = tmp_dir.child("merged.slow5");
+    let mut args = vec!["merge", "-o", merged.to_str().unwrap()];
+    args.extend(&parts);
+    run(&args);
+    let reader = FileReader::open(&merged)?;
+    assert_eq!(reader.iter_read_ids()?.count(), 5);
+
+    let read_ids = tmp_dir.child("read_ids.txt");
+    read_ids.write_str("r1\nr3\n\nnot_a_read\n")?;
+    let subset = tmp_dir.child("subset.blow5");
+    let output = run(&[
+        "subset",
+        merged.to_str().unwrap(),
+        "--read-ids",
+        read_ids.to_str().unwrap(),
+        "-o",
+        subset.to_str().unwrap(),
+    ]);
+    assert!(String::from_utf8(output.stderr)?.contains("not_a_read"));
+    let mut reader = FileReader::open(&subset)?;
+    let subset_ids = reader
+        .records()
+        .map(|rec| Ok(rec?.read_id().to_vec()))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    assert_eq!(subset_ids, [b"r1".to_vec(), b"r3".to_vec()]);
+    Ok(())
+}
+
+#[test]
+fn test_recompress() -> anyhow::Result<()> {
+    let tmp_dir = TempDir::new()?;
+    let output = tmp_dir.child("example3.blow5");
+    run(&[
+        "recompress",
+        "../examples/example3.blow5",
+        "-o",
+        output.to_str().unwrap(),
+        "--record",
+        "zlib",
+        "--signal",
+        "ex-zd",
+    ]);
+    let reader = FileReader::open(&output)?;
+    assert_eq!(reader.record_compression(), RecordCompression::Zlib);
+    assert_eq!(reader.signal_compression(), SignalCompression::ExZd);
+
+    // Merging the compressed parts keeps the compression
+    let out_dir = tmp_dir.child("parts");
+    let output = run(&[
+        "split",
+        output.to_str().unwrap(),
+        "-o",
+        out_dir.to_str().unwrap(),
+        "--records",
+        "2",
+    ]);
+    let parts = String::from_utf8(output.stdout)?;
+    let merged = tmp_dir.child("merged.blow5");
+    let mut args = vec!["merge", "-o", merged.to_str().unwrap()];
+    args.extend(parts.lines());
+    run(&args);
+    let reader = FileReader::open(&merged)?;
+    assert_eq!(reader.record_compression(), RecordCompression::Zlib);
+    assert_eq!(reader.signal_compression(), SignalCompression::ExZd);
+    Ok(())
+}
@@ -1,21 +1,25 @@
 use std::error::Error;
 
-use slow5::typed::{reader::FileReader, FieldExt};
+use slow5_typed::{FieldExt, FileReader};
 
 #[allow(dead_code)]
 #[derive(FieldExt)]
 struct MyAuxFields {
-    // Primitive types only supported for now
-    // Haven't implemented *char, arrays, enums, yet.
+    // Arrays are declared as Vec<T> or &[T], enums with #[slow5(labels(...))]
+    // Haven't implemented *char yet.
     // channel_number: String,
-    median_before: f64,
-    read_number: u32,
-    start_mux: u8,
+    // Missing values are returned as None
+    median_before: Option<f64>,
+    read_number: i32,
+    #[slow5(rename = "start_mux")]
+    mux: u8,
     start_time: u64,
+    #[slow5(skip)]
+    not_in_file: Vec<String>,
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let _slow5: FileReader<MyAuxFields> = FileReader::open("examples/example2.slow5")?;
+    let _slow5: FileReader<MyAuxFields> = FileReader::open("../examples/example2.slow5")?;
     Ok(())
 }
 
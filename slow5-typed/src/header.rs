@@ -5,15 +5,13 @@ use std::{
 
 use libc::c_char;
 use slow5lib_sys::{
-    slow5_aux_add, slow5_get_aux_names, slow5_hdr_add, slow5_hdr_get, slow5_hdr_set, slow5_hdr_t,
+    slow5_aux_add, slow5_aux_add_enum, slow5_aux_type_SLOW5_ENUM, slow5_get_aux_names,
+    slow5_hdr_add, slow5_hdr_get, slow5_hdr_set, slow5_hdr_t,
 };
 
-use slow5::{
-    {AuxField, FieldType},
-    Slow5Error,
-};
+use slow5::{FieldType, Slow5Error};
 
-use crate::to_cstring;
+use crate::{to_cstring, AuxFieldType};
 
 /// Represents a SLOW5 header generic over the auxiliary fields
 pub struct Header<'a, A> {
@@ -33,9 +31,9 @@ impl<'a, A> Header<'a, A> {
 
     /// Get the value of an attribute in a read group
     /// ```
-    /// use slow5::typed::FileReader;
+    /// use slow5_typed::FileReader;
     ///
-    /// let slow5: FileReader<()> = FileReader::open("examples/example.slow5").unwrap();
+    /// let slow5: FileReader<()> = FileReader::open("../examples/example.slow5").unwrap();
     /// let header = slow5.header();
     /// let attr = header.get_attribute("run_id", 0).unwrap();
     /// assert_eq!(attr, b"d6e473a6d513ec6bfc150c60fd4556d72f0e6d18");
@@ -102,7 +100,7 @@ impl<'a, A> Header<'a, A> {
     }
 
     /// Add auxiliary field to header used for setting the auxiliary field
-    /// of [`crate::typed::record::RecordT`].
+    /// of [`crate::RecordT`].
     pub fn add_aux_field<B>(&mut self, name: B, field_type: FieldType) -> Result<(), Slow5Error>
     where
         B: Into<Vec<u8>>,
@@ -117,15 +115,15 @@ impl<'a, A> Header<'a, A> {
     }
 
     /// Add auxiliary field to header used for setting the auxiliary field
-    /// of [`crate::typed::record::RecordT`]. Infer the type T from type
+    /// of [`crate::RecordT`]. Infer the type T from type
     /// arguments instead of directly passing a [`FieldType`].
     pub fn add_aux_field_t<B, T>(&'a self, name: B) -> Result<(), Slow5Error>
     where
         B: Into<Vec<u8>> + Clone,
-        T: AuxField,
+        T: AuxFieldType,
     {
         let cname = to_cstring(name)?;
-        let field_type = T::to_slow5_t();
+        let field_type = T::field_type();
         let ret = unsafe { slow5_aux_add(cname.as_ptr(), field_type.to_slow5_t().0, self.header) };
         if ret < 0 {
             Err(Slow5Error::Unknown)
@@ -133,6 +131,136 @@ impl<'a, A> Header<'a, A> {
             Ok(())
         }
     }
+
+    /// Add an array auxiliary field to the header, with the type of the
+    /// array elements inferred from the type argument T.
+    pub fn add_aux_array_field_t<B, T>(&'a self, name: B) -> Result<(), Slow5Error>
+    where
+        B: Into<Vec<u8>> + Clone,
+        T: AuxFieldType,
+    {
+        let cname = to_cstring(name)?;
+        let field_type = T::array_field_type().ok_or(Slow5Error::AuxTypeMismatch)?;
+        let ret = unsafe { slow5_aux_add(cname.as_ptr(), field_type.to_slow5_t().0, self.header) };
+        if ret < 0 {
+            Err(Slow5Error::Unknown)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Add an auxiliary enum field to the header with the given labels.
+    pub fn add_aux_enum_field<B, C>(&'a self, name: B, labels: Vec<C>) -> Result<(), Slow5Error>
+    where
+        B: Into<Vec<u8>>,
+        C: Into<Vec<u8>>,
+    {
+        let name = to_cstring(name)?;
+        let labels = labels
+            .into_iter()
+            .map(to_cstring)
+            .collect::<Result<Vec<_>, _>>()?;
+        if labels.len() > (u8::MAX as usize) {
+            return Err(Slow5Error::TooManyLabels(labels.len()));
+        }
+        let mut label_ptrs: Vec<_> = labels.iter().map(|l| l.as_ptr()).collect();
+        let ret = unsafe {
+            slow5_aux_add_enum(
+                name.as_ptr(),
+                label_ptrs.as_mut_ptr(),
+                labels.len() as u8,
+                self.header,
+            )
+        };
+        if ret < 0 {
+            Err(Slow5Error::Unknown)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+// Every FieldType that isn't an enum, used to convert from the slow5lib type
+const FIELD_TYPES: [FieldType; 22] = [
+    FieldType::Int8,
+    FieldType::Int16,
+    FieldType::Int32,
+    FieldType::Int64,
+    FieldType::Uint8,
+    FieldType::Uint16,
+    FieldType::Uint32,
+    FieldType::Uint64,
+    FieldType::Float,
+    FieldType::Double,
+    FieldType::Char,
+    FieldType::Str,
+    FieldType::Uint8Array,
+    FieldType::Uint16Array,
+    FieldType::Uint32Array,
+    FieldType::Uint64Array,
+    FieldType::Int8Array,
+    FieldType::Int16Array,
+    FieldType::Int32Array,
+    FieldType::Int64Array,
+    FieldType::FloatArray,
+    FieldType::DoubleArray,
+];
+
+impl<'a, A> Header<'a, A> {
+    /// Get the FieldType of an auxiliary field from the header, None if the
+    /// field doesn't exist.
+    fn find_aux_field(&self, name: &str) -> Option<FieldType> {
+        let aux_meta = unsafe { (*self.header).aux_meta };
+        if aux_meta.is_null() {
+            return None;
+        }
+        let aux_meta = unsafe { &*aux_meta };
+        let idx = (0..aux_meta.num as usize).find(|&idx| {
+            let attr = unsafe { CStr::from_ptr(*aux_meta.attrs.add(idx)) };
+            attr.to_bytes() == name.as_bytes()
+        })?;
+        let aux_type = unsafe { *aux_meta.types.add(idx) };
+        if aux_type == slow5_aux_type_SLOW5_ENUM {
+            let labels = unsafe { *aux_meta.enum_labels.add(idx) };
+            let num_labels = unsafe { *aux_meta.enum_num_labels.add(idx) };
+            let labels = (0..num_labels as usize)
+                .map(|i| {
+                    unsafe { CStr::from_ptr(*labels.add(i)) }
+                        .to_bytes()
+                        .to_vec()
+                })
+                .collect();
+            Some(FieldType::Enum(labels))
+        } else {
+            FIELD_TYPES
+                .into_iter()
+                .find(|ty| ty.to_slow5_t().0 == aux_type)
+        }
+    }
+
+    /// Check that every field in the schema is in the header with a compatible
+    /// type. Enum fields only need to be enums, their labels are not compared.
+    pub(crate) fn validate_schema(
+        &self,
+        schema: &[(&'static str, FieldType)],
+    ) -> Result<(), Slow5Error> {
+        for (field, expected) in schema {
+            let found = self.find_aux_field(field);
+            let compatible = match (expected, &found) {
+                (FieldType::Enum(_), Some(FieldType::Enum(_))) => true,
+                (expected, Some(found)) => expected == found,
+                (_, None) => false,
+            };
+            if !compatible {
+                return Err(Slow5Error::SchemaMismatch {
+                    field: field.to_string(),
+                    expected: expected.clone(),
+                    found,
+                });
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Iterator over auxiliary field names of a [`Header`], usually using
@@ -164,6 +292,7 @@ impl<'a> Iterator for AuxNamesIter<'a> {
         if self.idx < self.num_aux {
             let aux_name = unsafe { self.auxs.offset(self.idx as isize) };
             let aux_name = unsafe { CStr::from_ptr(*aux_name) };
+            self.idx += 1;
             Some(aux_name.to_bytes())
         } else {
             None
@@ -3,29 +3,113 @@
 mod header;
 pub mod reader;
 pub mod record;
+pub mod writer;
 
 use std::ffi::CString;
 
 pub use header::Header;
 pub use reader::FileReader;
-use slow5::Slow5Error;
+pub use record::RecordT;
+pub use writer::FileWriter;
+// Re-exported so code generated by the derive macro only depends on this crate
+pub use slow5::{FieldType, Slow5Error};
 pub use slow5_derive::FieldExt;
 
 /// Represents a trait for auxiliary types that set the header field.
 /// Usually automatically implemented using the FieldExt derive macro.
 pub trait FieldExt {
-    /// Set the auxiliary fields for a header. Types representing auxiliary
-    /// fields implement this so FileWriter<A> will set the auxiliary fields
-    /// at initiliazation.
+    /// Set the auxiliary fields for a header.
     fn set_header_aux_fields(header: &Header<Self>)
     where
         Self: Sized;
+
+    /// Set the auxiliary fields of a record from the values in self, used by
+    /// [`FileWriter::add_record`].
+    fn set_record_aux_fields(
+        &self,
+        _writer: &mut FileWriter<Self>,
+        _rec: &mut RecordT<Self>,
+    ) -> Result<(), Slow5Error>
+    where
+        Self: Sized,
+    {
+        Ok(())
+    }
+
+    /// Names and types of the auxiliary fields, [`FileWriter::create`] adds
+    /// these to the header and [`FileReader::open`] checks them against the
+    /// header of the file.
+    fn aux_schema() -> Vec<(&'static str, FieldType)>
+    where
+        Self: Sized,
+    {
+        Vec::new()
+    }
 }
 
 impl FieldExt for () {
     fn set_header_aux_fields(_header: &Header<Self>) {}
 }
 
+/// Rust types that can be registered as auxiliary fields in a header.
+pub trait AuxFieldType {
+    /// FieldType for a single value of this type
+    fn field_type() -> FieldType;
+
+    /// FieldType for an array of this type, None if SLOW5 doesn't support
+    /// arrays of this type
+    fn array_field_type() -> Option<FieldType> {
+        None
+    }
+}
+
+macro_rules! impl_aux_field_type {
+    ($($rtype:ty => $ftype:ident, $atype:ident);* $(;)?) => {
+        $(
+            impl AuxFieldType for $rtype {
+                fn field_type() -> FieldType {
+                    FieldType::$ftype
+                }
+
+                fn array_field_type() -> Option<FieldType> {
+                    Some(FieldType::$atype)
+                }
+            }
+        )*
+    };
+}
+
+impl_aux_field_type!(
+    i8 => Int8, Int8Array;
+    i16 => Int16, Int16Array;
+    i32 => Int32, Int32Array;
+    i64 => Int64, Int64Array;
+    u8 => Uint8, Uint8Array;
+    u16 => Uint16, Uint16Array;
+    u32 => Uint32, Uint32Array;
+    u64 => Uint64, Uint64Array;
+    f32 => Float, FloatArray;
+    f64 => Double, DoubleArray;
+);
+
+impl AuxFieldType for char {
+    fn field_type() -> FieldType {
+        FieldType::Char
+    }
+}
+
+impl AuxFieldType for String {
+    fn field_type() -> FieldType {
+        FieldType::Str
+    }
+}
+
+impl AuxFieldType for &str {
+    fn field_type() -> FieldType {
+        FieldType::Str
+    }
+}
+
 pub(crate) fn to_cstring<T: Into<Vec<u8>>>(x: T) -> Result<CString, Slow5Error> {
-    CString::new(x)
+    CString::new(x).map_err(Slow5Error::InteriorNul)
 }
\ No newline at end of file
@@ -5,16 +5,20 @@ use std::{
     mem::size_of,
     os::unix::prelude::OsStrExt,
     path::Path,
+    ptr::null_mut,
 };
 
 use cstr::cstr;
-use libc::c_char;
-use slow5lib_sys::{slow5_file_t, slow5_get, slow5_get_rids, slow5_hdr_t, slow5_rec_t};
+use libc::{c_char, c_void};
+use slow5lib_sys::{
+    slow5_file_t, slow5_get, slow5_get_rids, slow5_hdr_t, slow5_rec_free, slow5_rec_t,
+    SLOW5_ERR_EOF, SLOW5_ERR_NOTFOUND,
+};
 
 use crate::to_cstring;
 
-use super::{header::Header, FieldExt};
-use slow5::{Record, RecordIter, Slow5Error};
+use super::{header::Header, record::RecordT, FieldExt};
+use slow5::Slow5Error;
 
 /// Read from a SLOW5 file
 pub struct FileReader<A = ()> {
@@ -22,6 +26,12 @@ pub struct FileReader<A = ()> {
     aux: PhantomData<A>,
 }
 
+impl<A> std::fmt::Debug for FileReader<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileReader").finish()
+    }
+}
+
 impl<A: FieldExt> FileReader<A> {
     pub(crate) fn new(slow5_file: *mut slow5_file_t) -> Self {
         Self {
@@ -32,13 +42,17 @@ impl<A: FieldExt> FileReader<A> {
 
     /// Open a SLOW5 file, creates an index if one doesn't exist.
     ///
+    /// The auxiliary fields of A are checked against the header of the file,
+    /// returning [`Slow5Error::SchemaMismatch`] if a field is missing or has
+    /// a different type.
+    ///
     /// # Example
     /// ```
     /// # use std::error::Error;
-    /// use slow5::typed::FileReader;
+    /// use slow5_typed::FileReader;
     ///
     /// # fn main() -> Result<(), Box<dyn Error>> {
-    /// let reader: FileReader<()> = FileReader::open("examples/example.slow5")?;
+    /// let reader: FileReader<()> = FileReader::open("../examples/example.slow5")?;
     /// # Ok(())
     /// # }
     /// ```
@@ -49,16 +63,24 @@ impl<A: FieldExt> FileReader<A> {
             slow5lib_sys::slow5_set_log_level(slow5lib_sys::slow5_log_level_opt_SLOW5_LOG_OFF);
         }
 
-        let file_path = file_path.as_ref().as_os_str().as_bytes();
-        let file_path = to_cstring(file_path)?;
+        let file_path = file_path.as_ref();
+        if !file_path.exists() {
+            return Err(Slow5Error::IncorrectPath(file_path.to_owned()));
+        }
+        let file_path = to_cstring(file_path.as_os_str().as_bytes())?;
         let mode = cstr!("r");
         let slow5_file: *mut slow5_file_t =
             unsafe { slow5lib_sys::slow5_open(file_path.as_ptr(), mode.as_ptr()) };
+        if slow5_file.is_null() {
+            return Err(Slow5Error::IOError);
+        }
         let ret = unsafe { slow5lib_sys::slow5_idx_load(slow5_file) };
         if ret == -1 {
+            unsafe { slow5lib_sys::slow5_close(slow5_file) };
             Err(Slow5Error::NoIndex)
         } else {
             let reader = FileReader::new(slow5_file);
+            reader.header().validate_schema(&A::aux_schema())?;
             Ok(reader)
         }
     }
@@ -69,39 +91,36 @@ impl<A: FieldExt> FileReader<A> {
         Header::new(header)
     }
 
-    /// Return iterator over each read in a SLOW5 file as a [`RecordIter`].
+    /// Return iterator over each read in a SLOW5 file as a [`RecordT`].
     ///
     /// # Example
-    /// ```ignore
+    /// ```
     /// # use std::error::Error;
-    /// # use slow5::typed::FileReader;
+    /// # use slow5_typed::FileReader;
     /// use slow5::RecordExt;
     ///
     /// # fn main() -> Result<(), Box<dyn Error>> {
-    /// # let mut reader: FileReader<()> = FileReader::open("examples/example.slow5")?;
+    /// # let mut reader: FileReader<()> = FileReader::open("../examples/example.slow5")?;
     /// for record in reader.records() {
     ///     println!("{:?}", record?.read_id());
     /// }
     /// # Ok(())
     /// # }
     /// ```
-    // TODO I could avoid the reader being consumed by using rewinding the file ptr
-    // and making it &mut self. RecordIter would need to do the rewinding once its
-    // finished.
-    // pub fn records(&mut self) -> RecordIter {
-    //     RecordIter::new(self.slow5_file)
-    // }
-
-    /// Random-access a single [`Record`] by read_id.
+    pub fn records(&mut self) -> RecordIterT<'_, A> {
+        RecordIterT::new(self)
+    }
+
+    /// Random-access a single [`RecordT`] by read_id.
     ///
     /// # Example
-    /// ```ignore
-    /// # use slow5::typed::FileReader;
+    /// ```
+    /// # use slow5_typed::FileReader;
     /// # use std::error::Error;
     /// use slow5::RecordExt;
     ///
     /// # fn main() -> Result<(), Box<dyn Error>> {
-    /// # let reader: FileReader<()> = FileReader::open("examples/example.slow5")?;
+    /// # let reader: FileReader<()> = FileReader::open("../examples/example.slow5")?;
     /// let read_id = "r3";
     /// let record = reader.get_record(read_id)?;
     /// assert_eq!(record.read_id(), read_id.as_bytes());
@@ -110,27 +129,31 @@ impl<A: FieldExt> FileReader<A> {
     /// ```
     ///
     /// Mutating the Record will not cause changes in the SLOW5 file.
-    // pub fn get_record<B: Into<Vec<u8>>>(&self, read_id: B) -> Result<Record, Slow5Error> {
-    //     let mut slow5_rec =
-    //         unsafe { libc::calloc(1, size_of::<slow5_rec_t>()) as *mut slow5_rec_t };
-    //     let read_id = to_cstring(read_id)?;
-    //     let rid_ptr = read_id.into_raw();
-    //     let ret = unsafe { slow5_get(rid_ptr, &mut slow5_rec, self.slow5_file) };
-    //     let _ = unsafe { CString::from_raw(rid_ptr) };
-    //     if ret >= 0 {
-    //         Ok(Record::new(slow5_rec))
-    //     } else {
-    //         // TODO Handle error code properly
-    //         Err(Slow5Error::Unknown)
-    //     }
-    // }
+    pub fn get_record<B: Into<Vec<u8>>>(&self, read_id: B) -> Result<RecordT<A>, Slow5Error> {
+        let mut slow5_rec =
+            unsafe { libc::calloc(1, size_of::<slow5_rec_t>()) as *mut slow5_rec_t };
+        let read_id = to_cstring(read_id)?;
+        let rid_ptr = read_id.into_raw();
+        let ret = unsafe { slow5_get(rid_ptr, &mut slow5_rec, self.slow5_file) };
+        let _ = unsafe { CString::from_raw(rid_ptr) };
+        if ret >= 0 {
+            Ok(RecordT::new(slow5_rec))
+        } else {
+            unsafe { libc::free(slow5_rec as *mut c_void) };
+            if ret == SLOW5_ERR_NOTFOUND {
+                Err(Slow5Error::GetRecordFailed)
+            } else {
+                Err(Slow5Error::native(ret))
+            }
+        }
+    }
 
     /// Returns iterator over all the read ids in a SLOW5 file
-    /// ```ignore
-    /// # use slow5::typed::FileReader;
+    /// ```
+    /// # use slow5_typed::FileReader;
     /// use std::str;
     ///
-    /// let slow5: FileReader<()> = FileReader::open("examples/example.slow5").unwrap();
+    /// let slow5: FileReader<()> = FileReader::open("../examples/example.slow5").unwrap();
     /// # let mut read_ids = Vec::new();
     /// let read_id_iter = slow5.iter_read_ids().unwrap();
     /// for rid in read_id_iter {
@@ -141,14 +164,9 @@ impl<A: FieldExt> FileReader<A> {
     /// # assert_eq!(read_ids[0], b"r1");
     /// # assert_eq!(read_ids[1], b"r2");
     /// ```
-    // TODO figure out how to seek back after
-    // Records has to take ownership because the file pointer is changed during
-    // iteration Maybe ideal to fseek + other with the fp after dropping the
-    // RecordIter
-    // pub fn iter_read_ids(&self) -> Result<ReadIdIter<'_>, Slow5Error> {
-    //     ReadIdIter::new(self)
-    // }
-    fn _placeholder() {}
+    pub fn iter_read_ids(&self) -> Result<ReadIdIter<'_>, Slow5Error> {
+        ReadIdIter::new(self)
+    }
 }
 
 impl<A> Drop for FileReader<A> {
@@ -159,6 +177,58 @@ impl<A> Drop for FileReader<A> {
     }
 }
 
+/// Iterator over [`RecordT`] from a typed [`FileReader`], generated by calling
+/// [`records`].
+///
+/// If an error occurs, the iterator will produce Some(Err(_)) and then None
+/// on subsequent iterations.
+///
+/// [`records`]: crate::FileReader::records
+pub struct RecordIterT<'a, A> {
+    reader: &'a mut FileReader<A>,
+    errored: bool,
+}
+
+impl<'a, A> std::fmt::Debug for RecordIterT<'a, A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RecordIterT").finish()
+    }
+}
+
+impl<'a, A> RecordIterT<'a, A> {
+    fn new(reader: &'a mut FileReader<A>) -> Self {
+        Self {
+            reader,
+            errored: false,
+        }
+    }
+}
+
+impl<'a, A> Iterator for RecordIterT<'a, A> {
+    type Item = Result<RecordT<A>, Slow5Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.errored {
+            return None;
+        }
+        let mut rec = null_mut() as *mut slow5_rec_t;
+        let ret = unsafe { slow5lib_sys::slow5_get_next(&mut rec, self.reader.slow5_file) };
+        if ret >= 0 {
+            return Some(Ok(RecordT::new(rec)));
+        }
+        // slow5_get_next may have allocated the record before failing
+        if !rec.is_null() {
+            unsafe { slow5_rec_free(rec) };
+        }
+        if ret == SLOW5_ERR_EOF {
+            None
+        } else {
+            self.errored = true;
+            Some(Err(Slow5Error::native(ret)))
+        }
+    }
+}
+
 /// Iterator over Read IDs for a typed [`FileReader`]
 pub struct ReadIdIter<'a> {
     idx: u64,
@@ -167,6 +237,15 @@ pub struct ReadIdIter<'a> {
     _lifetime: PhantomData<&'a ()>,
 }
 
+impl<'a> std::fmt::Debug for ReadIdIter<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReadIdIter")
+            .field("idx", &self.idx)
+            .field("num_reads", &self.num_reads)
+            .finish()
+    }
+}
+
 impl<'a> ReadIdIter<'a> {
     fn new<A>(reader: &FileReader<A>) -> Result<Self, Slow5Error> {
         let mut num_reads = 0;
@@ -199,24 +278,29 @@ impl<'a> Iterator for ReadIdIter<'a> {
     }
 }
 
-// #[cfg(test)]
-// mod test {
-//     use super::*;
-//     use crate::RecordExt;
-
-//     #[test]
-//     fn test_reader() {
-//         let filename = "examples/example.slow5";
-//         let mut reader: FileReader<()> = FileReader::open(filename).unwrap();
-
-//         let read_id = "r3";
-//         let rec = reader.get_record(read_id).unwrap();
-//         assert_eq!(rec.read_id(), read_id.as_bytes());
-
-//         let mut acc = Vec::new();
-//         for rec in reader.records() {
-//             acc.push(rec);
-//         }
-//         assert!(!acc.is_empty());
-//     }
-// }
+#[cfg(test)]
+mod test {
+    use slow5::RecordExt;
+
+    use super::*;
+
+    #[test]
+    fn test_reader() {
+        let filename = "../examples/example.slow5";
+        let mut reader: FileReader<()> = FileReader::open(filename).unwrap();
+
+        let read_id = "r3";
+        let rec = reader.get_record(read_id).unwrap();
+        assert_eq!(rec.read_id(), read_id.as_bytes());
+        assert!(matches!(
+            reader.get_record("not a read"),
+            Err(Slow5Error::GetRecordFailed)
+        ));
+
+        let mut acc = Vec::new();
+        for rec in reader.records() {
+            acc.push(rec.unwrap());
+        }
+        assert_eq!(acc.len(), 5);
+    }
+}
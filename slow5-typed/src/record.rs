@@ -5,27 +5,36 @@ use std::marker::PhantomData;
 
 use slow5lib_sys::slow5_rec_t;
 
-use slow5::{
-    RecordExt, Slow5Error, AuxField,
-};
+use slow5::{AuxField, RecPtr, Record, RecordExt, RecordPointer, Slow5Error};
 
 /// SLOW5 record generic over the auxiliary type
 pub struct RecordT<A = ()> {
-    pub(crate) slow5_rec: *mut slow5_rec_t,
+    pub(crate) rec: Record,
     _aux: PhantomData<A>,
 }
 
-// impl<A> RecPtr for RecordT<A> {
-//     fn ptr(&self) -> RecordPointer {
-//         RecordPointer {
-//             ptr: self.slow5_rec,
-//         }
-//     }
-// }
+impl<A> std::fmt::Debug for RecordT<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RecordT")
+            .field("read_id", &String::from_utf8_lossy(self.read_id()))
+            .finish()
+    }
+}
+
+impl<A> RecPtr for RecordT<A> {
+    fn ptr(&self) -> RecordPointer {
+        self.rec.ptr()
+    }
+}
 
-// impl<A> RecordExt for RecordT<A> {}
+impl<A> RecordExt for RecordT<A> {}
 
 impl<A> RecordT<A> {
+    // Take ownership of a record read by slow5lib
+    pub(crate) fn new(slow5_rec: *mut slow5_rec_t) -> Self {
+        unsafe { Record::from_raw(slow5_rec) }.into()
+    }
+
     /// Get the value of an auxiliary field from the RecordT
     pub fn get_aux_field<T>(&self, name: &str) -> Result<T, Slow5Error>
     where
@@ -33,4 +42,26 @@ impl<A> RecordT<A> {
     {
         T::aux_get(self, name)
     }
+
+    /// Get the value of an auxiliary field from the RecordT, returning None if
+    /// the field has no value for this record
+    pub fn get_aux_field_opt<T>(&self, name: &str) -> Result<Option<T>, Slow5Error>
+    where
+        T: AuxField,
+    {
+        T::aux_get_opt(self, name)
+    }
+}
+
+impl<A> From<Record> for RecordT<A> {
+    /// Take ownership of a Record, ie built with [`slow5::RecordBuilder`], so
+    /// it can be written with a typed [`FileWriter`].
+    ///
+    /// [`FileWriter`]: crate::FileWriter
+    fn from(rec: Record) -> Self {
+        Self {
+            rec,
+            _aux: PhantomData,
+        }
+    }
 }
@@ -0,0 +1,160 @@
+//! Provides a FileWriter generic over the auxiliary fields.
+use std::{marker::PhantomData, path::Path};
+
+use slow5::{AuxFieldSetExt, Header, Slow5Error, WriteOptions};
+
+use crate::{record::RecordT, FieldExt};
+
+/// Write a SLOW5 file with the auxiliary fields described by A, using a
+/// [`slow5::FileWriter`]
+pub struct FileWriter<A = ()> {
+    writer: slow5::FileWriter,
+    aux: PhantomData<A>,
+}
+
+impl<A> std::fmt::Debug for FileWriter<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileWriter").finish()
+    }
+}
+
+impl<A: FieldExt> FileWriter<A> {
+    /// Create a new SLOW5 or BLOW5 file, based on the file extension, with the
+    /// auxiliary fields of A added to the header. If the file already exists,
+    /// it will be written over.
+    ///
+    /// # Example
+    /// ```
+    /// # use assert_fs::TempDir;
+    /// # use assert_fs::fixture::PathChild;
+    /// use slow5_typed::FileWriter;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp_dir = TempDir::new()?;
+    /// # let file_path = tmp_dir.child("test.blow5");
+    /// let writer: FileWriter<()> = FileWriter::create(file_path)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn create<P: AsRef<Path>>(file_path: P) -> Result<Self, Slow5Error> {
+        Self::create_with(&WriteOptions::default(), file_path)
+    }
+
+    /// Create a new SLOW5 or BLOW5 file with the header and compression from
+    /// opts, and the auxiliary fields of A added to the header. The header
+    /// can't be changed after the file is created, so every attribute and
+    /// auxiliary field is declared here.
+    ///
+    /// # Example
+    /// ```
+    /// # use assert_fs::TempDir;
+    /// # use assert_fs::fixture::PathChild;
+    /// use slow5::{SignalCompression, WriteOptions};
+    /// use slow5_typed::FileWriter;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp_dir = TempDir::new()?;
+    /// # let file_path = tmp_dir.child("test.blow5");
+    /// let mut opts = WriteOptions::default();
+    /// opts.attr("run_id", "run_0", 0)
+    ///     .signal_compression(SignalCompression::StreamVByte);
+    /// let writer: FileWriter<()> = FileWriter::create_with(&opts, file_path)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Same as [`WriteOptions::create`].
+    pub fn create_with<P: AsRef<Path>>(
+        opts: &WriteOptions,
+        file_path: P,
+    ) -> Result<Self, Slow5Error> {
+        let mut opts = opts.clone();
+        for (name, field_type) in A::aux_schema() {
+            opts.aux(name, field_type);
+        }
+        Ok(FileWriter {
+            writer: opts.create(file_path)?,
+            aux: PhantomData,
+        })
+    }
+
+    /// Access header of the FileWriter
+    pub fn header(&self) -> Header<'_> {
+        self.writer.header()
+    }
+
+    /// Set the value of an auxiliary field for a record. Usually called by
+    /// the code generated by the FieldExt derive macro.
+    pub fn set_aux_field<T>(
+        &mut self,
+        rec: &mut RecordT<A>,
+        name: &str,
+        value: &T,
+    ) -> Result<(), Slow5Error>
+    where
+        T: AuxFieldSetExt,
+    {
+        value.aux_set(&mut rec.rec, name, &mut self.writer)
+    }
+
+    /// Set every auxiliary field of the record from aux, then write the
+    /// record to the file.
+    ///
+    /// Fields declared as `Option<T>` that are `None` are left unset.
+    ///
+    /// # Errors
+    /// Same as [`slow5::FileWriter::add_record`], or an Err if an auxiliary
+    /// field fails to be set.
+    pub fn add_record(&mut self, rec: &mut RecordT<A>, aux: &A) -> Result<(), Slow5Error> {
+        aux.set_record_aux_fields(self, rec)?;
+        self.writer.add_record(&rec.rec)
+    }
+
+    /// Close the SLOW5 file. Dropping the FileWriter also closes the file,
+    /// but any errors are only logged.
+    ///
+    /// # Errors
+    /// Same as [`slow5::FileWriter::close`].
+    pub fn close(self) -> Result<(), Slow5Error> {
+        self.writer.close()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use assert_fs::{fixture::PathChild, TempDir};
+    use slow5::{RecordBuilder, SignalCompression};
+
+    use super::*;
+
+    #[test]
+    fn test_create_with() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let file_path = tmp_dir.child("test.blow5");
+        let mut opts = WriteOptions::default();
+        opts.attr("run_id", "run_0", 0)
+            .signal_compression(SignalCompression::StreamVByte);
+        let mut writer: FileWriter<()> = FileWriter::create_with(&opts, &file_path)?;
+        assert_eq!(writer.header().get_attribute("run_id", 0)?, b"run_0");
+        let mut rec: RecordT<()> = RecordBuilder::default()
+            .read_id("read_0")
+            .read_group(0)
+            .digitisation(4096.0)
+            .offset(4.0)
+            .range(12.0)
+            .sampling_rate(4000.0)
+            .raw_signal(&[0, 1, 2, 3])
+            .build()?
+            .into();
+        writer.set_aux_field(&mut rec, "unknown", &1u8).unwrap_err();
+        writer.add_record(&mut rec, &())?;
+        writer.close()?;
+
+        let reader = slow5::FileReader::open(&file_path)?;
+        assert_eq!(reader.signal_compression(), SignalCompression::StreamVByte);
+        assert_eq!(reader.header().get_attribute("run_id", 0)?, b"run_0");
+        assert!(reader.get_record("read_0").is_ok());
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+use assert_fs::{fixture::PathChild, TempDir};
+use slow5::{EnumField, FieldType, RecordBuilder, Slow5Error};
+use slow5_typed::{FieldExt, FileReader, FileWriter, RecordT};
+
+#[derive(FieldExt)]
+struct Aux {
+    read_number: u32,
+    median: Option<f32>,
+    missing: Option<f64>,
+    signal_summary: Vec<u16>,
+    #[slow5(rename = "channel_number")]
+    channel: String,
+    #[slow5(labels("unknown", "signal_positive"))]
+    end_reason: EnumField,
+    #[slow5(skip)]
+    _not_in_file: Vec<String>,
+}
+
+#[test]
+fn test_typed_writer() -> anyhow::Result<()> {
+    let tmp_dir = TempDir::new()?;
+    let file_path = tmp_dir.child("test.blow5");
+    let mut writer: FileWriter<Aux> = FileWriter::create(&file_path)?;
+    let mut rec: RecordT<Aux> = RecordBuilder::default()
+        .read_id("read_0")
+        .read_group(0)
+        .digitisation(4096.0)
+        .offset(4.0)
+        .range(12.0)
+        .sampling_rate(4000.0)
+        .raw_signal(&[0, 1, 2, 3])
+        .build()?
+        .into();
+    let aux = Aux {
+        read_number: 10,
+        median: Some(1.5),
+        missing: None,
+        signal_summary: vec![1, 2, 3],
+        channel: String::from("123"),
+        end_reason: EnumField(1),
+        _not_in_file: Vec::new(),
+    };
+    writer.add_record(&mut rec, &aux)?;
+    writer.close()?;
+
+    let reader: FileReader<Aux> = FileReader::open(&file_path)?;
+    let rec = reader.get_record("read_0")?;
+    assert_eq!(Aux::read_number(&rec)?, 10);
+    assert_eq!(Aux::median(&rec)?, Some(1.5));
+    assert_eq!(Aux::missing(&rec)?, None);
+    assert_eq!(Aux::signal_summary(&rec)?, vec![1, 2, 3]);
+    assert_eq!(Aux::channel(&rec)?, "123");
+    let EnumField(end_reason) = Aux::end_reason(&rec)?;
+    assert_eq!(end_reason, 1);
+
+    let loaded = Aux::from_record(&rec)?;
+    assert_eq!(loaded.read_number, 10);
+    assert_eq!(loaded.median, Some(1.5));
+    assert_eq!(loaded.missing, None);
+    assert_eq!(loaded.signal_summary, vec![1, 2, 3]);
+    assert_eq!(loaded.channel, "123");
+    assert_eq!(loaded.end_reason.0, 1);
+    assert!(loaded._not_in_file.is_empty());
+    Ok(())
+}
+
+#[derive(FieldExt)]
+struct WrongType {
+    read_number: u8,
+}
+
+#[derive(FieldExt)]
+struct Missing {
+    _not_a_field: Option<u32>,
+}
+
+#[test]
+fn test_typed_reader_schema() -> anyhow::Result<()> {
+    let tmp_dir = TempDir::new()?;
+    let file_path = tmp_dir.child("test.blow5");
+    let writer: FileWriter<Aux> = FileWriter::create(&file_path)?;
+    writer.close()?;
+
+    let res: Result<FileReader<WrongType>, _> = FileReader::open(&file_path);
+    assert!(matches!(
+        res,
+        Err(Slow5Error::SchemaMismatch {
+            expected: FieldType::Uint8,
+            found: Some(FieldType::Uint32),
+            ..
+        })
+    ));
+
+    let res: Result<FileReader<Missing>, _> = FileReader::open(&file_path);
+    assert!(matches!(
+        res,
+        Err(Slow5Error::SchemaMismatch { found: None, .. })
+    ));
+    Ok(())
+}
@@ -0,0 +1,218 @@
+//! Remove identifying information from a SLOW5 file before sharing it
+use crate::{error::Slow5Error, reader::FileReader, writer::FileWriter};
+
+// Header attributes that identify the sequencing machine, the sample, or when
+// it was sequenced
+const IDENTIFYING_ATTRS: [&str; 5] = [
+    "device_id",
+    "host",
+    "hostname",
+    "sample_id",
+    "exp_start_time",
+];
+
+/// What [`anonymize`] does with the value of an identifying attribute
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttrAction {
+    /// Replace the value with ".", the value of a missing attribute
+    Strip,
+    /// Replace the value with the attribute name and a number, ie
+    /// `device_id_0`, numbered in the order the values first appear in the
+    /// read groups. Read groups with the same value still have the same
+    /// value, but nothing about the value itself is kept.
+    Replace,
+}
+
+/// Options for removing identifying information from a file, ie which header
+/// attributes are removed and whether read ids are replaced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnonymizeOptions {
+    attributes: Vec<Vec<u8>>,
+    action: AttrAction,
+    renumber_read_ids: bool,
+}
+
+impl Default for AnonymizeOptions {
+    fn default() -> Self {
+        Self {
+            attributes: IDENTIFYING_ATTRS
+                .iter()
+                .map(|attr| attr.as_bytes().to_vec())
+                .collect(),
+            action: AttrAction::Strip,
+            renumber_read_ids: false,
+        }
+    }
+}
+
+impl AnonymizeOptions {
+    /// Set the attributes to anonymize. By default, these are device_id,
+    /// host, hostname, sample_id, and exp_start_time.
+    pub fn attributes<I, B>(&mut self, attrs: I) -> &mut Self
+    where
+        I: IntoIterator<Item = B>,
+        B: Into<Vec<u8>>,
+    {
+        self.attributes = attrs.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set whether attribute values are stripped or replaced, by default they
+    /// are stripped.
+    pub fn action(&mut self, action: AttrAction) -> &mut Self {
+        self.action = action;
+        self
+    }
+
+    /// Replace the read id of each record with read_n, where n is the number
+    /// of records written before it.
+    pub fn renumber_read_ids(&mut self, renumber: bool) -> &mut Self {
+        self.renumber_read_ids = renumber;
+        self
+    }
+
+    /// Write the records of reader to writer without identifying information.
+    /// See [`anonymize`] for more information.
+    pub fn anonymize(
+        &self,
+        reader: &mut FileReader,
+        writer: &mut FileWriter,
+    ) -> Result<usize, Slow5Error> {
+        let mut values = Vec::new();
+        let header = writer.header();
+        for attr in self.attributes.iter() {
+            let mut seen = Vec::new();
+            for rg in 0..header.num_read_groups() {
+                if let Ok(value) = header.get_attribute(attr.clone(), rg) {
+                    values.push((attr.clone(), rg, self.replace(attr, value, &mut seen)));
+                }
+            }
+        }
+        let mut header = writer.header_mut()?;
+        for (attr, rg, value) in values {
+            header.set_attribute(attr, value, rg)?;
+        }
+
+        let mut n = 0;
+        for rec in reader.records() {
+            let mut rec = rec?;
+            if self.renumber_read_ids {
+                rec.set_read_id(format!("read_{n}"))?;
+            }
+            writer.add_record(&rec)?;
+            n += 1;
+        }
+        Ok(n)
+    }
+
+    // New value of attr, with seen the distinct values of attr in the read
+    // groups before this one
+    fn replace(&self, attr: &[u8], value: &[u8], seen: &mut Vec<Vec<u8>>) -> Vec<u8> {
+        match self.action {
+            AttrAction::Strip => b".".to_vec(),
+            AttrAction::Replace => {
+                let n = match seen.iter().position(|v| v == value) {
+                    Some(n) => n,
+                    None => {
+                        seen.push(value.to_vec());
+                        seen.len() - 1
+                    }
+                };
+                [attr, format!("_{n}").as_bytes()].concat()
+            }
+        }
+    }
+}
+
+/// Write the records of reader to writer with the identifying header
+/// attributes stripped or replaced, and optionally with new read ids, ie to
+/// share signal data publicly. Returns the number of records written.
+///
+/// The writer should be created with the header of the reader, ie with
+/// [`FileWriter::create_from`], and have no records yet. Attributes that
+/// aren't in the header are skipped. Auxiliary fields can be removed as well
+/// with [`WriteOptions::remove_aux`].
+///
+/// # Example
+/// ```
+/// # use assert_fs::TempDir;
+/// # use assert_fs::fixture::PathChild;
+/// use slow5::{anonymize, AnonymizeOptions, AttrAction, FileReader, FileWriter};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// # let tmp_dir = TempDir::new()?;
+/// # let file_path = tmp_dir.child("public.blow5");
+/// let mut reader = FileReader::open("examples/example.slow5")?;
+/// let mut writer = FileWriter::create_from(&reader, file_path)?;
+/// let mut opts = AnonymizeOptions::default();
+/// opts.action(AttrAction::Replace).renumber_read_ids(true);
+/// let n = anonymize(&mut reader, &mut writer, &opts)?;
+/// writer.close()?;
+/// assert_eq!(n, 5);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// Returns [`Slow5Error::HeaderAlreadyWritten`] if a record was already added
+/// to writer, or an Err if a record fails to be read or written.
+///
+/// [`WriteOptions::remove_aux`]: crate::WriteOptions::remove_aux
+pub fn anonymize(
+    reader: &mut FileReader,
+    writer: &mut FileWriter,
+    opts: &AnonymizeOptions,
+) -> Result<usize, Slow5Error> {
+    opts.anonymize(reader, writer)
+}
+
+#[cfg(test)]
+mod test {
+    use assert_fs::{fixture::PathChild, TempDir};
+
+    use super::*;
+    use crate::{HeaderExt, RecordExt};
+
+    #[test]
+    fn test_anonymize() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let file_path = tmp_dir.child("public.slow5");
+        let mut reader = FileReader::open("examples/example.slow5")?;
+        let mut writer = FileWriter::create_from(&reader, &file_path)?;
+        let mut opts = AnonymizeOptions::default();
+        opts.attributes(["asic_id"]).renumber_read_ids(true);
+        assert_eq!(anonymize(&mut reader, &mut writer, &opts)?, 5);
+        writer.close()?;
+
+        let original = FileReader::open("examples/example.slow5")?;
+        let public = FileReader::open(&file_path)?;
+        assert_ne!(
+            public.get_attribute("asic_id", 0)?,
+            original.get_attribute("asic_id", 0)?
+        );
+        assert_eq!(
+            public.get_attribute("bream_is_standard", 0)?,
+            original.get_attribute("bream_is_standard", 0)?
+        );
+        assert!(public.get_record("r1").is_err());
+        let rec = public.get_record("read_0")?;
+        let original_rec = original.get_record("r1")?;
+        assert!(rec.raw_signal_iter().eq(original_rec.raw_signal_iter()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_replace_attr() {
+        let mut opts = AnonymizeOptions::default();
+        let mut seen = Vec::new();
+        assert_eq!(opts.replace(b"device_id", b"MN12345", &mut seen), b".");
+
+        opts.action(AttrAction::Replace);
+        let values = [b"MN12345", b"MN12346", b"MN12345"]
+            .map(|value| opts.replace(b"device_id", value, &mut seen));
+        assert_eq!(
+            values,
+            [&b"device_id_0"[..], b"device_id_1", b"device_id_0"]
+        );
+    }
+}
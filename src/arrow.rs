@@ -0,0 +1,389 @@
+//! Export records as Apache Arrow [`RecordBatch`]es.
+//!
+//! Each batch has a column for each primary field, the raw signal as a
+//! `LargeList<Int16>`, and a column for each selected auxiliary field.
+//! Auxiliary fields without a value for a record are null.
+//!
+//! # Example
+//! ```
+//! use slow5::{arrow::to_record_batches, FileReader};
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let mut reader = FileReader::open("examples/example2.slow5")?;
+//! let batches =
+//!     to_record_batches(&mut reader, 2).aux_fields(["read_number", "channel_number"])?;
+//! for batch in batches {
+//!     let batch = batch?;
+//!     assert!(batch.column_by_name("read_number").is_some());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use std::sync::Arc;
+
+use arrow_array::{
+    builder::{
+        ArrayBuilder, Float64Builder, Int16Builder, LargeListBuilder, PrimitiveBuilder,
+        StringBuilder, UInt32Builder, UInt64Builder,
+    },
+    types::{
+        Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type, UInt16Type,
+        UInt32Type, UInt64Type, UInt8Type,
+    },
+    ArrayRef, RecordBatch,
+};
+use arrow_schema::SchemaRef;
+
+use crate::{
+    error::Slow5Error,
+    header::HeaderExt,
+    reader::FileReader,
+    record::{Record, RecordExt, RecordIter},
+    EnumField, FieldType,
+};
+
+fn arrow_err<E: std::fmt::Display>(e: E) -> Slow5Error {
+    Slow5Error::Arrow(e.to_string())
+}
+
+macro_rules! aux_column {
+    ($(($variant:ident, $rtype:ty, $arrow:ty)),*) => {
+        paste::paste! {
+            // Builder for the column of an auxiliary field
+            enum AuxColumn {
+                $(
+                    $variant(PrimitiveBuilder<$arrow>),
+                    [<$variant Array>](LargeListBuilder<PrimitiveBuilder<$arrow>>),
+                )*
+                Char(StringBuilder),
+                Str(StringBuilder),
+                Enum(StringBuilder, Vec<String>),
+            }
+
+            impl AuxColumn {
+                fn new(field_type: &FieldType) -> Self {
+                    match field_type {
+                        $(
+                            FieldType::$variant => AuxColumn::$variant(PrimitiveBuilder::new()),
+                            FieldType::[<$variant Array>] => {
+                                AuxColumn::[<$variant Array>](LargeListBuilder::new(
+                                    PrimitiveBuilder::new(),
+                                ))
+                            }
+                        )*
+                        FieldType::Char => AuxColumn::Char(StringBuilder::new()),
+                        FieldType::Str => AuxColumn::Str(StringBuilder::new()),
+                        FieldType::Enum(labels) => AuxColumn::Enum(
+                            StringBuilder::new(),
+                            labels
+                                .iter()
+                                .map(|l| String::from_utf8_lossy(l).into_owned())
+                                .collect(),
+                        ),
+                    }
+                }
+
+                fn append(&mut self, rec: &Record, name: &[u8]) -> Result<(), Slow5Error> {
+                    match self {
+                        $(
+                            AuxColumn::$variant(builder) => {
+                                builder.append_option(rec.get_aux_field_opt::<$rtype>(name)?);
+                            }
+                            AuxColumn::[<$variant Array>](builder) => {
+                                match rec.get_aux_field_opt::<Vec<$rtype>>(name)? {
+                                    Some(values) => {
+                                        builder.values().append_slice(&values);
+                                        builder.append(true);
+                                    }
+                                    None => builder.append_null(),
+                                }
+                            }
+                        )*
+                        AuxColumn::Char(builder) => {
+                            let value = rec.get_aux_field_opt::<char>(name)?;
+                            builder.append_option(value.map(String::from));
+                        }
+                        AuxColumn::Str(builder) => {
+                            builder.append_option(rec.get_aux_field_opt::<String>(name)?);
+                        }
+                        AuxColumn::Enum(builder, labels) => {
+                            let value = rec.get_aux_field_opt::<EnumField>(name)?;
+                            let label = value.and_then(|EnumField(idx)| labels.get(idx));
+                            builder.append_option(label);
+                        }
+                    }
+                    Ok(())
+                }
+
+                fn finish(&mut self) -> ArrayRef {
+                    match self {
+                        $(
+                            AuxColumn::$variant(builder) => Arc::new(builder.finish()),
+                            AuxColumn::[<$variant Array>](builder) => Arc::new(builder.finish()),
+                        )*
+                        AuxColumn::Char(builder) | AuxColumn::Str(builder) => {
+                            Arc::new(builder.finish())
+                        }
+                        AuxColumn::Enum(builder, _) => Arc::new(builder.finish()),
+                    }
+                }
+            }
+        }
+    };
+}
+
+aux_column!(
+    (Int8, i8, Int8Type),
+    (Int16, i16, Int16Type),
+    (Int32, i32, Int32Type),
+    (Int64, i64, Int64Type),
+    (Uint8, u8, UInt8Type),
+    (Uint16, u16, UInt16Type),
+    (Uint32, u32, UInt32Type),
+    (Uint64, u64, UInt64Type),
+    (Float, f32, Float32Type),
+    (Double, f64, Float64Type)
+);
+
+// Builders for the primary fields and signal
+struct PrimaryColumns {
+    read_id: StringBuilder,
+    read_group: UInt32Builder,
+    digitisation: Float64Builder,
+    offset: Float64Builder,
+    range: Float64Builder,
+    sampling_rate: Float64Builder,
+    len_raw_signal: UInt64Builder,
+    raw_signal: LargeListBuilder<Int16Builder>,
+}
+
+impl PrimaryColumns {
+    fn new() -> Self {
+        Self {
+            read_id: StringBuilder::new(),
+            read_group: UInt32Builder::new(),
+            digitisation: Float64Builder::new(),
+            offset: Float64Builder::new(),
+            range: Float64Builder::new(),
+            sampling_rate: Float64Builder::new(),
+            len_raw_signal: UInt64Builder::new(),
+            raw_signal: LargeListBuilder::new(Int16Builder::new()),
+        }
+    }
+
+    fn append(&mut self, rec: &Record) {
+        self.read_id
+            .append_value(String::from_utf8_lossy(rec.read_id()));
+        self.read_group.append_value(rec.read_group());
+        self.digitisation.append_value(rec.digitisation());
+        self.offset.append_value(rec.offset());
+        self.range.append_value(rec.range());
+        self.sampling_rate.append_value(rec.sampling_rate());
+        self.len_raw_signal.append_value(rec.len_signal());
+        for sample in rec.raw_signal_iter() {
+            self.raw_signal.values().append_value(sample);
+        }
+        self.raw_signal.append(true);
+    }
+
+    fn len(&self) -> usize {
+        self.read_id.len()
+    }
+
+    fn finish(&mut self, include_signal: bool) -> Vec<(String, ArrayRef, bool)> {
+        let columns: [(&str, ArrayRef); 8] = [
+            ("read_id", Arc::new(self.read_id.finish())),
+            ("read_group", Arc::new(self.read_group.finish())),
+            ("digitisation", Arc::new(self.digitisation.finish())),
+            ("offset", Arc::new(self.offset.finish())),
+            ("range", Arc::new(self.range.finish())),
+            ("sampling_rate", Arc::new(self.sampling_rate.finish())),
+            ("len_raw_signal", Arc::new(self.len_raw_signal.finish())),
+            ("raw_signal", Arc::new(self.raw_signal.finish())),
+        ];
+        columns
+            .into_iter()
+            .filter(|(name, _)| include_signal || *name != "raw_signal")
+            .map(|(name, array)| (name.to_string(), array, false))
+            .collect()
+    }
+}
+
+/// Return iterator over the records of reader as Arrow [`RecordBatch`]es of
+/// at most batch_size rows. By default only the primary fields and the signal
+/// are included, use [`RecordBatchIter::aux_fields`] to add auxiliary fields.
+pub fn to_record_batches(reader: &mut FileReader, batch_size: usize) -> RecordBatchIter<'_> {
+    let header_aux = reader
+        .aux_fields_iter()
+        .map(|(name, field_type)| (name.to_vec(), field_type))
+        .collect();
+    RecordBatchIter {
+        records: reader.records(),
+        batch_size: batch_size.max(1),
+        header_aux,
+        aux: Vec::new(),
+        include_signal: true,
+        errored: false,
+    }
+}
+
+/// Iterator over Arrow [`RecordBatch`]es, generated by calling
+/// [`to_record_batches`].
+///
+/// If an error occurs, the iterator will produce Some(Err(_)) and then None
+/// on subsequent iterations.
+pub struct RecordBatchIter<'a> {
+    records: RecordIter<'a>,
+    batch_size: usize,
+    // Auxiliary fields available in the header
+    header_aux: Vec<(Vec<u8>, FieldType)>,
+    // Auxiliary fields that are exported
+    aux: Vec<(Vec<u8>, FieldType)>,
+    include_signal: bool,
+    errored: bool,
+}
+
+impl<'a> std::fmt::Debug for RecordBatchIter<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RecordBatchIter")
+            .field("batch_size", &self.batch_size)
+            .finish()
+    }
+}
+
+impl<'a> RecordBatchIter<'a> {
+    /// Add a column for each of the auxiliary fields, in the given order.
+    ///
+    /// # Errors
+    /// Returns [`Slow5Error::MissingAttribute`] if an auxiliary field isn't in
+    /// the header.
+    pub fn aux_fields<I, B>(mut self, names: I) -> Result<Self, Slow5Error>
+    where
+        I: IntoIterator<Item = B>,
+        B: Into<Vec<u8>>,
+    {
+        for name in names {
+            let name = name.into();
+            let field_type = self
+                .header_aux
+                .iter()
+                .find(|(aux_name, _)| *aux_name == name)
+                .map(|(_, field_type)| field_type.clone())
+                .ok_or(Slow5Error::MissingAttribute)?;
+            self.aux.push((name, field_type));
+        }
+        Ok(self)
+    }
+
+    // Add a column for every auxiliary field in the header
+    pub(crate) fn all_aux_fields(mut self) -> Self {
+        self.aux = self.header_aux.clone();
+        self
+    }
+
+    /// Set whether the raw signal column is included, by default it is.
+    pub fn include_signal(mut self, include: bool) -> Self {
+        self.include_signal = include;
+        self
+    }
+
+    /// Schema of the batches
+    pub fn schema(&self) -> Result<SchemaRef, Slow5Error> {
+        let (mut primary, mut aux) = self.builders();
+        Ok(self.finish_batch(&mut primary, &mut aux)?.schema())
+    }
+
+    fn builders(&self) -> (PrimaryColumns, Vec<AuxColumn>) {
+        let aux = self
+            .aux
+            .iter()
+            .map(|(_, field_type)| AuxColumn::new(field_type))
+            .collect();
+        (PrimaryColumns::new(), aux)
+    }
+
+    fn finish_batch(
+        &self,
+        primary: &mut PrimaryColumns,
+        aux: &mut [AuxColumn],
+    ) -> Result<RecordBatch, Slow5Error> {
+        let mut columns = primary.finish(self.include_signal);
+        for ((name, _), column) in self.aux.iter().zip(aux.iter_mut()) {
+            let name = String::from_utf8_lossy(name).into_owned();
+            columns.push((name, column.finish(), true));
+        }
+        RecordBatch::try_from_iter_with_nullable(columns).map_err(arrow_err)
+    }
+
+    fn next_batch(&mut self) -> Option<Result<RecordBatch, Slow5Error>> {
+        let (mut primary, mut aux) = self.builders();
+        while primary.len() < self.batch_size {
+            let rec = match self.records.next() {
+                Some(Ok(rec)) => rec,
+                Some(Err(e)) => return Some(Err(e)),
+                None => break,
+            };
+            primary.append(&rec);
+            for ((name, _), column) in self.aux.iter().zip(aux.iter_mut()) {
+                if let Err(e) = column.append(&rec, name) {
+                    return Some(Err(e));
+                }
+            }
+        }
+        if primary.len() == 0 {
+            return None;
+        }
+        Some(self.finish_batch(&mut primary, &mut aux))
+    }
+}
+
+impl<'a> Iterator for RecordBatchIter<'a> {
+    type Item = Result<RecordBatch, Slow5Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.errored {
+            return None;
+        }
+        let batch = self.next_batch();
+        if matches!(batch, Some(Err(_))) {
+            self.errored = true;
+        }
+        batch
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use arrow_array::{cast::AsArray, Array};
+
+    use super::*;
+
+    #[test]
+    fn test_record_batches() -> anyhow::Result<()> {
+        let mut reader = FileReader::open("examples/example2.slow5")?;
+        let batches = to_record_batches(&mut reader, 2)
+            .aux_fields(["read_number", "channel_number"])?
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(batches.len(), 3);
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 5);
+        let batch = &batches[0];
+        assert_eq!(batch.num_columns(), 10);
+        let signal = batch.column_by_name("raw_signal").unwrap().as_list::<i64>();
+        let len = batch
+            .column_by_name("len_raw_signal")
+            .unwrap()
+            .as_primitive::<UInt64Type>();
+        assert_eq!(signal.value(0).len() as u64, len.value(0));
+        let read_number = batch
+            .column_by_name("read_number")
+            .unwrap()
+            .as_primitive::<Int32Type>();
+        assert!(!read_number.is_null(0));
+
+        let mut reader = FileReader::open("examples/example2.slow5")?;
+        assert!(to_record_batches(&mut reader, 2)
+            .aux_fields(["not a field"])
+            .is_err());
+        Ok(())
+    }
+}
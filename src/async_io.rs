@@ -0,0 +1,299 @@
+//! Read and write SLOW5 files from async code, ie services built on tokio.
+//!
+//! slow5lib only has blocking functions, so they are run with
+//! [`tokio::task::spawn_blocking`] and the results are sent back to the
+//! async task. Every method must be called from within a tokio runtime.
+use std::{
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard},
+    task::{Context, Poll},
+};
+
+use futures_core::Stream;
+use tokio::{sync::mpsc, task::JoinHandle};
+
+use crate::{
+    error::Slow5Error,
+    reader::FileReader,
+    record::Record,
+    writer::{FileWriter, WriteOptions},
+};
+
+// Number of records read ahead of the consumer of a RecordStream
+const RECORD_BUFFER: usize = 64;
+
+// Run f on the blocking thread pool and wait for it
+async fn blocking<F, T>(f: F) -> Result<T, Slow5Error>
+where
+    F: FnOnce() -> Result<T, Slow5Error> + Send + 'static,
+    T: Send + 'static,
+{
+    match tokio::task::spawn_blocking(f).await {
+        Ok(res) => res,
+        Err(e) => Err(Slow5Error::Async(e.to_string())),
+    }
+}
+
+fn lock(reader: &Mutex<FileReader>) -> MutexGuard<'_, FileReader> {
+    // A panic while reading doesn't leave the reader in a state that matters
+    // here, the next read will fail if the file is broken
+    reader.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Read a SLOW5 file from async code. Reads run on tokio's blocking thread
+/// pool, one at a time.
+///
+/// # Example
+/// ```
+/// use slow5::{AsyncFileReader, RecordExt};
+/// use futures::StreamExt;
+///
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// let reader = AsyncFileReader::open("examples/example.slow5").await?;
+/// let rec = reader.get_record("r3").await?;
+/// assert_eq!(rec.read_id(), b"r3");
+///
+/// let mut records = reader.records();
+/// while let Some(rec) = records.next().await {
+///     let _ = rec?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct AsyncFileReader {
+    inner: Arc<Mutex<FileReader>>,
+}
+
+impl AsyncFileReader {
+    /// Open a SLOW5 file, like [`FileReader::open`]
+    ///
+    /// [`FileReader::open`]: crate::FileReader::open
+    pub async fn open<P: AsRef<Path>>(file_path: P) -> Result<Self, Slow5Error> {
+        let file_path: PathBuf = file_path.as_ref().to_owned();
+        let reader = blocking(move || FileReader::open(file_path)).await?;
+        Ok(Self::from(reader))
+    }
+
+    /// Get a record by read id, like [`FileReader::get_record`]
+    ///
+    /// [`FileReader::get_record`]: crate::FileReader::get_record
+    pub async fn get_record<B>(&self, read_id: B) -> Result<Record, Slow5Error>
+    where
+        B: Into<Vec<u8>>,
+    {
+        let read_id = read_id.into();
+        let inner = Arc::clone(&self.inner);
+        blocking(move || lock(&inner).get_record(read_id)).await
+    }
+
+    /// Returns a stream of all the records in the file, like
+    /// [`FileReader::records`]. Records are read on a blocking thread, which
+    /// stops once the stream is dropped.
+    ///
+    /// Each stream reads from its own handle to the file, from
+    /// [`FileReader::try_clone`], so it starts at the first record and other
+    /// reads don't wait for it. For readers that can't be cloned, ie from
+    /// [`FileReader::from_reader`], the stream only yields the Err.
+    ///
+    /// [`FileReader::records`]: crate::FileReader::records
+    /// [`FileReader::try_clone`]: crate::FileReader::try_clone
+    /// [`FileReader::from_reader`]: crate::FileReader::from_reader
+    pub fn records(&self) -> RecordStream {
+        let (tx, rx) = mpsc::channel(RECORD_BUFFER);
+        let inner = Arc::clone(&self.inner);
+        tokio::task::spawn_blocking(move || {
+            let mut reader = match lock(&inner).try_clone() {
+                Ok(reader) => reader,
+                Err(e) => {
+                    let _ = tx.blocking_send(Err(e));
+                    return;
+                }
+            };
+            for rec in reader.records() {
+                // Receiver was dropped, no need to read the rest
+                if tx.blocking_send(rec).is_err() {
+                    break;
+                }
+            }
+        });
+        RecordStream { rx }
+    }
+}
+
+impl From<FileReader> for AsyncFileReader {
+    fn from(reader: FileReader) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(reader)),
+        }
+    }
+}
+
+/// Stream of the records in a file, generated by [`AsyncFileReader::records`]
+#[derive(Debug)]
+pub struct RecordStream {
+    rx: mpsc::Receiver<Result<Record, Slow5Error>>,
+}
+
+impl Stream for RecordStream {
+    type Item = Result<Record, Slow5Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+/// Write a SLOW5 file from async code. Records are queued in a bounded
+/// channel and written by a blocking task, so [`add_record`] waits when the
+/// writer falls behind.
+///
+/// # Example
+/// ```
+/// use slow5::{AsyncFileReader, AsyncFileWriter, FileReader, WriteOptions};
+/// use futures::StreamExt;
+/// # use assert_fs::{fixture::PathChild, TempDir};
+///
+/// # #[tokio::main]
+/// # async fn main() -> anyhow::Result<()> {
+/// # let tmp_dir = TempDir::new()?;
+/// # let file_path = tmp_dir.child("test.blow5");
+/// let reader = FileReader::open("examples/example3.blow5")?;
+/// let opts = WriteOptions::from_reader(&reader)?;
+/// let mut writer = AsyncFileWriter::create(&file_path, &opts).await?;
+/// let mut records = AsyncFileReader::from(reader).records();
+/// while let Some(rec) = records.next().await {
+///     writer.add_record(rec?).await?;
+/// }
+/// writer.close().await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`add_record`]: crate::AsyncFileWriter::add_record
+#[derive(Debug)]
+pub struct AsyncFileWriter {
+    tx: Option<mpsc::Sender<Record>>,
+    task: Option<JoinHandle<Result<(), Slow5Error>>>,
+}
+
+impl AsyncFileWriter {
+    /// Create a file with the options, like [`WriteOptions::create`]
+    ///
+    /// [`WriteOptions::create`]: crate::WriteOptions::create
+    pub async fn create<P: AsRef<Path>>(
+        file_path: P,
+        opts: &WriteOptions,
+    ) -> Result<Self, Slow5Error> {
+        let file_path: PathBuf = file_path.as_ref().to_owned();
+        let opts = opts.clone();
+        let writer = blocking(move || opts.create(file_path)).await?;
+        Ok(Self::new(writer))
+    }
+
+    /// Write the records added to the AsyncFileWriter with writer
+    pub fn new(mut writer: FileWriter) -> Self {
+        let (tx, mut rx) = mpsc::channel::<Record>(RECORD_BUFFER);
+        let task = tokio::task::spawn_blocking(move || {
+            while let Some(rec) = rx.blocking_recv() {
+                writer.add_record(&rec)?;
+            }
+            writer.close()
+        });
+        Self {
+            tx: Some(tx),
+            task: Some(task),
+        }
+    }
+
+    /// Queue the record to be written, waiting if the queue is full
+    ///
+    /// # Errors
+    /// Returns the Err from writing an earlier record, after which the file
+    /// is closed and no more records can be added.
+    pub async fn add_record(&mut self, record: Record) -> Result<(), Slow5Error> {
+        let tx = self.tx.as_ref().ok_or(Slow5Error::Unknown)?;
+        if tx.send(record).await.is_err() {
+            // Writer task stopped after an error
+            self.tx = None;
+            return self.join().await;
+        }
+        Ok(())
+    }
+
+    /// Write the queued records and close the file
+    ///
+    /// # Errors
+    /// Returns an Err if a record or the file failed to be written or closed
+    pub async fn close(mut self) -> Result<(), Slow5Error> {
+        self.tx = None;
+        self.join().await
+    }
+
+    // Wait for the writer task to stop, returning its error
+    async fn join(&mut self) -> Result<(), Slow5Error> {
+        let task = self.task.take().ok_or(Slow5Error::Unknown)?;
+        match task.await {
+            Ok(res) => res,
+            Err(e) => Err(Slow5Error::Async(e.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use assert_fs::{fixture::PathChild, TempDir};
+    use futures::StreamExt;
+
+    use super::*;
+    use crate::RecordExt;
+
+    #[tokio::test]
+    async fn test_async_reader() -> anyhow::Result<()> {
+        let reader = AsyncFileReader::open("examples/example.slow5").await?;
+        let rec = reader.get_record("r3").await?;
+        assert_eq!(rec.read_id(), b"r3");
+
+        let mut records = reader.records();
+        let mut read_ids = Vec::new();
+        while let Some(rec) = records.next().await {
+            read_ids.push(rec?.read_id().to_vec());
+        }
+        assert_eq!(read_ids.len(), 5);
+        assert!(reader.get_record("missing").await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_async_reader_concurrent() -> anyhow::Result<()> {
+        let reader = AsyncFileReader::open("examples/example.slow5").await?;
+        let mut first = reader.records();
+        let first_rec = first.next().await.unwrap()?;
+        assert_eq!(reader.get_record("r3").await?.read_id(), b"r3");
+
+        let second = reader.records().collect::<Vec<_>>().await;
+        assert_eq!(second.len(), 5);
+        assert_eq!(second[0].as_ref().unwrap().read_id(), first_rec.read_id());
+        assert_eq!(first.count().await, 4);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_async_writer() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let file_path = tmp_dir.child("test.slow5");
+        let reader = AsyncFileReader::open("examples/example.slow5").await?;
+        let opts = WriteOptions::from_reader(&lock(&reader.inner))?;
+        let mut writer = AsyncFileWriter::create(&file_path, &opts).await?;
+        let mut records = reader.records();
+        while let Some(rec) = records.next().await {
+            writer.add_record(rec?).await?;
+        }
+        writer.close().await?;
+
+        let written = FileReader::open(&file_path)?;
+        assert_eq!(written.get_record("r3")?.read_id(), b"r3");
+        Ok(())
+    }
+}
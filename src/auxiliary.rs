@@ -2,99 +2,32 @@ use std::ffi::CStr;
 
 use libc::c_void;
 use slow5lib_sys::{
-    slow5_aux_get_char, slow5_aux_get_double, slow5_aux_get_enum, slow5_aux_get_float,
-    slow5_aux_get_int16, slow5_aux_get_int32, slow5_aux_get_int64, slow5_aux_get_int8,
-    slow5_aux_get_string, slow5_aux_get_uint16, slow5_aux_get_uint32, slow5_aux_get_uint64,
-    slow5_aux_get_uint8, slow5_aux_set, slow5_aux_set_string, slow5_aux_type_SLOW5_CHAR,
-    slow5_aux_type_SLOW5_DOUBLE, slow5_aux_type_SLOW5_DOUBLE_ARRAY, slow5_aux_type_SLOW5_ENUM,
-    slow5_aux_type_SLOW5_FLOAT, slow5_aux_type_SLOW5_FLOAT_ARRAY, slow5_aux_type_SLOW5_INT16_T,
-    slow5_aux_type_SLOW5_INT16_T_ARRAY, slow5_aux_type_SLOW5_INT32_T,
+    slow5_aux_array_set, slow5_aux_get_char, slow5_aux_get_double, slow5_aux_get_enum,
+    slow5_aux_get_float, slow5_aux_get_int16, slow5_aux_get_int32, slow5_aux_get_int64,
+    slow5_aux_get_int8, slow5_aux_get_string, slow5_aux_get_uint16, slow5_aux_get_uint32,
+    slow5_aux_get_uint64, slow5_aux_get_uint8, slow5_aux_set, slow5_aux_set_string,
+    slow5_aux_type_SLOW5_CHAR, slow5_aux_type_SLOW5_DOUBLE, slow5_aux_type_SLOW5_DOUBLE_ARRAY,
+    slow5_aux_type_SLOW5_ENUM, slow5_aux_type_SLOW5_FLOAT, slow5_aux_type_SLOW5_FLOAT_ARRAY,
+    slow5_aux_type_SLOW5_INT16_T, slow5_aux_type_SLOW5_INT16_T_ARRAY, slow5_aux_type_SLOW5_INT32_T,
     slow5_aux_type_SLOW5_INT32_T_ARRAY, slow5_aux_type_SLOW5_INT64_T,
     slow5_aux_type_SLOW5_INT64_T_ARRAY, slow5_aux_type_SLOW5_INT8_T,
     slow5_aux_type_SLOW5_INT8_T_ARRAY, slow5_aux_type_SLOW5_STRING, slow5_aux_type_SLOW5_UINT16_T,
     slow5_aux_type_SLOW5_UINT16_T_ARRAY, slow5_aux_type_SLOW5_UINT32_T,
     slow5_aux_type_SLOW5_UINT32_T_ARRAY, slow5_aux_type_SLOW5_UINT64_T,
-    slow5_aux_type_SLOW5_UINT8_T, slow5_aux_type_SLOW5_UINT8_T_ARRAY,
+    slow5_aux_type_SLOW5_UINT64_T_ARRAY, slow5_aux_type_SLOW5_UINT8_T,
+    slow5_aux_type_SLOW5_UINT8_T_ARRAY, SLOW5_ERR_ARG, SLOW5_ERR_NOAUX, SLOW5_ERR_TYPE,
 };
 
-use crate::{to_cstring, FileWriter, Record, RecordExt, Slow5Error};
-
-/// Maps between Rust types and SLOW5 C types
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub enum FieldType {
-    /// i8
-    Int8,
-    /// i16
-    Int16,
-    /// i32
-    Int32,
-    /// i64
-    Int64,
-    /// u8
-    Uint8,
-    /// u16
-    Uint16,
-    /// u32
-    Uint32,
-    /// u64
-    Uint64,
-    /// f32
-    Float,
-    /// f64
-    Double,
-    /// char
-    Char,
-    /// &str,
-    Str,
-
-    /// &[u8], not a string representation
-    Uint8Array,
-
-    /// &[u16]
-    Uint16Array,
-
-    /// &[u32]
-    Uint32Array,
-
-    /// &[u64]
-    Uint64Array,
-
-    /// &[i8]
-    Int8Array,
-
-    /// &[i16]
-    Int16Array,
-
-    /// &[i32]
-    Int32Array,
-
-    /// &[i64]
-    Int64Array,
-
-    /// &[f32]
-    FloatArray,
-
-    /// &[f64]
-    DoubleArray,
-
-    /// EnumField
-    Enum(Vec<Vec<u8>>),
-}
-
-impl<B> From<Vec<B>> for FieldType
-where
-    B: Into<Vec<u8>>,
-{
-    fn from(value: Vec<B>) -> Self {
-        FieldType::Enum(value.into_iter().map(|b| b.into()).collect())
-    }
-}
+use crate::{to_cstring, EnumField, FieldType, FileWriter, Record, RecordExt, Slow5Error};
 
 /// Wrapper around slow5lib-sys aux type
-pub(crate) struct Slow5AuxType(pub(crate) u32);
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct Slow5AuxType(pub u32);
 
 impl FieldType {
-    pub(crate) fn to_slow5_t(&self) -> Slow5AuxType {
+    #[doc(hidden)]
+    pub fn to_slow5_t(&self) -> Slow5AuxType {
         Slow5AuxType(match self {
             FieldType::Int8 => slow5_aux_type_SLOW5_INT8_T,
             FieldType::Int16 => slow5_aux_type_SLOW5_INT16_T,
@@ -117,25 +50,52 @@ impl FieldType {
             FieldType::Uint8Array => slow5_aux_type_SLOW5_UINT8_T_ARRAY,
             FieldType::Uint16Array => slow5_aux_type_SLOW5_UINT16_T_ARRAY,
             FieldType::Uint32Array => slow5_aux_type_SLOW5_UINT32_T_ARRAY,
-            FieldType::Uint64Array => slow5_aux_type_SLOW5_INT64_T_ARRAY,
+            FieldType::Uint64Array => slow5_aux_type_SLOW5_UINT64_T_ARRAY,
             FieldType::Enum(_) => slow5_aux_type_SLOW5_ENUM,
         })
     }
 }
 
-/// Represents the value for an enum field. This struct wraps an index into the
-/// labels used for auxiiliary enum field.
-///
-/// The intended way to use is to index into the output from
-/// [`AuxEnumlabelIter`]
-///
-/// [`AuxEnumLabelIter`]: crate::reader::AuxEnumLabelIter
-#[derive(Debug)]
-pub struct EnumField(pub usize);
+impl FieldType {
+    /// Convert from slow5lib aux type, enums are handled separately since the
+    /// labels are stored in the header
+    pub(crate) fn from_slow5_t(aux_type: u32) -> Option<FieldType> {
+        #[allow(non_upper_case_globals)]
+        let field_type = match aux_type {
+            slow5_aux_type_SLOW5_INT8_T => FieldType::Int8,
+            slow5_aux_type_SLOW5_INT16_T => FieldType::Int16,
+            slow5_aux_type_SLOW5_INT32_T => FieldType::Int32,
+            slow5_aux_type_SLOW5_INT64_T => FieldType::Int64,
+            slow5_aux_type_SLOW5_UINT8_T => FieldType::Uint8,
+            slow5_aux_type_SLOW5_UINT16_T => FieldType::Uint16,
+            slow5_aux_type_SLOW5_UINT32_T => FieldType::Uint32,
+            slow5_aux_type_SLOW5_UINT64_T => FieldType::Uint64,
+            slow5_aux_type_SLOW5_FLOAT => FieldType::Float,
+            slow5_aux_type_SLOW5_DOUBLE => FieldType::Double,
+            slow5_aux_type_SLOW5_CHAR => FieldType::Char,
+            slow5_aux_type_SLOW5_STRING => FieldType::Str,
+            slow5_aux_type_SLOW5_DOUBLE_ARRAY => FieldType::DoubleArray,
+            slow5_aux_type_SLOW5_FLOAT_ARRAY => FieldType::FloatArray,
+            slow5_aux_type_SLOW5_INT8_T_ARRAY => FieldType::Int8Array,
+            slow5_aux_type_SLOW5_INT16_T_ARRAY => FieldType::Int16Array,
+            slow5_aux_type_SLOW5_INT32_T_ARRAY => FieldType::Int32Array,
+            slow5_aux_type_SLOW5_INT64_T_ARRAY => FieldType::Int64Array,
+            slow5_aux_type_SLOW5_UINT8_T_ARRAY => FieldType::Uint8Array,
+            slow5_aux_type_SLOW5_UINT16_T_ARRAY => FieldType::Uint16Array,
+            slow5_aux_type_SLOW5_UINT32_T_ARRAY => FieldType::Uint32Array,
+            slow5_aux_type_SLOW5_UINT64_T_ARRAY => FieldType::Uint64Array,
+            _ => return None,
+        };
+        Some(field_type)
+    }
+}
 
 // TODO Use an associated type to separate FieldType from Enum related types
 /// Helper trait to get auxiliary field values from [`Record`]
 ///
+/// Slices and `&str` borrow from the underlying record, while `Vec<T>` and
+/// `String` copy the value into an owned buffer.
+///
 /// [`Record`]: crate::Record
 pub trait AuxField {
     /// Get the auxiliary field with name from the Record
@@ -144,6 +104,76 @@ pub trait AuxField {
         B: Into<Vec<u8>>,
         R: RecordExt,
         Self: std::marker::Sized;
+
+    /// Get the auxiliary field with name from the Record, returning None if
+    /// the field has no value for this Record
+    fn aux_get_opt<B, R>(rec: &R, name: B) -> Result<Option<Self>, Slow5Error>
+    where
+        B: Into<Vec<u8>>,
+        R: RecordExt,
+        Self: std::marker::Sized,
+    {
+        unset_to_none(Self::aux_get(rec, name))
+    }
+
+    /// Get the auxiliary field with name from the Record, with the name
+    /// already converted to a C string, so it isn't converted on every call
+    fn aux_get_cstr<R>(rec: &R, name: &CStr) -> Result<Self, Slow5Error>
+    where
+        R: RecordExt,
+        Self: std::marker::Sized,
+    {
+        Self::aux_get(rec, name.to_bytes())
+    }
+}
+
+/// Convert error code from slow5_aux_get_* into Slow5Error
+fn parse_aux_field_get_error(err: i32) -> Slow5Error {
+    match err {
+        SLOW5_ERR_NOAUX => Slow5Error::AuxFieldUnset,
+        SLOW5_ERR_TYPE => Slow5Error::AuxTypeMismatch,
+        SLOW5_ERR_ARG => Slow5Error::Argument,
+        _ => Slow5Error::AuxLoadFailure,
+    }
+}
+
+fn unset_to_none<T>(res: Result<T, Slow5Error>) -> Result<Option<T>, Slow5Error> {
+    match res {
+        Ok(value) => Ok(Some(value)),
+        Err(Slow5Error::AuxFieldUnset) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+// slow5lib stores missing values for primitive types as a sentinel value
+pub(crate) trait MissingValue {
+    fn is_missing(&self) -> bool;
+}
+
+macro_rules! impl_missing_value {
+    ($($rtype:ty),*) => {
+        $(
+            impl MissingValue for $rtype {
+                fn is_missing(&self) -> bool {
+                    *self == <$rtype>::MAX
+                }
+            }
+        )*
+    };
+}
+
+impl_missing_value!(i8, i16, i32, i64, u8, u16, u32, u64);
+
+impl MissingValue for f32 {
+    fn is_missing(&self) -> bool {
+        self.is_nan()
+    }
+}
+
+impl MissingValue for f64 {
+    fn is_missing(&self) -> bool {
+        self.is_nan()
+    }
 }
 
 macro_rules! impl_auxfield {
@@ -153,43 +183,110 @@ macro_rules! impl_auxfield {
             where
                 B: Into<Vec<u8>>,
                 R: RecordExt,
+            {
+                Self::aux_get_cstr(rec, &to_cstring(name)?)
+            }
+
+            fn aux_get_cstr<R>(rec: &R, name: &CStr) -> Result<Self, Slow5Error>
+            where
+                R: RecordExt,
             {
                 let mut ret = 0;
-                let name: Vec<u8> = name.into();
-                let name = crate::to_cstring(name)?;
                 let data = unsafe {
                     paste::paste!( [<slow5_aux_get_ $ctype:lower >] )(rec.ptr().ptr, name.as_ptr(), &mut ret)
                 };
                 if ret != 0 {
-                    Err(Slow5Error::AuxLoadFailure)
+                    Err(parse_aux_field_get_error(ret))
                 } else {
                     Ok(data)
                 }
             }
-        }
 
-        impl AuxField for &[$rtype] {
-            fn aux_get<B, R>(rec: &R, name: B) -> Result<Self, Slow5Error>
+            fn aux_get_opt<B, R>(rec: &R, name: B) -> Result<Option<Self>, Slow5Error>
             where
                 B: Into<Vec<u8>>,
                 R: RecordExt,
+            {
+                let data = unset_to_none(Self::aux_get(rec, name))?;
+                Ok(data.filter(|x| !x.is_missing()))
+            }
+        }
+
+        paste::paste! {
+            fn [<get_ $ctype:lower _array>]<'a, R>(
+                rec: &R,
+                name: &CStr,
+            ) -> Result<Option<&'a [$rtype]>, Slow5Error>
+            where
+                R: RecordExt,
             {
                 use slow5lib_sys::*;
                 let mut err = 0;
                 let mut len = 0;
-                let name: Vec<u8> = name.into();
-                let name = crate::to_cstring(name)?;
                 let data = unsafe {
-                    paste::paste!( [<slow5_aux_get_ $ctype:lower _array>] )(rec.ptr().ptr, name.as_ptr(), &mut len, &mut err)
+                    [<slow5_aux_get_ $ctype:lower _array>](rec.ptr().ptr, name.as_ptr(), &mut len, &mut err)
                 };
                 if err != 0 {
-                    Err(Slow5Error::AuxLoadFailure)
+                    Err(parse_aux_field_get_error(err))
+                } else if data.is_null() {
+                    Ok(None)
                 } else {
                     let data: &[$rtype] = unsafe { std::slice::from_raw_parts(data, len as usize) };
-                    Ok(data)
+                    Ok(Some(data))
                 }
             }
         }
+
+        impl AuxField for &[$rtype] {
+            fn aux_get<B, R>(rec: &R, name: B) -> Result<Self, Slow5Error>
+            where
+                B: Into<Vec<u8>>,
+                R: RecordExt,
+            {
+                Self::aux_get_cstr(rec, &to_cstring(name)?)
+            }
+
+            fn aux_get_opt<B, R>(rec: &R, name: B) -> Result<Option<Self>, Slow5Error>
+            where
+                B: Into<Vec<u8>>,
+                R: RecordExt,
+            {
+                let name = to_cstring(name)?;
+                unset_to_none(paste::paste!( [<get_ $ctype:lower _array>] )(rec, &name)).map(Option::flatten)
+            }
+
+            fn aux_get_cstr<R>(rec: &R, name: &CStr) -> Result<Self, Slow5Error>
+            where
+                R: RecordExt,
+            {
+                paste::paste!( [<get_ $ctype:lower _array>] )(rec, name).map(Option::unwrap_or_default)
+            }
+        }
+
+        impl AuxField for Vec<$rtype> {
+            fn aux_get<B, R>(rec: &R, name: B) -> Result<Self, Slow5Error>
+            where
+                B: Into<Vec<u8>>,
+                R: RecordExt,
+            {
+                <&[$rtype]>::aux_get(rec, name).map(|data| data.to_vec())
+            }
+
+            fn aux_get_opt<B, R>(rec: &R, name: B) -> Result<Option<Self>, Slow5Error>
+            where
+                B: Into<Vec<u8>>,
+                R: RecordExt,
+            {
+                <&[$rtype]>::aux_get_opt(rec, name).map(|data| data.map(|data| data.to_vec()))
+            }
+
+            fn aux_get_cstr<R>(rec: &R, name: &CStr) -> Result<Self, Slow5Error>
+            where
+                R: RecordExt,
+            {
+                <&[$rtype]>::aux_get_cstr(rec, name).map(|data| data.to_vec())
+            }
+        }
     };
 }
 
@@ -211,16 +308,48 @@ impl AuxField for char {
     where
         B: Into<Vec<u8>>,
         R: RecordExt,
+    {
+        Self::aux_get_cstr(rec, &to_cstring(name)?)
+    }
+
+    fn aux_get_cstr<R>(rec: &R, name: &CStr) -> Result<Self, Slow5Error>
+    where
+        R: RecordExt,
     {
         let mut ret = 0;
-        let name = to_cstring(name)?;
         let data = unsafe { slow5_aux_get_char(rec.ptr().ptr, name.as_ptr(), &mut ret) };
         if ret != 0 {
-            Err(Slow5Error::AuxLoadFailure)
+            Err(parse_aux_field_get_error(ret))
         } else {
             Ok(data as u8 as char)
         }
     }
+
+    fn aux_get_opt<B, R>(rec: &R, name: B) -> Result<Option<Self>, Slow5Error>
+    where
+        B: Into<Vec<u8>>,
+        R: RecordExt,
+    {
+        let data = unset_to_none(Self::aux_get(rec, name))?;
+        Ok(data.filter(|&c| c != '\0'))
+    }
+}
+
+fn get_string<'a, R>(rec: &R, name: &CStr) -> Result<Option<&'a str>, Slow5Error>
+where
+    R: RecordExt,
+{
+    let mut err = 0;
+    let mut len = 0;
+    let data = unsafe { slow5_aux_get_string(rec.ptr().ptr, name.as_ptr(), &mut len, &mut err) };
+    if err != 0 {
+        Err(parse_aux_field_get_error(err))
+    } else if data.is_null() {
+        Ok(None)
+    } else {
+        let data = unsafe { CStr::from_ptr(data) };
+        Ok(Some(data.to_str()?))
+    }
 }
 
 impl AuxField for &str {
@@ -230,14 +359,50 @@ impl AuxField for &str {
         R: RecordExt,
         Self: std::marker::Sized,
     {
-        let mut err = 0;
-        let mut len = 0;
-        let name = to_cstring(name)?;
-        let data =
-            unsafe { slow5_aux_get_string(rec.ptr().ptr, name.as_ptr(), &mut len, &mut err) };
-        let data = unsafe { CStr::from_ptr(data) };
-        let data = data.to_str()?;
-        Ok(data)
+        Self::aux_get_cstr(rec, &to_cstring(name)?)
+    }
+
+    fn aux_get_opt<B, R>(rec: &R, name: B) -> Result<Option<Self>, Slow5Error>
+    where
+        B: Into<Vec<u8>>,
+        R: RecordExt,
+        Self: std::marker::Sized,
+    {
+        unset_to_none(get_string(rec, &to_cstring(name)?)).map(Option::flatten)
+    }
+
+    fn aux_get_cstr<R>(rec: &R, name: &CStr) -> Result<Self, Slow5Error>
+    where
+        R: RecordExt,
+    {
+        get_string(rec, name).map(Option::unwrap_or_default)
+    }
+}
+
+impl AuxField for String {
+    fn aux_get<B, R>(rec: &R, name: B) -> Result<Self, Slow5Error>
+    where
+        B: Into<Vec<u8>>,
+        R: RecordExt,
+        Self: std::marker::Sized,
+    {
+        <&str>::aux_get(rec, name).map(String::from)
+    }
+
+    fn aux_get_opt<B, R>(rec: &R, name: B) -> Result<Option<Self>, Slow5Error>
+    where
+        B: Into<Vec<u8>>,
+        R: RecordExt,
+        Self: std::marker::Sized,
+    {
+        <&str>::aux_get_opt(rec, name).map(|data| data.map(String::from))
+    }
+
+    fn aux_get_cstr<R>(rec: &R, name: &CStr) -> Result<Self, Slow5Error>
+    where
+        R: RecordExt,
+    {
+        <&str>::aux_get_cstr(rec, name).map(String::from)
     }
 }
 
@@ -247,20 +412,36 @@ impl AuxField for EnumField {
         B: Into<Vec<u8>>,
         R: RecordExt,
         Self: std::marker::Sized,
+    {
+        Self::aux_get_cstr(rec, &to_cstring(name)?)
+    }
+
+    fn aux_get_cstr<R>(rec: &R, name: &CStr) -> Result<Self, Slow5Error>
+    where
+        R: RecordExt,
     {
         let mut err = 0;
-        let name = to_cstring(name)?;
         let ef = unsafe { slow5_aux_get_enum(rec.ptr().ptr, name.as_ptr(), &mut err) };
         if err < 0 {
-            Err(Slow5Error::Unknown)
+            Err(parse_aux_field_get_error(err))
         } else {
             Ok(EnumField(ef as usize))
         }
     }
+
+    fn aux_get_opt<B, R>(rec: &R, name: B) -> Result<Option<Self>, Slow5Error>
+    where
+        B: Into<Vec<u8>>,
+        R: RecordExt,
+        Self: std::marker::Sized,
+    {
+        let data = unset_to_none(Self::aux_get(rec, name))?;
+        Ok(data.filter(|EnumField(idx)| *idx != u8::MAX as usize))
+    }
 }
 
 /// Convert return code from slow5_aux_set into Slow5Error
-fn parse_aux_field_set_error(ret: i32) -> Slow5Error {
+pub(crate) fn parse_aux_field_set_error(ret: i32) -> Slow5Error {
     match ret {
         -1 => Slow5Error::AuxTypeMismatch,
         -2 => Slow5Error::MissingAttribute,
@@ -271,8 +452,9 @@ fn parse_aux_field_set_error(ret: i32) -> Slow5Error {
 }
 
 /// Trait for values that we are allowed to set the values for in Records.
-/// Currently only primitive types, strings, and enums are allowed to be used to
-/// set auxiliary fields.
+/// Currently only primitive types, strings, enums, and arrays of primitive
+/// types (as slices, `Vec`, or arrays) are allowed to be used to set auxiliary
+/// fields.
 pub trait AuxFieldSetExt {
     /// Sets the value of a specific auxiliary field for the given record.
     fn aux_set<B>(
@@ -285,17 +467,9 @@ pub trait AuxFieldSetExt {
         Self: Sized,
         B: Into<Vec<u8>>,
     {
-        let name = to_cstring(field)?;
+        let name = writer.aux_name(field.into())?;
         let value_ptr = self as *const Self as *const c_void;
-        let ret = unsafe {
-            slow5_aux_set(
-                rec.slow5_rec,
-                name.as_ptr(),
-                value_ptr,
-                writer.header().header,
-            )
-        };
-        writer.auxiliary_fields.push(name);
+        let ret = unsafe { slow5_aux_set(rec.slow5_rec, name, value_ptr, writer.header().header) };
         if ret < 0 {
             Err(parse_aux_field_set_error(ret))
         } else {
@@ -326,17 +500,16 @@ impl AuxFieldSetExt for &str {
     where
         B: Into<Vec<u8>>,
     {
-        let name = to_cstring(field)?;
+        let name = writer.aux_name(field.into())?;
         let value_ptr = to_cstring(*self)?;
         let ret = unsafe {
             slow5_aux_set_string(
                 rec.slow5_rec,
-                name.as_ptr(),
+                name,
                 value_ptr.as_ptr(),
                 writer.header().header,
             )
         };
-        writer.auxiliary_fields.push(name);
         if ret < 0 {
             Err(parse_aux_field_set_error(ret))
         } else {
@@ -378,6 +551,71 @@ impl AuxFieldSetExt for EnumField {
     }
 }
 
+macro_rules! impl_auxfieldsetext_array {
+    ($($rtype:ty),*) => {
+        $(
+            impl AuxFieldSetExt for &[$rtype] {
+                fn aux_set<B>(
+                    &self,
+                    rec: &mut Record,
+                    field: B,
+                    writer: &mut FileWriter,
+                ) -> Result<(), Slow5Error>
+                where
+                    B: Into<Vec<u8>>,
+                {
+                    let name = writer.aux_name(field.into())?;
+                    let value_ptr = self.as_ptr() as *const c_void;
+                    let ret = unsafe {
+                        slow5_aux_array_set(
+                            rec.slow5_rec,
+                            name,
+                            value_ptr,
+                            self.len(),
+                            writer.header().header,
+                        )
+                    };
+                    if ret < 0 {
+                        Err(parse_aux_field_set_error(ret))
+                    } else {
+                        Ok(())
+                    }
+                }
+            }
+
+            impl AuxFieldSetExt for Vec<$rtype> {
+                fn aux_set<B>(
+                    &self,
+                    rec: &mut Record,
+                    field: B,
+                    writer: &mut FileWriter,
+                ) -> Result<(), Slow5Error>
+                where
+                    B: Into<Vec<u8>>,
+                {
+                    self.as_slice().aux_set(rec, field, writer)
+                }
+            }
+
+            impl<const N: usize> AuxFieldSetExt for [$rtype; N] {
+                fn aux_set<B>(
+                    &self,
+                    rec: &mut Record,
+                    field: B,
+                    writer: &mut FileWriter,
+                ) -> Result<(), Slow5Error>
+                where
+                    B: Into<Vec<u8>>,
+                {
+                    self.as_slice().aux_set(rec, field, writer)
+                }
+            }
+        )*
+    };
+}
+
+impl_auxfieldsetext_array!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
 // Seal the traits from downstream implementations
 mod private {
     #[allow(dead_code)]
@@ -446,4 +684,115 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_aux_set_array() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let file_path = tmp_dir.child("test.blow5");
+        let mut writer = WriteOptions::default()
+            .aux("u16_array", FieldType::Uint16Array)
+            .aux("u64_array", FieldType::Uint64Array)
+            .aux("f32_array", FieldType::FloatArray)
+            .aux("i8_array", FieldType::Int8Array)
+            .create(&file_path)?;
+        let mut rec = RecordBuilder::default()
+            .digitisation(0.123)
+            .offset(0.456)
+            .range(0.999)
+            .read_group(0)
+            .read_id("new")
+            .sampling_rate(0.777)
+            .raw_signal(&[1, 2, 3])
+            .build()?;
+        let u16s: &[u16] = &[1, 2, 3];
+        rec.set_aux_field(&mut writer, "u16_array", u16s)?;
+        rec.set_aux_field(&mut writer, "u64_array", vec![u64::MAX, 0])?;
+        rec.set_aux_field(&mut writer, "f32_array", [0.5f32, 1.5])?;
+        rec.set_aux_field(&mut writer, "i8_array", Vec::<i8>::new())?;
+        writer.add_record(&rec)?;
+        writer.close()?;
+
+        let reader = FileReader::open(&file_path)?;
+        let rec = reader.get_record("new")?;
+        assert_eq!(rec.get_aux_field::<&[u16]>("u16_array")?, &[1, 2, 3]);
+        assert_eq!(rec.get_aux_field::<&[u64]>("u64_array")?, &[u64::MAX, 0]);
+        assert_eq!(rec.get_aux_field::<&[f32]>("f32_array")?, &[0.5, 1.5]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_aux_get_owned() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let file_path = tmp_dir.child("test.blow5");
+        let mut writer = WriteOptions::default()
+            .aux("array", FieldType::Int16Array)
+            .aux("string", FieldType::Str)
+            .create(&file_path)?;
+        let mut rec = RecordBuilder::default()
+            .digitisation(0.123)
+            .offset(0.456)
+            .range(0.999)
+            .read_group(0)
+            .read_id("new")
+            .sampling_rate(0.777)
+            .raw_signal(&[1, 2, 3])
+            .build()?;
+        rec.set_aux_field(&mut writer, "array", vec![-1i16, 0, 1])?;
+        rec.set_aux_field(&mut writer, "string", "owned")?;
+        writer.add_record(&rec)?;
+        writer.close()?;
+
+        let reader = FileReader::open(&file_path)?;
+        let (array, string) = {
+            let rec = reader.get_record("new")?;
+            assert!(rec.get_aux_field::<Vec<u8>>("array").is_err());
+            (
+                rec.get_aux_field::<Vec<i16>>("array")?,
+                rec.get_aux_field::<String>("string")?,
+            )
+        };
+        assert_eq!(array, vec![-1, 0, 1]);
+        assert_eq!(string, "owned");
+        Ok(())
+    }
+
+    #[test]
+    fn test_aux_get_opt() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let file_path = tmp_dir.child("test.blow5");
+        let mut writer = WriteOptions::default()
+            .aux("set", FieldType::Uint32)
+            .aux("unset", FieldType::Uint32)
+            .aux("string", FieldType::Str)
+            .create(&file_path)?;
+        let mut rec = RecordBuilder::default()
+            .digitisation(0.123)
+            .offset(0.456)
+            .range(0.999)
+            .read_group(0)
+            .read_id("new")
+            .sampling_rate(0.777)
+            .raw_signal(&[1, 2, 3])
+            .build()?;
+        assert_eq!(rec.get_aux_field_opt::<u32>("set")?, None);
+        assert!(matches!(
+            rec.get_aux_field::<u32>("set"),
+            Err(Slow5Error::AuxFieldUnset)
+        ));
+        rec.set_aux_field(&mut writer, "set", 10u32)?;
+        writer.add_record(&rec)?;
+        writer.close()?;
+
+        let reader = FileReader::open(&file_path)?;
+        let rec = reader.get_record("new")?;
+        assert_eq!(rec.get_aux_field_opt::<u32>("set")?, Some(10));
+        assert_eq!(rec.get_aux_field_opt::<u32>("unset")?, None);
+        assert_eq!(rec.get_aux_field_opt::<&str>("string")?, None);
+        assert!(matches!(
+            rec.get_aux_field_opt::<u8>("set"),
+            Err(Slow5Error::AuxTypeMismatch)
+        ));
+        assert!(rec.get_aux_field_opt::<u32>("not real").is_err());
+        Ok(())
+    }
 }
@@ -0,0 +1,167 @@
+//! Map the bases of a basecalled read to the samples of its signal, using the
+//! move table (`mv`) and trimmed samples (`ts`) tags that basecallers like
+//! dorado add to BAM records.
+//!
+//! The `mv` tag is the stride of the basecaller followed by one move per
+//! stride, 1 if a new base starts there and 0 otherwise. The `ts` tag is the
+//! number of samples trimmed from the start of the signal before
+//! basecalling.
+//!
+//! # Example
+//! ```
+//! use slow5::bam::intervals_from_moves;
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! // Stride of 2, 3 bases, 10 samples trimmed
+//! let intervals = intervals_from_moves(2, &[1, 0, 1, 1, 0], 10, 100)?;
+//! assert_eq!(intervals, [10..14, 14..16, 16..20]);
+//! # Ok(())
+//! # }
+//! ```
+use std::ops::Range;
+
+use noodles_sam::alignment::{
+    record::data::field::Tag,
+    record_buf::data::field::{value::Array, Value},
+    RecordBuf,
+};
+
+use crate::{error::Slow5Error, record::RecordExt};
+
+fn bam_error(msg: impl Into<String>) -> Slow5Error {
+    Slow5Error::Bam(msg.into())
+}
+
+/// Interval of samples of each base from a move table, in the order the bases
+/// were called. Every base ends where the next one starts, and the last base
+/// ends after the last move. Intervals are clamped to n_samples, the length
+/// of the signal.
+///
+/// # Errors
+/// Returns [`Slow5Error::Bam`] if stride is 0, or the first move isn't the
+/// start of a base.
+pub fn intervals_from_moves(
+    stride: usize,
+    moves: &[u8],
+    trimmed: usize,
+    n_samples: usize,
+) -> Result<Vec<Range<usize>>, Slow5Error> {
+    if stride == 0 {
+        return Err(bam_error("move table stride is 0"));
+    }
+    if moves.first().is_some_and(|&m| m != 1) {
+        return Err(bam_error("move table doesn't start with a base"));
+    }
+    let sample = |idx: usize| (trimmed + idx * stride).min(n_samples);
+    let mut starts = moves
+        .iter()
+        .enumerate()
+        .filter(|&(_, &m)| m == 1)
+        .map(|(idx, _)| sample(idx))
+        .collect::<Vec<_>>();
+    starts.push(sample(moves.len()));
+    Ok(starts.windows(2).map(|w| w[0]..w[1]).collect())
+}
+
+// Values of an integer array tag
+fn array_values(array: &Array) -> Option<Vec<i64>> {
+    let values = match array {
+        Array::Int8(vs) => vs.iter().map(|&v| i64::from(v)).collect(),
+        Array::UInt8(vs) => vs.iter().map(|&v| i64::from(v)).collect(),
+        Array::Int16(vs) => vs.iter().map(|&v| i64::from(v)).collect(),
+        Array::UInt16(vs) => vs.iter().map(|&v| i64::from(v)).collect(),
+        Array::Int32(vs) => vs.iter().map(|&v| i64::from(v)).collect(),
+        Array::UInt32(vs) => vs.iter().map(|&v| i64::from(v)).collect(),
+        Array::Float(_) => return None,
+    };
+    Some(values)
+}
+
+fn int_value(value: &Value) -> Option<i64> {
+    match *value {
+        Value::Int8(v) => Some(i64::from(v)),
+        Value::UInt8(v) => Some(i64::from(v)),
+        Value::Int16(v) => Some(i64::from(v)),
+        Value::UInt16(v) => Some(i64::from(v)),
+        Value::Int32(v) => Some(i64::from(v)),
+        Value::UInt32(v) => Some(i64::from(v)),
+        _ => None,
+    }
+}
+
+/// Interval of samples in the signal of rec for each base of the sequence of
+/// bam_record, from its `mv` and `ts` tags. Intervals are in the order of the
+/// sequence in the BAM record, so are reversed for reads mapped to the
+/// reverse strand.
+///
+/// # Errors
+/// Returns [`Slow5Error::Bam`] if the read ids don't match, the `mv` tag is
+/// missing or isn't an integer array, or the number of bases in the move
+/// table isn't the length of the sequence.
+pub fn base_intervals<R: RecordExt>(
+    bam_record: &RecordBuf,
+    rec: &R,
+) -> Result<Vec<Range<usize>>, Slow5Error> {
+    let name: Option<&[u8]> = bam_record.name().map(|name| name.as_ref());
+    if name != Some(rec.read_id()) {
+        return Err(bam_error(format!(
+            "BAM record is for read {}",
+            String::from_utf8_lossy(name.unwrap_or_default())
+        )));
+    }
+    let data = bam_record.data();
+    let mv = match data.get(&Tag::from([b'm', b'v'])) {
+        Some(Value::Array(array)) => {
+            array_values(array).ok_or_else(|| bam_error("mv tag isn't an integer array"))?
+        }
+        Some(_) => return Err(bam_error("mv tag isn't an integer array")),
+        None => return Err(bam_error("no mv tag")),
+    };
+    let trimmed = match data.get(&Tag::from([b't', b's'])) {
+        Some(value) => int_value(value).ok_or_else(|| bam_error("ts tag isn't an integer"))?,
+        None => 0,
+    };
+    let (&stride, moves) = mv
+        .split_first()
+        .ok_or_else(|| bam_error("mv tag is empty"))?;
+    let moves = moves
+        .iter()
+        .map(|&m| u8::try_from(m))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| bam_error("move isn't 0 or 1"))?;
+    let stride = usize::try_from(stride).map_err(|_| bam_error("negative stride"))?;
+    let trimmed = usize::try_from(trimmed).map_err(|_| bam_error("negative ts tag"))?;
+    let n_samples = rec.len_signal() as usize;
+    let mut intervals = intervals_from_moves(stride, &moves, trimmed, n_samples)?;
+    let n_bases = bam_record.sequence().len();
+    if intervals.len() != n_bases {
+        return Err(bam_error(format!(
+            "move table has {} bases, sequence has {n_bases}",
+            intervals.len()
+        )));
+    }
+    if bam_record.flags().is_reverse_complemented() {
+        intervals.reverse();
+    }
+    Ok(intervals)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_intervals_from_moves() -> anyhow::Result<()> {
+        let intervals = intervals_from_moves(5, &[1, 1, 0, 0, 1, 0], 3, 100)?;
+        assert_eq!(intervals, [3..8, 8..23, 23..33]);
+
+        // Clamped to the length of the signal
+        let intervals = intervals_from_moves(5, &[1, 1, 0, 0, 1, 0], 3, 25)?;
+        assert_eq!(intervals, [3..8, 8..23, 23..25]);
+
+        assert!(intervals_from_moves(5, &[], 0, 100)?.is_empty());
+        assert!(intervals_from_moves(0, &[1], 0, 100).is_err());
+        assert!(intervals_from_moves(5, &[0, 1], 0, 100).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,210 @@
+//! Pack the signal of many records into one buffer, ie to upload a batch of
+//! reads to a GPU in one copy.
+//!
+//! # Example
+//! ```
+//! use slow5::{batch::SignalBatch, signal::Normalization, FileReader};
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let mut reader = FileReader::open("examples/example.slow5")?;
+//! for chunk in reader.records_chunked(2) {
+//!     let chunk = chunk?;
+//!     let norm = Some(Normalization::MedianMad);
+//!     let batch = SignalBatch::collect_picoamps(&chunk, Some(100), norm);
+//!     // One row of 100 measurements per read
+//!     assert_eq!(batch.signal().len(), chunk.len() * 100);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use crate::{
+    record::{raw_signal, RecordExt},
+    signal::{Normalization, Scale},
+};
+
+/// Signal of a batch of reads in one contiguous buffer, with the offset and
+/// length of each read in it.
+///
+/// Without padding, the reads are placed one after another. With padding to
+/// n, every read takes up n measurements, so the buffer can be viewed as a
+/// matrix with one row per read. Shorter reads are padded with zeros and
+/// longer reads are truncated, the length of a read is the number of
+/// measurements before the padding.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SignalBatch<T> {
+    signal: Vec<T>,
+    offsets: Vec<usize>,
+    lengths: Vec<usize>,
+}
+
+impl SignalBatch<i16> {
+    /// Pack the raw signal of the records, padding or truncating each read to
+    /// pad_to measurements if it is set.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{batch::SignalBatch, FileReader, RecordExt};
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut reader = FileReader::open("examples/example.slow5")?;
+    /// let records = reader.records().collect::<Result<Vec<_>, _>>()?;
+    /// let batch = SignalBatch::collect(&records, None);
+    /// assert_eq!(batch.len(), 5);
+    /// assert!(batch.get(0).unwrap().iter().copied().eq(records[0].raw_signal_iter()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn collect<'r, I, R>(records: I, pad_to: Option<usize>) -> Self
+    where
+        I: IntoIterator<Item = &'r R>,
+        R: RecordExt + 'r,
+    {
+        let mut batch = Self::default();
+        for rec in records {
+            let raw = raw_signal(rec);
+            batch.push_with(raw.len(), pad_to, |signal, len| {
+                signal.extend_from_slice(&raw[..len])
+            });
+        }
+        batch
+    }
+}
+
+impl SignalBatch<f32> {
+    /// Pack the signal of the records in picoamps, padding or truncating each
+    /// read to pad_to measurements if it is set. If normalization is set, each
+    /// read is normalized with the shift and scale of its whole signal, see
+    /// [`signal::normalize`].
+    ///
+    /// [`signal::normalize`]: crate::signal::normalize
+    pub fn collect_picoamps<'r, I, R>(
+        records: I,
+        pad_to: Option<usize>,
+        normalization: Option<Normalization>,
+    ) -> Self
+    where
+        I: IntoIterator<Item = &'r R>,
+        R: RecordExt + 'r,
+    {
+        let mut batch = Self::default();
+        // Reused for every read, since the scale needs the whole signal
+        let mut picoamps = Vec::new();
+        for rec in records {
+            picoamps.clear();
+            picoamps.extend(rec.picoamps_signal_iter());
+            let scale = normalization.map(|method| Scale::new(&picoamps, method));
+            batch.push_with(picoamps.len(), pad_to, |signal, len| {
+                let values = picoamps[..len].iter().map(|&x| match scale {
+                    Some(scale) => scale.apply(x) as f32,
+                    None => x as f32,
+                });
+                signal.extend(values)
+            });
+        }
+        batch
+    }
+}
+
+impl<T: Copy + Default> SignalBatch<T> {
+    // Add a read of len measurements, truncated to pad_to, with fill writing
+    // the measurements kept, then pad it to pad_to
+    fn push_with<F>(&mut self, len: usize, pad_to: Option<usize>, fill: F)
+    where
+        F: FnOnce(&mut Vec<T>, usize),
+    {
+        let offset = self.signal.len();
+        let len = pad_to.map_or(len, |n| len.min(n));
+        fill(&mut self.signal, len);
+        if let Some(n) = pad_to {
+            self.signal.resize(offset + n, T::default());
+        }
+        self.offsets.push(offset);
+        self.lengths.push(len);
+    }
+
+    /// Signal of every read, including the padding
+    pub fn signal(&self) -> &[T] {
+        &self.signal
+    }
+
+    /// Index in [`signal`] of the first measurement of each read
+    ///
+    /// [`signal`]: SignalBatch::signal
+    pub fn offsets(&self) -> &[usize] {
+        &self.offsets
+    }
+
+    /// Number of measurements of each read, without the padding
+    pub fn lengths(&self) -> &[usize] {
+        &self.lengths
+    }
+
+    /// Number of reads in the batch
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// Whether the batch has no reads
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// Signal of the ith read without the padding, None if i is out of bounds
+    pub fn get(&self, i: usize) -> Option<&[T]> {
+        let offset = *self.offsets.get(i)?;
+        Some(&self.signal[offset..offset + self.lengths[i]])
+    }
+
+    /// Take the signal, offsets and lengths of the batch, ie to hand them to
+    /// another library without copying
+    pub fn into_parts(self) -> (Vec<T>, Vec<usize>, Vec<usize>) {
+        (self.signal, self.offsets, self.lengths)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::FileReader;
+
+    #[test]
+    fn test_collect() -> anyhow::Result<()> {
+        let mut reader = FileReader::open("examples/example.slow5")?;
+        let records = reader.records().collect::<Result<Vec<_>, _>>()?;
+        let total: u64 = records.iter().map(|rec| rec.len_signal()).sum();
+
+        let batch = SignalBatch::collect(&records, None);
+        assert_eq!(batch.len(), records.len());
+        assert_eq!(batch.signal().len() as u64, total);
+        assert_eq!(batch.offsets()[0], 0);
+        for (i, rec) in records.iter().enumerate() {
+            assert_eq!(batch.lengths()[i] as u64, rec.len_signal());
+            let signal = batch.get(i).unwrap();
+            assert!(signal.iter().copied().eq(rec.raw_signal_iter()));
+        }
+        assert!(batch.get(records.len()).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_collect_padded() -> anyhow::Result<()> {
+        let mut reader = FileReader::open("examples/example.slow5")?;
+        let records = reader.records().collect::<Result<Vec<_>, _>>()?;
+        let pad_to = records[0].len_signal() as usize + 2;
+
+        let batch = SignalBatch::collect(&records[..1], Some(pad_to));
+        assert_eq!(batch.signal().len(), pad_to);
+        assert_eq!(batch.lengths(), [pad_to - 2]);
+        assert_eq!(batch.signal()[pad_to - 2..], [0, 0]);
+
+        let batch = SignalBatch::collect_picoamps(&records, Some(2), None);
+        assert_eq!(batch.offsets(), [0, 2, 4, 6, 8]);
+        assert_eq!(batch.lengths(), [2; 5]);
+        let first = records[0].picoamps_signal_iter().next().unwrap();
+        assert_eq!(batch.signal()[0], first as f32);
+
+        let batch = SignalBatch::collect_picoamps(&records, None, Some(Normalization::MeanStdev));
+        let mean = batch.get(0).unwrap().iter().sum::<f32>() / batch.lengths()[0] as f32;
+        assert!(mean.abs() < 1e-3);
+        Ok(())
+    }
+}
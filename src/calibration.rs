@@ -0,0 +1,11 @@
+//! Conversion between raw signal values and picoamps
+
+/// Convert raw signal into a picoamps measurement
+pub fn to_picoamps(raw_signal: f64, digitisation: f64, offset: f64, range: f64) -> f64 {
+    ((raw_signal) + offset) * (range / digitisation)
+}
+
+/// Convert picoamps signal into the raw signal
+pub fn to_raw_signal(picoamps: f64, digitisation: f64, offset: f64, range: f64) -> f64 {
+    (picoamps / (range / digitisation)) - offset
+}
@@ -0,0 +1,232 @@
+//! Concatenate SLOW5 files with the same header
+use std::path::Path;
+
+use crate::{
+    diff::compare_headers,
+    error::Slow5Error,
+    progress::{report_progress, ProgressSink},
+    reader::{FileFormat, FileReader},
+    writer::{check_file_ext, FileWriter, WriteOptions},
+};
+
+// Check other has the same header and compression as first
+fn check_header(first: &FileReader, other: &FileReader, path: &Path) -> Result<(), Slow5Error> {
+    let mismatch = |difference: String| Slow5Error::HeaderMismatch {
+        path: path.to_owned(),
+        difference,
+    };
+    let mut differences = Vec::new();
+    compare_headers(first, other, &mut differences)?;
+    if !differences.is_empty() {
+        let differences = differences
+            .iter()
+            .map(|d| d.to_string())
+            .collect::<Vec<_>>();
+        return Err(mismatch(differences.join(", ")));
+    }
+    let (a, b) = (first.record_compression(), other.record_compression());
+    if a != b {
+        return Err(mismatch(format!("record compression {a:?} != {b:?}")));
+    }
+    let (a, b) = (first.signal_compression(), other.signal_compression());
+    if a != b {
+        return Err(mismatch(format!("signal compression {a:?} != {b:?}")));
+    }
+    Ok(())
+}
+
+// Copy every record of reader into writer, without decoding them if the
+// format and compression are the same. Progress is reported on top of the
+// records and bytes of the inputs already copied.
+fn copy(
+    reader: &mut FileReader,
+    writer: &mut FileWriter,
+    progress: &mut dyn ProgressSink,
+    (records_before, bytes_before): (u64, u64),
+) -> Result<(usize, u64), Slow5Error> {
+    let raw = reader.has_index()
+        && reader.format() == writer.format()
+        && reader.record_compression() == writer.record_compression()
+        && reader.signal_compression() == writer.signal_compression();
+    let start_bytes = reader.stats().bytes();
+    let mut n = 0;
+    let mut bytes = 0;
+    if raw {
+        for read_id in reader.iter_read_ids()? {
+            writer.add_raw_record(&reader.raw_record_bytes(read_id)?)?;
+            n += 1;
+            bytes = reader.stats().bytes() - start_bytes;
+            report_progress(progress, records_before + n as u64, bytes_before + bytes)?;
+        }
+    } else {
+        let mut records = reader.records();
+        while let Some(rec) = records.next() {
+            writer.add_record(&rec?)?;
+            n += 1;
+            bytes = records.stats().bytes() - start_bytes;
+            report_progress(progress, records_before + n as u64, bytes_before + bytes)?;
+        }
+    }
+    Ok((n, bytes))
+}
+
+/// Concatenate the records of inputs into output, in order. Every input must
+/// have the same header, ie the same attributes and auxiliary fields, and the
+/// same compression. The headers are checked before output is created.
+///
+/// The output has the header of the inputs, and their compression if it is
+/// BLOW5. Its format is from its file extension. Records are copied without
+/// being decoded when the output has the same format as an input.
+///
+/// Returns the number of records written.
+///
+/// # Example
+/// ```
+/// # use assert_fs::TempDir;
+/// # use assert_fs::fixture::PathChild;
+/// use slow5::{cat, split, FileReader, SplitMode};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// # let tmp_dir = TempDir::new()?;
+/// # let out_dir = tmp_dir.path();
+/// # let output = tmp_dir.child("merged.slow5");
+/// let mut reader = FileReader::open("examples/example.slow5")?;
+/// let parts = split(&mut reader, out_dir, SplitMode::ByCount(2))?;
+/// assert_eq!(cat(&parts, output)?, 5);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// Returns [`Slow5Error::HeaderMismatch`] with what differs if an input has a
+/// different header than the first input, [`Slow5Error::Argument`] if there
+/// are no inputs, or an Err if a record fails to be read or written.
+pub fn cat<I, P, Q>(inputs: I, output: Q) -> Result<usize, Slow5Error>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    cat_with_progress(inputs, output, &mut ())
+}
+
+/// Same as [`cat`], with the number of records copied and bytes read from
+/// the inputs reported to progress after each record.
+///
+/// # Errors
+/// Same as [`cat`], or [`Slow5Error::Cancelled`] if progress cancels the
+/// copy, in which case output is left with the records copied so far.
+pub fn cat_with_progress<I, P, Q>(
+    inputs: I,
+    output: Q,
+    progress: &mut dyn ProgressSink,
+) -> Result<usize, Slow5Error>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let mut readers = inputs
+        .into_iter()
+        .map(|path| Ok((FileReader::open(path.as_ref())?, path.as_ref().to_owned())))
+        .collect::<Result<Vec<_>, Slow5Error>>()?;
+    let (first, _) = readers.first().ok_or(Slow5Error::Argument)?;
+    for (reader, path) in readers.iter().skip(1) {
+        check_header(first, reader, path)?;
+    }
+    let mut opts = WriteOptions::from_reader(first)?;
+    // SLOW5 can't be compressed, so only a BLOW5 output keeps the compression
+    if check_file_ext(&output)? == FileFormat::Blow5 {
+        opts.record_compression(first.record_compression())
+            .signal_compression(first.signal_compression());
+    }
+    let mut writer = opts.create(output)?;
+    let mut n = 0;
+    let mut bytes = 0;
+    for (reader, _) in readers.iter_mut() {
+        let (copied, read) = copy(reader, &mut writer, progress, (n as u64, bytes))?;
+        n += copied;
+        bytes += read;
+    }
+    writer.close()?;
+    Ok(n)
+}
+
+#[cfg(test)]
+mod test {
+    use assert_fs::{fixture::PathChild, TempDir};
+
+    use super::*;
+    use crate::{
+        diff::{compare, DiffOptions},
+        recompress, split, RecordCompression, SignalCompression, SplitMode,
+    };
+
+    #[test]
+    fn test_cat() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let output = tmp_dir.child("merged.blow5");
+        let mut reader = FileReader::open("examples/example3.blow5")?;
+        let parts = split(&mut reader, tmp_dir.path(), SplitMode::ByCount(3))?;
+        let n = cat(&parts, &output)?;
+
+        let mut merged = FileReader::open(&output)?;
+        let reader = FileReader::open("examples/example3.blow5")?;
+        assert_eq!(n, reader.iter_read_ids()?.count());
+        let report = compare(&mut merged, &reader, &DiffOptions::default())?;
+        assert!(report.is_identical(), "{:?}", report.differences());
+        Ok(())
+    }
+
+    #[test]
+    fn test_cat_compressed() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let input = tmp_dir.child("compressed.blow5");
+        let output = tmp_dir.child("merged.blow5");
+        let parts_dir = tmp_dir.child("parts");
+        std::fs::create_dir(&parts_dir)?;
+        let (record, signal) = (RecordCompression::Zlib, SignalCompression::StreamVByte);
+        recompress("examples/example.slow5", &input, record, signal, 1)?;
+        let mut reader = FileReader::open(&input)?;
+        let parts = split(&mut reader, parts_dir.path(), SplitMode::ByCount(2))?;
+        assert_eq!(cat(&parts, &output)?, 5);
+
+        let merged = FileReader::open(&output)?;
+        assert_eq!(merged.record_compression(), record);
+        assert_eq!(merged.signal_compression(), signal);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cat_with_progress() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let output = tmp_dir.child("merged.slow5");
+        let mut reader = FileReader::open("examples/example.slow5")?;
+        let parts = split(&mut reader, tmp_dir.path(), SplitMode::ByCount(2))?;
+        let mut updates = Vec::new();
+        let mut progress = |records: u64, bytes: u64| updates.push((records, bytes));
+        let n = cat_with_progress(&parts, &output, &mut progress)?;
+        assert_eq!(n, 5);
+        let records = updates.iter().map(|(records, _)| *records);
+        assert!(records.eq(1..=5));
+        assert!(updates.windows(2).all(|w| w[0].1 < w[1].1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_cat_header_mismatch() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let output = tmp_dir.child("merged.slow5");
+        let res = cat(
+            ["examples/example.slow5", "examples/example2.slow5"],
+            &output,
+        );
+        assert!(matches!(res, Err(Slow5Error::HeaderMismatch { .. })));
+        assert!(!output.path().exists());
+        assert!(matches!(
+            cat(Vec::<&str>::new(), &output),
+            Err(Slow5Error::Argument)
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,279 @@
+//! Find records by read id across many SLOW5 files, ie every file of a
+//! sequencing project.
+//!
+//! A [`Catalog`] maps each read id to the file it is in and its position in
+//! that file. It can be saved and loaded again, so the files only have to be
+//! indexed once. Files are opened the first time a record is read from them,
+//! without their own index.
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    error::Slow5Error,
+    reader::FileReader,
+    record::{Record, RecordExt, RecordIter},
+};
+
+// Start of a saved catalog, with the version of the format
+const MAGIC: &[u8; 8] = b"S5CATLG\x01";
+
+// Position of a record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Location {
+    file: u32,
+    offset: u64,
+}
+
+/// Map of read ids to the file and position of their record, created with
+/// [`build`] or [`Catalog::load`].
+///
+/// # Example
+/// ```
+/// # use slow5::RecordExt;
+/// # fn main() -> anyhow::Result<()> {
+/// let catalog = slow5::catalog::build(["examples/example.slow5", "examples/example3.blow5"])?;
+/// let rec = catalog.get("r3")?;
+/// assert_eq!(rec.read_id(), b"r3");
+/// # Ok(())
+/// # }
+/// ```
+pub struct Catalog {
+    files: Vec<PathBuf>,
+    locations: HashMap<Vec<u8>, Location>,
+    // Readers opened so far, one per file
+    readers: RefCell<Vec<Option<FileReader>>>,
+}
+
+impl std::fmt::Debug for Catalog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Catalog")
+            .field("files", &self.files)
+            .field("reads", &self.locations.len())
+            .finish()
+    }
+}
+
+impl Catalog {
+    fn new(files: Vec<PathBuf>, locations: HashMap<Vec<u8>, Location>) -> Self {
+        let readers = RefCell::new(files.iter().map(|_| None).collect());
+        Self {
+            files,
+            locations,
+            readers,
+        }
+    }
+
+    /// Files in the catalog
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// Number of read ids in the catalog
+    pub fn len(&self) -> usize {
+        self.locations.len()
+    }
+
+    /// Returns true if the catalog has no read ids
+    pub fn is_empty(&self) -> bool {
+        self.locations.is_empty()
+    }
+
+    /// Returns true if the read id is in the catalog
+    pub fn contains(&self, read_id: &[u8]) -> bool {
+        self.locations.contains_key(read_id)
+    }
+
+    /// File with the read id and the offset of its record in the file
+    pub fn locate(&self, read_id: &[u8]) -> Option<(&Path, u64)> {
+        let loc = self.locations.get(read_id)?;
+        Some((&self.files[loc.file as usize], loc.offset))
+    }
+
+    /// Get a record by read id, opening its file if it hasn't been opened
+    /// yet
+    ///
+    /// # Errors
+    /// Returns an Err if the read id isn't in the catalog, or the file fails
+    /// to be opened or no longer has the record at the same position
+    pub fn get<B: Into<Vec<u8>>>(&self, read_id: B) -> Result<Record, Slow5Error> {
+        let read_id = read_id.into();
+        let loc = self.locations.get(&read_id).ok_or_else(|| {
+            Slow5Error::ReadIDNotInIndex(String::from_utf8_lossy(&read_id).into_owned())
+        })?;
+        let mut readers = self.readers.borrow_mut();
+        let reader = &mut readers[loc.file as usize];
+        let reader = match reader {
+            Some(reader) => reader,
+            None => reader.insert(FileReader::open_without_index(
+                &self.files[loc.file as usize],
+            )?),
+        };
+        let fp = unsafe { (*reader.slow5_file).fp.cast() };
+        if unsafe { libc::fseeko(fp, loc.offset as libc::off_t, libc::SEEK_SET) } != 0 {
+            return Err(Slow5Error::IOError);
+        }
+        let rec = RecordIter::new(reader)
+            .next()
+            .ok_or(Slow5Error::GetRecordFailed)??;
+        if rec.read_id() != read_id {
+            return Err(Slow5Error::GetRecordFailed);
+        }
+        Ok(rec)
+    }
+
+    /// Save the catalog to a file
+    ///
+    /// # Errors
+    /// Returns an Err if the file fails to be written, or a path isn't valid
+    /// UTF-8
+    pub fn save<P: AsRef<Path>>(&self, file_path: P) -> Result<(), Slow5Error> {
+        let mut out = BufWriter::new(File::create(file_path)?);
+        out.write_all(MAGIC)?;
+        out.write_all(&(self.files.len() as u32).to_le_bytes())?;
+        for path in self.files.iter() {
+            let path = path
+                .to_str()
+                .ok_or_else(|| Slow5Error::InvalidFilePath(path.display().to_string()))?;
+            write_bytes(&mut out, path.as_bytes())?;
+        }
+        out.write_all(&(self.locations.len() as u64).to_le_bytes())?;
+        for (read_id, loc) in self.locations.iter() {
+            write_bytes(&mut out, read_id)?;
+            out.write_all(&loc.file.to_le_bytes())?;
+            out.write_all(&loc.offset.to_le_bytes())?;
+        }
+        out.flush()?;
+        Ok(())
+    }
+
+    /// Load a catalog saved with [`save`]
+    ///
+    /// # Errors
+    /// Returns an Err if the file fails to be read or isn't a catalog
+    ///
+    /// [`save`]: Catalog::save
+    pub fn load<P: AsRef<Path>>(file_path: P) -> Result<Self, Slow5Error> {
+        let mut input = BufReader::new(File::open(file_path)?);
+        let mut magic = [0; 8];
+        input.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not a catalog").into());
+        }
+        let n_files = u32::from_le_bytes(read_array(&mut input)?);
+        let files = (0..n_files)
+            .map(|_| {
+                let path = String::from_utf8(read_bytes(&mut input)?)
+                    .map_err(|_| invalid_data("path isn't valid UTF-8"))?;
+                Ok(PathBuf::from(path))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        let n_reads = u64::from_le_bytes(read_array(&mut input)?);
+        let mut locations = HashMap::new();
+        for _ in 0..n_reads {
+            let read_id = read_bytes(&mut input)?;
+            let file = u32::from_le_bytes(read_array(&mut input)?);
+            let offset = u64::from_le_bytes(read_array(&mut input)?);
+            if file >= n_files {
+                return Err(invalid_data("file out of range").into());
+            }
+            locations.insert(read_id, Location { file, offset });
+        }
+        Ok(Self::new(files, locations))
+    }
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn write_bytes<W: Write>(out: &mut W, bytes: &[u8]) -> io::Result<()> {
+    out.write_all(&(bytes.len() as u32).to_le_bytes())?;
+    out.write_all(bytes)
+}
+
+fn read_array<R: Read, const N: usize>(input: &mut R) -> io::Result<[u8; N]> {
+    let mut buf = [0; N];
+    input.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn read_bytes<R: Read>(input: &mut R) -> io::Result<Vec<u8>> {
+    let len = u32::from_le_bytes(read_array(input)?);
+    let mut buf = vec![0; len as usize];
+    input.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+/// Build a catalog of the records in the files. Each file is opened with its
+/// index, which is created if it doesn't exist. Paths are stored as absolute
+/// paths, so a saved catalog can be used from any directory.
+///
+/// # Errors
+/// Returns an Err if a file fails to be opened or indexed, or
+/// [`Slow5Error::DuplicateReadId`] if a read id is in more than one file
+pub fn build<I, P>(paths: I) -> Result<Catalog, Slow5Error>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    let mut files = Vec::new();
+    let mut locations = HashMap::new();
+    for path in paths {
+        let path = std::fs::canonicalize(path.as_ref())
+            .map_err(|_| Slow5Error::IncorrectPath(path.as_ref().to_owned()))?;
+        let reader = FileReader::open(&path)?;
+        let file = files.len() as u32;
+        for (read_id, offset, _) in reader.index_iter()? {
+            let loc = Location { file, offset };
+            if locations.insert(read_id.to_vec(), loc).is_some() {
+                let read_id = String::from_utf8_lossy(read_id).into_owned();
+                return Err(Slow5Error::DuplicateReadId(read_id));
+            }
+        }
+        files.push(path);
+    }
+    Ok(Catalog::new(files, locations))
+}
+
+#[cfg(test)]
+mod test {
+    use assert_fs::{fixture::PathChild, TempDir};
+
+    use super::*;
+
+    #[test]
+    fn test_catalog() -> anyhow::Result<()> {
+        let catalog = build(["examples/example.slow5", "examples/example3.blow5"])?;
+        let reader = FileReader::open("examples/example3.blow5")?;
+        let read_ids = reader
+            .iter_read_ids()?
+            .map(|read_id| read_id.to_vec())
+            .collect::<Vec<_>>();
+        assert_eq!(catalog.len(), 5 + read_ids.len());
+        assert_eq!(catalog.get("r3")?.read_id(), b"r3");
+        for read_id in read_ids.iter().rev() {
+            assert_eq!(catalog.get(read_id.as_slice())?.read_id(), read_id);
+        }
+        assert!(catalog.get("missing").is_err());
+        assert_eq!(catalog.locate(b"r1").unwrap().0, catalog.files()[0]);
+
+        let tmp_dir = TempDir::new()?;
+        let path = tmp_dir.child("project.catalog");
+        catalog.save(&path)?;
+        let loaded = Catalog::load(&path)?;
+        assert_eq!(loaded.files(), catalog.files());
+        assert_eq!(loaded.locate(b"r5"), catalog.locate(b"r5"));
+        assert_eq!(loaded.get("r5")?.read_id(), b"r5");
+
+        assert!(matches!(
+            build(["examples/example.slow5", "examples/example.slow5"]),
+            Err(Slow5Error::DuplicateReadId(_))
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,214 @@
+//! Convert between SLOW5 and BLOW5 files
+use std::path::Path;
+
+use crate::{
+    compression::{RecordCompression, SignalCompression},
+    error::Slow5Error,
+    progress::{report_progress, ProgressSink},
+    reader::FileReader,
+    writer::WriteOptions,
+};
+
+/// Convert input into output, ie SLOW5 to BLOW5 or BLOW5 to SLOW5, based on
+/// the file extension of output. Records are read and written one at a time,
+/// so the file is never loaded into memory.
+///
+/// The header of output is copied from input, with the compression and any
+/// attributes or auxiliary fields in opts added to it. Returns the number of
+/// records written.
+///
+/// # Example
+/// ```
+/// # use assert_fs::TempDir;
+/// # use assert_fs::fixture::PathChild;
+/// use slow5::{convert, SignalCompression, WriteOptions};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// # let tmp_dir = TempDir::new()?;
+/// # let output = tmp_dir.child("example.blow5");
+/// let mut opts = WriteOptions::default();
+/// opts.signal_compression(SignalCompression::StreamVByte);
+/// let n = convert("examples/example.slow5", output, &opts)?;
+/// assert_eq!(n, 5);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// Returns an Err if input can't be opened, if compression is set for a SLOW5
+/// output, or if any record fails to be read or written.
+pub fn convert<P, Q>(input: P, output: Q, opts: &WriteOptions) -> Result<usize, Slow5Error>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let mut reader = FileReader::open_without_index(input)?;
+    let mut writer = WriteOptions::from_reader(&reader)?
+        .extend_from(opts)
+        .create(output)?;
+    let mut n = 0;
+    for rec in reader.records() {
+        writer.add_record(&rec?)?;
+        n += 1;
+    }
+    writer.close()?;
+    Ok(n)
+}
+
+/// Write the records of input to a BLOW5 output with different compression,
+/// ie to move an archive from zlib and StreamVByte to zstd and ex-zd. The
+/// header, including the auxiliary fields, is copied from input. Records are
+/// compressed on n_threads threads, see [`FileWriter::with_threads`].
+///
+/// Returns the number of records written.
+///
+/// # Example
+/// ```
+/// # use assert_fs::TempDir;
+/// # use assert_fs::fixture::PathChild;
+/// use slow5::{recompress, RecordCompression, SignalCompression};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// # let tmp_dir = TempDir::new()?;
+/// # let output = tmp_dir.child("example3.blow5");
+/// let n = recompress(
+///     "examples/example3.blow5",
+///     output,
+///     RecordCompression::ZStd,
+///     SignalCompression::ExZd,
+///     4,
+/// )?;
+/// assert!(n > 0);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// Returns an Err if input can't be opened, if compression is set for a SLOW5
+/// output, or if any record fails to be read or written.
+///
+/// [`FileWriter::with_threads`]: crate::FileWriter::with_threads
+pub fn recompress<P, Q>(
+    input: P,
+    output: Q,
+    record_compression: RecordCompression,
+    signal_compression: SignalCompression,
+    n_threads: usize,
+) -> Result<usize, Slow5Error>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    recompress_with_progress(
+        input,
+        output,
+        record_compression,
+        signal_compression,
+        n_threads,
+        &mut (),
+    )
+}
+
+/// Same as [`recompress`], with the number of records written and bytes read
+/// from input reported to progress after each record.
+///
+/// # Errors
+/// Same as [`recompress`], or [`Slow5Error::Cancelled`] if progress cancels
+/// it, in which case output is left with the records written so far.
+pub fn recompress_with_progress<P, Q>(
+    input: P,
+    output: Q,
+    record_compression: RecordCompression,
+    signal_compression: SignalCompression,
+    n_threads: usize,
+    progress: &mut dyn ProgressSink,
+) -> Result<usize, Slow5Error>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let mut reader = FileReader::open_without_index(input)?;
+    let mut writer = WriteOptions::from_reader(&reader)?
+        .record_compression(record_compression)
+        .signal_compression(signal_compression)
+        .create(output)?
+        .with_threads(n_threads)?;
+    let mut n = 0;
+    let mut records = reader.records();
+    while let Some(rec) = records.next() {
+        writer.add_record(&rec?)?;
+        n += 1;
+        report_progress(progress, n as u64, records.stats().bytes())?;
+    }
+    writer.close()?;
+    Ok(n)
+}
+
+#[cfg(test)]
+mod test {
+    use assert_fs::{fixture::PathChild, TempDir};
+
+    use super::*;
+    use crate::{HeaderExt, RecordExt};
+
+    #[test]
+    fn test_convert_roundtrip() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let blow5 = tmp_dir.child("example2.blow5");
+        let slow5 = tmp_dir.child("example2.slow5");
+        let mut opts = WriteOptions::default();
+        opts.record_compression(RecordCompression::Zlib);
+        assert_eq!(convert("examples/example2.slow5", &blow5, &opts)?, 5);
+        assert!(convert(&blow5, &slow5, &opts).is_err());
+        assert_eq!(convert(&blow5, &slow5, &WriteOptions::default())?, 5);
+
+        let orig = FileReader::open("examples/example2.slow5")?;
+        let reader = FileReader::open(&blow5)?;
+        assert_eq!(reader.record_compression(), RecordCompression::Zlib);
+        let mut roundtrip = FileReader::open(&slow5)?;
+        assert_eq!(roundtrip.aux_fields_iter().count(), 5);
+        for rec in roundtrip.records() {
+            let rec = rec?;
+            let orig_rec = orig.get_record(rec.read_id())?;
+            assert_eq!(
+                rec.raw_signal_iter().collect::<Vec<_>>(),
+                orig_rec.raw_signal_iter().collect::<Vec<_>>()
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_recompress() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let output = tmp_dir.child("example3.blow5");
+        let n = recompress(
+            "examples/example3.blow5",
+            &output,
+            RecordCompression::None,
+            SignalCompression::None,
+            2,
+        )?;
+
+        let orig = FileReader::open("examples/example3.blow5")?;
+        let mut reader = FileReader::open(&output)?;
+        assert_eq!(reader.record_compression(), RecordCompression::None);
+        assert_eq!(reader.signal_compression(), SignalCompression::None);
+        assert_eq!(
+            reader.aux_fields_iter().count(),
+            orig.aux_fields_iter().count()
+        );
+        let mut count = 0;
+        for rec in reader.records() {
+            let rec = rec?;
+            let orig_rec = orig.get_record(rec.read_id())?;
+            assert_eq!(
+                rec.raw_signal_iter().collect::<Vec<_>>(),
+                orig_rec.raw_signal_iter().collect::<Vec<_>>()
+            );
+            count += 1;
+        }
+        assert_eq!(count, n);
+        Ok(())
+    }
+}
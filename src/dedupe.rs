@@ -0,0 +1,134 @@
+//! Find records with the same signal, ie written twice by a buggy merge
+use std::collections::{hash_map::Entry, HashMap};
+
+use crate::{error::Slow5Error, reader::FileReader, record::RecordExt, writer::FileWriter};
+
+/// Summary of the duplicate records found by [`dedupe`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DedupeReport {
+    records: usize,
+    written: usize,
+    duplicates: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl DedupeReport {
+    /// Number of records read
+    pub fn records(&self) -> usize {
+        self.records
+    }
+
+    /// Number of records written to the output
+    pub fn written(&self) -> usize {
+        self.written
+    }
+
+    /// Read id of each duplicate record, with the read id of the first
+    /// record with the same signal, in the order they were read
+    pub fn duplicates(&self) -> &[(Vec<u8>, Vec<u8>)] {
+        &self.duplicates
+    }
+}
+
+/// Read the records that haven't been read yet from reader and report the
+/// records with the same signal as an earlier record, compared with
+/// [`signal_hash`]. If writer is given, the first record with each signal is
+/// written to it, so the output has no duplicates.
+///
+/// The writer should have the same auxiliary fields as the reader, ie created
+/// with [`FileWriter::create_from`].
+///
+/// # Example
+/// ```
+/// # use assert_fs::TempDir;
+/// # use assert_fs::fixture::PathChild;
+/// use slow5::{dedupe, FileReader, FileWriter};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// # let tmp_dir = TempDir::new()?;
+/// # let file_path = tmp_dir.child("dedupe.slow5");
+/// let mut reader = FileReader::open("examples/example.slow5")?;
+/// let mut writer = FileWriter::create_from(&reader, file_path)?;
+/// let report = dedupe(&mut reader, Some(&mut writer))?;
+/// for (read_id, first) in report.duplicates() {
+///     println!("{} has the same signal as {}",
+///         String::from_utf8_lossy(read_id),
+///         String::from_utf8_lossy(first));
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// Returns an Err if a record fails to be read or written.
+///
+/// [`signal_hash`]: crate::RecordExt::signal_hash
+pub fn dedupe(
+    reader: &mut FileReader,
+    mut writer: Option<&mut FileWriter>,
+) -> Result<DedupeReport, Slow5Error> {
+    let mut report = DedupeReport::default();
+    let mut seen = HashMap::new();
+    for rec in reader.records() {
+        let rec = rec?;
+        report.records += 1;
+        match seen.entry(rec.signal_hash()) {
+            Entry::Occupied(first) => {
+                let first: &Vec<u8> = first.get();
+                report
+                    .duplicates
+                    .push((rec.read_id().to_vec(), first.clone()));
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(rec.read_id().to_vec());
+                if let Some(writer) = writer.as_deref_mut() {
+                    writer.add_record(&rec)?;
+                    report.written += 1;
+                }
+            }
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod test {
+    use assert_fs::{fixture::PathChild, TempDir};
+
+    use super::*;
+    use crate::RecordBuilder;
+
+    #[test]
+    fn test_dedupe() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let file_path = tmp_dir.child("merged.blow5");
+        let mut writer = FileWriter::options().create(&file_path)?;
+        let mut builder = RecordBuilder::default();
+        builder
+            .read_group(0)
+            .digitisation(4096.0)
+            .offset(4.0)
+            .range(12.0)
+            .sampling_rate(4000.0);
+        for (read_id, signal) in [("a", [1, 2, 3]), ("b", [4, 5, 6]), ("c", [1, 2, 3])] {
+            writer.add_record(&builder.read_id(read_id).raw_signal(&signal).build()?)?;
+        }
+        writer.close()?;
+
+        let deduped_path = tmp_dir.child("deduped.blow5");
+        let mut reader = FileReader::open(&file_path)?;
+        let mut writer = FileWriter::create_from(&reader, &deduped_path)?;
+        let report = dedupe(&mut reader, Some(&mut writer))?;
+        writer.close()?;
+        assert_eq!(report.records(), 3);
+        assert_eq!(report.written(), 2);
+        assert_eq!(report.duplicates(), [(b"c".to_vec(), b"a".to_vec())]);
+
+        let mut deduped = FileReader::open(&deduped_path)?;
+        let read_ids = deduped
+            .records()
+            .map(|rec| rec.map(|rec| rec.read_id().to_vec()))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(read_ids, [b"a", b"b"]);
+        Ok(())
+    }
+}
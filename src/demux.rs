@@ -0,0 +1,139 @@
+//! Demultiplex the records of a SLOW5 file into a file per key
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    error::Slow5Error,
+    reader::FileReader,
+    record::Record,
+    split::{split_options, stem_and_extension},
+    writer::FileWriter,
+};
+
+// Keys become part of a file name, so can't change the directory
+fn check_key(key: &str) -> Result<(), Slow5Error> {
+    if key.is_empty() || key == "." || key == ".." || key.contains(['/', '\\']) {
+        Err(Slow5Error::InvalidFilePath(key.to_string()))
+    } else {
+        Ok(())
+    }
+}
+
+/// Write each record of reader to the file for the key returned by key_fn,
+/// ie the value of an auxiliary field like `end_reason` or `channel_number`,
+/// or a barcode from a map of read ids. Records without a key are skipped.
+///
+/// Output files are created in out_dir the first time a key is seen, with the
+/// header, file type, and compression of the input, and are named after the
+/// input and the key, ie "example_barcode01.blow5" for "example.blow5".
+///
+/// Returns the path of the file for each key.
+///
+/// # Example
+/// ```
+/// # use assert_fs::TempDir;
+/// use slow5::{demux, EnumField, FileReader, RecordExt};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// # let tmp_dir = TempDir::new()?;
+/// # let out_dir = tmp_dir.path();
+/// let mut reader = FileReader::open("examples/example3.blow5")?;
+/// let labels = reader
+///     .iter_aux_enum_labels("end_reason")?
+///     .map(|x| String::from_utf8(x.to_vec()))
+///     .collect::<Result<Vec<_>, _>>()?;
+/// let files = demux(
+///     &mut reader,
+///     |rec| {
+///         let EnumField(idx) = rec.get_aux_field("end_reason").ok()?;
+///         labels.get(idx).cloned()
+///     },
+///     out_dir,
+/// )?;
+/// assert!(files.contains_key("unknown"));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// Returns [`Slow5Error::InvalidFilePath`] if a key is empty or has a path
+/// separator, or an Err if a record fails to be read or written.
+pub fn demux<F, P>(
+    reader: &mut FileReader,
+    mut key_fn: F,
+    out_dir: P,
+) -> Result<BTreeMap<String, PathBuf>, Slow5Error>
+where
+    F: FnMut(&Record) -> Option<String>,
+    P: AsRef<Path>,
+{
+    let out_dir = out_dir.as_ref();
+    let (stem, ext) = stem_and_extension(reader);
+    let opts = split_options(reader, None)?;
+    let mut paths = BTreeMap::new();
+    let mut writers: BTreeMap<String, FileWriter> = BTreeMap::new();
+    for rec in reader.records() {
+        let rec = rec?;
+        let Some(key) = key_fn(&rec) else {
+            continue;
+        };
+        let writer = match writers.entry(key) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                check_key(entry.key())?;
+                let path = out_dir.join(format!("{stem}_{}.{ext}", entry.key()));
+                let writer = opts.create(&path)?;
+                paths.insert(entry.key().clone(), path);
+                entry.insert(writer)
+            }
+        };
+        writer.add_record(&rec)?;
+    }
+    for writer in writers.into_values() {
+        writer.close()?;
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use assert_fs::TempDir;
+
+    use super::*;
+    use crate::{header::HeaderExt, record::RecordExt};
+
+    #[test]
+    fn test_demux() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let barcodes = HashMap::from([
+            (b"r1".to_vec(), "barcode01"),
+            (b"r2".to_vec(), "barcode02"),
+            (b"r4".to_vec(), "barcode01"),
+        ]);
+        let mut reader = FileReader::open("examples/example.slow5")?;
+        let files = demux(
+            &mut reader,
+            |rec| barcodes.get(rec.read_id()).map(|b| b.to_string()),
+            tmp_dir.path(),
+        )?;
+        assert_eq!(files.len(), 2);
+        assert!(files["barcode01"].ends_with("example_barcode01.slow5"));
+
+        let mut demuxed = FileReader::open(&files["barcode01"])?;
+        assert_eq!(demuxed.num_read_groups(), reader.num_read_groups());
+        let read_ids = demuxed
+            .records()
+            .map(|rec| rec.map(|rec| rec.read_id().to_vec()))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(read_ids, [b"r1", b"r4"]);
+
+        let mut reader = FileReader::open("examples/example.slow5")?;
+        let res = demux(&mut reader, |_| Some("../up".to_string()), tmp_dir.path());
+        assert!(matches!(res, Err(Slow5Error::InvalidFilePath(_))));
+        Ok(())
+    }
+}
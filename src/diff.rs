@@ -0,0 +1,455 @@
+//! Compare the contents of two SLOW5 files, ie to check a conversion or a
+//! round trip didn't change any records.
+//!
+//! Files are compared by their contents, so files with different compression
+//! or formats, or with records in a different order, can be identical.
+use std::collections::{BTreeSet, HashSet};
+
+use crate::{
+    error::Slow5Error,
+    export::format_aux,
+    header::HeaderExt,
+    reader::FileReader,
+    record::{Record, RecordExt},
+    FieldType,
+};
+
+/// Options for comparing files with [`compare`]. By default, headers and
+/// signals are compared and values must be identical.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffOptions {
+    header: bool,
+    signal: bool,
+    tolerance: f64,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            header: true,
+            signal: true,
+            tolerance: 0.0,
+        }
+    }
+}
+
+impl DiffOptions {
+    /// Set whether the header attributes and auxiliary fields are compared
+    pub fn header(&mut self, compare: bool) -> &mut Self {
+        self.header = compare;
+        self
+    }
+
+    /// Set whether the signals are compared
+    pub fn signal(&mut self, compare: bool) -> &mut Self {
+        self.signal = compare;
+        self
+    }
+
+    /// Set how much floating point values may differ and still be equal. If
+    /// the tolerance isn't zero, signals are compared in picoamps, so records
+    /// with different digitisation, offset, or range can have equal signal.
+    pub fn tolerance(&mut self, tolerance: f64) -> &mut Self {
+        self.tolerance = tolerance.abs();
+        self
+    }
+}
+
+/// Difference between two files found by [`compare`]. Values are formatted
+/// as text, with missing values as ".".
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Difference {
+    /// Files have a different number of read groups
+    ReadGroups {
+        /// Number of read groups in the first file
+        a: u32,
+        /// Number of read groups in the second file
+        b: u32,
+    },
+    /// Header attribute has different values
+    Attribute {
+        /// Read group of the attribute
+        read_group: u32,
+        /// Name of the attribute
+        name: String,
+        /// Value in the first file
+        a: String,
+        /// Value in the second file
+        b: String,
+    },
+    /// Auxiliary field is missing from a file or has a different type
+    AuxField {
+        /// Name of the field
+        name: String,
+        /// Type in the first file
+        a: Option<FieldType>,
+        /// Type in the second file
+        b: Option<FieldType>,
+    },
+    /// Read id is only in the first file
+    OnlyInA(String),
+    /// Read id is only in the second file
+    OnlyInB(String),
+    /// Primary or auxiliary field of a record has different values
+    Field {
+        /// Read id of the record
+        read_id: String,
+        /// Name of the field
+        name: String,
+        /// Value in the first file
+        a: String,
+        /// Value in the second file
+        b: String,
+    },
+    /// Signal of a record differs
+    Signal {
+        /// Read id of the record
+        read_id: String,
+        /// Position of the first measurement that differs, or the length of
+        /// the shorter signal if one signal starts with the other
+        position: u64,
+    },
+}
+
+impl std::fmt::Display for Difference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Difference::ReadGroups { a, b } => write!(f, "number of read groups {a} != {b}"),
+            Difference::Attribute {
+                read_group,
+                name,
+                a,
+                b,
+            } => write!(f, "attribute {name} of read group {read_group}: {a} != {b}"),
+            Difference::AuxField { name, a, b } => {
+                write!(f, "auxiliary field {name}: {a:?} != {b:?}")
+            }
+            Difference::OnlyInA(read_id) => write!(f, "read {read_id} is only in the first file"),
+            Difference::OnlyInB(read_id) => write!(f, "read {read_id} is only in the second file"),
+            Difference::Field {
+                read_id,
+                name,
+                a,
+                b,
+            } => write!(f, "{name} of read {read_id}: {a} != {b}"),
+            Difference::Signal { read_id, position } => {
+                write!(f, "signal of read {read_id} differs at {position}")
+            }
+        }
+    }
+}
+
+/// Differences between two files found by [`compare`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DiffReport {
+    records: usize,
+    differences: Vec<Difference>,
+}
+
+impl DiffReport {
+    /// Number of records in both files that were compared
+    pub fn records(&self) -> usize {
+        self.records
+    }
+
+    /// Differences found, in the order they were found
+    pub fn differences(&self) -> &[Difference] {
+        &self.differences
+    }
+
+    /// Returns true if no differences were found
+    pub fn is_identical(&self) -> bool {
+        self.differences.is_empty()
+    }
+}
+
+/// Compare the records that haven't been read yet from a with the records
+/// with the same read id in b, and the headers of the files. Records are
+/// matched by read id, so they can be in any order.
+///
+/// # Example
+/// ```
+/// use slow5::{
+///     diff::{compare, DiffOptions},
+///     FileReader,
+/// };
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let mut a = FileReader::open("examples/example.slow5")?;
+/// let b = FileReader::open("examples/example.slow5")?;
+/// let report = compare(&mut a, &b, &DiffOptions::default())?;
+/// assert!(report.is_identical());
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// Returns an Err if b doesn't have an index, or a record fails to be read.
+pub fn compare(
+    a: &mut FileReader,
+    b: &FileReader,
+    opts: &DiffOptions,
+) -> Result<DiffReport, Slow5Error> {
+    if !b.has_index() {
+        return Err(Slow5Error::NoIndex);
+    }
+    let mut report = DiffReport::default();
+    if opts.header {
+        compare_headers(a, b, &mut report.differences)?;
+    }
+
+    // Only the auxiliary fields with the same type in both files are compared
+    let b_aux_fields = b.aux_fields_iter().collect::<HashSet<_>>();
+    let aux_fields = a
+        .aux_fields_iter()
+        .filter(|field| b_aux_fields.contains(field))
+        .map(|(name, field_type)| (name.to_vec(), field_type))
+        .collect::<Vec<_>>();
+
+    let mut seen = HashSet::new();
+    for rec in a.records() {
+        let rec = rec?;
+        let read_id = rec.read_id().to_vec();
+        if b.contains_read_id(&read_id) {
+            let other = b.get_record(read_id.clone())?;
+            compare_records(&rec, &other, &aux_fields, opts, &mut report.differences)?;
+            report.records += 1;
+        } else {
+            report
+                .differences
+                .push(Difference::OnlyInA(lossy(&read_id)));
+        }
+        seen.insert(read_id);
+    }
+    for read_id in b.iter_read_ids()? {
+        if !seen.contains(read_id) {
+            report.differences.push(Difference::OnlyInB(lossy(read_id)));
+        }
+    }
+    Ok(report)
+}
+
+fn lossy(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+pub(crate) fn compare_headers(
+    a: &FileReader,
+    b: &FileReader,
+    differences: &mut Vec<Difference>,
+) -> Result<(), Slow5Error> {
+    let (a_groups, b_groups) = (a.num_read_groups(), b.num_read_groups());
+    if a_groups != b_groups {
+        differences.push(Difference::ReadGroups {
+            a: a_groups,
+            b: b_groups,
+        });
+    }
+    let attrs = a
+        .iter_attr_keys()?
+        .chain(b.iter_attr_keys()?)
+        .collect::<BTreeSet<_>>();
+    let value = |reader: &FileReader, attr: &[u8], rg| {
+        reader
+            .get_attribute(attr, rg)
+            .map_or_else(|_| String::from("."), lossy)
+    };
+    for read_group in 0..a_groups.min(b_groups) {
+        for &attr in attrs.iter() {
+            let (a, b) = (value(a, attr, read_group), value(b, attr, read_group));
+            if a != b {
+                differences.push(Difference::Attribute {
+                    read_group,
+                    name: lossy(attr),
+                    a,
+                    b,
+                });
+            }
+        }
+    }
+
+    let field_type = |reader: &FileReader, name: &[u8]| reader.aux_field_type(name).ok();
+    let names = a
+        .aux_names_iter()
+        .chain(b.aux_names_iter())
+        .collect::<BTreeSet<_>>();
+    for name in names {
+        let (a, b) = (field_type(a, name), field_type(b, name));
+        if a != b {
+            differences.push(Difference::AuxField {
+                name: lossy(name),
+                a,
+                b,
+            });
+        }
+    }
+    Ok(())
+}
+
+// Returns true if the values formatted as text are equal, allowing floating
+// point values to differ by the tolerance
+fn values_match(a: &str, b: &str, field_type: &FieldType, tolerance: f64) -> bool {
+    let float = matches!(
+        field_type,
+        FieldType::Float | FieldType::Double | FieldType::FloatArray | FieldType::DoubleArray
+    );
+    if a == b || !float || tolerance == 0.0 {
+        return a == b;
+    }
+    let (a, b) = (a.split(','), b.split(','));
+    a.clone().count() == b.clone().count()
+        && a.zip(b)
+            .all(|(a, b)| match (a.parse::<f64>(), b.parse::<f64>()) {
+                (Ok(a), Ok(b)) => (a - b).abs() <= tolerance,
+                _ => a == b,
+            })
+}
+
+fn compare_records(
+    a: &Record,
+    b: &Record,
+    aux_fields: &[(Vec<u8>, FieldType)],
+    opts: &DiffOptions,
+    differences: &mut Vec<Difference>,
+) -> Result<(), Slow5Error> {
+    let read_id = lossy(a.read_id());
+    let mut push = |name: &str, x: String, y: String, field_type: &FieldType| {
+        if !values_match(&x, &y, field_type, opts.tolerance) {
+            differences.push(Difference::Field {
+                read_id: read_id.clone(),
+                name: name.to_string(),
+                a: x,
+                b: y,
+            });
+        }
+    };
+
+    push(
+        "read_group",
+        a.read_group().to_string(),
+        b.read_group().to_string(),
+        &FieldType::Uint32,
+    );
+    let floats = [
+        ("digitisation", a.digitisation(), b.digitisation()),
+        ("offset", a.offset(), b.offset()),
+        ("range", a.range(), b.range()),
+        ("sampling_rate", a.sampling_rate(), b.sampling_rate()),
+    ];
+    for (name, x, y) in floats {
+        push(name, x.to_string(), y.to_string(), &FieldType::Double);
+    }
+    push(
+        "len_raw_signal",
+        a.len_signal().to_string(),
+        b.len_signal().to_string(),
+        &FieldType::Uint64,
+    );
+    for (name, field_type) in aux_fields {
+        let (x, y) = (
+            format_aux(a, name, field_type)?,
+            format_aux(b, name, field_type)?,
+        );
+        push(&String::from_utf8_lossy(name), x, y, field_type);
+    }
+
+    if opts.signal {
+        let position = if opts.tolerance == 0.0 {
+            first_difference(a.raw_signal_iter(), b.raw_signal_iter(), |x, y| x == y)
+        } else {
+            first_difference(
+                a.picoamps_signal_iter(),
+                b.picoamps_signal_iter(),
+                |x, y| (x - y).abs() <= opts.tolerance,
+            )
+        };
+        if let Some(position) = position {
+            differences.push(Difference::Signal {
+                read_id: lossy(a.read_id()),
+                position,
+            });
+        }
+    }
+    Ok(())
+}
+
+// Position of the first value that isn't equal, or the length of the shorter
+// iterator if they have different lengths
+fn first_difference<T, I, F>(mut a: I, mut b: I, eq: F) -> Option<u64>
+where
+    I: Iterator<Item = T>,
+    F: Fn(&T, &T) -> bool,
+{
+    let mut position = 0;
+    loop {
+        match (a.next(), b.next()) {
+            (None, None) => return None,
+            (Some(x), Some(y)) if eq(&x, &y) => position += 1,
+            _ => return Some(position),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use assert_fs::{fixture::PathChild, TempDir};
+
+    use super::*;
+    use crate::{RecordCompression, SignalCompression, WriteOptions};
+
+    #[test]
+    fn test_compare() -> anyhow::Result<()> {
+        // Same records with different compression
+        let tmp_dir = TempDir::new()?;
+        let file_path = tmp_dir.child("copy.blow5");
+        let mut reader = FileReader::open("examples/example3.blow5")?;
+        let mut opts = WriteOptions::from_reader(&reader)?;
+        opts.record_compression(RecordCompression::None)
+            .signal_compression(SignalCompression::None);
+        let mut writer = opts.create(&file_path)?;
+        for rec in reader.records() {
+            writer.add_record(&rec?)?;
+        }
+        writer.close()?;
+
+        let mut a = FileReader::open("examples/example3.blow5")?;
+        let b = FileReader::open(&file_path)?;
+        let report = compare(&mut a, &b, &DiffOptions::default())?;
+        assert!(report.is_identical(), "{:?}", report.differences());
+        assert!(report.records() > 0);
+
+        let mut a = FileReader::open("examples/example.slow5")?;
+        let b = FileReader::open("examples/example3.blow5")?;
+        let report = compare(&mut a, &b, &DiffOptions::default())?;
+        assert!(report
+            .differences()
+            .contains(&Difference::OnlyInA(String::from("r1"))));
+        assert!(report
+            .differences()
+            .iter()
+            .any(|diff| matches!(diff, Difference::OnlyInB(_))));
+        assert!(report
+            .differences()
+            .iter()
+            .any(|diff| matches!(diff, Difference::AuxField { .. })));
+        Ok(())
+    }
+
+    #[test]
+    fn test_tolerance() {
+        assert!(values_match("1.5,2", "1.55,2", &FieldType::FloatArray, 0.1));
+        assert!(!values_match("1.5,2", "1.55", &FieldType::FloatArray, 0.1));
+        assert!(!values_match("1.5", "1.55", &FieldType::Double, 0.0));
+        assert!(!values_match("1", "2", &FieldType::Int32, 10.0));
+        let eq = |x: &i32, y: &i32| x == y;
+        assert_eq!(
+            first_difference([1, 2].into_iter(), [1, 3].into_iter(), eq),
+            Some(1)
+        );
+        assert_eq!(
+            first_difference([1].iter().copied(), [1, 3].iter().copied(), eq),
+            Some(1)
+        );
+    }
+}
@@ -0,0 +1,209 @@
+//! Pool of threads that encode, ie compress, records for a [`FileWriter`].
+//!
+//! Records are encoded in parallel with slow5_encode, but written by the
+//! FileWriter in the order they were submitted.
+//!
+//! [`FileWriter`]: crate::FileWriter
+use std::{
+    collections::BTreeMap,
+    ptr::null_mut,
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use libc::c_void;
+use slow5lib_sys::{slow5_encode, slow5_file, slow5_write_bytes};
+
+use crate::{error::Slow5Error, record::Record, stats::Stats};
+
+// Pointer to the file being written, workers only use it for the compression
+// and auxiliary fields, which don't change once the header is written
+#[derive(Clone, Copy)]
+struct FilePtr(*mut slow5_file);
+
+unsafe impl Send for FilePtr {}
+
+impl FilePtr {
+    fn encode(self, record: &Record) -> Result<Encoded, Slow5Error> {
+        let mut mem = null_mut();
+        let mut bytes = 0;
+        let start = Instant::now();
+        let ret = unsafe { slow5_encode(&mut mem, &mut bytes, record.slow5_rec, self.0) };
+        let time = start.elapsed();
+        if ret < 0 || mem.is_null() {
+            Err(Slow5Error::from_errno(ret))
+        } else {
+            let len_raw_signal = unsafe { (*record.slow5_rec).len_raw_signal };
+            Ok(Encoded {
+                mem,
+                bytes,
+                len_raw_signal,
+                time,
+            })
+        }
+    }
+}
+
+// Record encoded by slow5lib, ready to be written
+struct Encoded {
+    mem: *mut c_void,
+    bytes: usize,
+    len_raw_signal: u64,
+    time: Duration,
+}
+
+unsafe impl Send for Encoded {}
+
+impl Drop for Encoded {
+    fn drop(&mut self) {
+        unsafe { libc::free(self.mem) }
+    }
+}
+
+type Job = (u64, Record);
+type Done = (u64, Result<Encoded, Slow5Error>);
+
+pub(crate) struct EncoderPool {
+    jobs: Option<mpsc::SyncSender<Job>>,
+    done: mpsc::Receiver<Done>,
+    workers: Vec<thread::JoinHandle<()>>,
+    // Encoded records waiting for the records submitted before them
+    pending: BTreeMap<u64, Result<Encoded, Slow5Error>>,
+    submitted: u64,
+    written: u64,
+    // Records written so far
+    pub(crate) stats: Stats,
+}
+
+impl EncoderPool {
+    pub(crate) fn new(slow5_file: *mut slow5_file, threads: usize) -> Self {
+        // Bound the number of records in flight, so a slow disk applies
+        // backpressure instead of buffering the whole input
+        let (jobs, job_rx) = mpsc::sync_channel::<Job>(threads * 2);
+        let job_rx = Arc::new(Mutex::new(job_rx));
+        let (done_tx, done) = mpsc::channel();
+        let file = FilePtr(slow5_file);
+        let workers = (0..threads)
+            .map(|_| {
+                let job_rx = Arc::clone(&job_rx);
+                let done_tx = done_tx.clone();
+                thread::spawn(move || loop {
+                    let job = match job_rx.lock() {
+                        Ok(rx) => rx.recv(),
+                        Err(_) => break,
+                    };
+                    let Ok((seq, record)) = job else {
+                        break;
+                    };
+                    if done_tx.send((seq, file.encode(&record))).is_err() {
+                        break;
+                    }
+                })
+            })
+            .collect();
+        Self {
+            jobs: Some(jobs),
+            done,
+            workers,
+            pending: BTreeMap::new(),
+            submitted: 0,
+            written: 0,
+            stats: Stats::default(),
+        }
+    }
+
+    // Number of records submitted so far
+    pub(crate) fn submitted(&self) -> u64 {
+        self.submitted
+    }
+
+    // Queue a record to be encoded, returning its position in the order
+    // records are written
+    pub(crate) fn submit(&mut self, record: Record) -> Result<u64, Slow5Error> {
+        let jobs = self.jobs.as_ref().ok_or(Slow5Error::Unknown)?;
+        let seq = self.submitted;
+        jobs.send((seq, record)).map_err(|_| Slow5Error::Unknown)?;
+        self.submitted += 1;
+        Ok(seq)
+    }
+
+    // Write encoded records in the order they were submitted. If block is
+    // true, wait until every submitted record has been written. Records that
+    // fail to be encoded or written are passed to on_error with their
+    // position, and the rest are still written.
+    pub(crate) fn write_ready<F>(
+        &mut self,
+        slow5_file: *mut slow5_file,
+        block: bool,
+        mut on_error: F,
+    ) where
+        F: FnMut(u64, Slow5Error),
+    {
+        loop {
+            while let Ok((seq, encoded)) = self.done.try_recv() {
+                self.pending.insert(seq, encoded);
+            }
+            while let Some(encoded) = self.pending.remove(&self.written) {
+                let seq = self.written;
+                self.written += 1;
+                match encoded {
+                    Ok(encoded) => {
+                        let ret =
+                            unsafe { slow5_write_bytes(encoded.mem, encoded.bytes, slow5_file) };
+                        if ret < 0 {
+                            on_error(seq, Slow5Error::IOError);
+                        } else {
+                            self.stats.add_record(
+                                encoded.bytes as u64,
+                                encoded.len_raw_signal,
+                                encoded.time,
+                            );
+                        }
+                    }
+                    Err(e) => on_error(seq, e),
+                }
+            }
+            if !block || self.written == self.submitted {
+                return;
+            }
+            match self.done.recv() {
+                Ok((seq, encoded)) => {
+                    self.pending.insert(seq, encoded);
+                }
+                Err(_) => {
+                    // Every worker has stopped, so the remaining records
+                    // will never be encoded
+                    for seq in self.written..self.submitted {
+                        on_error(seq, Slow5Error::Unknown);
+                    }
+                    self.written = self.submitted;
+                    return;
+                }
+            }
+        }
+    }
+
+    // Same as write_ready, but only returns the first error
+    pub(crate) fn write_ready_first_err(
+        &mut self,
+        slow5_file: *mut slow5_file,
+        block: bool,
+    ) -> Result<(), Slow5Error> {
+        let mut first = None;
+        self.write_ready(slow5_file, block, |_, e| {
+            first.get_or_insert(e);
+        });
+        first.map_or(Ok(()), Err)
+    }
+}
+
+impl Drop for EncoderPool {
+    fn drop(&mut self) {
+        // Closing the channel stops the workers once they are idle
+        self.jobs = None;
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
@@ -1,7 +1,18 @@
 use std::{ffi::NulError, path::PathBuf, str::Utf8Error};
 
+#[cfg(feature = "slow5lib")]
+use slow5lib_sys::{
+    slow5_errno_location, SLOW5_ERR_ARG, SLOW5_ERR_EOF, SLOW5_ERR_HDRPARSE, SLOW5_ERR_IO,
+    SLOW5_ERR_MAGIC, SLOW5_ERR_MEM, SLOW5_ERR_NOAUX, SLOW5_ERR_NOFLD, SLOW5_ERR_NOIDX,
+    SLOW5_ERR_NOTFOUND, SLOW5_ERR_OTHER, SLOW5_ERR_PRESS, SLOW5_ERR_RECPARSE, SLOW5_ERR_TRUNC,
+    SLOW5_ERR_TYPE, SLOW5_ERR_UNK, SLOW5_ERR_VERSION,
+};
 use thiserror::Error;
 
+#[cfg(feature = "slow5lib")]
+use crate::record::BuilderError;
+use crate::FieldType;
+
 /// Errors from slow5 library
 #[derive(Error, Debug)]
 #[non_exhaustive]
@@ -78,6 +89,10 @@ pub enum Slow5Error {
     #[error("Input file path does not exist {0}")]
     IncorrectPath(PathBuf),
 
+    /// File already exists and would be overwritten
+    #[error("File already exists {0}")]
+    FileExists(PathBuf),
+
     /// Error getting attribute
     #[error("Error getting attribute, attribute doesn't exist or read_group is out of range")]
     AttributeError,
@@ -127,6 +142,10 @@ pub enum Slow5Error {
     #[error("Enum index out of range")]
     EnumOutOfRange,
 
+    /// Label given for an auxiliary enum field is not one of its labels
+    #[error("Label {0} not found for auxiliary enum field")]
+    UnknownEnumLabel(String),
+
     /// The attribute was not found within the header
     ///
     /// Common cases include:
@@ -135,7 +154,185 @@ pub enum Slow5Error {
     #[error("Attribute name was not found in header")]
     MissingAttribute,
 
+    /// No auxiliary field values were set for the record
+    #[error("Auxiliary field value not set for record")]
+    AuxFieldUnset,
+
     /// Type requested or given doesn't match type in SLOW5 file
     #[error("Invalid input, type mismatch")]
     AuxTypeMismatch,
+
+    /// Auxiliary field in the header is missing or doesn't have the expected
+    /// type
+    #[error("Auxiliary field {field} expected to be {expected:?}, found {found:?}")]
+    SchemaMismatch {
+        /// Name of the auxiliary field
+        field: String,
+        /// Type expected for the field
+        expected: FieldType,
+        /// Type of the field in the header, None if the field is missing
+        found: Option<FieldType>,
+    },
+
+    /// Header can't be modified since it was already written to the file
+    #[error("Header was already written to the file")]
+    HeaderAlreadyWritten,
+
+    /// Raw record doesn't have the same format or compression as the output
+    #[error("Raw record format or compression doesn't match the output file")]
+    RawRecordMismatch,
+
+    /// Input file has a different header or compression than the first
+    /// input
+    #[error("Header of {path} differs from the first input: {difference}")]
+    HeaderMismatch {
+        /// Path of the input
+        path: PathBuf,
+        /// What differs, ie the name of an attribute and its values
+        difference: String,
+    },
+
+    /// Failed to build an Arrow RecordBatch
+    #[cfg(feature = "arrow")]
+    #[error("Arrow error: {0}")]
+    Arrow(String),
+
+    /// Failed to write a Parquet file
+    #[cfg(feature = "parquet")]
+    #[error("Parquet error: {0}")]
+    Parquet(String),
+
+    /// Failed to build a polars DataFrame
+    #[cfg(feature = "polars")]
+    #[error("Polars error: {0}")]
+    Polars(String),
+
+    /// Failed to read a POD5 file
+    #[cfg(feature = "pod5")]
+    #[error("POD5 error: {0}")]
+    Pod5(String),
+
+    /// Failed to fetch part of a remote file
+    #[cfg(feature = "remote")]
+    #[error("Remote error: {0}")]
+    Remote(String),
+
+    /// Blocking task for an async reader or writer failed
+    #[cfg(feature = "async")]
+    #[error("Async task error: {0}")]
+    Async(String),
+
+    /// Failed to map the bases of a BAM record to the signal
+    #[cfg(feature = "bam")]
+    #[error("BAM error: {0}")]
+    Bam(String),
+
+    /// Failed to read or write a file
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Failed to parse a line of text into a Record
+    #[error("Failed to import line {line}: {reason}")]
+    ImportParse {
+        /// Line number, starting from 1
+        line: usize,
+        /// Why the line failed to parse
+        reason: String,
+    },
+
+    /// Failed to build a Record
+    #[cfg(feature = "slow5lib")]
+    #[error("Failed to build record: {0}")]
+    Builder(#[from] BuilderError),
+
+    /// Error reported by slow5lib, with the value of slow5_errno
+    #[error("slow5lib error {code}: {message}")]
+    Native {
+        /// slow5_errno, one of the SLOW5_ERR_* constants
+        code: i32,
+        /// Description of the error code
+        message: String,
+    },
+
+    /// Operation was cancelled with its [`ProgressSink`]
+    ///
+    /// [`ProgressSink`]: crate::ProgressSink
+    #[error("Operation cancelled")]
+    Cancelled,
+}
+
+#[cfg(feature = "slow5lib")]
+impl Slow5Error {
+    // Create a Native error from slow5_errno after a slow5lib call failed,
+    // using the return code if slow5_errno wasn't set. slow5_errno is cleared
+    // so it isn't mistaken for the cause of a later failure.
+    pub(crate) fn from_errno(ret: i32) -> Self {
+        let errno = unsafe { slow5_errno_location() };
+        let code = match unsafe { *errno } {
+            0 => ret,
+            code => code,
+        };
+        unsafe { *errno = 0 };
+        Self::native(code)
+    }
+
+    // Create a Native error from an error code returned by slow5lib, public
+    // for slow5-typed
+    #[doc(hidden)]
+    pub fn native(code: i32) -> Self {
+        Slow5Error::Native {
+            code,
+            message: errno_message(code).to_string(),
+        }
+    }
+}
+
+// Descriptions of the error codes in slow5_error.h
+#[cfg(feature = "slow5lib")]
+fn errno_message(code: i32) -> &'static str {
+    match code {
+        SLOW5_ERR_EOF => "end of file reached",
+        SLOW5_ERR_ARG => "bad argument",
+        SLOW5_ERR_TRUNC => "file truncated",
+        SLOW5_ERR_RECPARSE => "record parsing error",
+        SLOW5_ERR_IO => "file I/O error",
+        SLOW5_ERR_NOIDX => "index not loaded",
+        SLOW5_ERR_NOTFOUND => "read id not found",
+        SLOW5_ERR_OTHER => "other error",
+        SLOW5_ERR_UNK => "unknown file format",
+        SLOW5_ERR_MEM => "memory allocation error",
+        SLOW5_ERR_NOAUX => "no auxiliary fields",
+        SLOW5_ERR_NOFLD => "auxiliary field not found",
+        SLOW5_ERR_PRESS => "compression or decompression failure",
+        SLOW5_ERR_MAGIC => "invalid magic number",
+        SLOW5_ERR_VERSION => "incompatible version",
+        SLOW5_ERR_HDRPARSE => "header parsing error",
+        SLOW5_ERR_TYPE => "auxiliary field type error",
+        _ => "unknown error",
+    }
+}
+
+#[cfg(all(test, feature = "slow5lib"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_from_errno() {
+        unsafe { *slow5_errno_location() = SLOW5_ERR_NOTFOUND };
+        let err = Slow5Error::from_errno(-1);
+        assert!(matches!(
+            err,
+            Slow5Error::Native { code: SLOW5_ERR_NOTFOUND, ref message } if message == "read id not found"
+        ));
+
+        // slow5_errno was cleared, so the return code is used
+        let err = Slow5Error::from_errno(SLOW5_ERR_MEM);
+        assert!(matches!(
+            err,
+            Slow5Error::Native {
+                code: SLOW5_ERR_MEM,
+                ..
+            }
+        ));
+    }
 }
@@ -0,0 +1,237 @@
+//! Export records as delimited text, ie TSV or CSV.
+use std::io::Write;
+
+use crate::{
+    error::Slow5Error,
+    header::HeaderExt,
+    reader::FileReader,
+    record::{Record, RecordExt},
+    EnumField, FieldType,
+};
+
+/// Options for exporting records with [`to_tsv`]. By default, the signal is
+/// included as raw values, every auxiliary field is included, and columns are
+/// separated by tabs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportOptions {
+    signal: bool,
+    picoamps: bool,
+    aux_fields: Option<Vec<Vec<u8>>>,
+    delimiter: u8,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            signal: true,
+            picoamps: false,
+            aux_fields: None,
+            delimiter: b'\t',
+        }
+    }
+}
+
+impl ExportOptions {
+    /// Set whether the signal is included
+    pub fn signal(&mut self, include: bool) -> &mut Self {
+        self.signal = include;
+        self
+    }
+
+    /// Set whether the signal is converted to picoamps
+    pub fn picoamps(&mut self, picoamps: bool) -> &mut Self {
+        self.picoamps = picoamps;
+        self
+    }
+
+    /// Only include these auxiliary fields, in the given order. Pass an empty
+    /// iterator to exclude all auxiliary fields.
+    pub fn aux_fields<I, B>(&mut self, names: I) -> &mut Self
+    where
+        I: IntoIterator<Item = B>,
+        B: Into<Vec<u8>>,
+    {
+        self.aux_fields = Some(names.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Set the column delimiter, ie b',' for CSV
+    pub fn delimiter(&mut self, delimiter: u8) -> &mut Self {
+        self.delimiter = delimiter;
+        self
+    }
+}
+
+// Missing values are written the same way as in SLOW5 files
+const MISSING: &str = ".";
+
+fn join<T: ToString>(values: impl Iterator<Item = T>) -> String {
+    values.map(|v| v.to_string()).collect::<Vec<_>>().join(",")
+}
+
+macro_rules! format_aux {
+    ($rec:expr, $name:expr, $field_type:expr, $(($variant:ident, $rtype:ty)),*) => {
+        paste::paste! {
+            match $field_type {
+                $(
+                    FieldType::$variant => $rec
+                        .get_aux_field_opt::<$rtype>($name)?
+                        .map(|v| v.to_string()),
+                    FieldType::[<$variant Array>] => $rec
+                        .get_aux_field_opt::<Vec<$rtype>>($name)?
+                        .map(|v| join(v.iter())),
+                )*
+                FieldType::Char => $rec.get_aux_field_opt::<char>($name)?.map(String::from),
+                FieldType::Str => $rec.get_aux_field_opt::<String>($name)?,
+                FieldType::Enum(labels) => $rec
+                    .get_aux_field_opt::<EnumField>($name)?
+                    .and_then(|EnumField(idx)| labels.get(idx))
+                    .map(|label| String::from_utf8_lossy(label).into_owned()),
+            }
+        }
+    };
+}
+
+// Value of an auxiliary field as text
+pub(crate) fn format_aux(
+    rec: &Record,
+    name: &[u8],
+    field_type: &FieldType,
+) -> Result<String, Slow5Error> {
+    let value = format_aux!(
+        rec,
+        name,
+        field_type,
+        (Int8, i8),
+        (Int16, i16),
+        (Int32, i32),
+        (Int64, i64),
+        (Uint8, u8),
+        (Uint16, u16),
+        (Uint32, u32),
+        (Uint64, u64),
+        (Float, f32),
+        (Double, f64)
+    );
+    Ok(value.unwrap_or_else(|| MISSING.to_string()))
+}
+
+/// Write one line per record to writer, with a header line of the column
+/// names. Columns are the primary fields, the signal as comma-separated
+/// values, and the auxiliary fields. Auxiliary fields without a value are
+/// written as ".". Returns the number of records written.
+///
+/// # Example
+/// ```
+/// use slow5::{
+///     export::{to_tsv, ExportOptions},
+///     FileReader,
+/// };
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let mut reader = FileReader::open("examples/example2.slow5")?;
+/// let mut opts = ExportOptions::default();
+/// opts.signal(false).aux_fields(["read_number"]);
+/// let mut tsv = Vec::new();
+/// to_tsv(&mut reader, &mut tsv, &opts)?;
+/// let header = String::from_utf8(tsv)?.lines().next().unwrap().to_string();
+/// assert!(header.ends_with("read_number"));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// Returns an Err if an auxiliary field isn't in the header, or if a record
+/// fails to be read or written.
+pub fn to_tsv<W>(
+    reader: &mut FileReader,
+    mut writer: W,
+    opts: &ExportOptions,
+) -> Result<usize, Slow5Error>
+where
+    W: Write,
+{
+    let aux_fields = match &opts.aux_fields {
+        Some(names) => names
+            .iter()
+            .map(|name| Ok((name.clone(), reader.aux_field_type(name.clone())?)))
+            .collect::<Result<Vec<_>, Slow5Error>>()?,
+        None => reader
+            .aux_fields_iter()
+            .map(|(name, field_type)| (name.to_vec(), field_type))
+            .collect(),
+    };
+    let delimiter = char::from(opts.delimiter).to_string();
+
+    let mut columns = vec![
+        "read_id",
+        "read_group",
+        "digitisation",
+        "offset",
+        "range",
+        "sampling_rate",
+        "len_raw_signal",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect::<Vec<_>>();
+    if opts.signal {
+        columns.push(String::from("raw_signal"));
+    }
+    for (name, _) in aux_fields.iter() {
+        columns.push(String::from_utf8_lossy(name).into_owned());
+    }
+    writeln!(writer, "{}", columns.join(&delimiter))?;
+
+    let mut n = 0;
+    for rec in reader.records() {
+        let rec = rec?;
+        let mut line = vec![
+            String::from_utf8_lossy(rec.read_id()).into_owned(),
+            rec.read_group().to_string(),
+            rec.digitisation().to_string(),
+            rec.offset().to_string(),
+            rec.range().to_string(),
+            rec.sampling_rate().to_string(),
+            rec.len_signal().to_string(),
+        ];
+        if opts.signal && opts.picoamps {
+            line.push(join(rec.picoamps_signal_iter()));
+        } else if opts.signal {
+            line.push(join(rec.raw_signal_iter()));
+        }
+        for (name, field_type) in aux_fields.iter() {
+            line.push(format_aux(&rec, name, field_type)?);
+        }
+        writeln!(writer, "{}", line.join(&delimiter))?;
+        n += 1;
+    }
+    Ok(n)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_to_tsv() -> anyhow::Result<()> {
+        let mut reader = FileReader::open("examples/example2.slow5")?;
+        let mut opts = ExportOptions::default();
+        opts.aux_fields(["read_number", "channel_number"])
+            .delimiter(b',');
+        let mut csv = Vec::new();
+        assert_eq!(to_tsv(&mut reader, &mut csv, &opts)?, 5);
+        let csv = String::from_utf8(csv)?;
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("read_id,read_group,digitisation,offset,range,sampling_rate,len_raw_signal,raw_signal,read_number,channel_number")
+        );
+        assert_eq!(lines.count(), 5);
+
+        let mut reader = FileReader::open("examples/example2.slow5")?;
+        opts.aux_fields(["not a field"]);
+        assert!(to_tsv(&mut reader, Vec::new(), &opts).is_err());
+        Ok(())
+    }
+}
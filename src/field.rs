@@ -0,0 +1,250 @@
+//! Handles to auxiliary fields, for reading or setting the same field on many
+//! records without converting the name to a C string and looking it up in
+//! slow5lib on every call.
+use std::{
+    cell::Cell,
+    ffi::{CStr, CString},
+    marker::PhantomData,
+    ptr::null,
+};
+
+use libc::{c_char, c_void};
+use slow5lib_sys::{kh_slow5_s2a_t, slow5_aux_set, slow5_hdr_t, slow5_rec_aux_data};
+
+use crate::{
+    auxiliary::{parse_aux_field_set_error, MissingValue},
+    error::Slow5Error,
+    to_cstring, AuxField, AuxFieldSetExt, FieldType, FileWriter, Header, Record, RecordExt,
+};
+
+/// Auxiliary field types that can be accessed through an [`AuxFieldHandle`],
+/// ie the primitive integer and floating point types.
+pub trait PrimitiveField: AuxField + AuxFieldSetExt + Copy {
+    /// Type of the auxiliary field in the header
+    const FIELD_TYPE: FieldType;
+
+    #[doc(hidden)]
+    fn is_missing_value(&self) -> bool;
+}
+
+macro_rules! impl_primitive_field {
+    ($($rtype:ty => $field_type:ident),*) => {
+        $(
+            impl PrimitiveField for $rtype {
+                const FIELD_TYPE: FieldType = FieldType::$field_type;
+
+                fn is_missing_value(&self) -> bool {
+                    MissingValue::is_missing(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_primitive_field!(
+    i8 => Int8, i16 => Int16, i32 => Int32, i64 => Int64,
+    u8 => Uint8, u16 => Uint16, u32 => Uint32, u64 => Uint64,
+    f32 => Float, f64 => Double
+);
+
+/// Typed handle to an auxiliary field, created with
+/// [`Header::aux_field_handle`]. The type is checked against the header once,
+/// when the handle is created.
+///
+/// Records read from the same file store their auxiliary fields in the same
+/// layout, so the handle remembers where it found the field and checks there
+/// first on the next record.
+///
+/// # Example
+/// ```
+/// # use slow5::FileReader;
+/// # fn main() -> anyhow::Result<()> {
+/// let mut reader = FileReader::open("examples/example3.blow5")?;
+/// let read_number = reader.header().aux_field_handle::<i32>("read_number")?;
+/// for rec in reader.records() {
+///     let rec = rec?;
+///     println!("{}", read_number.get(&rec)?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct AuxFieldHandle<T> {
+    name: CString,
+    // Bucket of the auxiliary map of the last record where the field was found
+    slot: Cell<usize>,
+    // Position of the field in the header it was last set with, and its name
+    // owned by that header, used as the key when setting the field so it
+    // outlives the handle
+    header_name: Cell<(*mut slow5_hdr_t, usize, *const c_char)>,
+    _type: PhantomData<T>,
+}
+
+unsafe impl<T> Send for AuxFieldHandle<T> {}
+
+impl<T> std::fmt::Debug for AuxFieldHandle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuxFieldHandle")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+impl<T: PrimitiveField> AuxFieldHandle<T> {
+    pub(crate) fn new(header: &Header, name: Vec<u8>) -> Result<Self, Slow5Error> {
+        if header.aux_field_type(name.clone())? != T::FIELD_TYPE {
+            return Err(Slow5Error::AuxTypeMismatch);
+        }
+        Ok(Self {
+            name: to_cstring(name)?,
+            slot: Cell::new(0),
+            header_name: Cell::new((header.header, 0, null())),
+            _type: PhantomData,
+        })
+    }
+
+    /// Name of the auxiliary field
+    pub fn name(&self) -> &[u8] {
+        self.name.to_bytes()
+    }
+
+    // Value of the field in the auxiliary map of a record
+    unsafe fn lookup(&self, map: *const kh_slow5_s2a_t) -> Option<&slow5_rec_aux_data> {
+        if map.is_null() {
+            return None;
+        }
+        let n_buckets = (*map).n_buckets as usize;
+        let matches = |i: usize| {
+            // Equivalent to kh_exist
+            let is_empty_or_deleted = (*(*map).flags.add(i >> 4) >> ((i & 0xf) << 1)) & 3;
+            is_empty_or_deleted == 0 && CStr::from_ptr(*(*map).keys.add(i)) == self.name.as_c_str()
+        };
+        let slot = self.slot.get();
+        let slot = if slot < n_buckets && matches(slot) {
+            slot
+        } else {
+            let slot = (0..n_buckets).find(|&i| matches(i))?;
+            self.slot.set(slot);
+            slot
+        };
+        Some(&*(*map).vals.add(slot))
+    }
+
+    /// Get the value of the field for the record, like
+    /// [`Record::get_aux_field`]
+    ///
+    /// # Errors
+    /// Returns an Err if the field wasn't set for the record, or has a
+    /// different type in the record than in the header
+    pub fn get<R: RecordExt>(&self, rec: &R) -> Result<T, Slow5Error> {
+        let val = unsafe { self.lookup((*rec.ptr().ptr).aux_map) };
+        let val = val.ok_or(Slow5Error::AuxFieldUnset)?;
+        if val.type_ != T::FIELD_TYPE.to_slow5_t().0 {
+            return Err(Slow5Error::AuxTypeMismatch);
+        }
+        if val.data.is_null() || (val.bytes as usize) < std::mem::size_of::<T>() {
+            return Err(Slow5Error::AuxFieldUnset);
+        }
+        Ok(unsafe { std::ptr::read_unaligned(val.data as *const T) })
+    }
+
+    /// Get the value of the field for the record, returning `Ok(None)` if the
+    /// record has no value for it, like [`Record::get_aux_field_opt`]
+    ///
+    /// # Errors
+    /// Returns an Err if the field has a different type in the record than in
+    /// the header
+    pub fn get_opt<R: RecordExt>(&self, rec: &R) -> Result<Option<T>, Slow5Error> {
+        match self.get(rec) {
+            Ok(value) => Ok(Some(value).filter(|value| !value.is_missing_value())),
+            Err(Slow5Error::AuxFieldUnset) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    // Name of the field in the header's auxiliary fields
+    fn header_name(&self, header: *mut slow5_hdr_t) -> Result<*const c_char, Slow5Error> {
+        let aux_meta = unsafe { (*header).aux_meta };
+        if aux_meta.is_null() {
+            return Err(Slow5Error::MissingAttribute);
+        }
+        let aux_meta = unsafe { &*aux_meta };
+        let attr = |i: usize| unsafe { *aux_meta.attrs.add(i) as *const c_char };
+        // Check the name is still in the header, in case another header was
+        // allocated at the same address
+        let (cached, idx, name) = self.header_name.get();
+        if cached == header && idx < aux_meta.num as usize && attr(idx) == name {
+            return Ok(name);
+        }
+        let (idx, name) = (0..aux_meta.num as usize)
+            .map(|i| (i, attr(i)))
+            .find(|&(_, attr)| unsafe { CStr::from_ptr(attr) } == self.name.as_c_str())
+            .ok_or(Slow5Error::MissingAttribute)?;
+        self.header_name.set((header, idx, name));
+        Ok(name)
+    }
+
+    /// Set the value of the field for the record, like
+    /// [`Record::set_aux_field`]
+    ///
+    /// # Errors
+    /// Returns an Err if the field isn't in the header of the writer
+    pub fn set(&self, rec: &mut Record, writer: &FileWriter, value: T) -> Result<(), Slow5Error> {
+        let header = writer.header().header;
+        let name = self.header_name(header)?;
+        let value_ptr = &value as *const T as *const c_void;
+        let ret = unsafe { slow5_aux_set(rec.slow5_rec, name, value_ptr, header) };
+        if ret < 0 {
+            Err(parse_aux_field_set_error(ret))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use assert_fs::{prelude::PathChild, TempDir};
+
+    use super::*;
+    use crate::{FileReader, RecordBuilder, WriteOptions};
+
+    #[test]
+    fn test_aux_field_handle() -> anyhow::Result<()> {
+        let mut reader = FileReader::open("examples/example3.blow5")?;
+        let read_number = reader.header().aux_field_handle::<i32>("read_number")?;
+        assert!(reader
+            .header()
+            .aux_field_handle::<i64>("read_number")
+            .is_err());
+        assert!(reader.header().aux_field_handle::<i32>("missing").is_err());
+        for rec in reader.records() {
+            let rec = rec?;
+            assert_eq!(
+                read_number.get(&rec)?,
+                rec.get_aux_field::<i32>("read_number")?
+            );
+        }
+
+        let tmp_dir = TempDir::new()?;
+        let mut writer = WriteOptions::default()
+            .aux("median", FieldType::Double)
+            .create(tmp_dir.child("test.blow5"))?;
+        let median = writer.header().aux_field_handle::<f64>("median")?;
+        let mut rec = RecordBuilder::default()
+            .digitisation(0.123)
+            .offset(0.456)
+            .range(0.999)
+            .read_group(0)
+            .read_id("new")
+            .sampling_rate(0.777)
+            .raw_signal(&[1, 2, 3])
+            .build()?;
+        assert_eq!(median.get_opt(&rec)?, None);
+        median.set(&mut rec, &writer, 81.5)?;
+        drop(median);
+        assert_eq!(rec.get_aux_field::<f64>("median")?, 81.5);
+        writer.add_record(&rec)?;
+        writer.close()?;
+        Ok(())
+    }
+}
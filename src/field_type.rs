@@ -0,0 +1,157 @@
+//! Types of auxiliary fields, shared by the slow5lib and pure Rust readers
+
+/// Maps between Rust types and SLOW5 C types
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FieldType {
+    /// i8
+    Int8,
+    /// i16
+    Int16,
+    /// i32
+    Int32,
+    /// i64
+    Int64,
+    /// u8
+    Uint8,
+    /// u16
+    Uint16,
+    /// u32
+    Uint32,
+    /// u64
+    Uint64,
+    /// f32
+    Float,
+    /// f64
+    Double,
+    /// char
+    Char,
+    /// &str,
+    Str,
+
+    /// &[u8], not a string representation
+    Uint8Array,
+
+    /// &[u16]
+    Uint16Array,
+
+    /// &[u32]
+    Uint32Array,
+
+    /// &[u64]
+    Uint64Array,
+
+    /// &[i8]
+    Int8Array,
+
+    /// &[i16]
+    Int16Array,
+
+    /// &[i32]
+    Int32Array,
+
+    /// &[i64]
+    Int64Array,
+
+    /// &[f32]
+    FloatArray,
+
+    /// &[f64]
+    DoubleArray,
+
+    /// EnumField
+    Enum(Vec<Vec<u8>>),
+}
+
+impl<B> From<Vec<B>> for FieldType
+where
+    B: Into<Vec<u8>>,
+{
+    fn from(value: Vec<B>) -> Self {
+        FieldType::Enum(value.into_iter().map(|b| b.into()).collect())
+    }
+}
+
+impl FieldType {
+    // Name of the type in a SLOW5 header
+    #[cfg(any(all(feature = "serde", feature = "slow5lib"), feature = "pure-rust"))]
+    pub(crate) fn type_name(&self) -> String {
+        let name = match self {
+            FieldType::Int8 => "int8_t",
+            FieldType::Int16 => "int16_t",
+            FieldType::Int32 => "int32_t",
+            FieldType::Int64 => "int64_t",
+            FieldType::Uint8 => "uint8_t",
+            FieldType::Uint16 => "uint16_t",
+            FieldType::Uint32 => "uint32_t",
+            FieldType::Uint64 => "uint64_t",
+            FieldType::Float => "float",
+            FieldType::Double => "double",
+            FieldType::Char => "char",
+            FieldType::Str => "char*",
+            FieldType::Int8Array => "int8_t*",
+            FieldType::Int16Array => "int16_t*",
+            FieldType::Int32Array => "int32_t*",
+            FieldType::Int64Array => "int64_t*",
+            FieldType::Uint8Array => "uint8_t*",
+            FieldType::Uint16Array => "uint16_t*",
+            FieldType::Uint32Array => "uint32_t*",
+            FieldType::Uint64Array => "uint64_t*",
+            FieldType::FloatArray => "float*",
+            FieldType::DoubleArray => "double*",
+            FieldType::Enum(labels) => {
+                let labels = labels
+                    .iter()
+                    .map(|label| String::from_utf8_lossy(label))
+                    .collect::<Vec<_>>();
+                return format!("enum{{{}}}", labels.join(","));
+            }
+        };
+        name.to_string()
+    }
+
+    // Whether values of the type are arrays, strings aren't arrays
+    #[cfg(feature = "slow5lib")]
+    pub(crate) fn is_array(&self) -> bool {
+        matches!(
+            self,
+            FieldType::Int8Array
+                | FieldType::Int16Array
+                | FieldType::Int32Array
+                | FieldType::Int64Array
+                | FieldType::Uint8Array
+                | FieldType::Uint16Array
+                | FieldType::Uint32Array
+                | FieldType::Uint64Array
+                | FieldType::FloatArray
+                | FieldType::DoubleArray
+        )
+    }
+
+    // Size in bytes of a single value, or element of an array. None for
+    // strings, since their size depends on the value.
+    #[cfg(feature = "slow5lib")]
+    pub(crate) fn elem_size(&self) -> Option<u64> {
+        let size = match self {
+            FieldType::Int8 | FieldType::Uint8 | FieldType::Char | FieldType::Enum(_) => 1,
+            FieldType::Int8Array | FieldType::Uint8Array => 1,
+            FieldType::Int16 | FieldType::Uint16 => 2,
+            FieldType::Int16Array | FieldType::Uint16Array => 2,
+            FieldType::Int32 | FieldType::Uint32 | FieldType::Float => 4,
+            FieldType::Int32Array | FieldType::Uint32Array | FieldType::FloatArray => 4,
+            FieldType::Int64 | FieldType::Uint64 | FieldType::Double => 8,
+            FieldType::Int64Array | FieldType::Uint64Array | FieldType::DoubleArray => 8,
+            FieldType::Str => return None,
+        };
+        Some(size)
+    }
+}
+
+/// Represents the value for an enum field. This struct wraps an index into the
+/// labels used for auxiiliary enum field.
+///
+/// The intended way to use is to index into the output from
+/// [`AuxEnumlabelIter`]
+///
+/// [`AuxEnumLabelIter`]: crate::reader::AuxEnumLabelIter
+#[derive(Debug)]
+pub struct EnumField(pub usize);
@@ -0,0 +1,237 @@
+//! Skip records that don't match a set of conditions while reading a file
+use crate::{
+    auxiliary::AuxField,
+    error::Slow5Error,
+    reader::FileReader,
+    record::{Record, RecordExt, RecordIter},
+};
+
+type Predicate = Box<dyn Fn(&Record) -> bool + Send + Sync>;
+
+/// Conditions a record must meet to be returned by
+/// [`FileReader::records_filtered`]. By default, every record matches.
+///
+/// When the reader has an index, the read id prefix is checked against the
+/// index, so records that don't match are never read from the file. The other
+/// conditions are checked on each record after it has been read.
+///
+/// # Example
+/// ```
+/// # use slow5::{FileReader, Filter, RecordExt};
+/// # fn main() -> anyhow::Result<()> {
+/// let mut reader = FileReader::open("examples/example.slow5")?;
+/// let mut filter = Filter::new();
+/// filter.read_id_prefix("r1").min_signal_len(1);
+/// for rec in reader.records_filtered(filter) {
+///     let rec = rec?;
+/// #   assert_eq!(rec.read_id(), b"r1");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`FileReader::records_filtered`]: crate::FileReader::records_filtered
+#[derive(Default)]
+pub struct Filter {
+    min_signal_len: Option<u64>,
+    max_signal_len: Option<u64>,
+    read_groups: Option<Vec<u32>>,
+    read_id_prefix: Option<Vec<u8>>,
+    predicates: Vec<Predicate>,
+}
+
+impl std::fmt::Debug for Filter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Filter")
+            .field("min_signal_len", &self.min_signal_len)
+            .field("max_signal_len", &self.max_signal_len)
+            .field("read_groups", &self.read_groups)
+            .field("read_id_prefix", &self.read_id_prefix)
+            .field("predicates", &self.predicates.len())
+            .finish()
+    }
+}
+
+impl Filter {
+    /// Create a Filter that matches every record
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only match records with at least len samples in the raw signal
+    pub fn min_signal_len(&mut self, len: u64) -> &mut Self {
+        self.min_signal_len = Some(len);
+        self
+    }
+
+    /// Only match records with at most len samples in the raw signal
+    pub fn max_signal_len(&mut self, len: u64) -> &mut Self {
+        self.max_signal_len = Some(len);
+        self
+    }
+
+    /// Only match records in the read group. Calling it more than once matches
+    /// records in any of the read groups.
+    pub fn read_group(&mut self, read_group: u32) -> &mut Self {
+        self.read_groups
+            .get_or_insert_with(Vec::new)
+            .push(read_group);
+        self
+    }
+
+    /// Only match records with a read id starting with prefix
+    pub fn read_id_prefix<B: Into<Vec<u8>>>(&mut self, prefix: B) -> &mut Self {
+        self.read_id_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Only match records where the auxiliary field has a value for which pred
+    /// returns true. Records missing the field, or where the field has a
+    /// different type, don't match.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::Filter;
+    /// let mut filter = Filter::new();
+    /// filter.aux_field("median", |median: f32| median > 80.0);
+    /// ```
+    pub fn aux_field<T, B, F>(&mut self, name: B, pred: F) -> &mut Self
+    where
+        T: AuxField,
+        B: Into<Vec<u8>>,
+        F: Fn(T) -> bool + Send + Sync + 'static,
+    {
+        let name = name.into();
+        self.predicates.push(Box::new(move |rec| {
+            rec.get_aux_field_opt::<T>(name.clone())
+                .ok()
+                .flatten()
+                .is_some_and(&pred)
+        }));
+        self
+    }
+
+    /// Only match records for which pred returns true
+    pub fn predicate<F>(&mut self, pred: F) -> &mut Self
+    where
+        F: Fn(&Record) -> bool + Send + Sync + 'static,
+    {
+        self.predicates.push(Box::new(pred));
+        self
+    }
+
+    fn matches_read_id(&self, read_id: &[u8]) -> bool {
+        self.read_id_prefix
+            .as_ref()
+            .map_or(true, |prefix| read_id.starts_with(prefix))
+    }
+
+    /// Returns true if the record meets every condition of the Filter
+    pub fn matches(&self, rec: &Record) -> bool {
+        let len = rec.len_signal();
+        self.matches_read_id(rec.read_id())
+            && self.min_signal_len.map_or(true, |min| len >= min)
+            && self.max_signal_len.map_or(true, |max| len <= max)
+            && self
+                .read_groups
+                .as_ref()
+                .map_or(true, |rgs| rgs.contains(&rec.read_group()))
+            && self.predicates.iter().all(|pred| pred(rec))
+    }
+}
+
+enum Source<'a> {
+    // Read ids from the index that match the prefix, in file order
+    Index {
+        reader: &'a FileReader,
+        read_ids: std::vec::IntoIter<Vec<u8>>,
+    },
+    Records(RecordIter<'a>),
+}
+
+/// Iterator over the records that match a [`Filter`], generated by
+/// [`FileReader::records_filtered`].
+///
+/// [`FileReader::records_filtered`]: crate::FileReader::records_filtered
+pub struct FilteredIter<'a> {
+    source: Source<'a>,
+    filter: Filter,
+}
+
+impl<'a> std::fmt::Debug for FilteredIter<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FilteredIter")
+            .field("filter", &self.filter)
+            .finish()
+    }
+}
+
+impl<'a> FilteredIter<'a> {
+    pub(crate) fn new(reader: &'a mut FileReader, filter: Filter) -> Self {
+        let source = match reader.index_iter() {
+            Ok(index) if filter.read_id_prefix.is_some() => {
+                let mut entries = index
+                    .filter(|(read_id, _, _)| filter.matches_read_id(read_id))
+                    .map(|(read_id, offset, _)| (offset, read_id.to_vec()))
+                    .collect::<Vec<_>>();
+                entries.sort_unstable();
+                let read_ids = entries
+                    .into_iter()
+                    .map(|(_, read_id)| read_id)
+                    .collect::<Vec<_>>();
+                Source::Index {
+                    reader,
+                    read_ids: read_ids.into_iter(),
+                }
+            }
+            _ => Source::Records(reader.records()),
+        };
+        Self { source, filter }
+    }
+}
+
+impl<'a> Iterator for FilteredIter<'a> {
+    type Item = Result<Record, Slow5Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let rec = match &mut self.source {
+                Source::Index { reader, read_ids } => reader.get_record(read_ids.next()?),
+                Source::Records(records) => records.next()?,
+            };
+            match rec {
+                Ok(rec) if !self.filter.matches(&rec) => continue,
+                rec => return Some(rec),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_records_filtered() -> anyhow::Result<()> {
+        let mut reader = FileReader::open("examples/example.slow5")?;
+        let mut filter = Filter::new();
+        filter.read_id_prefix("r3");
+        let records = reader
+            .records_filtered(filter)
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].read_id(), b"r3");
+
+        // Without an index, every record is read then filtered
+        let mut reader = FileReader::open_without_index("examples/example.slow5")?;
+        let mut filter = Filter::new();
+        filter.read_group(0).predicate(|rec| rec.read_id() != b"r1");
+        assert_eq!(reader.records_filtered(filter).count(), 4);
+
+        let mut reader = FileReader::open("examples/example.slow5")?;
+        let mut filter = Filter::new();
+        filter.min_signal_len(1).max_signal_len(0);
+        assert_eq!(reader.records_filtered(filter).count(), 0);
+        Ok(())
+    }
+}
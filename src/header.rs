@@ -1,16 +1,25 @@
 //! Module for dealing with SLOW5 headers
 use std::{
+    collections::BTreeMap,
     ffi::{CStr, CString},
     marker::PhantomData,
 };
 
-use libc::c_char;
+use libc::{c_char, c_void};
 use slow5lib_sys::{
-    slow5_aux_add, slow5_aux_add_enum, slow5_get_aux_names, slow5_hdr_add, slow5_hdr_get,
-    slow5_hdr_set, slow5_hdr_t,
+    slow5_aux_add, slow5_aux_add_enum, slow5_aux_meta, slow5_aux_type_SLOW5_ENUM,
+    slow5_fmt_SLOW5_FORMAT_ASCII, slow5_get_aux_enum_labels, slow5_get_aux_names,
+    slow5_get_hdr_keys, slow5_hdr_add, slow5_hdr_add_rg, slow5_hdr_get, slow5_hdr_set, slow5_hdr_t,
+    slow5_hdr_to_mem, slow5_press_method_SLOW5_COMPRESS_NONE, slow5_press_method_t,
 };
 
-use crate::{auxiliary::FieldType, error::Slow5Error, to_cstring};
+use crate::{
+    compression::{RecordCompression, SignalCompression},
+    error::Slow5Error,
+    field::{AuxFieldHandle, PrimitiveField},
+    field_type::FieldType,
+    to_cstring,
+};
 
 /// Trait for common Header methods
 pub trait HeaderExt {
@@ -38,6 +47,89 @@ pub trait HeaderExt {
         let auxs = unsafe { slow5_get_aux_names(self.header().header, &mut num_aux) };
         AuxNamesIter::new(0, num_aux, auxs)
     }
+
+    /// Get the [`FieldType`] of an auxiliary field
+    fn aux_field_type<B>(&self, name: B) -> Result<FieldType, Slow5Error>
+    where
+        B: Into<Vec<u8>>,
+    {
+        let name = name.into();
+        self.aux_fields_iter()
+            .find(|(aux_name, _)| *aux_name == name.as_slice())
+            .map(|(_, field_type)| field_type)
+            .ok_or(Slow5Error::MissingAttribute)
+    }
+
+    /// Iterator over auxiliary field names and their [`FieldType`]
+    fn aux_fields_iter(&self) -> AuxFieldsIter<'_> {
+        let aux_meta = unsafe { (*self.header().header).aux_meta };
+        AuxFieldsIter::new(aux_meta)
+    }
+
+    /// Number of read groups in the header
+    fn num_read_groups(&self) -> u32 {
+        unsafe { (*self.header().header).num_read_groups }
+    }
+
+    /// Iterator over the value of an attribute for each read group
+    fn attr_values<B>(&self, attr: B) -> Result<AttrValuesIter<'_>, Slow5Error>
+    where
+        B: Into<Vec<u8>>,
+    {
+        let attr = to_cstring(attr)?;
+        Ok(AttrValuesIter::new(attr, self.header().header))
+    }
+
+    /// Header formatted as the `#`-prefixed lines of a SLOW5 file
+    fn to_slow5_text(&self) -> Result<String, Slow5Error> {
+        let mut n = 0;
+        let text = unsafe {
+            // Compression is only used for BLOW5 headers
+            let comp = slow5_press_method_t {
+                record_method: slow5_press_method_SLOW5_COMPRESS_NONE,
+                signal_method: slow5_press_method_SLOW5_COMPRESS_NONE,
+            };
+            let mem = slow5_hdr_to_mem(
+                self.header().header,
+                slow5_fmt_SLOW5_FORMAT_ASCII,
+                comp,
+                &mut n,
+            ) as *mut u8;
+            if mem.is_null() {
+                return Err(Slow5Error::Allocation);
+            }
+            let text = std::slice::from_raw_parts(mem, n).to_vec();
+            libc::free(mem as *mut c_void);
+            text
+        };
+        let mut text = String::from_utf8(text).map_err(|e| e.utf8_error())?;
+        // Drop the nul terminator if slow5lib counted it
+        if text.ends_with('\0') {
+            text.pop();
+        }
+        Ok(text)
+    }
+}
+
+/// Get the labels of an auxiliary enum field, in order of their index
+pub(crate) fn aux_enum_labels<'a, B>(
+    header: &Header<'a>,
+    field: B,
+) -> Result<Vec<&'a CStr>, Slow5Error>
+where
+    B: Into<Vec<u8>>,
+{
+    let mut n = 0;
+    let field = to_cstring(field)?;
+    let label_ptr = unsafe { slow5_get_aux_enum_labels(header.header, field.as_ptr(), &mut n) };
+    if label_ptr.is_null() {
+        Err(Slow5Error::MissingAttribute)
+    } else {
+        let labels = (0..n as usize)
+            .map(|idx| unsafe { CStr::from_ptr(*label_ptr.add(idx)) })
+            .collect();
+        Ok(labels)
+    }
 }
 
 /// Represents a SLOW5 header
@@ -160,6 +252,233 @@ impl<'a> Header<'a> {
         AuxNamesIter::new(0, num_aux, auxs)
     }
 
+    /// Get the [`FieldType`] of an auxiliary field.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::FileReader;
+    /// use slow5::FieldType;
+    /// # fn main() -> anyhow::Result<()> {
+    /// let slow5 = FileReader::open("examples/example2.slow5")?;
+    /// let header = slow5.header();
+    /// assert_eq!(header.aux_field_type("read_number")?, FieldType::Int32);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an Err if there is no auxiliary field with that name.
+    pub fn aux_field_type<B>(&self, name: B) -> Result<FieldType, Slow5Error>
+    where
+        B: Into<Vec<u8>>,
+    {
+        HeaderExt::aux_field_type(self, name)
+    }
+
+    /// Get a typed handle to an auxiliary field, for reading or setting it on
+    /// many records without looking up the name each time. See
+    /// [`AuxFieldHandle`].
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::FileReader;
+    /// # fn main() -> anyhow::Result<()> {
+    /// let slow5 = FileReader::open("examples/example2.slow5")?;
+    /// let read_number = slow5.header().aux_field_handle::<i32>("read_number")?;
+    /// let rec = slow5.get_record("r0")?;
+    /// assert_eq!(read_number.get(&rec)?, 4019);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an Err if there is no auxiliary field with that name, or its
+    /// type isn't T.
+    pub fn aux_field_handle<T>(
+        &self,
+        name: impl Into<Vec<u8>>,
+    ) -> Result<AuxFieldHandle<T>, Slow5Error>
+    where
+        T: PrimitiveField,
+    {
+        AuxFieldHandle::new(self, name.into())
+    }
+
+    /// Get the labels of an auxiliary enum field, in order of their index, ie
+    /// to look up the label of an [`EnumField`]. Same as
+    /// [`FileReader::iter_aux_enum_labels`], but available wherever a Header
+    /// is, ie from a [`FileWriter`].
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::FileReader;
+    /// use slow5::EnumField;
+    /// # fn main() -> anyhow::Result<()> {
+    /// let reader = FileReader::open("examples/example3.blow5")?;
+    /// let labels = reader.header().aux_enum_labels("end_reason")?;
+    /// let rec = reader.get_record("0035aaf9-a746-4bbd-97c4-390ddc27c756")?;
+    /// let EnumField(idx) = rec.get_aux_field("end_reason")?;
+    /// assert_eq!(labels[idx], "unknown");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an Err if there is no auxiliary enum field with that name, or
+    /// if a label isn't valid UTF-8.
+    ///
+    /// [`EnumField`]: crate::EnumField
+    /// [`FileReader::iter_aux_enum_labels`]: crate::FileReader::iter_aux_enum_labels
+    /// [`FileWriter`]: crate::FileWriter
+    pub fn aux_enum_labels<B>(&self, field: B) -> Result<Vec<String>, Slow5Error>
+    where
+        B: Into<Vec<u8>>,
+    {
+        aux_enum_labels(self, field)?
+            .into_iter()
+            .map(|label| Ok(label.to_str()?.to_string()))
+            .collect()
+    }
+
+    /// Return iterator over auxiliary field names and their [`FieldType`], in
+    /// the order they are stored in the header.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::FileReader;
+    /// use slow5::FieldType;
+    /// # fn main() -> anyhow::Result<()> {
+    /// let slow5 = FileReader::open("examples/example2.slow5")?;
+    /// let header = slow5.header();
+    /// for (name, field_type) in header.aux_fields_iter() {
+    ///     println!("{}: {:?}", String::from_utf8_lossy(name), field_type);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn aux_fields_iter(&self) -> AuxFieldsIter<'_> {
+        HeaderExt::aux_fields_iter(self)
+    }
+
+    /// Number of read groups in the header.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::FileReader;
+    /// # fn main() -> anyhow::Result<()> {
+    /// let slow5 = FileReader::open("examples/example.slow5")?;
+    /// assert_eq!(slow5.header().num_read_groups(), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn num_read_groups(&self) -> u32 {
+        HeaderExt::num_read_groups(self)
+    }
+
+    /// Return iterator over the value of an attribute for each read group, in
+    /// order of the read group. If the attribute isn't set for a read group,
+    /// the iterator yields None for that read group.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::FileReader;
+    /// # fn main() -> anyhow::Result<()> {
+    /// let slow5 = FileReader::open("examples/example.slow5")?;
+    /// let header = slow5.header();
+    /// let values = header
+    ///     .attr_values("bream_is_standard")?
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(values, vec![Some(&b"1"[..])]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an Err if the attribute contains an interior null byte.
+    pub fn attr_values<B>(&self, attr: B) -> Result<AttrValuesIter<'_>, Slow5Error>
+    where
+        B: Into<Vec<u8>>,
+    {
+        HeaderExt::attr_values(self, attr)
+    }
+
+    /// Format the header as the `#`-prefixed lines of a SLOW5 file, including
+    /// the column names of the records.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::FileReader;
+    /// # fn main() -> anyhow::Result<()> {
+    /// let slow5 = FileReader::open("examples/example.slow5")?;
+    /// let text = slow5.header().to_slow5_text()?;
+    /// assert!(text.starts_with("#slow5_version"));
+    /// assert!(text.lines().all(|line| line.starts_with('#') || line.starts_with('@')));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an Err if slow5lib fails to format the header, or it isn't
+    /// valid UTF-8.
+    pub fn to_slow5_text(&self) -> Result<String, Slow5Error> {
+        HeaderExt::to_slow5_text(self)
+    }
+
+    /// Copy the version, attributes of each read group, and auxiliary fields
+    /// of the header into a [`HeaderOwned`], which isn't tied to the file, so
+    /// it can be kept after the file is closed or sent to another thread.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::FileReader;
+    /// # fn main() -> anyhow::Result<()> {
+    /// let slow5 = FileReader::open("examples/example2.slow5")?;
+    /// let header = slow5.header().snapshot();
+    /// drop(slow5);
+    /// assert_eq!(header.num_read_groups(), 1);
+    /// assert!(header.aux_field_type("read_number").is_some());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn snapshot(&self) -> HeaderOwned {
+        let version = unsafe { &(*self.header).version };
+        let mut n = 0;
+        let keys = unsafe { slow5_get_hdr_keys(self.header, &mut n) };
+        let keys = if keys.is_null() {
+            Vec::new()
+        } else {
+            let names = (0..n as usize)
+                .map(|idx| {
+                    unsafe { CStr::from_ptr(*keys.add(idx)) }
+                        .to_bytes()
+                        .to_vec()
+                })
+                .collect();
+            unsafe { libc::free(keys as *mut c_void) };
+            names
+        };
+        let read_groups = (0..self.num_read_groups())
+            .map(|rg| {
+                keys.iter()
+                    .filter_map(|key| {
+                        let value = self.get_attribute(key.clone(), rg).ok()?;
+                        Some((key.clone(), value.to_vec()))
+                    })
+                    .collect()
+            })
+            .collect();
+        let aux_fields = self
+            .aux_fields_iter()
+            .map(|(name, field_type)| (name.to_vec(), field_type))
+            .collect();
+        HeaderOwned {
+            version: (version.major, version.minor, version.patch),
+            read_groups,
+            aux_fields,
+            compression: None,
+        }
+    }
+
     /// Add auxiliary field to header, and return a [`Field`] that can be
     /// used for setting the auxiliary field of [`crate::Record`].
     pub(crate) fn add_aux_field<B>(
@@ -209,13 +528,176 @@ impl<'a> Header<'a> {
             )
         };
         if res < 0 {
-            Err(Slow5Error::Unknown)
+            Err(Slow5Error::from_errno(res))
         } else {
             Ok(())
         }
     }
 }
 
+/// Copy of a [`Header`] that owns its data, made with [`snapshot`]. Unlike
+/// a Header, it can be kept after the file is closed and sent between
+/// threads.
+///
+/// With the `serde` feature, it can be serialized, ie to JSON:
+/// ```json
+/// {
+///   "version": "0.2.0",
+///   "record_compression": "zstd",
+///   "signal_compression": "svb-zd",
+///   "read_groups": [{"asic_id": "...", "run_id": "..."}],
+///   "aux_fields": [{"name": "read_number", "type": "int32_t"}]
+/// }
+/// ```
+/// Types are written as in a SLOW5 header, and the compression is null if
+/// the snapshot was made with [`snapshot`] instead of
+/// [`FileReader::header_snapshot`].
+///
+/// [`snapshot`]: crate::Header::snapshot
+/// [`FileReader::header_snapshot`]: crate::FileReader::header_snapshot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaderOwned {
+    version: (u8, u8, u8),
+    read_groups: Vec<BTreeMap<Vec<u8>, Vec<u8>>>,
+    aux_fields: Vec<(Vec<u8>, FieldType)>,
+    compression: Option<(RecordCompression, SignalCompression)>,
+}
+
+impl HeaderOwned {
+    pub(crate) fn with_compression(
+        mut self,
+        record: RecordCompression,
+        signal: SignalCompression,
+    ) -> Self {
+        self.compression = Some((record, signal));
+        self
+    }
+
+    /// SLOW5 specification version of the file, as (major, minor, patch)
+    pub fn version(&self) -> (u8, u8, u8) {
+        self.version
+    }
+
+    /// Record compression of the file, None if the snapshot was made from a
+    /// [`Header`] alone
+    pub fn record_compression(&self) -> Option<RecordCompression> {
+        self.compression.map(|(record, _)| record)
+    }
+
+    /// Signal compression of the file, None if the snapshot was made from a
+    /// [`Header`] alone
+    pub fn signal_compression(&self) -> Option<SignalCompression> {
+        self.compression.map(|(_, signal)| signal)
+    }
+
+    /// Number of read groups in the header
+    pub fn num_read_groups(&self) -> u32 {
+        self.read_groups.len() as u32
+    }
+
+    /// Value of an attribute for a read group, None if the attribute isn't
+    /// set for that read group
+    pub fn get_attribute<B: AsRef<[u8]>>(&self, attr: B, read_group: u32) -> Option<&[u8]> {
+        self.read_groups
+            .get(read_group as usize)?
+            .get(attr.as_ref())
+            .map(Vec::as_slice)
+    }
+
+    /// Attributes set for a read group and their values, sorted by name.
+    /// Returns None if there is no such read group.
+    pub fn attributes(&self, read_group: u32) -> Option<&BTreeMap<Vec<u8>, Vec<u8>>> {
+        self.read_groups.get(read_group as usize)
+    }
+
+    /// Auxiliary field names and their [`FieldType`], in the order they are
+    /// stored in the header
+    pub fn aux_fields(&self) -> &[(Vec<u8>, FieldType)] {
+        &self.aux_fields
+    }
+
+    /// Type of an auxiliary field, None if there is no field with that name
+    pub fn aux_field_type<B: AsRef<[u8]>>(&self, name: B) -> Option<&FieldType> {
+        self.aux_fields
+            .iter()
+            .find(|(field, _)| field == name.as_ref())
+            .map(|(_, field_type)| field_type)
+    }
+
+    /// Labels of an auxiliary enum field, in order of their index. Returns
+    /// None if there is no enum field with that name.
+    pub fn aux_enum_labels<B: AsRef<[u8]>>(&self, name: B) -> Option<&[Vec<u8>]> {
+        match self.aux_field_type(name)? {
+            FieldType::Enum(labels) => Some(labels),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for HeaderOwned {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeMap;
+
+        let lossy = |bytes: &[u8]| String::from_utf8_lossy(bytes).into_owned();
+        let (major, minor, patch) = self.version;
+        let read_groups = self
+            .read_groups
+            .iter()
+            .map(|attrs| {
+                attrs
+                    .iter()
+                    .map(|(attr, value)| (lossy(attr), lossy(value)))
+                    .collect::<BTreeMap<_, _>>()
+            })
+            .collect::<Vec<_>>();
+        let aux_fields = self
+            .aux_fields
+            .iter()
+            .map(|(name, field_type)| {
+                BTreeMap::from([("name", lossy(name)), ("type", field_type.type_name())])
+            })
+            .collect::<Vec<_>>();
+
+        let mut state = serializer.serialize_map(Some(5))?;
+        state.serialize_entry("version", &format!("{major}.{minor}.{patch}"))?;
+        state.serialize_entry(
+            "record_compression",
+            &self.record_compression().map(record_compression_name),
+        )?;
+        state.serialize_entry(
+            "signal_compression",
+            &self.signal_compression().map(signal_compression_name),
+        )?;
+        state.serialize_entry("read_groups", &read_groups)?;
+        state.serialize_entry("aux_fields", &aux_fields)?;
+        state.end()
+    }
+}
+
+// Names used by slow5tools for the compression methods
+#[cfg(feature = "serde")]
+fn record_compression_name(compression: RecordCompression) -> &'static str {
+    match compression {
+        RecordCompression::None => "none",
+        RecordCompression::Zlib => "zlib",
+        #[cfg(feature = "zstd")]
+        RecordCompression::ZStd => "zstd",
+    }
+}
+
+#[cfg(feature = "serde")]
+fn signal_compression_name(compression: SignalCompression) -> &'static str {
+    match compression {
+        SignalCompression::None => "none",
+        SignalCompression::StreamVByte => "svb-zd",
+        SignalCompression::ExZd => "ex-zd",
+    }
+}
+
 /// Iterator over auxiliary field names of a [`Header`], usually using
 /// [`aux_names_iter`]
 ///
@@ -262,10 +744,189 @@ impl<'a> Iterator for AuxNamesIter<'a> {
     }
 }
 
+/// Mutable access to the header of a [`FileWriter`] before it is written to
+/// the file, usually using [`header_mut`]
+///
+/// [`FileWriter`]: crate::FileWriter
+/// [`header_mut`]: crate::FileWriter::header_mut
+pub struct HeaderMut<'a> {
+    header: Header<'a>,
+    _writer: PhantomData<&'a mut ()>,
+}
+
+impl<'a> std::fmt::Debug for HeaderMut<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HeaderMut").finish()
+    }
+}
+
+impl<'a> HeaderExt for HeaderMut<'a> {
+    fn header(&self) -> Header<'_> {
+        Header::new(self.header.header)
+    }
+}
+
+impl<'a> HeaderMut<'a> {
+    pub(crate) fn new(header: *mut slow5_hdr_t) -> Self {
+        Self {
+            header: Header::new(header),
+            _writer: PhantomData,
+        }
+    }
+
+    /// Add an attribute to the header, the value is set for each read group
+    /// with [`set_attribute`].
+    ///
+    /// [`set_attribute`]: crate::HeaderMut::set_attribute
+    pub fn add_attribute<B>(&mut self, attr: B) -> Result<(), Slow5Error>
+    where
+        B: Into<Vec<u8>>,
+    {
+        self.header.add_attribute(attr)
+    }
+
+    /// Set the value of an attribute for a particular read group
+    pub fn set_attribute<B, C>(
+        &mut self,
+        attr: B,
+        value: C,
+        read_group: u32,
+    ) -> Result<(), Slow5Error>
+    where
+        B: Into<Vec<u8>>,
+        C: Into<Vec<u8>>,
+    {
+        self.header.set_attribute(attr, value, read_group)
+    }
+
+    /// Add a new read group, returning its index
+    pub fn add_read_group(&mut self) -> Result<u32, Slow5Error> {
+        let ret = unsafe { slow5_hdr_add_rg(self.header.header) };
+        if ret < 0 {
+            Err(Slow5Error::FailedAddReadGroup(self.num_read_groups()))
+        } else {
+            Ok(ret as u32)
+        }
+    }
+}
+
+/// Iterator over the value of an attribute for each read group of a
+/// [`Header`], usually using [`attr_values`]
+///
+/// [`attr_values`]: crate::Header::attr_values
+pub struct AttrValuesIter<'a> {
+    attr: CString,
+    read_group: u32,
+    header: *mut slow5_hdr_t,
+    _lifetime: PhantomData<&'a ()>,
+}
+
+impl<'a> std::fmt::Debug for AttrValuesIter<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AttrValuesIter")
+            .field("attr", &self.attr)
+            .field("read_group", &self.read_group)
+            .finish()
+    }
+}
+
+impl<'a> AttrValuesIter<'a> {
+    fn new(attr: CString, header: *mut slow5_hdr_t) -> Self {
+        Self {
+            attr,
+            read_group: 0,
+            header,
+            _lifetime: PhantomData,
+        }
+    }
+}
+
+impl<'a> Iterator for AttrValuesIter<'a> {
+    type Item = Option<&'a [u8]>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.read_group >= unsafe { (*self.header).num_read_groups } {
+            return None;
+        }
+        let data = unsafe { slow5_hdr_get(self.attr.as_ptr(), self.read_group, self.header) };
+        self.read_group += 1;
+        if data.is_null() {
+            Some(None)
+        } else {
+            Some(Some(unsafe { CStr::from_ptr(data) }.to_bytes()))
+        }
+    }
+}
+
+/// Iterator over auxiliary field names and their [`FieldType`] of a
+/// [`Header`], usually using [`aux_fields_iter`]
+///
+/// [`aux_fields_iter`]: crate::Header::aux_fields_iter
+pub struct AuxFieldsIter<'a> {
+    idx: usize,
+    aux_meta: *mut slow5_aux_meta,
+    _lifetime: PhantomData<&'a ()>,
+}
+
+impl<'a> std::fmt::Debug for AuxFieldsIter<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuxFieldsIter")
+            .field("idx", &self.idx)
+            .finish()
+    }
+}
+
+impl<'a> AuxFieldsIter<'a> {
+    fn new(aux_meta: *mut slow5_aux_meta) -> Self {
+        Self {
+            idx: 0,
+            aux_meta,
+            _lifetime: PhantomData,
+        }
+    }
+}
+
+impl<'a> Iterator for AuxFieldsIter<'a> {
+    type Item = (&'a [u8], FieldType);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.aux_meta.is_null() {
+            return None;
+        }
+        let aux_meta = unsafe { &*self.aux_meta };
+        // Skip over any types we don't know about
+        while self.idx < aux_meta.num as usize {
+            let idx = self.idx;
+            self.idx += 1;
+            let name = unsafe { CStr::from_ptr(*aux_meta.attrs.add(idx)) };
+            let aux_type = unsafe { *aux_meta.types.add(idx) };
+            let field_type = if aux_type == slow5_aux_type_SLOW5_ENUM {
+                let labels = unsafe { *aux_meta.enum_labels.add(idx) };
+                let num_labels = unsafe { *aux_meta.enum_num_labels.add(idx) };
+                let labels = (0..num_labels as usize)
+                    .map(|i| {
+                        unsafe { CStr::from_ptr(*labels.add(i)) }
+                            .to_bytes()
+                            .to_vec()
+                    })
+                    .collect();
+                Some(FieldType::Enum(labels))
+            } else {
+                FieldType::from_slow5_t(aux_type)
+            };
+            if let Some(field_type) = field_type {
+                return Some((name.to_bytes(), field_type));
+            }
+        }
+        None
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::collections::HashSet;
 
+    use super::*;
     use crate::{FileReader, HeaderExt};
 
     #[test]
@@ -292,4 +953,108 @@ mod test {
         let mut aux_names = slow5.aux_names_iter();
         assert!(aux_names.next().is_none());
     }
+
+    #[test]
+    fn test_aux_fields_iter() -> anyhow::Result<()> {
+        let slow5 = FileReader::open("examples/example2.slow5")?;
+        let header = slow5.header();
+        assert_eq!(header.aux_fields_iter().count(), 5);
+        assert_eq!(header.aux_field_type("read_number")?, FieldType::Int32);
+        assert_eq!(header.aux_field_type("start_time")?, FieldType::Uint64);
+        assert_eq!(header.aux_field_type("channel_number")?, FieldType::Str);
+        assert!(header.aux_field_type("not a field").is_err());
+
+        let slow5 = FileReader::open("examples/example3.blow5")?;
+        let end_reason = slow5.aux_field_type("end_reason")?;
+        assert!(matches!(end_reason, FieldType::Enum(_)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_aux_enum_labels() -> anyhow::Result<()> {
+        let slow5 = FileReader::open("examples/example3.blow5")?;
+        let labels = slow5.header().aux_enum_labels("end_reason")?;
+        let expected = slow5
+            .iter_aux_enum_labels("end_reason")?
+            .map(|label| String::from_utf8(label.to_vec()))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(labels, expected);
+        assert!(slow5.header().aux_enum_labels("read_number").is_err());
+        assert!(slow5.header().aux_enum_labels("not a field").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_snapshot() -> anyhow::Result<()> {
+        let slow5 = FileReader::open("examples/example.slow5")?;
+        let header = slow5.header().snapshot();
+        assert_eq!(
+            header.get_attribute("asic_id", 0),
+            Some(slow5.get_attribute("asic_id", 0)?)
+        );
+        let keys = slow5.iter_attr_keys()?.count();
+        assert_eq!(header.attributes(0).map(|attrs| attrs.len()), Some(keys));
+        assert!(header.attributes(1).is_none());
+        assert!(header.aux_fields().is_empty());
+
+        let slow5 = FileReader::open("examples/example3.blow5")?;
+        let labels = slow5.header().aux_enum_labels("end_reason")?;
+        let header = slow5.header().snapshot();
+        drop(slow5);
+        let header = std::thread::spawn(move || header).join().unwrap();
+        let snapshot_labels = header.aux_enum_labels("end_reason").unwrap();
+        assert!(labels
+            .iter()
+            .map(|label| label.as_bytes())
+            .eq(snapshot_labels));
+        assert_eq!(header.aux_field_type("not a field"), None);
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize_snapshot() -> anyhow::Result<()> {
+        let slow5 = FileReader::open("examples/example2.slow5")?;
+        let header = slow5.header_snapshot();
+        let json: serde_json::Value = serde_json::to_value(&header)?;
+        assert!(json["version"].as_str().unwrap().starts_with("0."));
+        assert_eq!(json["record_compression"], "none");
+        assert_eq!(json["signal_compression"], "none");
+        assert_eq!(json["read_groups"].as_array().unwrap().len(), 1);
+        let aux_fields = json["aux_fields"].as_array().unwrap();
+        assert_eq!(aux_fields.len(), 5);
+        assert!(aux_fields.contains(&serde_json::json!({
+            "name": "read_number",
+            "type": "int32_t",
+        })));
+
+        let json = serde_json::to_value(slow5.header().snapshot())?;
+        assert!(json["record_compression"].is_null());
+        Ok(())
+    }
+
+    #[test]
+    fn test_attr_values() -> anyhow::Result<()> {
+        let slow5 = FileReader::open("examples/example.slow5")?;
+        let header = slow5.header();
+        assert_eq!(header.num_read_groups(), 1);
+        let values = header.attr_values("asic_id")?.collect::<Vec<_>>();
+        assert_eq!(values.len(), 1);
+        assert!(values[0].is_some());
+        let values = header.attr_values("not an attribute")?.collect::<Vec<_>>();
+        assert_eq!(values, vec![None]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_slow5_text() -> anyhow::Result<()> {
+        let reader = FileReader::open("examples/example2.slow5")?;
+        let text = reader.header().to_slow5_text()?;
+        assert!(text.starts_with("#slow5_version"));
+        assert!(text.contains("#num_read_groups\t1\n"));
+        let columns = text.lines().last().unwrap();
+        assert!(columns.starts_with("#read_id\tread_group"));
+        assert!(columns.contains("read_number"));
+        Ok(())
+    }
 }
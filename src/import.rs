@@ -0,0 +1,410 @@
+//! Import records from text, ie the TSV written by [`to_tsv`] or JSON lines.
+//!
+//! The auxiliary fields declared in the header of the [`FileWriter`] act as
+//! the schema, each value is parsed as the declared type and rejected if it
+//! doesn't fit.
+//!
+//! [`to_tsv`]: crate::export::to_tsv
+use std::{collections::HashMap, io::BufRead, str::FromStr};
+
+use crate::{
+    error::Slow5Error, header::HeaderExt, record::BuilderError, EnumField, FieldType, FileWriter,
+    Record, RecordBuilder, RecordExt,
+};
+
+// Missing values are written the same way as in SLOW5 files
+const MISSING: &str = ".";
+
+fn parse_error(line: usize, reason: impl Into<String>) -> Slow5Error {
+    Slow5Error::ImportParse {
+        line,
+        reason: reason.into(),
+    }
+}
+
+fn parse_array<T: FromStr>(value: &str) -> Result<Vec<T>, T::Err> {
+    if value.is_empty() {
+        return Ok(Vec::new());
+    }
+    value.split(',').map(str::parse).collect()
+}
+
+macro_rules! stage_aux {
+    ($builder:expr, $name:expr, $field_type:expr, $value:expr, $(($variant:ident, $rtype:ty)),*) => {
+        paste::paste! {
+            match $field_type {
+                $(
+                    FieldType::$variant => {
+                        $builder.aux_field($name, $value.parse::<$rtype>().ok()?);
+                    }
+                    FieldType::[<$variant Array>] => {
+                        $builder.aux_field($name, parse_array::<$rtype>($value).ok()?);
+                    }
+                )*
+                FieldType::Char => {
+                    let mut chars = $value.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(c), None) => $builder.aux_field($name, c),
+                        _ => return None,
+                    };
+                }
+                FieldType::Str => {
+                    $builder.aux_field($name, $value.to_string());
+                }
+                FieldType::Enum(labels) => {
+                    // Accept either the label or its index
+                    let idx = labels
+                        .iter()
+                        .position(|label| label == $value.as_bytes())
+                        .or_else(|| $value.parse().ok().filter(|&idx: &usize| idx < labels.len()))?;
+                    $builder.aux_field($name, EnumField(idx));
+                }
+            }
+        }
+    };
+}
+
+// Stage the value of an auxiliary field, None if it doesn't match the type
+fn stage_aux(
+    builder: &mut RecordBuilder,
+    name: &str,
+    field_type: &FieldType,
+    value: &str,
+) -> Option<()> {
+    stage_aux!(
+        builder,
+        name,
+        field_type,
+        value,
+        (Int8, i8),
+        (Int16, i16),
+        (Int32, i32),
+        (Int64, i64),
+        (Uint8, u8),
+        (Uint16, u16),
+        (Uint32, u32),
+        (Uint64, u64),
+        (Float, f32),
+        (Double, f64)
+    );
+    Some(())
+}
+
+// Build a record from pairs of field names and their values as text, where
+// None is a missing value
+fn build_record<'a, I>(
+    writer: &mut FileWriter,
+    schema: &HashMap<Vec<u8>, FieldType>,
+    fields: I,
+    line: usize,
+) -> Result<Record, Slow5Error>
+where
+    I: IntoIterator<Item = (&'a str, Option<&'a str>)>,
+{
+    let mut builder = RecordBuilder::default();
+    let mut len_raw_signal = None;
+    for (name, value) in fields {
+        let invalid = || parse_error(line, format!("invalid value for {name}"));
+        let Some(value) = value else {
+            if schema.contains_key(name.as_bytes()) {
+                continue;
+            }
+            return Err(parse_error(line, format!("missing value for {name}")));
+        };
+        match name {
+            "read_id" => {
+                builder.read_id(value);
+            }
+            "read_group" => {
+                builder.read_group(value.parse().map_err(|_| invalid())?);
+            }
+            "digitisation" => {
+                builder.digitisation(value.parse().map_err(|_| invalid())?);
+            }
+            "offset" => {
+                builder.offset(value.parse().map_err(|_| invalid())?);
+            }
+            "range" => {
+                builder.range(value.parse().map_err(|_| invalid())?);
+            }
+            "sampling_rate" => {
+                builder.sampling_rate(value.parse().map_err(|_| invalid())?);
+            }
+            "len_raw_signal" => {
+                len_raw_signal = Some(value.parse::<usize>().map_err(|_| invalid())?);
+            }
+            "raw_signal" => {
+                let signal = parse_array::<i16>(value).map_err(|_| invalid())?;
+                builder.raw_signal(&signal);
+            }
+            _ => {
+                let field_type = schema
+                    .get(name.as_bytes())
+                    .ok_or_else(|| parse_error(line, format!("unknown field {name}")))?;
+                stage_aux(&mut builder, name, field_type, value).ok_or_else(invalid)?;
+            }
+        }
+    }
+    let rec = builder.build_with_writer(writer).map_err(|e| match e {
+        Slow5Error::Builder(BuilderError::RequiredValueUnset(name)) => {
+            parse_error(line, format!("missing field {name}"))
+        }
+        e => e,
+    })?;
+    if len_raw_signal.is_some_and(|len| len != rec.len_signal() as usize) {
+        return Err(parse_error(line, "len_raw_signal doesn't match raw_signal"));
+    }
+    Ok(rec)
+}
+
+fn writer_schema(writer: &FileWriter) -> HashMap<Vec<u8>, FieldType> {
+    writer
+        .aux_fields_iter()
+        .map(|(name, field_type)| (name.to_vec(), field_type))
+        .collect()
+}
+
+/// Parse a TSV file, with a header line of the column names, into records
+/// and write them with writer. Reads the output of [`to_tsv`] when the
+/// signal is included as raw values.
+///
+/// The columns read_id, read_group, digitisation, offset, range,
+/// sampling_rate, and raw_signal are required. If there is a len_raw_signal
+/// column, it is checked against the length of the signal. Every other column
+/// must be an auxiliary field in the header of the writer, and values of "."
+/// leave the field unset. Returns the number of records written.
+///
+/// # Example
+/// ```
+/// # use assert_fs::TempDir;
+/// # use assert_fs::fixture::PathChild;
+/// use slow5::{import::from_tsv, FieldType, FileWriter};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// # let tmp_dir = TempDir::new()?;
+/// # let file_path = tmp_dir.child("test.blow5");
+/// let tsv = "read_id\tread_group\tdigitisation\toffset\trange\tsampling_rate\traw_signal\tmedian\n\
+///            read_0\t0\t4096\t4\t12\t4000\t0,1,2,3\t1.5\n";
+/// let mut writer = FileWriter::options()
+///     .aux("median", FieldType::Float)
+///     .create(file_path)?;
+/// assert_eq!(from_tsv(tsv.as_bytes(), &mut writer)?, 1);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`to_tsv`]: crate::export::to_tsv
+///
+/// # Errors
+/// Returns [`Slow5Error::ImportParse`] if a column is missing or isn't in the
+/// header, or if a value doesn't match its type. Returns an Err if a line
+/// fails to be read or a record fails to be written.
+pub fn from_tsv<R>(reader: R, writer: &mut FileWriter) -> Result<usize, Slow5Error>
+where
+    R: BufRead,
+{
+    let schema = writer_schema(writer);
+    let mut lines = reader.lines();
+    let columns = match lines.next() {
+        Some(header) => header?,
+        None => return Err(parse_error(1, "missing header line")),
+    };
+    let columns = columns.split('\t').collect::<Vec<_>>();
+
+    let mut n = 0;
+    for (idx, line) in lines.enumerate() {
+        let line_num = idx + 2;
+        let line = line?;
+        if line.is_empty() {
+            continue;
+        }
+        let values = line.split('\t').collect::<Vec<_>>();
+        if values.len() != columns.len() {
+            return Err(parse_error(line_num, "wrong number of columns"));
+        }
+        let fields = columns.iter().zip(values).map(|(name, value)| {
+            let value = if value == MISSING && schema.contains_key(name.as_bytes()) {
+                None
+            } else {
+                Some(value)
+            };
+            (*name, value)
+        });
+        let rec = build_record(writer, &schema, fields, line_num)?;
+        writer.add_record(&rec)?;
+        n += 1;
+    }
+    Ok(n)
+}
+
+/// Parse JSON lines, one object per record, into records and write them with
+/// writer.
+///
+/// Each object needs the keys read_id, read_group, digitisation, offset,
+/// range, sampling_rate, and raw_signal. Every other key must be an
+/// auxiliary field in the header of the writer, and null leaves the field
+/// unset. Enum fields can be either the label or its index. Returns the number
+/// of records written.
+///
+/// # Example
+/// ```
+/// # use assert_fs::TempDir;
+/// # use assert_fs::fixture::PathChild;
+/// use slow5::{import::from_jsonl, FieldType, FileWriter};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// # let tmp_dir = TempDir::new()?;
+/// # let file_path = tmp_dir.child("test.blow5");
+/// let jsonl = r#"{"read_id": "read_0", "read_group": 0, "digitisation": 4096, "offset": 4, "range": 12, "sampling_rate": 4000, "raw_signal": [0, 1, 2, 3], "median": 1.5}"#;
+/// let mut writer = FileWriter::options()
+///     .aux("median", FieldType::Float)
+///     .create(file_path)?;
+/// assert_eq!(from_jsonl(jsonl.as_bytes(), &mut writer)?, 1);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// Returns [`Slow5Error::ImportParse`] if a line isn't a JSON object, a key
+/// is missing or isn't in the header, or if a value doesn't match its type.
+/// Returns an Err if a line fails to be read or a record fails to be
+/// written.
+#[cfg(feature = "jsonl")]
+pub fn from_jsonl<R>(reader: R, writer: &mut FileWriter) -> Result<usize, Slow5Error>
+where
+    R: BufRead,
+{
+    use serde_json::{Map, Value};
+
+    // Values as text, so they are parsed the same way as TSV
+    fn to_text(value: &Value) -> Option<Option<String>> {
+        match value {
+            Value::Null => Some(None),
+            Value::String(s) => Some(Some(s.clone())),
+            Value::Number(n) => Some(Some(n.to_string())),
+            Value::Array(values) => values
+                .iter()
+                .map(|v| match v {
+                    Value::Number(n) => Some(n.to_string()),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()
+                .map(|values| Some(values.join(","))),
+            Value::Bool(_) | Value::Object(_) => None,
+        }
+    }
+
+    let schema = writer_schema(writer);
+    let mut n = 0;
+    for (idx, line) in reader.lines().enumerate() {
+        let line_num = idx + 1;
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let object: Map<String, Value> = serde_json::from_str(&line)
+            .map_err(|e| parse_error(line_num, format!("invalid JSON object: {e}")))?;
+        let fields = object
+            .iter()
+            .map(|(name, value)| {
+                to_text(value)
+                    .map(|value| (name.as_str(), value))
+                    .ok_or_else(|| parse_error(line_num, format!("invalid value for {name}")))
+            })
+            .collect::<Result<Vec<_>, Slow5Error>>()?;
+        let fields = fields.iter().map(|(name, value)| (*name, value.as_deref()));
+        let rec = build_record(writer, &schema, fields, line_num)?;
+        writer.add_record(&rec)?;
+        n += 1;
+    }
+    Ok(n)
+}
+
+#[cfg(test)]
+mod test {
+    use assert_fs::{fixture::PathChild, TempDir};
+
+    use super::*;
+    use crate::{
+        export::{to_tsv, ExportOptions},
+        FileReader,
+    };
+
+    #[test]
+    fn test_tsv_roundtrip() -> anyhow::Result<()> {
+        let mut reader = FileReader::open("examples/example2.slow5")?;
+        let mut tsv = Vec::new();
+        to_tsv(&mut reader, &mut tsv, &ExportOptions::default())?;
+
+        let tmp_dir = TempDir::new()?;
+        let file_path = tmp_dir.child("test.blow5");
+        let mut writer = FileWriter::create_from(&reader, &file_path)?;
+        assert_eq!(from_tsv(tsv.as_slice(), &mut writer)?, 5);
+        writer.close()?;
+
+        let mut imported = FileReader::open(&file_path)?;
+        for (rec, expected) in imported.records().zip(reader.records()) {
+            let (rec, expected) = (rec?, expected?);
+            assert_eq!(rec.read_id(), expected.read_id());
+            assert_eq!(
+                rec.raw_signal_iter().collect::<Vec<_>>(),
+                expected.raw_signal_iter().collect::<Vec<_>>()
+            );
+            assert_eq!(
+                rec.get_aux_field_opt::<u32>("read_number")?,
+                expected.get_aux_field_opt::<u32>("read_number")?
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_tsv_schema() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let file_path = tmp_dir.child("test.blow5");
+        let mut writer = FileWriter::options()
+            .aux("read_number", FieldType::Uint32)
+            .create(&file_path)?;
+        let header = "read_id\tread_group\tdigitisation\toffset\trange\tsampling_rate\traw_signal";
+
+        let tsv = format!("{header}\tread_number\nread_0\t0\t4096\t4\t12\t4000\t0,1\t1.5\n");
+        let res = from_tsv(tsv.as_bytes(), &mut writer);
+        assert!(matches!(res, Err(Slow5Error::ImportParse { line: 2, .. })));
+
+        let tsv = format!("{header}\tnot_a_field\nread_0\t0\t4096\t4\t12\t4000\t0,1\t1\n");
+        let res = from_tsv(tsv.as_bytes(), &mut writer);
+        assert!(matches!(res, Err(Slow5Error::ImportParse { line: 2, .. })));
+
+        let tsv = format!("{header}\tread_number\nread_0\t0\t4096\t4\t12\t4000\t0,1\t.\n");
+        assert_eq!(from_tsv(tsv.as_bytes(), &mut writer)?, 1);
+        Ok(())
+    }
+
+    #[cfg(feature = "jsonl")]
+    #[test]
+    fn test_from_jsonl() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let file_path = tmp_dir.child("test.blow5");
+        let mut writer = FileWriter::options()
+            .aux("channel_number", FieldType::Str)
+            .aux("end_reason", vec!["unknown", "signal_positive"])
+            .create(&file_path)?;
+        let jsonl = concat!(
+            r#"{"read_id": "r0", "read_group": 0, "digitisation": 4096, "offset": 4, "range": 12, "sampling_rate": 4000, "raw_signal": [0, 1], "channel_number": "1", "end_reason": "signal_positive"}"#,
+            "\n",
+            r#"{"read_id": "r1", "read_group": 0, "digitisation": 4096, "offset": 4, "range": 12, "sampling_rate": 4000, "raw_signal": [2, 3], "channel_number": null, "end_reason": 0}"#,
+        );
+        assert_eq!(from_jsonl(jsonl.as_bytes(), &mut writer)?, 2);
+
+        let jsonl = r#"{"read_id": "r2", "read_group": 0}"#;
+        let res = from_jsonl(jsonl.as_bytes(), &mut writer);
+        assert!(matches!(res, Err(Slow5Error::ImportParse { line: 1, .. })));
+        writer.close()?;
+
+        let reader = FileReader::open(&file_path)?;
+        let rec = reader.get_record("r0")?;
+        assert_eq!(rec.get_aux_field::<String>("channel_number")?, "1");
+        assert_eq!(rec.get_aux_field::<EnumField>("end_reason")?.0, 1);
+        Ok(())
+    }
+}
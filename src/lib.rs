@@ -1,34 +1,164 @@
 #![allow(unexpected_cfgs)]
-#![doc = include_str!("../README.md")]
+// The README's examples use FileReader, which needs slow5lib
+#![cfg_attr(feature = "slow5lib", doc = include_str!("../README.md"))]
+#![cfg_attr(
+    not(feature = "slow5lib"),
+    doc = "Read and write SLOW5 files. Without the `slow5lib` feature, only the pure \
+           Rust reader and writer in `pure` are available."
+)]
 #![warn(missing_docs, missing_debug_implementations, unreachable_pub)]
 #![cfg_attr(doc_auto_cfg, feature(doc_auto_cfg))]
 
+#[cfg(feature = "slow5lib")]
+mod anonymize;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+#[cfg(feature = "async")]
+mod async_io;
+#[cfg(feature = "slow5lib")]
 mod auxiliary;
+#[cfg(feature = "bam")]
+pub mod bam;
+#[cfg(feature = "slow5lib")]
+pub mod batch;
+mod calibration;
+#[cfg(feature = "slow5lib")]
+mod cat;
+#[cfg(feature = "slow5lib")]
+pub mod catalog;
+#[cfg(feature = "slow5lib")]
 mod compression;
+#[cfg(feature = "slow5lib")]
+mod convert;
+#[cfg(feature = "slow5lib")]
+mod dedupe;
+#[cfg(feature = "slow5lib")]
+mod demux;
+#[cfg(feature = "slow5lib")]
+pub mod diff;
+#[cfg(feature = "slow5lib")]
+mod encode;
 mod error;
+#[cfg(feature = "slow5lib")]
+pub mod export;
+#[cfg(feature = "slow5lib")]
+mod field;
+mod field_type;
+#[cfg(feature = "slow5lib")]
+mod filter;
+#[cfg(feature = "slow5lib")]
 mod header;
+#[cfg(feature = "slow5lib")]
+pub mod import;
+#[cfg(feature = "slow5lib")]
 mod log;
+#[cfg(feature = "slow5lib")]
+pub mod pairing;
+#[cfg(feature = "slow5lib")]
+pub mod parallel;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+#[cfg(feature = "pod5")]
+pub mod pod5;
+#[cfg(feature = "polars")]
+pub mod polars;
+#[cfg(feature = "slow5lib")]
+mod pool;
+#[cfg(feature = "slow5lib")]
+mod progress;
+#[cfg(feature = "pure-rust")]
+pub mod pure;
+#[cfg(feature = "slow5lib")]
 mod reader;
+#[cfg(feature = "slow5lib")]
 mod record;
+#[cfg(feature = "remote")]
+pub mod remote;
+#[cfg(feature = "slow5lib")]
+pub mod signal;
+#[cfg(feature = "slow5lib")]
+mod sort;
+#[cfg(feature = "slow5lib")]
+mod split;
+#[cfg(feature = "slow5lib")]
+pub mod stats;
+#[cfg(feature = "slow5lib")]
+mod stream;
+#[cfg(feature = "slow5lib")]
+mod subset;
+#[cfg(feature = "slow5lib")]
+mod verify;
+#[cfg(feature = "slow5lib")]
 mod writer;
 
+#[cfg(feature = "slow5lib")]
 use std::ffi::CString;
 
-pub use auxiliary::{AuxField, AuxFieldSetExt, EnumField, FieldType};
+#[cfg(feature = "slow5lib")]
+pub use anonymize::{anonymize, AnonymizeOptions, AttrAction};
+#[cfg(feature = "async")]
+pub use async_io::{AsyncFileReader, AsyncFileWriter, RecordStream};
+#[cfg(feature = "slow5lib")]
+#[doc(hidden)]
+pub use auxiliary::Slow5AuxType;
+#[cfg(feature = "slow5lib")]
+pub use auxiliary::{AuxField, AuxFieldSetExt};
+pub use calibration::{to_picoamps, to_raw_signal};
+#[cfg(feature = "slow5lib")]
+pub use cat::{cat, cat_with_progress};
+#[cfg(feature = "slow5lib")]
 pub use compression::{RecordCompression, SignalCompression};
+#[cfg(feature = "slow5lib")]
+pub use convert::{convert, recompress, recompress_with_progress};
+#[cfg(feature = "slow5lib")]
+pub use dedupe::{dedupe, DedupeReport};
+#[cfg(feature = "slow5lib")]
+pub use demux::demux;
 pub use error::Slow5Error;
-pub use header::{AuxNamesIter, Header, HeaderExt};
-pub use reader::{AuxEnumLabelIter, FileReader, ReadIdIter};
+#[cfg(feature = "slow5lib")]
+pub use field::{AuxFieldHandle, PrimitiveField};
+pub use field_type::{EnumField, FieldType};
+#[cfg(feature = "slow5lib")]
+pub use filter::{Filter, FilteredIter};
+#[cfg(feature = "slow5lib")]
+pub use header::{
+    AttrValuesIter, AuxFieldsIter, AuxNamesIter, Header, HeaderExt, HeaderMut, HeaderOwned,
+};
+#[cfg(feature = "slow5lib")]
+pub use log::{forward_slow5lib_logs, slow5_set_log_level, LogForwarder, LogLevel};
+#[cfg(feature = "slow5lib")]
+pub use pool::ReadPool;
+#[cfg(feature = "slow5lib")]
+pub use progress::{CancellationToken, ProgressSink};
+#[cfg(feature = "slow5lib")]
+pub use reader::{
+    AuxEnumLabelIter, FileFormat, FileReader, IndexIter, OpenOptions, RawRecord, ReadIdIter,
+};
+#[cfg(feature = "slow5lib")]
 pub use record::{
-    to_picoamps, to_raw_signal, PicoAmpsSignalIter, RawSignalIter, Record, RecordBuilder,
-    RecordExt, RecordIter,
+    BuilderError, PicoAmpsSignalIter, RawSignalIter, Record, RecordBuilder, RecordChunks,
+    RecordExt, RecordIter, RecordView, RecordsInto, RecoverIter, ShardIter,
 };
+#[cfg(feature = "slow5lib")]
+#[doc(hidden)]
+pub use record::{RecPtr, RecordPointer};
+#[cfg(feature = "slow5lib")]
+pub use sort::{sort, SortKey, SortOptions};
+#[cfg(feature = "slow5lib")]
+pub use split::{split, SplitMode};
+#[cfg(feature = "slow5lib")]
+pub use stats::Stats;
+#[cfg(feature = "slow5lib")]
+pub use subset::{subset, SubsetOptions, SubsetReport};
+#[cfg(feature = "slow5lib")]
+pub use verify::{VerifyIssue, VerifyLevel, VerifyReport};
+#[cfg(feature = "slow5lib")]
 pub use writer::{FileWriter, WriteOptions};
-pub use log::{LogLevel, slow5_set_log_level};
 
+#[cfg(feature = "slow5lib")]
 pub(crate) fn to_cstring<T: Into<Vec<u8>>>(x: T) -> Result<CString, Slow5Error> {
     CString::new(x).map_err(Slow5Error::InteriorNul)
 }
 
-#[cfg(doctest)]
+#[cfg(all(doctest, feature = "slow5lib"))]
 doc_comment::doctest!("../README.md", readme);
@@ -1,13 +1,29 @@
 #![allow(dead_code)]
 
+use std::{
+    cell::Cell,
+    fs::File,
+    io::{self, BufRead, BufReader, Write},
+    os::unix::prelude::FromRawFd,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Mutex, MutexGuard, PoisonError,
+    },
+    thread::{self, JoinHandle},
+};
+
+use ::log::{Level, LevelFilter};
+use libc::c_int;
 use slow5lib_sys::{
     slow5_log_level_opt_SLOW5_LOG_DBUG, slow5_log_level_opt_SLOW5_LOG_ERR,
     slow5_log_level_opt_SLOW5_LOG_INFO, slow5_log_level_opt_SLOW5_LOG_OFF,
     slow5_log_level_opt_SLOW5_LOG_VERB, slow5_log_level_opt_SLOW5_LOG_WARN,
 };
 
+use crate::error::Slow5Error;
+
 /// Set the log level based on desired verbosity.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LogLevel {
     /// No logs will be generated.
     Off,
@@ -24,7 +40,7 @@ pub enum LogLevel {
 }
 
 impl LogLevel {
-    fn to_slow5_log_lvl(&self) -> u32 {
+    fn to_slow5_log_lvl(self) -> u32 {
         match self {
             LogLevel::Off => slow5_log_level_opt_SLOW5_LOG_OFF,
             LogLevel::Error => slow5_log_level_opt_SLOW5_LOG_ERR,
@@ -39,8 +55,277 @@ impl LogLevel {
 /// Sets the global variable for slow5lib to control the
 /// libraries logging verbosity. These represent internal logs
 /// of the slow5lib and doesn't interact with logging done by Rust
-/// crates.
+/// crates, unless they are forwarded with [`forward_slow5lib_logs`].
+///
+/// To change the log level for a single file, see [`OpenOptions::log_level`]
+/// and [`WriteOptions::log_level`].
+///
+/// [`OpenOptions::log_level`]: crate::OpenOptions::log_level
+/// [`WriteOptions::log_level`]: crate::WriteOptions::log_level
 pub fn slow5_set_log_level(lvl: LogLevel) {
-    let slow5_lvl = lvl.to_slow5_log_lvl();
-    unsafe { slow5lib_sys::slow5_set_log_level(slow5_lvl) }
+    set_global_log_level(lvl.to_slow5_log_lvl());
+}
+
+// Log level for the whole process, restored after a call on a file with its
+// own log level. slow5lib's default is SLOW5_LOG_INFO.
+static LOG_LEVEL: AtomicU32 = AtomicU32::new(slow5_log_level_opt_SLOW5_LOG_INFO);
+
+// Held while a file with its own log level has changed slow5lib's log level
+static LOG_LEVEL_LOCK: Mutex<()> = Mutex::new(());
+
+thread_local! {
+    // Whether this thread holds LOG_LEVEL_LOCK, so nested calls don't deadlock
+    static HOLDS_LOCK: Cell<bool> = const { Cell::new(false) };
+}
+
+fn set_global_log_level(lvl: u32) {
+    LOG_LEVEL.store(lvl, Ordering::SeqCst);
+    if HOLDS_LOCK.with(Cell::get) {
+        // Set when the LogLevelGuard is dropped
+        return;
+    }
+    let _lock = LOG_LEVEL_LOCK
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    unsafe { slow5lib_sys::slow5_set_log_level(lvl) }
+}
+
+// Sets slow5lib's log level for a file until dropped, then restores the log
+// level of the process. Does nothing if the file doesn't have its own log
+// level, or the thread already holds a LogLevelGuard.
+pub(crate) struct LogLevelGuard(Option<MutexGuard<'static, ()>>);
+
+impl LogLevelGuard {
+    pub(crate) fn new(lvl: Option<LogLevel>) -> Self {
+        let Some(lvl) = lvl else {
+            return Self(None);
+        };
+        if HOLDS_LOCK.with(Cell::get) {
+            return Self(None);
+        }
+        let lock = LOG_LEVEL_LOCK
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        HOLDS_LOCK.with(|held| held.set(true));
+        unsafe { slow5lib_sys::slow5_set_log_level(lvl.to_slow5_log_lvl()) }
+        Self(Some(lock))
+    }
+}
+
+impl Drop for LogLevelGuard {
+    fn drop(&mut self) {
+        if let Some(lock) = self.0.take() {
+            unsafe { slow5lib_sys::slow5_set_log_level(LOG_LEVEL.load(Ordering::SeqCst)) }
+            HOLDS_LOCK.with(|held| held.set(false));
+            drop(lock);
+        }
+    }
+}
+
+// Set while slow5lib logs are forwarded, so opening a file doesn't silence them
+static FORWARDING: AtomicBool = AtomicBool::new(false);
+
+// If we aren't testing or running in debug mode, silence slow5lib logs, unless
+// they are being forwarded to the log crate
+pub(crate) fn silence_slow5lib_logs() {
+    #[cfg(any(not(test), not(debug_assertions)))]
+    if !FORWARDING.load(Ordering::SeqCst) {
+        set_global_log_level(slow5_log_level_opt_SLOW5_LOG_OFF);
+    }
+}
+
+/// Forward the messages slow5lib prints to stderr to the [`log`] crate, with
+/// the `slow5lib` target. The slow5lib log level is set from
+/// [`log::max_level`], so set up the logger before calling this.
+///
+/// slow5lib only writes its messages to stderr, so stderr is redirected until
+/// the returned [`LogForwarder`] is dropped. Anything else written to stderr
+/// is passed through unchanged.
+///
+/// # Example
+/// ```
+/// # use slow5::FileReader;
+/// # fn main() -> anyhow::Result<()> {
+/// let _forwarder = slow5::forward_slow5lib_logs()?;
+/// let reader = FileReader::open("examples/example.slow5")?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// Returns an Err if slow5lib logs are already being forwarded, or stderr
+/// fails to be redirected.
+///
+/// [`log`]: https://docs.rs/log
+/// [`log::max_level`]: https://docs.rs/log/latest/log/fn.max_level.html
+pub fn forward_slow5lib_logs() -> Result<LogForwarder, Slow5Error> {
+    if FORWARDING.swap(true, Ordering::SeqCst) {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            "slow5lib logs are already being forwarded",
+        )
+        .into());
+    }
+    match LogForwarder::new() {
+        Ok(forwarder) => {
+            let lvl = match ::log::max_level() {
+                LevelFilter::Off => LogLevel::Off,
+                LevelFilter::Error => LogLevel::Error,
+                LevelFilter::Warn => LogLevel::Warn,
+                LevelFilter::Info => LogLevel::Info,
+                LevelFilter::Debug => LogLevel::Verbose,
+                LevelFilter::Trace => LogLevel::Debug,
+            };
+            slow5_set_log_level(lvl);
+            Ok(forwarder)
+        }
+        Err(e) => {
+            FORWARDING.store(false, Ordering::SeqCst);
+            Err(e.into())
+        }
+    }
+}
+
+/// Forwards slow5lib messages to the [`log`] crate until dropped, see
+/// [`forward_slow5lib_logs`].
+///
+/// [`log`]: https://docs.rs/log
+#[derive(Debug)]
+pub struct LogForwarder {
+    // Copy of the original stderr, restored on drop
+    stderr: c_int,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl LogForwarder {
+    fn new() -> io::Result<Self> {
+        let mut fds = [0; 2];
+        if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let [read_fd, write_fd] = fds;
+        let stderr = unsafe { libc::dup(libc::STDERR_FILENO) };
+        let passthrough = if stderr < 0 {
+            -1
+        } else {
+            unsafe { libc::dup(stderr) }
+        };
+        if passthrough < 0 || unsafe { libc::dup2(write_fd, libc::STDERR_FILENO) } < 0 {
+            let err = io::Error::last_os_error();
+            unsafe {
+                libc::close(read_fd);
+                libc::close(write_fd);
+                if stderr >= 0 {
+                    libc::close(stderr);
+                }
+                if passthrough >= 0 {
+                    libc::close(passthrough);
+                }
+            }
+            return Err(err);
+        }
+        unsafe { libc::close(write_fd) };
+
+        // Takes ownership of the file descriptors
+        let lines = BufReader::new(unsafe { File::from_raw_fd(read_fd) });
+        let mut passthrough = unsafe { File::from_raw_fd(passthrough) };
+        let thread = thread::spawn(move || {
+            for line in lines.split(b'\n') {
+                let Ok(line) = line else {
+                    break;
+                };
+                match parse_log_line(&line) {
+                    Some((level, msg)) => ::log::log!(target: "slow5lib", level, "{msg}"),
+                    None => {
+                        let _ = passthrough.write_all(&line);
+                        let _ = passthrough.write_all(b"\n");
+                    }
+                }
+            }
+        });
+        Ok(Self {
+            stderr,
+            thread: Some(thread),
+        })
+    }
+}
+
+impl Drop for LogForwarder {
+    fn drop(&mut self) {
+        // Restoring stderr closes the pipe, which stops the thread
+        unsafe {
+            libc::dup2(self.stderr, libc::STDERR_FILENO);
+            libc::close(self.stderr);
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        FORWARDING.store(false, Ordering::SeqCst);
+    }
+}
+
+// Parse a message printed by slow5lib, ie "[slow5_open::ERROR] message At
+// file.c:10", into its level and message, with the terminal colors removed.
+// Returns None if the line wasn't printed by slow5lib.
+fn parse_log_line(line: &[u8]) -> Option<(Level, String)> {
+    let line = String::from_utf8_lossy(line);
+    let rest = line.strip_prefix('[')?;
+    let (tag, msg) = rest.split_once(']')?;
+    let (func, level) = tag.split_once("::")?;
+    if func.is_empty() || !func.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return None;
+    }
+    let level = match level {
+        "ERROR" => Level::Error,
+        "WARNING" => Level::Warn,
+        "INFO" => Level::Info,
+        "VERBOSE" => Level::Debug,
+        "DEBUG" => Level::Trace,
+        _ => return None,
+    };
+
+    // Remove the escape codes for colors, ie "\x1b[1;31m"
+    let mut msg_stripped = String::with_capacity(msg.len());
+    let mut chars = msg.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            chars.by_ref().find(|&c| c == 'm');
+        } else {
+            msg_stripped.push(c);
+        }
+    }
+    Some((level, format!("{func}: {}", msg_stripped.trim())))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_log_level_guard() {
+        let outer = LogLevelGuard::new(Some(LogLevel::Debug));
+        assert!(HOLDS_LOCK.with(Cell::get));
+        // Nested calls on the same thread don't deadlock
+        let inner = LogLevelGuard::new(Some(LogLevel::Error));
+        assert!(inner.0.is_none());
+        drop(inner);
+        drop(outer);
+        assert!(!HOLDS_LOCK.with(Cell::get));
+        assert!(LogLevelGuard::new(None).0.is_none());
+    }
+
+    #[test]
+    fn test_parse_log_line() {
+        let line = b"[slow5_open::ERROR]\x1b[1;31m Failed to open file\x1b[0m At slow5.c:96";
+        let (level, msg) = parse_log_line(line).unwrap();
+        assert_eq!(level, Level::Error);
+        assert_eq!(msg, "slow5_open: Failed to open file At slow5.c:96");
+
+        let (level, _) = parse_log_line(b"[slow5_idx_init::INFO]\x1b[1;34m Index\x1b[0m").unwrap();
+        assert_eq!(level, Level::Info);
+
+        // Other messages on stderr, ie from env_logger
+        assert!(parse_log_line(b"[2024-01-01T00:00:00Z ERROR slow5::reader] error").is_none());
+        assert!(parse_log_line(b"not from slow5lib").is_none());
+    }
 }
@@ -0,0 +1,282 @@
+//! Pair records with the basecalls of the same reads, using the read ids in
+//! FASTQ headers or a `sequencing_summary.txt`.
+//!
+//! # Example
+//! ```
+//! use slow5::{pairing, FileReader, RecordExt};
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let fastq = "@r1 runid=abc ch=12 start_time=2019-01-01T00:00:00Z\nACGT\n+\n!!!!\n";
+//! let metas = pairing::read_fastq_headers(fastq.as_bytes())?;
+//! let mut reader = FileReader::open("examples/example.slow5")?;
+//! let mut pairs = pairing::pair(&mut reader, metas);
+//! for pair in pairs.by_ref() {
+//!     let (rec, meta) = pair?;
+//!     assert_eq!(rec.read_id(), meta.read_id());
+//!     assert_eq!(meta.get("ch"), Some("12"));
+//! }
+//! assert_eq!(pairs.unmatched_records().len(), 4);
+//! # Ok(())
+//! # }
+//! ```
+use std::{collections::HashMap, io::BufRead};
+
+use crate::{
+    error::Slow5Error,
+    reader::FileReader,
+    record::{Record, RecordExt, RecordIter},
+};
+
+fn parse_error(line: usize, reason: impl Into<String>) -> Slow5Error {
+    Slow5Error::ImportParse {
+        line,
+        reason: reason.into(),
+    }
+}
+
+/// Read id and fields of a read from a FASTQ header or a row of a sequencing
+/// summary
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FastqMeta {
+    read_id: Vec<u8>,
+    fields: Vec<(String, String)>,
+}
+
+impl FastqMeta {
+    /// Read id of the read
+    pub fn read_id(&self) -> &[u8] {
+        &self.read_id
+    }
+
+    /// Fields of the read in the order they were read, ie the `key=value`
+    /// pairs of a FASTQ header or the columns of a sequencing summary
+    pub fn fields(&self) -> &[(String, String)] {
+        &self.fields
+    }
+
+    /// Value of a field, None if the read doesn't have it
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Read the header of every read of a FASTQ file, ie
+/// `@read_id runid=... ch=... start_time=...`. Words after the read id are
+/// fields if they are `key=value`, and are ignored otherwise.
+///
+/// # Errors
+/// Returns [`Slow5Error::ImportParse`] if a header doesn't start with `@` or
+/// the last read is incomplete, or an Err if a line fails to be read.
+pub fn read_fastq_headers<R>(reader: R) -> Result<Vec<FastqMeta>, Slow5Error>
+where
+    R: BufRead,
+{
+    let mut metas = Vec::new();
+    let mut n_lines = 0;
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        n_lines = idx + 1;
+        // Each read is 4 lines, header, sequence, separator, and qualities
+        if idx % 4 != 0 {
+            continue;
+        }
+        let header = line
+            .strip_prefix('@')
+            .ok_or_else(|| parse_error(idx + 1, "FASTQ header doesn't start with @"))?;
+        let mut words = header.split_whitespace();
+        let read_id = words
+            .next()
+            .ok_or_else(|| parse_error(idx + 1, "FASTQ header has no read id"))?;
+        let fields = words
+            .filter_map(|word| word.split_once('='))
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        metas.push(FastqMeta {
+            read_id: read_id.as_bytes().to_vec(),
+            fields,
+        });
+    }
+    if n_lines % 4 != 0 {
+        return Err(parse_error(n_lines, "incomplete FASTQ read"));
+    }
+    Ok(metas)
+}
+
+/// Read the rows of a `sequencing_summary.txt`, a TSV with a `read_id`
+/// column. Every other column is a field.
+///
+/// # Errors
+/// Returns [`Slow5Error::ImportParse`] if there is no `read_id` column or a
+/// row has a different number of columns than the header, or an Err if a
+/// line fails to be read.
+pub fn read_sequencing_summary<R>(reader: R) -> Result<Vec<FastqMeta>, Slow5Error>
+where
+    R: BufRead,
+{
+    let mut lines = reader.lines();
+    let columns = match lines.next() {
+        Some(line) => line?
+            .split('\t')
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        None => return Ok(Vec::new()),
+    };
+    let read_id_col = columns
+        .iter()
+        .position(|c| c == "read_id")
+        .ok_or_else(|| parse_error(1, "no read_id column"))?;
+    let mut metas = Vec::new();
+    for (idx, line) in lines.enumerate() {
+        let line = line?;
+        if line.is_empty() {
+            continue;
+        }
+        let values = line.split('\t').collect::<Vec<_>>();
+        if values.len() != columns.len() {
+            return Err(parse_error(
+                idx + 2,
+                format!("expected {} columns, found {}", columns.len(), values.len()),
+            ));
+        }
+        let fields = columns
+            .iter()
+            .zip(values.iter())
+            .enumerate()
+            .filter(|&(col, _)| col != read_id_col)
+            .map(|(_, (k, v))| (k.clone(), v.to_string()))
+            .collect();
+        metas.push(FastqMeta {
+            read_id: values[read_id_col].as_bytes().to_vec(),
+            fields,
+        });
+    }
+    Ok(metas)
+}
+
+/// Iterator over records paired with their [`FastqMeta`], created with
+/// [`pair`]
+pub struct Pairs<'a> {
+    records: RecordIter<'a>,
+    metas: HashMap<Vec<u8>, FastqMeta>,
+    unmatched_records: Vec<Vec<u8>>,
+}
+
+impl<'a> std::fmt::Debug for Pairs<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pairs")
+            .field("metas", &self.metas.len())
+            .field("unmatched_records", &self.unmatched_records.len())
+            .finish()
+    }
+}
+
+impl<'a> Pairs<'a> {
+    /// Read ids of the records without a [`FastqMeta`] read so far
+    pub fn unmatched_records(&self) -> &[Vec<u8>] {
+        &self.unmatched_records
+    }
+
+    /// Read ids of the [`FastqMeta`]s without a record, sorted. Only complete
+    /// once every record has been read.
+    pub fn unmatched_metas(&self) -> Vec<&[u8]> {
+        let mut read_ids = self
+            .metas
+            .keys()
+            .map(|read_id| read_id.as_slice())
+            .collect::<Vec<_>>();
+        read_ids.sort_unstable();
+        read_ids
+    }
+}
+
+impl<'a> Iterator for Pairs<'a> {
+    type Item = Result<(Record, FastqMeta), Slow5Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let rec = match self.records.next()? {
+                Ok(rec) => rec,
+                Err(e) => return Some(Err(e)),
+            };
+            match self.metas.remove(rec.read_id()) {
+                Some(meta) => return Some(Ok((rec, meta))),
+                None => self.unmatched_records.push(rec.read_id().to_vec()),
+            }
+        }
+    }
+}
+
+/// Pair the records of reader that haven't been read yet with the
+/// [`FastqMeta`] with the same read id. Records are read in order, so the
+/// reader doesn't need an index. If more than one [`FastqMeta`] has the same
+/// read id, the last one is used.
+///
+/// See the [module documentation](self) for an example.
+pub fn pair<I>(reader: &mut FileReader, metas: I) -> Pairs<'_>
+where
+    I: IntoIterator<Item = FastqMeta>,
+{
+    let metas = metas
+        .into_iter()
+        .map(|meta| (meta.read_id.clone(), meta))
+        .collect();
+    Pairs {
+        records: reader.records(),
+        metas,
+        unmatched_records: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_read_fastq_headers() -> anyhow::Result<()> {
+        let fastq = "@r1 runid=abc ch=3 extra\nAC\n+\n!!\n@r9 ch=4\nGT\n+\n!!\n";
+        let metas = read_fastq_headers(fastq.as_bytes())?;
+        assert_eq!(metas.len(), 2);
+        assert_eq!(metas[0].read_id(), b"r1");
+        assert_eq!(metas[0].fields().len(), 2);
+        assert_eq!(metas[0].get("ch"), Some("3"));
+        assert_eq!(metas[1].get("runid"), None);
+
+        let res = read_fastq_headers("r1\nAC\n+\n!!\n".as_bytes());
+        assert!(matches!(res, Err(Slow5Error::ImportParse { line: 1, .. })));
+        let res = read_fastq_headers("@r1\nAC\n+\n".as_bytes());
+        assert!(matches!(res, Err(Slow5Error::ImportParse { line: 3, .. })));
+        Ok(())
+    }
+
+    #[test]
+    fn test_pair_sequencing_summary() -> anyhow::Result<()> {
+        let summary = "filename\tread_id\tchannel\tpasses_filtering\n\
+                       a.fast5\tr3\t7\tTRUE\n\
+                       a.fast5\tr1\t2\tFALSE\n\
+                       a.fast5\tr9\t5\tTRUE\n";
+        let metas = read_sequencing_summary(summary.as_bytes())?;
+        assert_eq!(metas.len(), 3);
+        assert_eq!(metas[0].get("channel"), Some("7"));
+        assert_eq!(metas[0].get("read_id"), None);
+
+        let mut reader = FileReader::open("examples/example.slow5")?;
+        let mut pairs = pair(&mut reader, metas);
+        let paired = pairs
+            .by_ref()
+            .map(|pair| pair.map(|(rec, meta)| (rec.read_id().to_vec(), meta)))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(paired.len(), 2);
+        assert_eq!(paired[0].0, b"r1");
+        assert_eq!(paired[0].1.get("channel"), Some("2"));
+        assert_eq!(paired[1].0, b"r3");
+        assert_eq!(pairs.unmatched_records(), [b"r2", b"r4", b"r5"]);
+        assert_eq!(pairs.unmatched_metas(), [b"r9"]);
+
+        let res = read_sequencing_summary("filename\nr1\n".as_bytes());
+        assert!(matches!(res, Err(Slow5Error::ImportParse { line: 1, .. })));
+        Ok(())
+    }
+}
@@ -0,0 +1,310 @@
+//! Process records on a pool of threads.
+//!
+//! `par_bridge()` over [`FileReader::records`] hands out records in whatever
+//! order the threads finish them. The functions here read records on the
+//! calling thread, decode and process them on a pool, and yield the results
+//! in the order the records are stored in the file.
+//!
+//! [`FileReader::records`]: crate::FileReader::records
+use std::{
+    collections::BTreeMap,
+    panic::{self, AssertUnwindSafe},
+    ptr::null_mut,
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use libc::{c_char, c_void};
+use slow5lib_sys::{slow5_decode, slow5_file_t, slow5_get_next_bytes, slow5_rec_t};
+
+use crate::{error::Slow5Error, reader::FileReader, record::Record};
+
+// Records read ahead of the consumer for each thread
+const RECORDS_PER_THREAD: usize = 4;
+
+// Pointer to the file being read, workers only use it for the header, ie the
+// compression and auxiliary fields, which don't change while reading
+#[derive(Clone, Copy)]
+struct FilePtr(*mut slow5_file_t);
+
+unsafe impl Send for FilePtr {}
+
+impl FilePtr {
+    fn decode(self, mut raw: Raw) -> Result<(Record, Duration), Slow5Error> {
+        let mut rec = null_mut() as *mut slow5_rec_t;
+        let start = Instant::now();
+        let ret = unsafe { slow5_decode(&mut raw.mem, &mut raw.bytes, &mut rec, self.0) };
+        let time = start.elapsed();
+        if ret < 0 {
+            if !rec.is_null() {
+                drop(Record::new(rec));
+            }
+            Err(Slow5Error::from_errno(ret))
+        } else {
+            Ok((Record::new(rec), time))
+        }
+    }
+}
+
+// Record read by slow5_get_next_bytes, not yet decoded
+struct Raw {
+    mem: *mut c_void,
+    bytes: usize,
+}
+
+unsafe impl Send for Raw {}
+
+impl Drop for Raw {
+    fn drop(&mut self) {
+        unsafe { libc::free(self.mem) }
+    }
+}
+
+type Job = (u64, Raw);
+// Result of f, with the length of the raw signal and time spent decoding for
+// the reader's stats
+type Processed<T> = Result<(T, u64, Duration), Slow5Error>;
+// Processed record, or the panic from f to resume on the consumer's thread
+type Done<T> = (u64, thread::Result<Processed<T>>);
+
+/// Iterator over the results of a function applied to every record, in file
+/// order, generated by [`map_records_ordered`].
+pub struct OrderedMap<'a, T> {
+    reader: &'a mut FileReader,
+    jobs: Option<mpsc::SyncSender<Job>>,
+    done: mpsc::Receiver<Done<T>>,
+    workers: Vec<thread::JoinHandle<()>>,
+    // Results waiting for the records read before them
+    pending: BTreeMap<u64, Processed<T>>,
+    // Size of each record that has been submitted but not yielded
+    sizes: BTreeMap<u64, u64>,
+    capacity: u64,
+    submitted: u64,
+    yielded: u64,
+    // Error from reading the next record, returned after every record before
+    // it
+    read_err: Option<Slow5Error>,
+    eof: bool,
+}
+
+impl<'a, T> std::fmt::Debug for OrderedMap<'a, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OrderedMap")
+            .field("threads", &self.workers.len())
+            .field("submitted", &self.submitted)
+            .field("yielded", &self.yielded)
+            .finish()
+    }
+}
+
+/// Apply f to every record that hasn't been read yet from reader, using
+/// threads to decode and process the records, and return the results in the
+/// order the records are stored in the file.
+///
+/// Records are read ahead of the results being consumed, but only a few per
+/// thread, so a slow consumer doesn't buffer the whole file in memory.
+///
+/// # Example
+/// ```
+/// # use slow5::{FileReader, RecordExt};
+/// # fn main() -> anyhow::Result<()> {
+/// let mut reader = FileReader::open("examples/example.slow5")?;
+/// let read_ids = slow5::parallel::map_records_ordered(&mut reader, 4, |rec| {
+///     rec.read_id().to_vec()
+/// })
+/// .collect::<Result<Vec<_>, _>>()?;
+/// assert_eq!(read_ids, [b"r1", b"r2", b"r3", b"r4", b"r5"]);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// Yields an Err in place of a record that fails to be decoded, and the
+/// records after it are still processed. Yields an Err and stops if a record
+/// fails to be read from the file.
+///
+/// # Panics
+/// If f panics, the panic is resumed on the thread calling
+/// [`OrderedMap::next`].
+pub fn map_records_ordered<F, T>(reader: &mut FileReader, threads: usize, f: F) -> OrderedMap<'_, T>
+where
+    F: Fn(Record) -> T + Send + Sync + 'static,
+    T: Send + 'static,
+{
+    let threads = threads.max(1);
+    let capacity = threads * RECORDS_PER_THREAD;
+    let (jobs, job_rx) = mpsc::sync_channel::<Job>(capacity);
+    let job_rx = Arc::new(Mutex::new(job_rx));
+    let (done_tx, done) = mpsc::channel();
+    let file = FilePtr(reader.slow5_file);
+    let f = Arc::new(f);
+    let workers = (0..threads)
+        .map(|_| {
+            let job_rx = Arc::clone(&job_rx);
+            let done_tx = done_tx.clone();
+            let f = Arc::clone(&f);
+            thread::spawn(move || loop {
+                let job = match job_rx.lock() {
+                    Ok(rx) => rx.recv(),
+                    Err(_) => break,
+                };
+                let Ok((seq, raw)) = job else {
+                    break;
+                };
+                // A panic is sent back instead of leaving the consumer waiting
+                // for this record forever
+                let res = panic::catch_unwind(AssertUnwindSafe(|| {
+                    file.decode(raw).map(|(rec, time)| {
+                        let len_raw_signal = unsafe { (*rec.slow5_rec).len_raw_signal };
+                        (f(rec), len_raw_signal, time)
+                    })
+                }));
+                if done_tx.send((seq, res)).is_err() {
+                    break;
+                }
+            })
+        })
+        .collect();
+    OrderedMap {
+        reader,
+        jobs: Some(jobs),
+        done,
+        workers,
+        pending: BTreeMap::new(),
+        sizes: BTreeMap::new(),
+        capacity: capacity as u64,
+        submitted: 0,
+        yielded: 0,
+        read_err: None,
+        eof: false,
+    }
+}
+
+impl<'a, T> OrderedMap<'a, T> {
+    // Read records until the pool is full or the file ends
+    fn fill(&mut self) {
+        let _lvl = self.reader.log_level_guard();
+        while !self.eof && self.submitted - self.yielded < self.capacity {
+            let mut mem = null_mut() as *mut c_char;
+            let mut bytes = 0;
+            let ret = unsafe { slow5_get_next_bytes(&mut mem, &mut bytes, self.reader.slow5_file) };
+            if ret < 0 || mem.is_null() {
+                self.eof = true;
+                if ret != slow5lib_sys::SLOW5_ERR_EOF {
+                    self.read_err = Some(Slow5Error::from_errno(ret));
+                }
+                break;
+            }
+            let raw = Raw {
+                mem: mem.cast(),
+                bytes,
+            };
+            let sent = self
+                .jobs
+                .as_ref()
+                .is_some_and(|jobs| jobs.send((self.submitted, raw)).is_ok());
+            if !sent {
+                // Every worker has stopped
+                self.eof = true;
+                self.read_err = Some(Slow5Error::Unknown);
+                break;
+            }
+            self.sizes.insert(self.submitted, bytes as u64);
+            self.submitted += 1;
+        }
+    }
+}
+
+impl<'a, T> Iterator for OrderedMap<'a, T> {
+    type Item = Result<T, Slow5Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.fill();
+        loop {
+            if let Some(res) = self.pending.remove(&self.yielded) {
+                let bytes = self.sizes.remove(&self.yielded).unwrap_or_default();
+                self.yielded += 1;
+                return Some(res.map(|(value, len_raw_signal, time)| {
+                    self.reader
+                        .update_stats(|stats| stats.add_record(bytes, len_raw_signal, time));
+                    value
+                }));
+            }
+            if self.yielded == self.submitted {
+                return self.read_err.take().map(Err);
+            }
+            match self.done.recv() {
+                Ok((seq, Ok(res))) => {
+                    self.pending.insert(seq, res);
+                }
+                Ok((_, Err(payload))) => panic::resume_unwind(payload),
+                Err(_) => {
+                    // A worker panicked, so the remaining records will never
+                    // be processed
+                    self.yielded = self.submitted;
+                    self.eof = true;
+                    return Some(Err(Slow5Error::Unknown));
+                }
+            }
+        }
+    }
+}
+
+impl<'a, T> Drop for OrderedMap<'a, T> {
+    fn drop(&mut self) {
+        // Workers stop once the queued records are processed, and must be
+        // done with the file before the reader can be used again
+        self.jobs = None;
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::RecordExt;
+
+    #[test]
+    fn test_map_records_ordered() -> anyhow::Result<()> {
+        let mut reader = FileReader::open("examples/example3.blow5")?;
+        let expected = reader
+            .records()
+            .map(|rec| rec.map(|rec| rec.read_id().to_vec()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut reader = FileReader::open("examples/example3.blow5")?;
+        let read_ids = map_records_ordered(&mut reader, 3, |rec| rec.read_id().to_vec())
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(read_ids, expected);
+        assert_eq!(reader.stats().records(), expected.len() as u64);
+
+        // Dropped early, without reading every record
+        let mut reader = FileReader::open("examples/example.slow5")?;
+        let mut lens = map_records_ordered(&mut reader, 2, |rec| rec.len_signal());
+        assert!(lens.next().is_some());
+        drop(lens);
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_records_ordered_panic() -> anyhow::Result<()> {
+        let mut reader = FileReader::open("examples/example.slow5")?;
+        let res = panic::catch_unwind(AssertUnwindSafe(|| {
+            map_records_ordered(&mut reader, 3, |rec| {
+                if rec.read_id() == b"r2" {
+                    panic!("failed to process r2");
+                }
+                rec.len_signal()
+            })
+            .collect::<Result<Vec<_>, _>>()
+        }));
+        assert!(res.is_err());
+
+        // The workers are stopped, so the reader can still be used
+        assert!(reader.get_record("r1").is_ok());
+        Ok(())
+    }
+}
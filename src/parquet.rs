@@ -0,0 +1,81 @@
+//! Export the metadata of each record to a Parquet file.
+use std::{fs::File, path::Path};
+
+use ::parquet::arrow::ArrowWriter;
+
+use crate::{arrow::to_record_batches, error::Slow5Error, reader::FileReader};
+
+// Number of records in each row group
+const BATCH_SIZE: usize = 4096;
+
+fn parquet_err<E: std::fmt::Display>(e: E) -> Slow5Error {
+    Slow5Error::Parquet(e.to_string())
+}
+
+/// Write one row per record to a Parquet file, with the primary fields and
+/// every auxiliary field of the header as columns. The raw signal is only
+/// included if include_signal is true. Returns the number of rows written.
+///
+/// # Example
+/// ```
+/// # use assert_fs::TempDir;
+/// # use assert_fs::fixture::PathChild;
+/// use slow5::{parquet::export_metadata, FileReader};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// # let tmp_dir = TempDir::new()?;
+/// # let path = tmp_dir.child("metadata.parquet");
+/// let mut reader = FileReader::open("examples/example2.slow5")?;
+/// let n = export_metadata(&mut reader, path, false)?;
+/// assert_eq!(n, 5);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// Returns an Err if the file can't be created or a record fails to be read.
+pub fn export_metadata<P>(
+    reader: &mut FileReader,
+    path: P,
+    include_signal: bool,
+) -> Result<usize, Slow5Error>
+where
+    P: AsRef<Path>,
+{
+    let batches = to_record_batches(reader, BATCH_SIZE)
+        .all_aux_fields()
+        .include_signal(include_signal);
+    let file = File::create(path).map_err(parquet_err)?;
+    let mut writer = ArrowWriter::try_new(file, batches.schema()?, None).map_err(parquet_err)?;
+    let mut n = 0;
+    for batch in batches {
+        let batch = batch?;
+        n += batch.num_rows();
+        writer.write(&batch).map_err(parquet_err)?;
+    }
+    writer.close().map_err(parquet_err)?;
+    Ok(n)
+}
+
+#[cfg(test)]
+mod test {
+    use assert_fs::{fixture::PathChild, TempDir};
+    use parquet::file::reader::{FileReader as _, SerializedFileReader};
+
+    use super::*;
+
+    #[test]
+    fn test_export_metadata() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let path = tmp_dir.child("metadata.parquet");
+        let mut reader = FileReader::open("examples/example2.slow5")?;
+        assert_eq!(export_metadata(&mut reader, &path, true)?, 5);
+
+        let parquet = SerializedFileReader::new(File::open(&path)?)?;
+        let metadata = parquet.metadata().file_metadata();
+        assert_eq!(metadata.num_rows(), 5);
+        // 8 primary fields and 5 auxiliary fields
+        assert_eq!(metadata.schema_descr().root_schema().get_fields().len(), 13);
+        Ok(())
+    }
+}
@@ -0,0 +1,690 @@
+//! Read POD5 files so their reads can be written as SLOW5/BLOW5 records.
+//!
+//! A POD5 file is a container of Apache Arrow IPC files, a reads table with
+//! the metadata of each read, a signal table with the (compressed) signal
+//! chunks, and a run info table with the metadata of each acquisition.
+//! [`Pod5Reader`] joins them into a [`Pod5Read`] for each read.
+//!
+//! # Example
+//! ```no_run
+//! use slow5::pod5::Pod5Reader;
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let mut pod5 = Pod5Reader::open("reads.pod5")?;
+//! let mut writer = Pod5Reader::write_options().create("reads.blow5")?;
+//! for read in pod5.reads() {
+//!     let rec = read?.to_builder().build_with_writer(&mut writer)?;
+//!     writer.add_record(&rec)?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+use arrow_array::{
+    cast::AsArray, types::Int16Type, Array, FixedSizeBinaryArray, Float32Array, Int16Array,
+    LargeBinaryArray, LargeListArray, ListArray, RecordBatch, StringArray, UInt16Array,
+    UInt32Array, UInt64Array, UInt8Array,
+};
+use arrow_ipc::reader::FileReader as IpcReader;
+use arrow_schema::DataType;
+
+use crate::{error::Slow5Error, FieldType, RecordBuilder, WriteOptions};
+
+// Every POD5 file starts and ends with this signature
+const SIGNATURE: [u8; 8] = [0x8b, b'P', b'O', b'D', b'\r', b'\n', 0x1a, b'\n'];
+const SECTION_MARKER_LEN: u64 = 16;
+
+// Values of the ContentType enum in the footer
+const READS_TABLE: i16 = 1;
+const SIGNAL_TABLE: i16 = 2;
+const RUN_INFO_TABLE: i16 = 4;
+
+fn pod5_err<E: std::fmt::Display>(e: E) -> Slow5Error {
+    Slow5Error::Pod5(e.to_string())
+}
+
+// Location of an embedded Arrow file within the POD5 file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct EmbeddedFile {
+    offset: u64,
+    length: u64,
+    content_type: i16,
+}
+
+// Minimal reader for the flatbuffer footer, which only needs the list of
+// embedded files
+struct Footer<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Footer<'a> {
+    fn read<const N: usize>(&self, pos: usize) -> Result<[u8; N], Slow5Error> {
+        self.buf
+            .get(pos..pos + N)
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| pod5_err("footer is truncated"))
+    }
+
+    fn u32(&self, pos: usize) -> Result<usize, Slow5Error> {
+        Ok(u32::from_le_bytes(self.read(pos)?) as usize)
+    }
+
+    // Position of field idx in the table at pos, None if the field isn't set
+    fn field(&self, table: usize, idx: usize) -> Result<Option<usize>, Slow5Error> {
+        let soffset = i32::from_le_bytes(self.read(table)?) as isize;
+        let vtable = (table as isize - soffset) as usize;
+        let vtable_len = u16::from_le_bytes(self.read(vtable)?) as usize;
+        let entry = 4 + 2 * idx;
+        if entry >= vtable_len {
+            return Ok(None);
+        }
+        let offset = u16::from_le_bytes(self.read(vtable + entry)?) as usize;
+        Ok((offset != 0).then_some(table + offset))
+    }
+
+    fn embedded_files(&self) -> Result<Vec<EmbeddedFile>, Slow5Error> {
+        let root = self.u32(0)?;
+        // Footer { file_identifier, software, pod5_version, contents }
+        let Some(contents) = self.field(root, 3)? else {
+            return Err(pod5_err("footer has no embedded files"));
+        };
+        let vector = contents + self.u32(contents)?;
+        let len = self.u32(vector)?;
+        (0..len)
+            .map(|i| {
+                let elem = vector + 4 + 4 * i;
+                let table = elem + self.u32(elem)?;
+                // EmbeddedFile { offset, length, format, content_type }
+                let offset = match self.field(table, 0)? {
+                    Some(pos) => i64::from_le_bytes(self.read(pos)?) as u64,
+                    None => 0,
+                };
+                let length = match self.field(table, 1)? {
+                    Some(pos) => i64::from_le_bytes(self.read(pos)?) as u64,
+                    None => 0,
+                };
+                let content_type = match self.field(table, 3)? {
+                    Some(pos) => i16::from_le_bytes(self.read(pos)?),
+                    None => 0,
+                };
+                Ok(EmbeddedFile {
+                    offset,
+                    length,
+                    content_type,
+                })
+            })
+            .collect()
+    }
+}
+
+// Read the footer at the end of the POD5 file
+fn read_embedded_files(file: &mut File) -> Result<Vec<EmbeddedFile>, Slow5Error> {
+    let mut signature = [0u8; 8];
+    file.read_exact(&mut signature).map_err(pod5_err)?;
+    if signature != SIGNATURE {
+        return Err(pod5_err("missing POD5 signature"));
+    }
+
+    // ... footer, footer length (i64), section marker, signature
+    let trailer = 8 + SECTION_MARKER_LEN + 8;
+    let end = file.seek(SeekFrom::End(0)).map_err(pod5_err)?;
+    if end < trailer + 8 {
+        return Err(pod5_err("file is too small to be POD5"));
+    }
+    file.seek(SeekFrom::Start(end - trailer))
+        .map_err(pod5_err)?;
+    let mut footer_len = [0u8; 8];
+    file.read_exact(&mut footer_len).map_err(pod5_err)?;
+    let footer_len = i64::from_le_bytes(footer_len) as u64;
+    if footer_len > end - trailer {
+        return Err(pod5_err("footer length is larger than the file"));
+    }
+
+    let mut buf = vec![0u8; footer_len as usize];
+    file.seek(SeekFrom::Start(end - trailer - footer_len))
+        .map_err(pod5_err)?;
+    file.read_exact(&mut buf).map_err(pod5_err)?;
+    Footer { buf: &buf }.embedded_files()
+}
+
+// Restricts reads and seeks to an embedded file, so it can be opened as an
+// Arrow IPC file
+#[derive(Debug)]
+struct Section {
+    file: File,
+    start: u64,
+    len: u64,
+    pos: u64,
+}
+
+impl Section {
+    fn new(file: File, embedded: EmbeddedFile) -> Self {
+        Self {
+            file,
+            start: embedded.offset,
+            len: embedded.length,
+            pos: 0,
+        }
+    }
+}
+
+impl Read for Section {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.len.saturating_sub(self.pos) as usize;
+        let n = buf.len().min(remaining);
+        if n == 0 {
+            return Ok(0);
+        }
+        self.file.seek(SeekFrom::Start(self.start + self.pos))?;
+        let n = self.file.read(&mut buf[..n])?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl Seek for Section {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let pos = match pos {
+            SeekFrom::Start(n) => n as i64,
+            SeekFrom::End(n) => self.len as i64 + n,
+            SeekFrom::Current(n) => self.pos as i64 + n,
+        };
+        if pos < 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "seek before start of embedded file",
+            ));
+        }
+        self.pos = pos as u64;
+        Ok(self.pos)
+    }
+}
+
+fn open_table(
+    path: &Path,
+    files: &[EmbeddedFile],
+    content_type: i16,
+) -> Result<IpcReader<Section>, Slow5Error> {
+    let embedded = files
+        .iter()
+        .find(|f| f.content_type == content_type)
+        .ok_or_else(|| pod5_err(format!("missing table with content type {content_type}")))?;
+    let file = File::open(path).map_err(pod5_err)?;
+    IpcReader::try_new(Section::new(file, *embedded), None).map_err(pod5_err)
+}
+
+fn column<'b, T: 'static>(batch: &'b RecordBatch, name: &str) -> Result<&'b T, Slow5Error> {
+    batch
+        .column_by_name(name)
+        .and_then(|col| col.as_any().downcast_ref::<T>())
+        .ok_or_else(|| pod5_err(format!("missing or invalid column {name}")))
+}
+
+// Decode signal compressed with zstd and svb16, a zigzag delta encoding where
+// each value is stored in one or two bytes, flagged by one bit in the keys
+fn decompress_signal(compressed: &[u8], samples: usize) -> Result<Vec<i16>, Slow5Error> {
+    let encoded = zstd::decode_all(compressed).map_err(pod5_err)?;
+    decode_svb16(&encoded, samples)
+}
+
+fn decode_svb16(encoded: &[u8], samples: usize) -> Result<Vec<i16>, Slow5Error> {
+    let keys_len = (samples + 7) / 8;
+    if encoded.len() < keys_len {
+        return Err(pod5_err("signal is truncated"));
+    }
+    let (keys, data) = encoded.split_at(keys_len);
+    let mut pos = 0;
+    let mut signal = Vec::with_capacity(samples);
+    let mut prev = 0i16;
+    for i in 0..samples {
+        let wide = (keys[i / 8] >> (i % 8)) & 1 == 1;
+        let width = if wide { 2 } else { 1 };
+        let Some(bytes) = data.get(pos..pos + width) else {
+            return Err(pod5_err("signal is truncated"));
+        };
+        pos += width;
+        let value = if wide {
+            u16::from_le_bytes([bytes[0], bytes[1]])
+        } else {
+            bytes[0] as u16
+        };
+        let delta = ((value >> 1) as i16) ^ -((value & 1) as i16);
+        prev = prev.wrapping_add(delta);
+        signal.push(prev);
+    }
+    Ok(signal)
+}
+
+// Acquisition metadata needed to convert the calibration of a read
+#[derive(Debug, Clone, Copy)]
+struct RunInfo {
+    sampling_rate: f64,
+    digitisation: f64,
+}
+
+// Signal table, which is loaded one batch at a time since it is the bulk of
+// the file
+struct SignalTable {
+    reader: IpcReader<Section>,
+    // First row of each batch that has been loaded so far
+    batch_starts: Vec<usize>,
+    current: Option<(usize, RecordBatch)>,
+}
+
+impl SignalTable {
+    fn new(reader: IpcReader<Section>) -> Self {
+        Self {
+            reader,
+            batch_starts: vec![0],
+            current: None,
+        }
+    }
+
+    fn load_batch(&mut self, idx: usize) -> Result<&RecordBatch, Slow5Error> {
+        if !matches!(self.current, Some((current, _)) if current == idx) {
+            self.reader.set_index(idx).map_err(pod5_err)?;
+            let batch = self
+                .reader
+                .next()
+                .ok_or_else(|| pod5_err("signal row out of range"))?
+                .map_err(pod5_err)?;
+            if idx + 1 == self.batch_starts.len() {
+                self.batch_starts
+                    .push(self.batch_starts[idx] + batch.num_rows());
+            }
+            self.current = Some((idx, batch));
+        }
+        Ok(&self.current.as_ref().expect("batch was just loaded").1)
+    }
+
+    // Decompressed signal for a row of the signal table
+    fn signal(&mut self, row: usize) -> Result<Vec<i16>, Slow5Error> {
+        // Batches are loaded in order until one contains the row
+        let mut idx = match self.batch_starts.binary_search(&row) {
+            Ok(idx) => idx,
+            Err(idx) => idx - 1,
+        };
+        loop {
+            let batch_start = self.batch_starts[idx];
+            let batch = self.load_batch(idx)?;
+            if row < batch_start + batch.num_rows() {
+                let batch = batch.clone();
+                return signal_from_batch(&batch, row - batch_start);
+            }
+            idx += 1;
+        }
+    }
+}
+
+fn signal_from_batch(batch: &RecordBatch, row: usize) -> Result<Vec<i16>, Slow5Error> {
+    let signal = batch
+        .column_by_name("signal")
+        .ok_or_else(|| pod5_err("missing column signal"))?;
+    match signal.data_type() {
+        DataType::LargeBinary => {
+            let samples = column::<UInt32Array>(batch, "samples")?.value(row) as usize;
+            let compressed = column::<LargeBinaryArray>(batch, "signal")?.value(row);
+            decompress_signal(compressed, samples)
+        }
+        DataType::LargeList(_) => {
+            let values = column::<LargeListArray>(batch, "signal")?.value(row);
+            let values = values
+                .as_any()
+                .downcast_ref::<Int16Array>()
+                .ok_or_else(|| pod5_err("uncompressed signal is not int16"))?;
+            Ok(values.values().to_vec())
+        }
+        other => Err(pod5_err(format!("unsupported signal type {other}"))),
+    }
+}
+
+/// Read from a POD5 file, converting each read into a [`Pod5Read`].
+pub struct Pod5Reader {
+    reads: IpcReader<Section>,
+    signal: SignalTable,
+    run_info: HashMap<String, RunInfo>,
+}
+
+impl std::fmt::Debug for Pod5Reader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pod5Reader").finish()
+    }
+}
+
+impl Pod5Reader {
+    /// Open a POD5 file
+    ///
+    /// # Errors
+    /// Returns an Err if the file doesn't exist or isn't a valid POD5 file.
+    pub fn open<P: AsRef<Path>>(file_path: P) -> Result<Self, Slow5Error> {
+        let file_path = file_path.as_ref();
+        if !file_path.exists() {
+            return Err(Slow5Error::IncorrectPath(file_path.to_owned()));
+        }
+        let mut file = File::open(file_path).map_err(pod5_err)?;
+        let files = read_embedded_files(&mut file)?;
+
+        let mut run_info = HashMap::new();
+        for batch in open_table(file_path, &files, RUN_INFO_TABLE)? {
+            let batch = batch.map_err(pod5_err)?;
+            let acquisition_id = column::<StringArray>(&batch, "acquisition_id")?;
+            let sample_rate = column::<UInt16Array>(&batch, "sample_rate")?;
+            let adc_max = column::<Int16Array>(&batch, "adc_max")?;
+            let adc_min = column::<Int16Array>(&batch, "adc_min")?;
+            for row in 0..batch.num_rows() {
+                let digitisation = adc_max.value(row) as f64 - adc_min.value(row) as f64 + 1.0;
+                run_info.insert(
+                    acquisition_id.value(row).to_string(),
+                    RunInfo {
+                        sampling_rate: sample_rate.value(row) as f64,
+                        digitisation,
+                    },
+                );
+            }
+        }
+
+        Ok(Self {
+            reads: open_table(file_path, &files, READS_TABLE)?,
+            signal: SignalTable::new(open_table(file_path, &files, SIGNAL_TABLE)?),
+            run_info,
+        })
+    }
+
+    /// [`WriteOptions`] with the auxiliary fields set by
+    /// [`Pod5Read::to_builder`], so the reads can be written to a SLOW5 or
+    /// BLOW5 file.
+    pub fn write_options() -> WriteOptions {
+        let mut opts = WriteOptions::default();
+        opts.aux("channel_number", FieldType::Str)
+            .aux("median_before", FieldType::Double)
+            .aux("read_number", FieldType::Int32)
+            .aux("start_mux", FieldType::Uint8)
+            .aux("start_time", FieldType::Uint64);
+        opts
+    }
+
+    /// Return iterator over each read in the POD5 file
+    pub fn reads(&mut self) -> Pod5ReadIter<'_> {
+        Pod5ReadIter {
+            reader: self,
+            batch: None,
+            row: 0,
+            errored: false,
+        }
+    }
+
+    fn read_at(&mut self, batch: &RecordBatch, row: usize) -> Result<Pod5Read, Slow5Error> {
+        let read_id = column::<FixedSizeBinaryArray>(batch, "read_id")?.value(row);
+        let read_id = format_uuid(read_id)?;
+
+        let run_info = batch
+            .column_by_name("run_info")
+            .and_then(|col| col.as_dictionary_opt::<Int16Type>())
+            .ok_or_else(|| pod5_err("missing or invalid column run_info"))?;
+        let key = run_info.keys().value(row) as usize;
+        let acquisition_id = run_info
+            .values()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .ok_or_else(|| pod5_err("invalid column run_info"))?
+            .value(key);
+        let run_info = *self
+            .run_info
+            .get(acquisition_id)
+            .ok_or_else(|| pod5_err(format!("missing run info {acquisition_id}")))?;
+
+        let rows = column::<ListArray>(batch, "signal")?.value(row);
+        let rows = rows
+            .as_any()
+            .downcast_ref::<UInt64Array>()
+            .ok_or_else(|| pod5_err("invalid column signal"))?;
+        let mut signal = Vec::new();
+        for signal_row in rows.values().iter() {
+            signal.extend(self.signal.signal(*signal_row as usize)?);
+        }
+
+        Ok(Pod5Read {
+            read_id,
+            read_number: column::<UInt32Array>(batch, "read_number")?.value(row),
+            channel: column::<UInt16Array>(batch, "channel")?.value(row),
+            well: column::<UInt8Array>(batch, "well")?.value(row),
+            start: column::<UInt64Array>(batch, "start")?.value(row),
+            median_before: column::<Float32Array>(batch, "median_before")?.value(row),
+            calibration_offset: column::<Float32Array>(batch, "calibration_offset")?.value(row),
+            calibration_scale: column::<Float32Array>(batch, "calibration_scale")?.value(row),
+            digitisation: run_info.digitisation,
+            sampling_rate: run_info.sampling_rate,
+            signal,
+        })
+    }
+}
+
+// Read ids are stored as the 16 bytes of a UUID
+fn format_uuid(bytes: &[u8]) -> Result<String, Slow5Error> {
+    if bytes.len() != 16 {
+        return Err(pod5_err("read id is not a UUID"));
+    }
+    let hex = bytes.iter().map(|b| format!("{b:02x}")).collect::<String>();
+    Ok(format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    ))
+}
+
+/// Iterator over the reads of a [`Pod5Reader`], generated by calling
+/// [`reads`].
+///
+/// If an error occurs, the iterator will produce Some(Err(_)) and then None
+/// on subsequent iterations.
+///
+/// [`reads`]: crate::pod5::Pod5Reader::reads
+pub struct Pod5ReadIter<'a> {
+    reader: &'a mut Pod5Reader,
+    batch: Option<RecordBatch>,
+    row: usize,
+    errored: bool,
+}
+
+impl<'a> std::fmt::Debug for Pod5ReadIter<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pod5ReadIter")
+            .field("row", &self.row)
+            .finish()
+    }
+}
+
+impl<'a> Iterator for Pod5ReadIter<'a> {
+    type Item = Result<Pod5Read, Slow5Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.errored {
+            return None;
+        }
+        loop {
+            let row = self.row;
+            if let Some(batch) = self.batch.as_ref().filter(|b| row < b.num_rows()) {
+                let batch = batch.clone();
+                let read = self.reader.read_at(&batch, row);
+                self.row += 1;
+                self.errored = read.is_err();
+                return Some(read);
+            }
+            match self.reader.reads.next()? {
+                Ok(batch) => {
+                    self.batch = Some(batch);
+                    self.row = 0;
+                }
+                Err(e) => {
+                    self.errored = true;
+                    return Some(Err(pod5_err(e)));
+                }
+            }
+        }
+    }
+}
+
+/// Single read from a POD5 file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pod5Read {
+    read_id: String,
+    read_number: u32,
+    channel: u16,
+    well: u8,
+    start: u64,
+    median_before: f32,
+    calibration_offset: f32,
+    calibration_scale: f32,
+    digitisation: f64,
+    sampling_rate: f64,
+    signal: Vec<i16>,
+}
+
+impl Pod5Read {
+    /// Read id, as a UUID string
+    pub fn read_id(&self) -> &str {
+        &self.read_id
+    }
+
+    /// Read number
+    pub fn read_number(&self) -> u32 {
+        self.read_number
+    }
+
+    /// Channel the read came from
+    pub fn channel(&self) -> u16 {
+        self.channel
+    }
+
+    /// Well (mux) the read came from
+    pub fn well(&self) -> u8 {
+        self.well
+    }
+
+    /// Sample the read started at
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    /// Median current level before the read
+    pub fn median_before(&self) -> f32 {
+        self.median_before
+    }
+
+    /// Offset of the signal, in the same units as the raw signal
+    pub fn offset(&self) -> f64 {
+        self.calibration_offset as f64
+    }
+
+    /// Range of the signal in picoamps, ie calibration scale × digitisation
+    pub fn range(&self) -> f64 {
+        self.calibration_scale as f64 * self.digitisation
+    }
+
+    /// Digitisation, number of ADC levels
+    pub fn digitisation(&self) -> f64 {
+        self.digitisation
+    }
+
+    /// Sampling rate in Hz
+    pub fn sampling_rate(&self) -> f64 {
+        self.sampling_rate
+    }
+
+    /// Raw signal
+    pub fn raw_signal(&self) -> &[i16] {
+        &self.signal
+    }
+
+    /// Convert into a [`RecordBuilder`] in read group 0 with the primary
+    /// fields set and the auxiliary fields of
+    /// [`Pod5Reader::write_options`] staged.
+    pub fn to_builder(&self) -> RecordBuilder {
+        let mut builder = RecordBuilder::default();
+        builder
+            .read_id(self.read_id.as_str())
+            .read_group(0)
+            .digitisation(self.digitisation())
+            .offset(self.offset())
+            .range(self.range())
+            .sampling_rate(self.sampling_rate())
+            .raw_signal(&self.signal)
+            .aux_field("channel_number", self.channel.to_string())
+            .aux_field("median_before", self.median_before as f64)
+            .aux_field("read_number", self.read_number as i32)
+            .aux_field("start_mux", self.well)
+            .aux_field("start_time", self.start);
+        builder
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_decode_svb16() {
+        // Signal 10, 300, 299, encoded as zigzag deltas 20, 580, 1
+        let encoded = [0b010, 20, 0x44, 0x02, 1];
+        assert_eq!(decode_svb16(&encoded, 3).unwrap(), vec![10, 300, 299]);
+        assert!(decode_svb16(&encoded[..4], 3).is_err());
+    }
+
+    #[test]
+    fn test_footer() {
+        // Footer with one EmbeddedFile { offset: 24, length: 100, format: 0,
+        // content_type: 2 }
+        let mut buf = Vec::new();
+        // root offset
+        buf.extend(16u32.to_le_bytes());
+        // Footer vtable: len 12, table len 8, fields 0-2 unset, contents at 4
+        buf.extend([12u16, 8, 0, 0, 0, 4].iter().flat_map(|x| x.to_le_bytes()));
+        // Footer table at 16, soffset to vtable at 4
+        buf.extend(12i32.to_le_bytes());
+        // contents vector at 24
+        buf.extend(4u32.to_le_bytes());
+        buf.extend(1u32.to_le_bytes());
+        // element offset to table at 48
+        buf.extend(20u32.to_le_bytes());
+        buf.extend([0u8; 4]);
+        // EmbeddedFile vtable at 36: len 12, table len 24, fields
+        buf.extend(
+            [12u16, 24, 4, 12, 0, 20]
+                .iter()
+                .flat_map(|x| x.to_le_bytes()),
+        );
+        // EmbeddedFile table at 48
+        buf.extend(12i32.to_le_bytes());
+        buf.extend(24i64.to_le_bytes());
+        buf.extend(100i64.to_le_bytes());
+        buf.extend(2i16.to_le_bytes());
+
+        let files = Footer { buf: &buf }.embedded_files().unwrap();
+        assert_eq!(
+            files,
+            vec![EmbeddedFile {
+                offset: 24,
+                length: 100,
+                content_type: SIGNAL_TABLE
+            }]
+        );
+    }
+
+    #[test]
+    fn test_format_uuid() {
+        let bytes = (0u8..16).collect::<Vec<_>>();
+        assert_eq!(
+            format_uuid(&bytes).unwrap(),
+            "00010203-0405-0607-0809-0a0b0c0d0e0f"
+        );
+    }
+}
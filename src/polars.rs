@@ -0,0 +1,265 @@
+//! Load records into a polars [`DataFrame`], ie for interactive analysis.
+//!
+//! The DataFrame has a column for each primary field and a column for each
+//! selected auxiliary field. Auxiliary fields without a value for a record are
+//! null. The raw signal is only included if it is selected, as a
+//! `List<Int16>` column.
+//!
+//! # Example
+//! ```
+//! use slow5::{polars::to_dataframe, FileReader};
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let mut reader = FileReader::open("examples/example2.slow5")?;
+//! let df = to_dataframe(&mut reader, ["read_number", "raw_signal"])?;
+//! assert_eq!(df.height(), 5);
+//! assert!(df.column("read_number").is_ok());
+//! # Ok(())
+//! # }
+//! ```
+use ::polars::prelude::{DataFrame, IntoSeries, ListChunked, NamedFrom, Series};
+
+use crate::{
+    error::Slow5Error,
+    header::HeaderExt,
+    reader::FileReader,
+    record::{raw_signal, Record, RecordExt},
+    EnumField, FieldType,
+};
+
+// Name of the column with the raw signal
+const SIGNAL_COLUMN: &str = "raw_signal";
+
+fn polars_err<E: std::fmt::Display>(e: E) -> Slow5Error {
+    Slow5Error::Polars(e.to_string())
+}
+
+// Column of lists, named name
+fn list_series(name: &str, values: Vec<Option<Series>>) -> Series {
+    let mut series = values.into_iter().collect::<ListChunked>().into_series();
+    series.rename(name);
+    series
+}
+
+macro_rules! aux_column {
+    ($(($variant:ident, $rtype:ty)),*) => {
+        paste::paste! {
+            // Values of an auxiliary field
+            enum AuxColumn {
+                $(
+                    $variant(Vec<Option<$rtype>>),
+                    [<$variant Array>](Vec<Option<Series>>),
+                )*
+                Char(Vec<Option<String>>),
+                Str(Vec<Option<String>>),
+                Enum(Vec<Option<String>>, Vec<String>),
+            }
+
+            impl AuxColumn {
+                fn new(field_type: &FieldType) -> Self {
+                    match field_type {
+                        $(
+                            FieldType::$variant => AuxColumn::$variant(Vec::new()),
+                            FieldType::[<$variant Array>] => {
+                                AuxColumn::[<$variant Array>](Vec::new())
+                            }
+                        )*
+                        FieldType::Char => AuxColumn::Char(Vec::new()),
+                        FieldType::Str => AuxColumn::Str(Vec::new()),
+                        FieldType::Enum(labels) => AuxColumn::Enum(
+                            Vec::new(),
+                            labels
+                                .iter()
+                                .map(|l| String::from_utf8_lossy(l).into_owned())
+                                .collect(),
+                        ),
+                    }
+                }
+
+                fn append(&mut self, rec: &Record, name: &[u8]) -> Result<(), Slow5Error> {
+                    match self {
+                        $(
+                            AuxColumn::$variant(values) => {
+                                values.push(rec.get_aux_field_opt::<$rtype>(name)?);
+                            }
+                            AuxColumn::[<$variant Array>](values) => {
+                                let value = rec.get_aux_field_opt::<Vec<$rtype>>(name)?;
+                                values.push(value.map(|v| Series::new("", v)));
+                            }
+                        )*
+                        AuxColumn::Char(values) => {
+                            let value = rec.get_aux_field_opt::<char>(name)?;
+                            values.push(value.map(String::from));
+                        }
+                        AuxColumn::Str(values) => {
+                            values.push(rec.get_aux_field_opt::<String>(name)?);
+                        }
+                        AuxColumn::Enum(values, labels) => {
+                            let value = rec.get_aux_field_opt::<EnumField>(name)?;
+                            let label = value.and_then(|EnumField(idx)| labels.get(idx));
+                            values.push(label.cloned());
+                        }
+                    }
+                    Ok(())
+                }
+
+                fn finish(self, name: &str) -> Series {
+                    match self {
+                        $(
+                            AuxColumn::$variant(values) => Series::new(name, values),
+                            AuxColumn::[<$variant Array>](values) => list_series(name, values),
+                        )*
+                        AuxColumn::Char(values)
+                        | AuxColumn::Str(values)
+                        | AuxColumn::Enum(values, _) => Series::new(name, values),
+                    }
+                }
+            }
+        }
+    };
+}
+
+aux_column!(
+    (Int8, i8),
+    (Int16, i16),
+    (Int32, i32),
+    (Int64, i64),
+    (Uint8, u8),
+    (Uint16, u16),
+    (Uint32, u32),
+    (Uint64, u64),
+    (Float, f32),
+    (Double, f64)
+);
+
+// Values of the primary fields and signal
+#[derive(Default)]
+struct PrimaryColumns {
+    read_id: Vec<String>,
+    read_group: Vec<u32>,
+    digitisation: Vec<f64>,
+    offset: Vec<f64>,
+    range: Vec<f64>,
+    sampling_rate: Vec<f64>,
+    len_raw_signal: Vec<u64>,
+    raw_signal: Option<Vec<Option<Series>>>,
+}
+
+impl PrimaryColumns {
+    fn new(include_signal: bool) -> Self {
+        Self {
+            raw_signal: include_signal.then(Vec::new),
+            ..Self::default()
+        }
+    }
+
+    fn append(&mut self, rec: &Record) {
+        self.read_id
+            .push(String::from_utf8_lossy(rec.read_id()).into_owned());
+        self.read_group.push(rec.read_group());
+        self.digitisation.push(rec.digitisation());
+        self.offset.push(rec.offset());
+        self.range.push(rec.range());
+        self.sampling_rate.push(rec.sampling_rate());
+        self.len_raw_signal.push(rec.len_signal());
+        if let Some(signal) = self.raw_signal.as_mut() {
+            signal.push(Some(Series::new("", raw_signal(rec))));
+        }
+    }
+
+    fn finish(self) -> Vec<Series> {
+        let mut columns = vec![
+            Series::new("read_id", self.read_id),
+            Series::new("read_group", self.read_group),
+            Series::new("digitisation", self.digitisation),
+            Series::new("offset", self.offset),
+            Series::new("range", self.range),
+            Series::new("sampling_rate", self.sampling_rate),
+            Series::new("len_raw_signal", self.len_raw_signal),
+        ];
+        if let Some(signal) = self.raw_signal {
+            columns.push(list_series(SIGNAL_COLUMN, signal));
+        }
+        columns
+    }
+}
+
+/// Read the records of reader into a [`DataFrame`] with one row per record.
+/// The DataFrame has the primary fields and a column for each of columns, in
+/// the given order, which are the names of auxiliary fields, or `raw_signal`
+/// for the raw signal.
+///
+/// Every record is read into memory, so for large files select only the
+/// columns needed, or use [`arrow::to_record_batches`] to read the records in
+/// batches.
+///
+/// # Errors
+/// Returns [`Slow5Error::MissingAttribute`] if a column isn't an auxiliary
+/// field in the header, or an Err if a record fails to be read.
+///
+/// [`arrow::to_record_batches`]: crate::arrow::to_record_batches
+pub fn to_dataframe<I, S>(reader: &mut FileReader, columns: I) -> Result<DataFrame, Slow5Error>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut include_signal = false;
+    let mut aux = Vec::new();
+    for column in columns {
+        let column = column.as_ref();
+        if column == SIGNAL_COLUMN {
+            include_signal = true;
+            continue;
+        }
+        let field_type = reader
+            .aux_fields_iter()
+            .find(|(name, _)| *name == column.as_bytes())
+            .map(|(_, field_type)| field_type)
+            .ok_or(Slow5Error::MissingAttribute)?;
+        aux.push((column.to_string(), AuxColumn::new(&field_type)));
+    }
+
+    let mut primary = PrimaryColumns::new(include_signal);
+    for rec in reader.records() {
+        let rec = rec?;
+        primary.append(&rec);
+        for (name, column) in aux.iter_mut() {
+            column.append(&rec, name.as_bytes())?;
+        }
+    }
+    let mut series = primary.finish();
+    series.extend(aux.into_iter().map(|(name, column)| column.finish(&name)));
+    DataFrame::new(series).map_err(polars_err)
+}
+
+#[cfg(test)]
+mod test {
+    use ::polars::prelude::DataType;
+
+    use super::*;
+
+    #[test]
+    fn test_to_dataframe() -> anyhow::Result<()> {
+        let mut reader = FileReader::open("examples/example2.slow5")?;
+        let df = to_dataframe(&mut reader, ["read_number", "channel_number"])?;
+        assert_eq!(df.shape(), (5, 9));
+        assert_eq!(df.column("read_number")?.dtype(), &DataType::Int32);
+        assert_eq!(df.column("channel_number")?.dtype(), &DataType::String);
+        assert!(df.column(SIGNAL_COLUMN).is_err());
+
+        let mut reader = FileReader::open("examples/example2.slow5")?;
+        let df = to_dataframe(&mut reader, [SIGNAL_COLUMN])?;
+        let signal = df.column(SIGNAL_COLUMN)?.list()?;
+        let lens = df.column("len_raw_signal")?.u64()?;
+        let first = signal.get_as_series(0).unwrap();
+        assert_eq!(Some(first.len() as u64), lens.get(0));
+        assert_eq!(first.dtype(), &DataType::Int16);
+
+        let mut reader = FileReader::open("examples/example2.slow5")?;
+        assert!(matches!(
+            to_dataframe(&mut reader, ["not a field"]),
+            Err(Slow5Error::MissingAttribute)
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,152 @@
+//! Random access to records from multiple threads
+use std::{
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex, MutexGuard,
+    },
+};
+
+use crate::{error::Slow5Error, reader::FileReader, record::Record};
+
+/// Pool of readers of the same file, so [`get_record`] can be called from any
+/// thread. slow5lib file handles can't be shared between threads, so each
+/// call uses one of the handles that isn't in use, waiting if every handle is
+/// busy. The handles share one index, which is only loaded once.
+///
+/// # Example
+/// ```
+/// use std::thread;
+///
+/// use slow5::{ReadPool, RecordExt};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let pool = ReadPool::open("examples/example.slow5", 2)?;
+/// thread::scope(|s| {
+///     for read_id in ["r1", "r3", "r5"] {
+///         let pool = &pool;
+///         s.spawn(move || {
+///             let rec = pool.get_record(read_id).unwrap();
+///             assert_eq!(rec.read_id(), read_id.as_bytes());
+///         });
+///     }
+/// });
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`get_record`]: crate::ReadPool::get_record
+#[derive(Debug)]
+pub struct ReadPool {
+    readers: Vec<Mutex<FileReader>>,
+    // Handle to try first on the next call, so calls are spread over the
+    // handles
+    next: AtomicUsize,
+}
+
+impl ReadPool {
+    /// Open a file with the given number of handles, at least one. The index
+    /// is created if it doesn't exist.
+    ///
+    /// # Errors
+    /// Returns an Err if the file or its index fail to be opened
+    pub fn open<P: AsRef<Path>>(file_path: P, handles: usize) -> Result<Self, Slow5Error> {
+        Self::from_reader(FileReader::open(file_path)?, handles)
+    }
+
+    /// Create a pool from a reader, opening handles to the same file until
+    /// there are the given number of handles, at least one.
+    ///
+    /// # Errors
+    /// Returns an Err if the reader doesn't have an index, or reading from
+    /// a file isn't supported, ie with [`FileReader::from_reader`].
+    ///
+    /// [`FileReader::from_reader`]: crate::FileReader::from_reader
+    pub fn from_reader(reader: FileReader, handles: usize) -> Result<Self, Slow5Error> {
+        if !reader.has_index() {
+            return Err(Slow5Error::NoIndex);
+        }
+        let mut readers = Vec::with_capacity(handles.max(1));
+        for _ in 1..handles {
+            readers.push(Mutex::new(reader.try_clone()?));
+        }
+        readers.push(Mutex::new(reader));
+        Ok(Self {
+            readers,
+            next: AtomicUsize::new(0),
+        })
+    }
+
+    /// Number of handles to the file
+    pub fn handles(&self) -> usize {
+        self.readers.len()
+    }
+
+    // Lock a handle that isn't in use, or wait for one
+    fn acquire(&self) -> MutexGuard<'_, FileReader> {
+        let n = self.readers.len();
+        let start = self.next.fetch_add(1, Ordering::Relaxed) % n;
+        for i in 0..n {
+            if let Ok(reader) = self.readers[(start + i) % n].try_lock() {
+                return reader;
+            }
+        }
+        // A panic while reading doesn't leave the reader in a state that
+        // matters here, the next read will fail if the file is broken
+        self.readers[start]
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Get a record by read id, like [`FileReader::get_record`]
+    ///
+    /// # Errors
+    /// Returns an Err if the read id isn't in the file, or the record fails to
+    /// be read
+    ///
+    /// [`FileReader::get_record`]: crate::FileReader::get_record
+    pub fn get_record<B: Into<Vec<u8>>>(&self, read_id: B) -> Result<Record, Slow5Error> {
+        self.acquire().get_record(read_id)
+    }
+
+    /// Returns true if the read id is in the index
+    pub fn contains_read_id(&self, read_id: &[u8]) -> bool {
+        self.acquire().contains_read_id(read_id)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::thread;
+
+    use super::*;
+    use crate::RecordExt;
+
+    #[test]
+    fn test_read_pool() -> anyhow::Result<()> {
+        let reader = FileReader::open("examples/example3.blow5")?;
+        let read_ids = reader
+            .iter_read_ids()?
+            .map(|read_id| read_id.to_vec())
+            .collect::<Vec<_>>();
+        let pool = ReadPool::from_reader(reader, 3)?;
+        assert_eq!(pool.handles(), 3);
+        thread::scope(|s| {
+            for read_id in &read_ids {
+                let pool = &pool;
+                s.spawn(move || {
+                    let rec = pool.get_record(read_id.as_slice()).unwrap();
+                    assert_eq!(rec.read_id(), read_id.as_slice());
+                });
+            }
+        });
+        assert!(pool.get_record("missing").is_err());
+
+        let reader = FileReader::open_without_index("examples/example3.blow5")?;
+        assert!(matches!(
+            ReadPool::from_reader(reader, 2),
+            Err(Slow5Error::NoIndex)
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+//! Report the progress of long running operations, ie to draw a progress bar,
+//! and cancel them
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use crate::error::Slow5Error;
+
+/// Receives the progress of long running operations, like
+/// [`cat_with_progress`] and [`summarize_with_progress`], and can cancel
+/// them. Implemented for closures taking the number of records processed and
+/// bytes read, for `()`, which ignores the progress, and for
+/// [`CancellationToken`].
+///
+/// # Example
+/// ```
+/// # use assert_fs::TempDir;
+/// # use assert_fs::fixture::PathChild;
+/// use slow5::cat_with_progress;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// # let tmp_dir = TempDir::new()?;
+/// # let output = tmp_dir.child("merged.slow5");
+/// let mut last = 0;
+/// let mut progress = |records: u64, bytes: u64| {
+///     if records - last >= 1000 {
+///         eprintln!("{records} records, {bytes} bytes read");
+///         last = records;
+///     }
+/// };
+/// cat_with_progress(["examples/example.slow5"], output, &mut progress)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`cat_with_progress`]: crate::cat_with_progress
+/// [`summarize_with_progress`]: crate::stats::summarize_with_progress
+pub trait ProgressSink {
+    /// Called after each record is processed, with the number of records
+    /// processed and bytes read from the input so far. Called often, so
+    /// anything slow, ie redrawing, should be done only every so often.
+    fn update(&mut self, records: u64, bytes: u64);
+
+    /// Checked after each update, the operation stops with
+    /// [`Slow5Error::Cancelled`] once this returns true. Anything written
+    /// before then, ie part of the output file, isn't removed. By default, an
+    /// operation is never cancelled.
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+}
+
+impl<F> ProgressSink for F
+where
+    F: FnMut(u64, u64),
+{
+    fn update(&mut self, records: u64, bytes: u64) {
+        self(records, bytes)
+    }
+}
+
+impl ProgressSink for () {
+    fn update(&mut self, _records: u64, _bytes: u64) {}
+}
+
+/// Flag for cancelling a long running operation from another thread. Clones
+/// share the same flag, so one clone is passed to the operation as a
+/// [`ProgressSink`] and another is kept to cancel it.
+///
+/// # Example
+/// ```
+/// # use assert_fs::TempDir;
+/// # use assert_fs::fixture::PathChild;
+/// use slow5::{cat_with_progress, CancellationToken, Slow5Error};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// # let tmp_dir = TempDir::new()?;
+/// # let output = tmp_dir.child("merged.slow5");
+/// let token = CancellationToken::new();
+/// token.cancel();
+/// let res = cat_with_progress(["examples/example.slow5"], output, &mut token.clone());
+/// assert!(matches!(res, Err(Slow5Error::Cancelled)));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Create a token that isn't cancelled yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the operations using this token or any of its clones
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the token has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl ProgressSink for CancellationToken {
+    fn update(&mut self, _records: u64, _bytes: u64) {}
+
+    fn is_cancelled(&self) -> bool {
+        CancellationToken::is_cancelled(self)
+    }
+}
+
+// Report progress, returning an Err if the operation was cancelled
+pub(crate) fn report_progress(
+    progress: &mut dyn ProgressSink,
+    records: u64,
+    bytes: u64,
+) -> Result<(), Slow5Error> {
+    progress.update(records, bytes);
+    if progress.is_cancelled() {
+        Err(Slow5Error::Cancelled)
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cancellation_token() {
+        let token = CancellationToken::new();
+        let mut sink = token.clone();
+        assert!(report_progress(&mut sink, 1, 10).is_ok());
+        token.cancel();
+        assert!(matches!(
+            report_progress(&mut sink, 2, 20),
+            Err(Slow5Error::Cancelled)
+        ));
+
+        let mut updates = 0;
+        assert!(report_progress(&mut |_, _| updates += 1, 1, 10).is_ok());
+        assert_eq!(updates, 1);
+    }
+}
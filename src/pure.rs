@@ -0,0 +1,384 @@
+//! Read and write SLOW5 files in Rust, without slow5lib.
+//!
+//! For platforms where building slow5lib is painful, ie where there is no C
+//! compiler for the target. Records are plain owned data, read into a
+//! [`Record`] with the auxiliary fields in the order of the [`Header`]. To
+//! not build slow5lib at all, build with `--no-default-features --features
+//! pure-rust`.
+//!
+//! SLOW5 files are read with [`Slow5Reader`] and written with
+//! [`Slow5Writer`]. BLOW5 files can only be read, with [`Blow5Reader`], and
+//! [`Reader`] picks between the two from the start of the file.
+//!
+//! # Example
+//! ```
+//! use slow5::{
+//!     pure::{AuxValue, Header, Record, Slow5Reader, Slow5Writer},
+//!     FieldType,
+//! };
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let mut header = Header::new(1);
+//! header.set_attribute("run_id", "run_0", 0)?;
+//! header.add_aux("median", FieldType::Float);
+//! let mut writer = Slow5Writer::new(Vec::new(), header)?;
+//! let rec = Record {
+//!     read_id: "read_0".to_string(),
+//!     raw_signal: vec![1, 2, 3],
+//!     aux: vec![Some(AuxValue::Float(1.5))],
+//!     ..Record::default()
+//! };
+//! writer.write_record(&rec)?;
+//! let text = writer.finish()?;
+//!
+//! let mut reader = Slow5Reader::new(text.as_slice())?;
+//! assert_eq!(reader.header().attribute("run_id", 0), Some("run_0"));
+//! assert_eq!(reader.next().unwrap()?, rec);
+//! # Ok(())
+//! # }
+//! ```
+mod ascii;
+mod binary;
+
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+};
+
+pub use ascii::{Slow5Reader, Slow5Writer};
+pub use binary::Blow5Reader;
+
+use crate::{error::Slow5Error, FieldType};
+
+/// Version of the SLOW5 format written
+pub const SLOW5_VERSION: &str = "0.2.0";
+
+// Name and type of the primary fields, in the order they are in a record
+const PRIMARY_FIELDS: [(&str, &str); 8] = [
+    ("read_id", "char*"),
+    ("read_group", "uint32_t"),
+    ("digitisation", "double"),
+    ("offset", "double"),
+    ("range", "double"),
+    ("sampling_rate", "double"),
+    ("len_raw_signal", "uint64_t"),
+    ("raw_signal", "int16_t*"),
+];
+
+/// Attributes of each read group and the auxiliary fields of a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    version: String,
+    num_read_groups: u32,
+    attributes: Vec<(String, Vec<Option<String>>)>,
+    aux_fields: Vec<(String, FieldType)>,
+}
+
+impl Header {
+    /// Empty header with n read groups
+    pub fn new(num_read_groups: u32) -> Self {
+        Self {
+            version: SLOW5_VERSION.to_string(),
+            num_read_groups: num_read_groups.max(1),
+            attributes: Vec::new(),
+            aux_fields: Vec::new(),
+        }
+    }
+
+    /// Version of the SLOW5 format of the file
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Number of read groups
+    pub fn num_read_groups(&self) -> u32 {
+        self.num_read_groups
+    }
+
+    /// Value of an attribute of a read group, None if it isn't set
+    pub fn attribute(&self, key: &str, read_group: u32) -> Option<&str> {
+        let (_, values) = self.attributes.iter().find(|(k, _)| k == key)?;
+        values.get(read_group as usize)?.as_deref()
+    }
+
+    /// Iterator over the attribute keys, in the order they are in the file
+    pub fn attribute_keys(&self) -> impl Iterator<Item = &str> {
+        self.attributes.iter().map(|(key, _)| key.as_str())
+    }
+
+    /// Set the value of an attribute of a read group, adding the attribute
+    /// if it isn't in the header yet
+    ///
+    /// # Errors
+    /// Returns [`Slow5Error::AttributeError`] if the read group isn't in the
+    /// header, or the key or value has a tab or newline
+    pub fn set_attribute<K, V>(
+        &mut self,
+        key: K,
+        value: V,
+        read_group: u32,
+    ) -> Result<(), Slow5Error>
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let (key, value) = (key.into(), value.into());
+        if read_group >= self.num_read_groups
+            || key.is_empty()
+            || !is_text(&key)
+            || !is_text(&value)
+        {
+            return Err(Slow5Error::AttributeError);
+        }
+        let n = self.num_read_groups as usize;
+        let idx = match self.attributes.iter().position(|(k, _)| *k == key) {
+            Some(idx) => idx,
+            None => {
+                self.attributes.push((key, vec![None; n]));
+                self.attributes.len() - 1
+            }
+        };
+        self.attributes[idx].1[read_group as usize] = Some(value);
+        Ok(())
+    }
+
+    /// Auxiliary fields and their types, in the order of [`Record::aux`]
+    pub fn aux_fields(&self) -> &[(String, FieldType)] {
+        &self.aux_fields
+    }
+
+    /// Position of an auxiliary field in [`Record::aux`]
+    pub fn aux_index(&self, name: &str) -> Option<usize> {
+        self.aux_fields.iter().position(|(n, _)| n == name)
+    }
+
+    /// Add an auxiliary field, records written with this header have a value
+    /// for it in [`Record::aux`]
+    pub fn add_aux<B: Into<String>>(&mut self, name: B, field_type: FieldType) -> &mut Self {
+        self.aux_fields.push((name.into(), field_type));
+        self
+    }
+}
+
+/// Reader of either a SLOW5 or BLOW5 file, decided at runtime from the magic
+/// number at the start of the file
+///
+/// # Example
+/// ```
+/// use slow5::pure::Reader;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// for path in ["examples/example.slow5", "examples/example3.blow5"] {
+///     let reader = Reader::open(path)?;
+///     println!("{} has {} read groups", path, reader.header().num_read_groups());
+///     for rec in reader {
+///         println!("{}", rec?.read_id);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub enum Reader<R> {
+    /// SLOW5 text file
+    Slow5(Slow5Reader<R>),
+    /// BLOW5 binary file
+    Blow5(Blow5Reader<R>),
+}
+
+impl Reader<BufReader<File>> {
+    /// Open a SLOW5 or BLOW5 file and read its header
+    ///
+    /// # Errors
+    /// Returns an Err if the file fails to be opened or its header fails to
+    /// be parsed
+    pub fn open<P: AsRef<Path>>(file_path: P) -> Result<Self, Slow5Error> {
+        Self::new(BufReader::new(File::open(file_path)?))
+    }
+}
+
+impl<R: BufRead> Reader<R> {
+    /// Read the header from input, as BLOW5 if input starts with the BLOW5
+    /// magic number and as SLOW5 otherwise
+    ///
+    /// # Errors
+    /// Returns an Err if the header fails to be read or parsed
+    pub fn new(mut input: R) -> Result<Self, Slow5Error> {
+        if input.fill_buf()?.starts_with(binary::MAGIC) {
+            Ok(Self::Blow5(Blow5Reader::new(input)?))
+        } else {
+            Ok(Self::Slow5(Slow5Reader::new(input)?))
+        }
+    }
+
+    /// Header of the file
+    pub fn header(&self) -> &Header {
+        match self {
+            Self::Slow5(reader) => reader.header(),
+            Self::Blow5(reader) => reader.header(),
+        }
+    }
+
+    /// Read the next record, None at the end of the file
+    ///
+    /// # Errors
+    /// Returns an Err if the record fails to be read or parsed
+    pub fn read_record(&mut self) -> Result<Option<Record>, Slow5Error> {
+        match self {
+            Self::Slow5(reader) => reader.read_record(),
+            Self::Blow5(reader) => reader.read_record(),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Reader<R> {
+    type Item = Result<Record, Slow5Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+// Text can't have the separators of the SLOW5 format
+fn is_text(s: &str) -> bool {
+    !s.contains(['\t', '\n', '\r'])
+}
+
+/// Value of an auxiliary field, with the variant of its [`FieldType`]
+#[derive(Debug, Clone, PartialEq)]
+#[allow(missing_docs)]
+pub enum AuxValue {
+    Int8(i8),
+    Int16(i16),
+    Int32(i32),
+    Int64(i64),
+    Uint8(u8),
+    Uint16(u16),
+    Uint32(u32),
+    Uint64(u64),
+    Float(f32),
+    Double(f64),
+    Char(u8),
+    Str(String),
+    Int8Array(Vec<i8>),
+    Int16Array(Vec<i16>),
+    Int32Array(Vec<i32>),
+    Int64Array(Vec<i64>),
+    Uint8Array(Vec<u8>),
+    Uint16Array(Vec<u16>),
+    Uint32Array(Vec<u32>),
+    Uint64Array(Vec<u64>),
+    FloatArray(Vec<f32>),
+    DoubleArray(Vec<f64>),
+    /// Index into the labels of the field
+    Enum(u8),
+}
+
+/// Read with its signal and the values of its auxiliary fields
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Record {
+    /// Read id
+    pub read_id: String,
+    /// Read group, an index into the read groups of the header
+    pub read_group: u32,
+    /// Digitisation, used to convert the raw signal to picoamps
+    pub digitisation: f64,
+    /// Offset, used to convert the raw signal to picoamps
+    pub offset: f64,
+    /// Range, used to convert the raw signal to picoamps
+    pub range: f64,
+    /// Sampling rate in Hz
+    pub sampling_rate: f64,
+    /// Raw signal
+    pub raw_signal: Vec<i16>,
+    /// Value of each auxiliary field of the header, None if it isn't set
+    pub aux: Vec<Option<AuxValue>>,
+}
+
+impl Record {
+    /// Value of an auxiliary field, None if the field isn't in the header or
+    /// isn't set
+    pub fn aux_field(&self, header: &Header, name: &str) -> Option<&AuxValue> {
+        self.aux.get(header.aux_index(name)?)?.as_ref()
+    }
+
+    /// Signal in picoamps
+    pub fn picoamps(&self) -> Vec<f64> {
+        self.raw_signal
+            .iter()
+            .map(|&x| crate::to_picoamps(x.into(), self.digitisation, self.offset, self.range))
+            .collect()
+    }
+}
+
+// Inverse of FieldType::type_name
+fn parse_type_name(name: &str) -> Option<FieldType> {
+    let field_type = match name {
+        "int8_t" => FieldType::Int8,
+        "int16_t" => FieldType::Int16,
+        "int32_t" => FieldType::Int32,
+        "int64_t" => FieldType::Int64,
+        "uint8_t" => FieldType::Uint8,
+        "uint16_t" => FieldType::Uint16,
+        "uint32_t" => FieldType::Uint32,
+        "uint64_t" => FieldType::Uint64,
+        "float" => FieldType::Float,
+        "double" => FieldType::Double,
+        "char" => FieldType::Char,
+        "char*" => FieldType::Str,
+        "int8_t*" => FieldType::Int8Array,
+        "int16_t*" => FieldType::Int16Array,
+        "int32_t*" => FieldType::Int32Array,
+        "int64_t*" => FieldType::Int64Array,
+        "uint8_t*" => FieldType::Uint8Array,
+        "uint16_t*" => FieldType::Uint16Array,
+        "uint32_t*" => FieldType::Uint32Array,
+        "uint64_t*" => FieldType::Uint64Array,
+        "float*" => FieldType::FloatArray,
+        "double*" => FieldType::DoubleArray,
+        _ => {
+            let labels = name.strip_prefix("enum{")?.strip_suffix('}')?;
+            let labels = labels.split(',').map(|label| label.as_bytes().to_vec());
+            FieldType::Enum(labels.collect())
+        }
+    };
+    Some(field_type)
+}
+
+// Whether the value has the type of the field
+fn value_matches(value: &AuxValue, field_type: &FieldType) -> bool {
+    macro_rules! matches_type {
+        ($($variant:ident),*) => {
+            match (value, field_type) {
+                $((AuxValue::$variant(_), FieldType::$variant) => true,)*
+                (AuxValue::Enum(idx), FieldType::Enum(labels)) => (*idx as usize) < labels.len(),
+                _ => false,
+            }
+        };
+    }
+    matches_type!(
+        Int8,
+        Int16,
+        Int32,
+        Int64,
+        Uint8,
+        Uint16,
+        Uint32,
+        Uint64,
+        Float,
+        Double,
+        Char,
+        Str,
+        Int8Array,
+        Int16Array,
+        Int32Array,
+        Int64Array,
+        Uint8Array,
+        Uint16Array,
+        Uint32Array,
+        Uint64Array,
+        FloatArray,
+        DoubleArray
+    )
+}
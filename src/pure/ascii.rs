@@ -0,0 +1,515 @@
+//! SLOW5 text format
+use std::{
+    fmt::Write as _,
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::Path,
+    str::FromStr,
+};
+
+use super::{is_text, parse_type_name, value_matches, AuxValue, Header, Record, PRIMARY_FIELDS};
+use crate::{error::Slow5Error, FieldType};
+
+// Missing values, for unset attributes and auxiliary fields
+const MISSING: &str = ".";
+
+fn parse_error(line: usize, reason: impl Into<String>) -> Slow5Error {
+    Slow5Error::ImportParse {
+        line,
+        reason: reason.into(),
+    }
+}
+
+fn parse_array<T: FromStr>(value: &str) -> Option<Vec<T>> {
+    if value.is_empty() {
+        return Some(Vec::new());
+    }
+    value.split(',').map(|v| v.parse().ok()).collect()
+}
+
+macro_rules! parse_aux {
+    ($value:expr, $field_type:expr, $($variant:ident),*) => {
+        paste::paste! {
+            match $field_type {
+                $(
+                    FieldType::$variant => AuxValue::$variant($value.parse().ok()?),
+                    FieldType::[<$variant Array>] => AuxValue::[<$variant Array>](parse_array($value)?),
+                )*
+                FieldType::Char => match $value.as_bytes() {
+                    &[c] => AuxValue::Char(c),
+                    _ => return None,
+                },
+                FieldType::Str => AuxValue::Str($value.to_string()),
+                FieldType::Enum(labels) => {
+                    let idx = $value.parse().ok()?;
+                    if usize::from(idx) >= labels.len() {
+                        return None;
+                    }
+                    AuxValue::Enum(idx)
+                }
+            }
+        }
+    };
+}
+
+// Parse the value of an auxiliary field, None if it doesn't match the type
+fn parse_aux(value: &str, field_type: &FieldType) -> Option<AuxValue> {
+    let value = parse_aux!(
+        value, field_type, Int8, Int16, Int32, Int64, Uint8, Uint16, Uint32, Uint64, Float, Double
+    );
+    Some(value)
+}
+
+fn join<T: ToString>(values: &[T]) -> String {
+    values
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+macro_rules! format_aux {
+    ($value:expr, $($variant:ident),*) => {
+        paste::paste! {
+            match $value {
+                $(
+                    AuxValue::$variant(v) => v.to_string(),
+                    AuxValue::[<$variant Array>](vs) => join(vs),
+                )*
+                AuxValue::Char(c) => char::from(*c).to_string(),
+                AuxValue::Str(s) => s.clone(),
+                AuxValue::Enum(idx) => idx.to_string(),
+            }
+        }
+    };
+}
+
+fn format_aux(value: &AuxValue) -> String {
+    format_aux!(value, Int8, Int16, Int32, Int64, Uint8, Uint16, Uint32, Uint64, Float, Double)
+}
+
+// Next line without its line ending, None at the end of the input
+fn read_line<'a, R: BufRead>(
+    input: &mut R,
+    buf: &'a mut String,
+    line: &mut usize,
+) -> Result<Option<&'a str>, Slow5Error> {
+    buf.clear();
+    if input.read_line(buf)? == 0 {
+        return Ok(None);
+    }
+    *line += 1;
+    Ok(Some(buf.trim_end_matches(['\n', '\r'])))
+}
+
+// Read the header lines into header, which has the number of read groups if
+// the input doesn't, ie the header of a BLOW5 file
+pub(super) fn read_header<R: BufRead>(
+    input: &mut R,
+    line: &mut usize,
+    header: &mut Header,
+) -> Result<(), Slow5Error> {
+    let mut buf = String::new();
+    let mut types = None;
+    while types.is_none() {
+        let text = read_line(input, &mut buf, line)?
+            .map(ToString::to_string)
+            .ok_or_else(|| parse_error(*line, "header ended early"))?;
+        let mut values = text.split('\t');
+        let key = values.next().unwrap_or_default();
+        let values = values.map(ToString::to_string).collect::<Vec<_>>();
+        if let Some(key) = key.strip_prefix('@') {
+            if values.len() != header.num_read_groups as usize {
+                let reason = format!("attribute {key} doesn't have a value per read group");
+                return Err(parse_error(*line, reason));
+            }
+            let values = values
+                .into_iter()
+                .map(|v| (v != MISSING).then_some(v))
+                .collect();
+            header.attributes.push((key.to_string(), values));
+            continue;
+        }
+        match (key, values.as_slice()) {
+            ("#slow5_version", [version]) => header.version = version.clone(),
+            ("#num_read_groups", [n]) => {
+                header.num_read_groups = n
+                    .parse()
+                    .ok()
+                    .filter(|&n| n > 0)
+                    .ok_or_else(|| parse_error(*line, "invalid num_read_groups"))?;
+            }
+            ("#char*", _) => {
+                types = Some(text.trim_start_matches('#').to_string());
+            }
+            _ => return Err(parse_error(*line, "invalid header line")),
+        }
+    }
+    let types = types.unwrap_or_default();
+    let names = read_line(input, &mut buf, line)?
+        .map(|names| names.trim_start_matches('#').to_string())
+        .ok_or_else(|| parse_error(*line, "no column names"))?;
+    let types = types.split('\t').collect::<Vec<_>>();
+    let names = names.split('\t').collect::<Vec<_>>();
+    if types.len() != names.len() || types.len() < PRIMARY_FIELDS.len() {
+        return Err(parse_error(*line, "column names don't match types"));
+    }
+    for (idx, (name, ty)) in PRIMARY_FIELDS.iter().enumerate() {
+        if names[idx] != *name || types[idx] != *ty {
+            let reason = format!("expected {name} column of type {ty}");
+            return Err(parse_error(*line, reason));
+        }
+    }
+    for (name, ty) in names.iter().zip(types.iter()).skip(PRIMARY_FIELDS.len()) {
+        let field_type = parse_type_name(ty)
+            .ok_or_else(|| parse_error(*line - 1, format!("unknown type {ty}")))?;
+        header.aux_fields.push((name.to_string(), field_type));
+    }
+    Ok(())
+}
+
+/// Read the header and records of a SLOW5 text file
+///
+/// # Example
+/// ```
+/// use slow5::pure::Slow5Reader;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let reader = Slow5Reader::open("examples/example.slow5")?;
+/// for rec in reader {
+///     let rec = rec?;
+///     println!("{} has {} samples", rec.read_id, rec.raw_signal.len());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Slow5Reader<R> {
+    input: R,
+    header: Header,
+    // Number of lines read so far
+    line: usize,
+    buf: String,
+}
+
+impl Slow5Reader<BufReader<File>> {
+    /// Open a SLOW5 text file and read its header
+    ///
+    /// # Errors
+    /// Returns an Err if the file fails to be opened or its header fails to
+    /// be parsed
+    pub fn open<P: AsRef<Path>>(file_path: P) -> Result<Self, Slow5Error> {
+        Self::new(BufReader::new(File::open(file_path)?))
+    }
+}
+
+impl<R: BufRead> Slow5Reader<R> {
+    /// Read the header from input, leaving it at the first record
+    ///
+    /// # Errors
+    /// Returns [`Slow5Error::ImportParse`] if the header isn't valid, or an
+    /// Err if input fails to be read
+    pub fn new(input: R) -> Result<Self, Slow5Error> {
+        let mut reader = Self {
+            input,
+            header: Header::new(1),
+            line: 0,
+            buf: String::new(),
+        };
+        read_header(&mut reader.input, &mut reader.line, &mut reader.header)?;
+        Ok(reader)
+    }
+
+    /// Header of the file
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Read the next record, None at the end of the file
+    ///
+    /// # Errors
+    /// Returns [`Slow5Error::ImportParse`] if the record isn't valid, or an
+    /// Err if the input fails to be read
+    pub fn read_record(&mut self) -> Result<Option<Record>, Slow5Error> {
+        let line_number = self.line + 1;
+        let Some(line) = read_line(&mut self.input, &mut self.buf, &mut self.line)? else {
+            return Ok(None);
+        };
+        let header = &self.header;
+        let values = line.split('\t').collect::<Vec<_>>();
+        let n_columns = PRIMARY_FIELDS.len() + header.aux_fields.len();
+        if values.len() != n_columns {
+            let reason = format!("expected {n_columns} columns, found {}", values.len());
+            return Err(parse_error(line_number, reason));
+        }
+        let invalid = |idx: usize| {
+            let name = PRIMARY_FIELDS
+                .get(idx)
+                .map(|(name, _)| *name)
+                .unwrap_or_else(|| header.aux_fields[idx - PRIMARY_FIELDS.len()].0.as_str());
+            parse_error(line_number, format!("invalid value for {name}"))
+        };
+        let read_group = values[1].parse().map_err(|_| invalid(1))?;
+        if read_group >= header.num_read_groups {
+            return Err(invalid(1));
+        }
+        let len_raw_signal = values[6].parse::<usize>().map_err(|_| invalid(6))?;
+        let raw_signal = parse_array(values[7]).ok_or_else(|| invalid(7))?;
+        if raw_signal.len() != len_raw_signal {
+            return Err(parse_error(
+                line_number,
+                "len_raw_signal doesn't match raw_signal",
+            ));
+        }
+        let aux = header
+            .aux_fields
+            .iter()
+            .zip(values.iter().skip(PRIMARY_FIELDS.len()))
+            .enumerate()
+            .map(|(idx, ((_, field_type), value))| match *value {
+                MISSING => Ok(None),
+                value => parse_aux(value, field_type)
+                    .map(Some)
+                    .ok_or_else(|| invalid(PRIMARY_FIELDS.len() + idx)),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Some(Record {
+            read_id: values[0].to_string(),
+            read_group,
+            digitisation: values[2].parse().map_err(|_| invalid(2))?,
+            offset: values[3].parse().map_err(|_| invalid(3))?,
+            range: values[4].parse().map_err(|_| invalid(4))?,
+            sampling_rate: values[5].parse().map_err(|_| invalid(5))?,
+            raw_signal,
+            aux,
+        }))
+    }
+}
+
+impl<R: BufRead> Iterator for Slow5Reader<R> {
+    type Item = Result<Record, Slow5Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+/// Write a header and records as a SLOW5 text file
+#[derive(Debug)]
+pub struct Slow5Writer<W: Write> {
+    output: W,
+    header: Header,
+}
+
+impl Slow5Writer<BufWriter<File>> {
+    /// Create a SLOW5 text file and write the header
+    ///
+    /// # Errors
+    /// Returns [`Slow5Error::FileExists`] if the file exists, or an Err if it
+    /// fails to be written
+    pub fn create<P: AsRef<Path>>(file_path: P, header: Header) -> Result<Self, Slow5Error> {
+        let file_path = file_path.as_ref();
+        if file_path.exists() {
+            return Err(Slow5Error::FileExists(file_path.to_owned()));
+        }
+        Self::new(BufWriter::new(File::create(file_path)?), header)
+    }
+}
+
+impl<W: Write> Slow5Writer<W> {
+    /// Write the header to output
+    ///
+    /// # Errors
+    /// Returns [`Slow5Error::AttributeError`] if the name of an auxiliary
+    /// field has a tab or newline, or an Err if output fails to be written
+    pub fn new(mut output: W, header: Header) -> Result<Self, Slow5Error> {
+        if header.aux_fields.iter().any(|(name, _)| !is_text(name)) {
+            return Err(Slow5Error::AttributeError);
+        }
+        let mut text = String::new();
+        let _ = writeln!(text, "#slow5_version\t{}", header.version);
+        let _ = writeln!(text, "#num_read_groups\t{}", header.num_read_groups);
+        for (key, values) in header.attributes.iter() {
+            let _ = write!(text, "@{key}");
+            for value in values.iter() {
+                let _ = write!(text, "\t{}", value.as_deref().unwrap_or(MISSING));
+            }
+            text.push('\n');
+        }
+        let types = PRIMARY_FIELDS
+            .iter()
+            .map(|(_, ty)| ty.to_string())
+            .chain(header.aux_fields.iter().map(|(_, ty)| ty.type_name()));
+        let names = PRIMARY_FIELDS
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .chain(header.aux_fields.iter().map(|(name, _)| name.clone()));
+        let _ = writeln!(text, "#{}", types.collect::<Vec<_>>().join("\t"));
+        let _ = writeln!(text, "#{}", names.collect::<Vec<_>>().join("\t"));
+        output.write_all(text.as_bytes())?;
+        Ok(Self { output, header })
+    }
+
+    /// Header of the file
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Write a record
+    ///
+    /// # Errors
+    /// Returns [`Slow5Error::Argument`] if the read group isn't in the
+    /// header, the read id has a tab or newline, or the record doesn't have a
+    /// value of the right type for each auxiliary field. Returns an Err if the
+    /// output fails to be written.
+    pub fn write_record(&mut self, rec: &Record) -> Result<(), Slow5Error> {
+        let header = &self.header;
+        let valid =
+            rec.read_group < header.num_read_groups
+                && !rec.read_id.is_empty()
+                && is_text(&rec.read_id)
+                && rec.aux.len() == header.aux_fields.len()
+                && rec.aux.iter().zip(header.aux_fields.iter()).all(
+                    |(value, (_, ty))| match value {
+                        Some(AuxValue::Str(s)) => is_text(s),
+                        Some(value) => value_matches(value, ty),
+                        None => true,
+                    },
+                );
+        if !valid {
+            return Err(Slow5Error::Argument);
+        }
+        let mut line = format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            rec.read_id,
+            rec.read_group,
+            rec.digitisation,
+            rec.offset,
+            rec.range,
+            rec.sampling_rate,
+            rec.raw_signal.len(),
+            join(&rec.raw_signal)
+        );
+        for value in rec.aux.iter() {
+            line.push('\t');
+            match value {
+                Some(value) => line.push_str(&format_aux(value)),
+                None => line.push_str(MISSING),
+            }
+        }
+        line.push('\n');
+        self.output.write_all(line.as_bytes())?;
+        Ok(())
+    }
+
+    /// Flush the output and return it
+    ///
+    /// # Errors
+    /// Returns an Err if the output fails to be flushed
+    pub fn finish(mut self) -> Result<W, Slow5Error> {
+        self.output.flush()?;
+        Ok(self.output)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[cfg(feature = "slow5lib")]
+    use crate::{FileReader, HeaderExt, RecordExt};
+
+    #[test]
+    fn test_read_slow5() -> anyhow::Result<()> {
+        let text = "#slow5_version\t0.2.0\n\
+                    #num_read_groups\t2\n\
+                    @run_id\trun_0\t.\n\
+                    #char*\tuint32_t\tdouble\tdouble\tdouble\tdouble\tuint64_t\tint16_t*\tenum{a,b}\tfloat*\n\
+                    #read_id\tread_group\tdigitisation\toffset\trange\tsampling_rate\tlen_raw_signal\traw_signal\tend\tlevels\n\
+                    r0\t1\t4096\t3\t1402.882\t4000\t3\t1,-2,3\t1\t0.5,1.5\n\
+                    r1\t0\t4096\t3\t1402.882\t4000\t0\t\t.\t\n";
+        let mut reader = Slow5Reader::new(text.as_bytes())?;
+        let header = reader.header();
+        assert_eq!(header.num_read_groups(), 2);
+        assert_eq!(header.attribute("run_id", 0), Some("run_0"));
+        assert_eq!(header.attribute("run_id", 1), None);
+        assert_eq!(header.aux_fields()[0].1, FieldType::from(vec!["a", "b"]));
+        let rec = reader.next().unwrap()?;
+        assert_eq!(rec.read_group, 1);
+        assert_eq!(rec.range, 1402.882);
+        assert_eq!(rec.raw_signal, [1, -2, 3]);
+        assert_eq!(rec.aux[0], Some(AuxValue::Enum(1)));
+        assert_eq!(
+            rec.aux_field(reader.header(), "levels"),
+            Some(&AuxValue::FloatArray(vec![0.5, 1.5]))
+        );
+        let rec = reader.next().unwrap()?;
+        assert!(rec.raw_signal.is_empty());
+        assert_eq!(rec.aux, [None, Some(AuxValue::FloatArray(Vec::new()))]);
+        assert!(reader.next().is_none());
+
+        let bad = text.replace("\t3\t1,-2,3", "\t4\t1,-2,3");
+        let mut reader = Slow5Reader::new(bad.as_bytes())?;
+        assert!(matches!(
+            reader.next(),
+            Some(Err(Slow5Error::ImportParse { line: 6, .. }))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_round_trip() -> anyhow::Result<()> {
+        let mut header = Header::new(1);
+        header.set_attribute("asic_id", "123", 0)?;
+        header
+            .add_aux("channel_number", FieldType::Str)
+            .add_aux("read_number", FieldType::Int32)
+            .add_aux("start_mux", FieldType::Char);
+        let rec = Record {
+            read_id: "read_0".to_string(),
+            digitisation: 8192.0,
+            offset: 6.0,
+            range: 1467.6,
+            sampling_rate: 4000.0,
+            raw_signal: vec![430, 472, 463],
+            aux: vec![
+                Some(AuxValue::Str("391".to_string())),
+                None,
+                Some(AuxValue::Char(b'1')),
+            ],
+            ..Record::default()
+        };
+        let mut writer = Slow5Writer::new(Vec::new(), header.clone())?;
+        writer.write_record(&rec)?;
+        let mut wrong_type = rec.clone();
+        wrong_type.aux[1] = Some(AuxValue::Uint8(1));
+        assert!(matches!(
+            writer.write_record(&wrong_type),
+            Err(Slow5Error::Argument)
+        ));
+        let text = writer.finish()?;
+
+        let reader = Slow5Reader::new(text.as_slice())?;
+        assert_eq!(reader.header(), &header);
+        let records = reader.collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(records, [rec]);
+        Ok(())
+    }
+
+    #[cfg(feature = "slow5lib")]
+    #[test]
+    fn test_matches_slow5lib() -> anyhow::Result<()> {
+        let mut reader = FileReader::open("examples/example.slow5")?;
+        let pure = Slow5Reader::open("examples/example.slow5")?;
+        assert_eq!(pure.header().num_read_groups(), reader.num_read_groups());
+        for (rec, pure_rec) in reader.records().zip(pure) {
+            let (rec, pure_rec) = (rec?, pure_rec?);
+            assert_eq!(rec.read_id(), pure_rec.read_id.as_bytes());
+            assert_eq!(rec.range(), pure_rec.range);
+            assert_eq!(
+                rec.raw_signal_iter().collect::<Vec<_>>(),
+                pure_rec.raw_signal
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,429 @@
+//! BLOW5 binary format
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Read},
+    mem::size_of,
+    path::Path,
+};
+
+use super::{ascii::read_header, AuxValue, Header, Record};
+use crate::{error::Slow5Error, FieldType};
+
+pub(super) const MAGIC: &[u8; 6] = b"BLOW5\x01";
+const EOF: &[u8; 5] = b"5WOLB";
+// Offset of the size of the header text, the fields before it are padded
+const HEADER_SIZE_OFFSET: usize = 64;
+
+// Compression methods, as numbered by slow5lib
+const COMPRESS_NONE: u8 = 0;
+const COMPRESS_ZLIB: u8 = 1;
+const COMPRESS_SVB_ZD: u8 = 2;
+const COMPRESS_ZSTD: u8 = 3;
+
+fn invalid_data(msg: impl Into<String>) -> Slow5Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into()).into()
+}
+
+// Reads little-endian values from the bytes of a record
+struct Bytes<'a>(&'a [u8]);
+
+impl<'a> Bytes<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], Slow5Error> {
+        if n > self.0.len() {
+            return Err(invalid_data("record is truncated"));
+        }
+        let (head, tail) = self.0.split_at(n);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], Slow5Error> {
+        let mut buf = [0; N];
+        buf.copy_from_slice(self.take(N)?);
+        Ok(buf)
+    }
+
+    // Length of an array or string
+    fn len(&mut self) -> Result<usize, Slow5Error> {
+        usize::try_from(u64::from_le_bytes(self.array()?))
+            .map_err(|_| invalid_data("length is too large"))
+    }
+}
+
+// Values of an array, with the number of elements before them
+fn read_array<T, const N: usize>(
+    bytes: &mut Bytes,
+    from_le_bytes: fn([u8; N]) -> T,
+) -> Result<Vec<T>, Slow5Error> {
+    let len = bytes.len()?;
+    let n_bytes = len
+        .checked_mul(N)
+        .ok_or_else(|| invalid_data("length is too large"))?;
+    let mut values = Bytes(bytes.take(n_bytes)?);
+    (0..len)
+        .map(|_| Ok(from_le_bytes(values.array()?)))
+        .collect()
+}
+
+macro_rules! read_aux {
+    ($bytes:expr, $field_type:expr, ints: $($int:ident => $itype:ty),*; floats: $($float:ident => $ftype:ty),*) => {
+        paste::paste! {
+            match $field_type {
+                // Missing values are the maximum of integer types and NaN for
+                // floats
+                $(
+                    FieldType::$int => {
+                        let value = <$itype>::from_le_bytes($bytes.array()?);
+                        (value != <$itype>::MAX).then_some(AuxValue::$int(value))
+                    }
+                    FieldType::[<$int Array>] => {
+                        let values = read_array($bytes, <$itype>::from_le_bytes)?;
+                        (!values.is_empty()).then_some(AuxValue::[<$int Array>](values))
+                    }
+                )*
+                $(
+                    FieldType::$float => {
+                        let value = <$ftype>::from_le_bytes($bytes.array()?);
+                        (!value.is_nan()).then_some(AuxValue::$float(value))
+                    }
+                    FieldType::[<$float Array>] => {
+                        let values = read_array($bytes, <$ftype>::from_le_bytes)?;
+                        (!values.is_empty()).then_some(AuxValue::[<$float Array>](values))
+                    }
+                )*
+                FieldType::Char => {
+                    let [c] = $bytes.array()?;
+                    (c != 0).then_some(AuxValue::Char(c))
+                }
+                FieldType::Str => {
+                    let len = $bytes.len()?;
+                    let s = std::str::from_utf8($bytes.take(len)?)?;
+                    (!s.is_empty()).then(|| AuxValue::Str(s.to_string()))
+                }
+                FieldType::Enum(_) => {
+                    let [idx] = $bytes.array()?;
+                    (idx != u8::MAX).then_some(AuxValue::Enum(idx))
+                }
+            }
+        }
+    };
+}
+
+fn read_aux(bytes: &mut Bytes, field_type: &FieldType) -> Result<Option<AuxValue>, Slow5Error> {
+    let value = read_aux!(
+        bytes, field_type,
+        ints: Int8 => i8, Int16 => i16, Int32 => i32, Int64 => i64,
+            Uint8 => u8, Uint16 => u16, Uint32 => u32, Uint64 => u64;
+        floats: Float => f32, Double => f64
+    );
+    Ok(value)
+}
+
+// Decode a signal compressed with svb-zd, zigzag encoded differences between
+// samples packed with streamvbyte
+fn decode_svb_zd(bytes: &mut Bytes) -> Result<Vec<i16>, Slow5Error> {
+    let count = u32::from_le_bytes(bytes.array()?) as usize;
+    // 2 bits per value for its number of bytes, minus 1
+    let keys = bytes.take((count + 3) / 4)?;
+    let n_bytes = |idx: usize| usize::from((keys[idx / 4] >> ((idx % 4) * 2)) & 3) + 1;
+    let data_len = (0..count).map(n_bytes).sum();
+    let mut data = Bytes(bytes.take(data_len)?);
+    let mut prev = 0i32;
+    let mut signal = Vec::with_capacity(count);
+    for idx in 0..count {
+        let mut value = [0; 4];
+        let n = n_bytes(idx);
+        value[..n].copy_from_slice(data.take(n)?);
+        let zigzag = u32::from_le_bytes(value);
+        let delta = (zigzag >> 1) as i32 ^ -((zigzag & 1) as i32);
+        prev = prev.wrapping_add(delta);
+        let sample = i16::try_from(prev).map_err(|_| invalid_data("sample out of range"))?;
+        signal.push(sample);
+    }
+    Ok(signal)
+}
+
+/// Read the header and records of a BLOW5 file, with records compressed with
+/// zlib or zstd, or not compressed, and signals compressed with svb-zd or
+/// not compressed
+///
+/// # Example
+/// ```
+/// use slow5::pure::Blow5Reader;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let reader = Blow5Reader::open("examples/example3.blow5")?;
+/// for rec in reader {
+///     let rec = rec?;
+///     println!("{} has {} samples", rec.read_id, rec.raw_signal.len());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Blow5Reader<R> {
+    input: R,
+    header: Header,
+    record_compression: u8,
+    signal_compression: u8,
+    done: bool,
+}
+
+impl Blow5Reader<BufReader<File>> {
+    /// Open a BLOW5 file and read its header
+    ///
+    /// # Errors
+    /// Returns an Err if the file fails to be opened or its header fails to
+    /// be parsed
+    pub fn open<P: AsRef<Path>>(file_path: P) -> Result<Self, Slow5Error> {
+        Self::new(BufReader::new(File::open(file_path)?))
+    }
+}
+
+impl<R: BufRead> Blow5Reader<R> {
+    /// Read the header from input, leaving it at the first record
+    ///
+    /// # Errors
+    /// Returns an Err if input isn't a BLOW5 file, uses a compression method
+    /// that isn't supported, or fails to be read
+    pub fn new(mut input: R) -> Result<Self, Slow5Error> {
+        let mut fixed = [0; HEADER_SIZE_OFFSET];
+        input.read_exact(&mut fixed)?;
+        if !fixed.starts_with(MAGIC) {
+            return Err(invalid_data("not a BLOW5 file"));
+        }
+        let mut bytes = Bytes(&fixed[MAGIC.len()..]);
+        let [major, minor, patch] = bytes.array()?;
+        let [record_compression] = bytes.array()?;
+        let num_read_groups = u32::from_le_bytes(bytes.array()?);
+        let [signal_compression] = bytes.array()?;
+        if ![COMPRESS_NONE, COMPRESS_ZLIB, COMPRESS_ZSTD].contains(&record_compression) {
+            return Err(invalid_data("record compression isn't supported"));
+        }
+        if ![COMPRESS_NONE, COMPRESS_SVB_ZD].contains(&signal_compression) {
+            return Err(invalid_data("signal compression isn't supported"));
+        }
+
+        let mut size = [0; 4];
+        input.read_exact(&mut size)?;
+        let mut text = vec![0; u32::from_le_bytes(size) as usize];
+        input.read_exact(&mut text)?;
+        let mut header = Header::new(num_read_groups);
+        header.version = format!("{major}.{minor}.{patch}");
+        read_header(&mut text.as_slice(), &mut 0, &mut header)?;
+        Ok(Self {
+            input,
+            header,
+            record_compression,
+            signal_compression,
+            done: false,
+        })
+    }
+
+    /// Header of the file
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    fn decompress(&self, compressed: Vec<u8>) -> Result<Vec<u8>, Slow5Error> {
+        match self.record_compression {
+            COMPRESS_ZLIB => miniz_oxide::inflate::decompress_to_vec_zlib(&compressed)
+                .map_err(|_| invalid_data("record fails to be decompressed with zlib")),
+            COMPRESS_ZSTD => {
+                let mut decoder = ruzstd::StreamingDecoder::new(compressed.as_slice())
+                    .map_err(|_| invalid_data("record fails to be decompressed with zstd"))?;
+                let mut record = Vec::new();
+                decoder.read_to_end(&mut record)?;
+                Ok(record)
+            }
+            _ => Ok(compressed),
+        }
+    }
+
+    /// Read the next record, None at the end of the file
+    ///
+    /// # Errors
+    /// Returns an Err if the record fails to be read, decompressed or
+    /// parsed, or the file ends without the end of file marker
+    pub fn read_record(&mut self) -> Result<Option<Record>, Slow5Error> {
+        if self.done {
+            return Ok(None);
+        }
+        let mut size = [0; 8];
+        self.input.read_exact(&mut size[..EOF.len()])?;
+        if &size[..EOF.len()] == EOF && self.input.fill_buf()?.is_empty() {
+            self.done = true;
+            return Ok(None);
+        }
+        self.input.read_exact(&mut size[EOF.len()..])?;
+        let size = usize::try_from(u64::from_le_bytes(size))
+            .map_err(|_| invalid_data("record is too large"))?;
+        let mut compressed = vec![0; size];
+        self.input.read_exact(&mut compressed)?;
+        let record = self.decompress(compressed)?;
+
+        let mut bytes = Bytes(&record);
+        let read_id_len = u16::from_le_bytes(bytes.array()?);
+        let read_id = std::str::from_utf8(bytes.take(read_id_len.into())?)?.to_string();
+        let read_group = u32::from_le_bytes(bytes.array()?);
+        let digitisation = f64::from_le_bytes(bytes.array()?);
+        let offset = f64::from_le_bytes(bytes.array()?);
+        let range = f64::from_le_bytes(bytes.array()?);
+        let sampling_rate = f64::from_le_bytes(bytes.array()?);
+        let len_raw_signal = bytes.len()?;
+        let raw_signal = if self.signal_compression == COMPRESS_SVB_ZD {
+            decode_svb_zd(&mut bytes)?
+        } else {
+            let n_bytes = len_raw_signal
+                .checked_mul(size_of::<i16>())
+                .ok_or_else(|| invalid_data("length is too large"))?;
+            let mut signal = Bytes(bytes.take(n_bytes)?);
+            (0..len_raw_signal)
+                .map(|_| Ok(i16::from_le_bytes(signal.array()?)))
+                .collect::<Result<_, Slow5Error>>()?
+        };
+        if raw_signal.len() != len_raw_signal {
+            return Err(invalid_data("len_raw_signal doesn't match raw_signal"));
+        }
+        let aux = self
+            .header
+            .aux_fields
+            .iter()
+            .map(|(_, field_type)| read_aux(&mut bytes, field_type))
+            .collect::<Result<_, _>>()?;
+        Ok(Some(Record {
+            read_id,
+            read_group,
+            digitisation,
+            offset,
+            range,
+            sampling_rate,
+            raw_signal,
+            aux,
+        }))
+    }
+}
+
+impl<R: BufRead> Iterator for Blow5Reader<R> {
+    type Item = Result<Record, Slow5Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[cfg(feature = "slow5lib")]
+    use crate::{FileReader, HeaderExt, RecordExt};
+
+    fn encode_svb_zd(signal: &[i16]) -> Vec<u8> {
+        let mut out = (signal.len() as u32).to_le_bytes().to_vec();
+        let mut keys = vec![0u8; (signal.len() + 3) / 4];
+        let mut data = Vec::new();
+        let mut prev = 0i32;
+        for (idx, &sample) in signal.iter().enumerate() {
+            let delta = i32::from(sample) - prev;
+            prev = i32::from(sample);
+            let zigzag = ((delta << 1) ^ (delta >> 31)) as u32;
+            let n = (4 - zigzag.leading_zeros() as usize / 8).max(1);
+            keys[idx / 4] |= ((n - 1) as u8) << ((idx % 4) * 2);
+            data.extend_from_slice(&zigzag.to_le_bytes()[..n]);
+        }
+        out.extend(keys);
+        out.extend(data);
+        out
+    }
+
+    fn blow5(signal_compression: u8) -> Vec<u8> {
+        let mut file = MAGIC.to_vec();
+        file.extend([0, 2, 0, COMPRESS_ZLIB]);
+        file.extend(1u32.to_le_bytes());
+        file.push(signal_compression);
+        file.resize(HEADER_SIZE_OFFSET, 0);
+        let text = "@run_id\trun_0\n\
+                    #char*\tuint32_t\tdouble\tdouble\tdouble\tdouble\tuint64_t\tint16_t*\tint32_t\tchar*\tuint8_t*\n\
+                    #read_id\tread_group\tdigitisation\toffset\trange\tsampling_rate\tlen_raw_signal\traw_signal\tread_number\tchannel_number\tmux\n";
+        file.extend((text.len() as u32).to_le_bytes());
+        file.extend(text.as_bytes());
+
+        let signal = [430i16, 472, -463, 32000, -32000];
+        let mut rec = 2u16.to_le_bytes().to_vec();
+        rec.extend(b"r0");
+        rec.extend(0u32.to_le_bytes());
+        for x in [4096.0f64, 3.0, 1402.882, 4000.0] {
+            rec.extend(x.to_le_bytes());
+        }
+        rec.extend((signal.len() as u64).to_le_bytes());
+        if signal_compression == COMPRESS_SVB_ZD {
+            rec.extend(encode_svb_zd(&signal));
+        } else {
+            signal.iter().for_each(|x| rec.extend(x.to_le_bytes()));
+        }
+        rec.extend(i32::MAX.to_le_bytes());
+        rec.extend(3u64.to_le_bytes());
+        rec.extend(b"391");
+        rec.extend(2u64.to_le_bytes());
+        rec.extend([1, 2]);
+        let compressed = miniz_oxide::deflate::compress_to_vec_zlib(&rec, 6);
+        file.extend((compressed.len() as u64).to_le_bytes());
+        file.extend(compressed);
+        file.extend(EOF);
+        file
+    }
+
+    #[test]
+    fn test_read_blow5() -> anyhow::Result<()> {
+        for signal_compression in [COMPRESS_NONE, COMPRESS_SVB_ZD] {
+            let file = blow5(signal_compression);
+            let mut reader = Blow5Reader::new(file.as_slice())?;
+            assert_eq!(reader.header().version(), "0.2.0");
+            assert_eq!(reader.header().attribute("run_id", 0), Some("run_0"));
+            let rec = reader.next().unwrap()?;
+            assert_eq!(rec.read_id, "r0");
+            assert_eq!(rec.range, 1402.882);
+            assert_eq!(rec.raw_signal, [430, 472, -463, 32000, -32000]);
+            assert_eq!(
+                rec.aux,
+                [
+                    None,
+                    Some(AuxValue::Str("391".to_string())),
+                    Some(AuxValue::Uint8Array(vec![1, 2]))
+                ]
+            );
+            assert!(reader.next().is_none());
+
+            let truncated = &file[..file.len() - EOF.len()];
+            let mut reader = Blow5Reader::new(truncated)?;
+            reader.next().unwrap()?;
+            assert!(reader.next().unwrap().is_err());
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "slow5lib")]
+    #[test]
+    fn test_matches_slow5lib() -> anyhow::Result<()> {
+        let mut reader = FileReader::open("examples/example3.blow5")?;
+        let pure = Blow5Reader::open("examples/example3.blow5")?;
+        assert_eq!(pure.header().num_read_groups(), reader.num_read_groups());
+        let header = pure.header().clone();
+        let mut n = 0;
+        for (rec, pure_rec) in reader.records().zip(pure) {
+            let (rec, pure_rec) = (rec?, pure_rec?);
+            assert_eq!(rec.read_id(), pure_rec.read_id.as_bytes());
+            assert_eq!(
+                rec.raw_signal_iter().collect::<Vec<_>>(),
+                pure_rec.raw_signal
+            );
+            assert_eq!(
+                pure_rec.aux_field(&header, "read_number"),
+                Some(&AuxValue::Int32(rec.get_aux_field("read_number")?))
+            );
+            n += 1;
+        }
+        assert_eq!(n, reader.iter_read_ids()?.count());
+        Ok(())
+    }
+}
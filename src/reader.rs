@@ -1,32 +1,65 @@
 use std::{
+    cell::{Cell, OnceCell},
     ffi::{CStr, CString},
+    io::{Read, Seek},
+    iter::Take,
     marker::PhantomData,
     mem::size_of,
     os::unix::prelude::OsStrExt,
-    path::Path,
+    path::{Path, PathBuf},
+    ptr::null_mut,
+    sync::Arc,
+    time::Instant,
 };
 
 use cstr::cstr;
 use libc::{c_char, c_void};
 use slow5lib_sys::{
-    slow5_file_t, slow5_get, slow5_get_aux_enum_labels, slow5_get_hdr_keys, slow5_get_rids,
-    slow5_hdr_t, slow5_rec_t,
+    slow5_file_t, slow5_fmt_SLOW5_FORMAT_ASCII, slow5_fmt_SLOW5_FORMAT_BINARY, slow5_get,
+    slow5_get_aux_enum_labels, slow5_get_hdr_keys, slow5_get_mem, slow5_get_rids, slow5_hdr_t,
+    slow5_idx, slow5_idx_free, slow5_idx_get, slow5_init, slow5_rec_free, slow5_rec_idx,
+    slow5_rec_size_t, slow5_rec_t, SLOW5_ERR_EOF, SLOW5_ERR_NOTFOUND, SLOW5_ERR_OTHER,
 };
 
 use crate::{
     error::Slow5Error,
+    filter::{Filter, FilteredIter},
     header::HeaderExt,
-    record::{Record, RecordIter},
-    to_cstring, Header, RecordCompression, SignalCompression,
+    log::{silence_slow5lib_logs, LogLevel, LogLevelGuard},
+    record::{Record, RecordChunks, RecordIter, RecordView, RecordsInto, RecoverIter, ShardIter},
+    stats::Stats,
+    stream, to_cstring, Header, HeaderOwned, RecordCompression, SignalCompression,
 };
 
+// Path given to slow5lib for data that isn't read from a file
+pub(crate) const STREAM_PATH: &CStr = cstr!("-");
+
 /// Read from a SLOW5 file
 pub struct FileReader {
     pub(crate) slow5_file: *mut slow5_file_t,
+    pub(crate) file_path: PathBuf,
+    log_level: Option<LogLevel>,
+    pub(crate) stats: Cell<Stats>,
+    // Set once the index is shared with another reader of the same file, which
+    // then owns the index instead of slow5lib
+    shared_index: OnceCell<Arc<SharedIndex>>,
 }
 
 unsafe impl Send for FileReader {}
 
+// Index loaded by slow5lib, shared by readers of the same file. Lookups don't
+// modify the index, so it can be used from multiple threads.
+struct SharedIndex(*mut slow5_idx);
+
+unsafe impl Send for SharedIndex {}
+unsafe impl Sync for SharedIndex {}
+
+impl Drop for SharedIndex {
+    fn drop(&mut self) {
+        unsafe { slow5_idx_free(self.0) }
+    }
+}
+
 impl std::fmt::Debug for FileReader {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("FileReader").finish()
@@ -34,28 +67,79 @@ impl std::fmt::Debug for FileReader {
 }
 
 impl FileReader {
-    fn new(slow5_file: *mut slow5_file_t) -> Self {
-        Self { slow5_file }
+    fn new(slow5_file: *mut slow5_file_t, file_path: PathBuf) -> Self {
+        Self {
+            slow5_file,
+            file_path,
+            log_level: None,
+            stats: Cell::new(Stats::default()),
+            shared_index: OnceCell::new(),
+        }
     }
 
-    /// Open a SLOW5 file, creates an index if one doesn't exist.
+    // Index of the file that can be shared with another reader, None if no
+    // index has been loaded
+    fn shared_index(&self) -> Option<Arc<SharedIndex>> {
+        let index = unsafe { (*self.slow5_file).index };
+        if index.is_null() {
+            return None;
+        }
+        let shared = self
+            .shared_index
+            .get_or_init(|| Arc::new(SharedIndex(index)));
+        Some(Arc::clone(shared))
+    }
+
+    /// Open another handle to the same file, which uses the same index
+    /// instead of loading it again. The new reader starts at the first record,
+    /// with its own [`stats`]. Useful for giving each thread its own reader,
+    /// ie with [`records_shard`].
     ///
     /// # Example
     /// ```
-    /// use slow5::FileReader;
-    ///
+    /// # use slow5::FileReader;
     /// # fn main() -> anyhow::Result<()> {
     /// let reader = FileReader::open("examples/example.slow5")?;
+    /// let clone = reader.try_clone()?;
+    /// assert!(clone.has_index());
     /// # Ok(())
     /// # }
     /// ```
-    pub fn open<P: AsRef<Path>>(file_path: P) -> Result<Self, Slow5Error> {
-        // If we aren't testing or running in debug mode, silence slow5lib logs
-        #[cfg(any(not(test), not(debug_assertions)))]
-        unsafe {
-            slow5lib_sys::slow5_set_log_level(slow5lib_sys::slow5_log_level_opt_SLOW5_LOG_OFF);
+    ///
+    /// # Errors
+    /// Returns an Err if the file fails to be opened again, ie for readers
+    /// that aren't reading a file, like [`from_reader`].
+    ///
+    /// [`stats`]: crate::FileReader::stats
+    /// [`records_shard`]: crate::FileReader::records_shard
+    /// [`from_reader`]: crate::FileReader::from_reader
+    pub fn try_clone(&self) -> Result<Self, Slow5Error> {
+        let _lvl = self.log_level_guard();
+        let slow5_file = Self::open_file(&self.file_path)?;
+        let mut reader = FileReader::new(slow5_file, self.file_path.clone());
+        reader.log_level = self.log_level;
+        if let Some(index) = self.shared_index() {
+            unsafe { (*slow5_file).index = index.0 };
+            let _ = reader.shared_index.set(index);
         }
-        let file_path = file_path.as_ref();
+        Ok(reader)
+    }
+
+    // Stop using a shared index, so slow5lib doesn't free it
+    fn release_shared_index(&mut self) {
+        if self.shared_index.take().is_some() {
+            unsafe { (*self.slow5_file).index = null_mut() };
+        }
+    }
+
+    // Set slow5lib's log level for this file until the guard is dropped
+    pub(crate) fn log_level_guard(&self) -> LogLevelGuard {
+        LogLevelGuard::new(self.log_level)
+    }
+
+    // Open the file without touching the index
+    fn open_file(file_path: &Path) -> Result<*mut slow5_file_t, Slow5Error> {
+        silence_slow5lib_logs();
         if !file_path.exists() {
             log::error!("File path doesn't exist: {file_path:?}");
             return Err(Slow5Error::IncorrectPath(file_path.to_owned()));
@@ -66,15 +150,249 @@ impl FileReader {
         let mode = cstr!("r");
         let slow5_file: *mut slow5_file_t =
             unsafe { slow5lib_sys::slow5_open(file_path.as_ptr(), mode.as_ptr()) };
-        let ret = unsafe { slow5lib_sys::slow5_idx_load(slow5_file) };
-        if ret == -1 {
+        if slow5_file.is_null() {
+            log::error!("Failed to open SLOW5 file");
+            Err(Slow5Error::IOError)
+        } else {
+            Ok(slow5_file)
+        }
+    }
+
+    /// Open a SLOW5 file, creates an index if one doesn't exist.
+    ///
+    /// # Example
+    /// ```
+    /// use slow5::FileReader;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let reader = FileReader::open("examples/example.slow5")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn open<P: AsRef<Path>>(file_path: P) -> Result<Self, Slow5Error> {
+        Self::open_with(file_path, &OpenOptions::default())
+    }
+
+    /// Open a SLOW5 file with the given options, ie to set the slow5lib log
+    /// level for this file only.
+    ///
+    /// # Example
+    /// ```
+    /// use slow5::{FileReader, LogLevel, OpenOptions};
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut opts = OpenOptions::default();
+    /// opts.log_level(LogLevel::Debug);
+    /// let reader = FileReader::open_with("examples/example.slow5", &opts)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn open_with<P: AsRef<Path>>(file_path: P, opts: &OpenOptions) -> Result<Self, Slow5Error> {
+        let file_path = file_path.as_ref();
+        let _lvl = LogLevelGuard::new(opts.log_level);
+        let slow5_file = Self::open_file(file_path)?;
+        if opts.index {
+            let ret = unsafe { slow5lib_sys::slow5_idx_load(slow5_file) };
+            if ret == -1 {
+                log::error!("No index was loaded");
+                unsafe { slow5lib_sys::slow5_close(slow5_file) };
+                return Err(Slow5Error::NoIndex);
+            }
+        }
+        let mut reader = FileReader::new(slow5_file, file_path.to_owned());
+        reader.log_level = opts.log_level;
+        Ok(reader)
+    }
+
+    /// Open a SLOW5 file without loading or creating an index. Useful for
+    /// read-only filesystems or when only streaming through the records.
+    ///
+    /// Methods that require an index, such as [`get_record`] and
+    /// [`iter_read_ids`], will return an Err until an index is loaded with
+    /// [`load_index_from`].
+    ///
+    /// [`get_record`]: crate::FileReader::get_record
+    /// [`iter_read_ids`]: crate::FileReader::iter_read_ids
+    /// [`load_index_from`]: crate::FileReader::load_index_from
+    ///
+    /// # Example
+    /// ```
+    /// use slow5::FileReader;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut reader = FileReader::open_without_index("examples/example.slow5")?;
+    /// assert!(reader.get_record("r3").is_err());
+    /// assert_eq!(reader.records().count(), 5);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn open_without_index<P: AsRef<Path>>(file_path: P) -> Result<Self, Slow5Error> {
+        Self::open_with(file_path, OpenOptions::default().index(false))
+    }
+
+    /// Read SLOW5 or BLOW5 data from any source, ie a blob from a database or
+    /// a network response, without writing it to a temporary file. The
+    /// format is detected from the first bytes.
+    ///
+    /// No index is loaded, so the records can only be read in order with
+    /// [`records`] and similar methods.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    ///
+    /// use slow5::FileReader;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let blob = std::fs::read("examples/example3.blow5")?;
+    /// let mut reader = FileReader::from_reader(Cursor::new(blob))?;
+    /// for rec in reader.records() {
+    ///     let _ = rec?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`records`]: crate::FileReader::records
+    pub fn from_reader<R>(mut reader: R) -> Result<Self, Slow5Error>
+    where
+        R: Read + Seek + Send + 'static,
+    {
+        silence_slow5lib_logs();
+        let format = stream::detect_format(&mut reader).map_err(|_| Slow5Error::IOError)?;
+        let fp = stream::open(Box::new(stream::Seekable(reader)), cstr!("r"))?;
+        Self::from_stream(fp, format)
+    }
+
+    /// Read SLOW5 or BLOW5 data from stdin, so programs can be used in
+    /// pipelines like `slow5tools`. The format is detected from the first
+    /// byte. Like [`from_reader`], no index is loaded.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use slow5::FileReader;
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut reader = FileReader::stdin()?;
+    /// for rec in reader.records() {
+    ///     let _ = rec?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`from_reader`]: crate::FileReader::from_reader
+    pub fn stdin() -> Result<Self, Slow5Error> {
+        silence_slow5lib_logs();
+        // Duplicate stdin so closing the reader leaves it open
+        let fd = unsafe { libc::dup(libc::STDIN_FILENO) };
+        if fd < 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        let fp = unsafe { libc::fdopen(fd, cstr!("r").as_ptr()) };
+        if fp.is_null() {
+            unsafe { libc::close(fd) };
+            return Err(std::io::Error::last_os_error().into());
+        }
+        // stdin can't seek, so peek at the first byte and put it back
+        let first = unsafe { libc::fgetc(fp) };
+        if first != libc::EOF {
+            unsafe { libc::ungetc(first, fp) };
+        }
+        let format = if first == b'B' as libc::c_int {
+            FileFormat::Blow5
+        } else {
+            FileFormat::Slow5
+        };
+        Self::from_stream(fp, format)
+    }
+
+    // Parse the header from a stream, ie stdin or one created with the
+    // stream module
+    fn from_stream(fp: *mut libc::FILE, format: FileFormat) -> Result<Self, Slow5Error> {
+        let format = match format {
+            FileFormat::Slow5 => slow5_fmt_SLOW5_FORMAT_ASCII,
+            FileFormat::Blow5 => slow5_fmt_SLOW5_FORMAT_BINARY,
+        };
+        let slow5_file = unsafe { slow5_init(fp.cast(), STREAM_PATH.as_ptr(), format) };
+        if slow5_file.is_null() {
+            log::error!("Failed to parse SLOW5 header");
+            unsafe { libc::fclose(fp) };
+            return Err(Slow5Error::IOError);
+        }
+        Ok(FileReader::new(slow5_file, PathBuf::from("-")))
+    }
+
+    /// Returns the default path of the index for this file, ie
+    /// "example.slow5.idx" for "example.slow5". The index file may not exist.
+    pub fn index_path(&self) -> PathBuf {
+        let mut idx_path = self.file_path.clone().into_os_string();
+        idx_path.push(".idx");
+        PathBuf::from(idx_path)
+    }
+
+    /// Load an index from an explicit path, replacing any index that was
+    /// previously loaded.
+    ///
+    /// # Example
+    /// ```
+    /// use slow5::FileReader;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut reader = FileReader::open_without_index("examples/example.slow5")?;
+    /// reader.load_index_from("examples/example.slow5.idx")?;
+    /// let rec = reader.get_record("r3")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an Err if the index file doesn't exist or failed to load.
+    pub fn load_index_from<P: AsRef<Path>>(&mut self, index_path: P) -> Result<(), Slow5Error> {
+        let index_path = index_path.as_ref();
+        if !index_path.exists() {
+            log::error!("Index path doesn't exist: {index_path:?}");
+            return Err(Slow5Error::IncorrectPath(index_path.to_owned()));
+        }
+        let index_path = to_cstring(index_path.as_os_str().as_bytes())?;
+        let _lvl = self.log_level_guard();
+        self.release_shared_index();
+        unsafe {
+            if !(*self.slow5_file).index.is_null() {
+                slow5lib_sys::slow5_idx_unload(self.slow5_file);
+            }
+        }
+        let ret =
+            unsafe { slow5lib_sys::slow5_idx_load_with(self.slow5_file, index_path.as_ptr()) };
+        if ret < 0 {
             log::error!("No index was loaded");
             Err(Slow5Error::NoIndex)
         } else {
-            Ok(FileReader::new(slow5_file))
+            Ok(())
         }
     }
 
+    /// Returns true if an index has been loaded for the file
+    pub fn has_index(&self) -> bool {
+        unsafe { !(*self.slow5_file).index.is_null() }
+    }
+
+    /// Records and bytes read so far with [`get_record`] and [`records`],
+    /// and the time spent decoding them. Bytes read by [`raw_record_bytes`]
+    /// are also counted, but not as records.
+    ///
+    /// [`get_record`]: crate::FileReader::get_record
+    /// [`records`]: crate::FileReader::records
+    /// [`raw_record_bytes`]: crate::FileReader::raw_record_bytes
+    pub fn stats(&self) -> Stats {
+        self.stats.get()
+    }
+
+    pub(crate) fn update_stats<F: FnOnce(&mut Stats)>(&self, f: F) {
+        let mut stats = self.stats.get();
+        f(&mut stats);
+        self.stats.set(stats);
+    }
+
     /// Get file's record compression
     pub fn record_compression(&self) -> RecordCompression {
         let compress = unsafe { (*self.slow5_file).compress };
@@ -95,6 +413,52 @@ impl FileReader {
         SignalCompression::from_u32(signal_press)
     }
 
+    /// Get file's format, ie SLOW5 or BLOW5
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{FileFormat, FileReader};
+    /// # fn main() -> anyhow::Result<()> {
+    /// let reader = FileReader::open("examples/example3.blow5")?;
+    /// assert_eq!(reader.format(), FileFormat::Blow5);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn format(&self) -> FileFormat {
+        let format = unsafe { (*self.slow5_file).format };
+        if format == slow5_fmt_SLOW5_FORMAT_BINARY {
+            FileFormat::Blow5
+        } else {
+            FileFormat::Slow5
+        }
+    }
+
+    /// Copy the header into a [`HeaderOwned`], like [`Header::snapshot`],
+    /// along with the record and signal compression of the file.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{FileReader, SignalCompression};
+    /// # fn main() -> anyhow::Result<()> {
+    /// let reader = FileReader::open("examples/example.slow5")?;
+    /// let header = reader.header_snapshot();
+    /// assert_eq!(header.signal_compression(), Some(SignalCompression::None));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn header_snapshot(&self) -> HeaderOwned {
+        self.header()
+            .snapshot()
+            .with_compression(self.record_compression(), self.signal_compression())
+    }
+
+    /// Get the SLOW5 specification version of the file, as (major, minor,
+    /// patch)
+    pub fn slow5_version(&self) -> (u8, u8, u8) {
+        let version = unsafe { &(*(*self.slow5_file).header).version };
+        (version.major, version.minor, version.patch)
+    }
+
     /// Access header of a SLOW5 file
     pub fn header(&self) -> Header<'_> {
         let header: *mut slow5_hdr_t = unsafe { (*self.slow5_file).header };
@@ -123,6 +487,244 @@ impl FileReader {
         RecordIter::new(self)
     }
 
+    /// Return iterator over the reads in the file in batches of n records,
+    /// ie for building an Arrow batch or a batch for a GPU. The last batch
+    /// has the remaining records, and n of 0 is treated as 1. If a record
+    /// fails to be read, the error is returned in place of its batch, the
+    /// records read before it in that batch are dropped, and the iterator
+    /// stops.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::FileReader;
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut reader = FileReader::open("examples/example.slow5")?;
+    /// let sizes = reader
+    ///     .records_chunked(2)
+    ///     .map(|chunk| chunk.map(|recs| recs.len()))
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(sizes, [2, 2, 1]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn records_chunked(&mut self, n: usize) -> RecordChunks {
+        RecordChunks::new(self, n)
+    }
+
+    /// Return iterator that reads each read in the file into rec, replacing
+    /// its contents, instead of allocating a new [`Record`] for every read.
+    /// Any record can be used as the buffer, ie the first record read with
+    /// [`records`].
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{FileReader, RecordExt};
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut reader = FileReader::open("examples/example.slow5")?;
+    /// let mut rec = reader.records().next().unwrap()?;
+    /// let mut samples = rec.len_signal();
+    /// let mut records = reader.records_into(&mut rec);
+    /// while let Some(res) = records.next() {
+    ///     res?;
+    ///     samples += records.record().len_signal();
+    /// }
+    /// # assert!(samples > 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`records`]: crate::FileReader::records
+    pub fn records_into<'a>(&'a mut self, rec: &'a mut Record) -> RecordsInto<'a> {
+        RecordsInto::new(self, rec)
+    }
+
+    /// Call f with each read in the file, like [`records`], but every read is
+    /// decoded into the same buffer instead of allocating a new [`Record`].
+    /// Useful for computing statistics over a whole file.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{FileReader, RecordExt};
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut reader = FileReader::open("examples/example.slow5")?;
+    /// let mut samples = 0;
+    /// reader.for_each_record(|rec| samples += rec.len_signal())?;
+    /// # assert!(samples > 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an Err if a read fails to be read, after calling f with every
+    /// read before it.
+    ///
+    /// [`records`]: crate::FileReader::records
+    pub fn for_each_record<F>(&mut self, mut f: F) -> Result<(), Slow5Error>
+    where
+        F: FnMut(&RecordView),
+    {
+        let mut rec = null_mut() as *mut slow5_rec_t;
+        let _lvl = self.log_level_guard();
+        let fp = unsafe { (*self.slow5_file).fp.cast() };
+        let res = loop {
+            let pos = unsafe { libc::ftello(fp) };
+            let start = Instant::now();
+            let ret = unsafe { slow5lib_sys::slow5_get_next(&mut rec, self.slow5_file) };
+            let time = start.elapsed();
+            if ret < 0 {
+                break if ret == SLOW5_ERR_EOF {
+                    Ok(())
+                } else {
+                    Err(Slow5Error::native(ret))
+                };
+            }
+            let bytes = (unsafe { libc::ftello(fp) } - pos).max(0) as u64;
+            let len_raw_signal = unsafe { (*rec).len_raw_signal };
+            self.update_stats(|stats| stats.add_record(bytes, len_raw_signal, time));
+            f(&RecordView::new(rec));
+        };
+        if !rec.is_null() {
+            unsafe { slow5_rec_free(rec) };
+        }
+        res
+    }
+
+    /// Return iterator over each read in the file, like [`records`], that
+    /// continues after a record fails to be read instead of stopping. The
+    /// error is returned, then the iterator resumes at the next record,
+    /// using the index if one is loaded. Useful for salvaging the records of
+    /// files that are corrupted or truncated.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::FileReader;
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut reader = FileReader::open("examples/example3.blow5")?;
+    /// let mut salvaged = Vec::new();
+    /// for record in reader.records_recover() {
+    ///     match record {
+    ///         Ok(rec) => salvaged.push(rec),
+    ///         Err(e) => eprintln!("Skipping record: {e}"),
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`records`]: crate::FileReader::records
+    pub fn records_recover(&mut self) -> RecoverIter {
+        RecoverIter::new(self)
+    }
+
+    /// Return iterator over the reads in the file that match the [`Filter`].
+    /// If an index is loaded and the filter has a read id prefix, only the
+    /// matching records are read, otherwise every record is read and checked.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{FileReader, Filter, RecordExt};
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut reader = FileReader::open("examples/example.slow5")?;
+    /// let mut filter = Filter::new();
+    /// filter.min_signal_len(1000).read_group(0);
+    /// for rec in reader.records_filtered(filter) {
+    ///     let rec = rec?;
+    ///     assert!(rec.len_signal() >= 1000);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn records_filtered(&mut self, filter: Filter) -> FilteredIter {
+        FilteredIter::new(self, filter)
+    }
+
+    /// Return iterator over at most limit reads, starting from the read at
+    /// position offset in the file. The reader seeks to the start of that
+    /// read using the index, so the reads before it aren't read, which is
+    /// useful for paging through a file.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{FileReader, RecordExt};
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut reader = FileReader::open("examples/example.slow5")?;
+    /// let page = reader.records_range(2, 2)?.collect::<Result<Vec<_>, _>>()?;
+    /// assert_eq!(page.len(), 2);
+    /// assert_eq!(page[0].read_id(), b"r3");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an Err if the reader doesn't have an index or fails to seek to
+    /// the read.
+    pub fn records_range(
+        &mut self,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Take<RecordIter>, Slow5Error> {
+        let start = self.index_iter()?.nth(offset).map(|(_, start, _)| start);
+        let limit = match start {
+            Some(start) => {
+                let fp = unsafe { (*self.slow5_file).fp.cast() };
+                if unsafe { libc::fseeko(fp, start as libc::off_t, libc::SEEK_SET) } != 0 {
+                    return Err(Slow5Error::IOError);
+                }
+                limit
+            }
+            // Past the last read
+            None => 0,
+        };
+        Ok(RecordIter::new(self).take(limit))
+    }
+
+    /// Return iterator over shard i of n, ie the reads at positions i, i + n,
+    /// i + 2n, and so on in the index. Each thread can read a different
+    /// shard with its own reader from [`try_clone`], splitting the file
+    /// between threads without sending records over a channel.
+    ///
+    /// # Example
+    /// ```
+    /// use std::thread;
+    ///
+    /// # use slow5::FileReader;
+    /// # fn main() -> anyhow::Result<()> {
+    /// let reader = FileReader::open("examples/example.slow5")?;
+    /// let n = 2;
+    /// let readers = (0..n)
+    ///     .map(|_| reader.try_clone())
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    /// let counts = thread::scope(|s| {
+    ///     let handles = readers
+    ///         .into_iter()
+    ///         .enumerate()
+    ///         .map(|(i, reader)| s.spawn(move || reader.records_shard(i, n).unwrap().count()))
+    ///         .collect::<Vec<_>>();
+    ///     handles.into_iter().map(|h| h.join().unwrap()).sum::<usize>()
+    /// });
+    /// assert_eq!(counts, 5);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an Err if the reader doesn't have an index, or i isn't less
+    /// than n.
+    ///
+    /// [`try_clone`]: crate::FileReader::try_clone
+    pub fn records_shard(&self, i: usize, n: usize) -> Result<ShardIter<'_>, Slow5Error> {
+        if i >= n {
+            return Err(Slow5Error::Argument);
+        }
+        let read_ids = self
+            .index_iter()?
+            .skip(i)
+            .step_by(n)
+            .map(|(read_id, _, _)| read_id.to_vec())
+            .collect();
+        Ok(ShardIter::new(self, read_ids))
+    }
+
     /// Random-access a single [`Record`] by read_id.
     ///
     /// # Example
@@ -140,22 +742,39 @@ impl FileReader {
     /// ```
     ///
     /// Mutating the Record will not cause changes in the SLOW5 file.
+    ///
+    /// # Errors
+    /// Returns an Err if the read id isn't in the file or if the reader was
+    /// opened without an index.
     pub fn get_record<B>(&self, read_id: B) -> Result<Record, Slow5Error>
     where
         B: Into<Vec<u8>>,
     {
+        if !self.has_index() {
+            return Err(Slow5Error::NoIndex);
+        }
         let mut slow5_rec =
             unsafe { libc::calloc(1, size_of::<slow5_rec_t>()) as *mut slow5_rec_t };
         let read_id = to_cstring(read_id)?;
         let rid_ptr = read_id.into_raw();
+        let _lvl = self.log_level_guard();
+        let start = Instant::now();
         let ret = unsafe { slow5_get(rid_ptr, &mut slow5_rec, self.slow5_file) };
-        let _ = unsafe { CString::from_raw(rid_ptr) };
+        let time = start.elapsed();
+        let read_id = unsafe { CString::from_raw(rid_ptr) };
         if ret >= 0 {
+            let index = unsafe { (*self.slow5_file).index };
+            let bytes = index_entry(index, &read_id).map_or(0, |(_, size)| size);
+            let len_raw_signal = unsafe { (*slow5_rec).len_raw_signal };
+            self.update_stats(|stats| stats.add_record(bytes, len_raw_signal, time));
             Ok(Record::new(slow5_rec))
         } else {
-            // TODO Handle error code properly
             unsafe { libc::free(slow5_rec as *mut c_void) };
-            Err(Slow5Error::GetRecordFailed)
+            if ret == SLOW5_ERR_NOTFOUND {
+                Err(Slow5Error::GetRecordFailed)
+            } else {
+                Err(Slow5Error::native(ret))
+            }
         }
     }
 
@@ -183,6 +802,119 @@ impl FileReader {
         ReadIdIter::new(self)
     }
 
+    /// Returns true if the read id is in the loaded index. Always false if no
+    /// index has been loaded.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::FileReader;
+    /// # fn main() -> anyhow::Result<()> {
+    /// let slow5 = FileReader::open("examples/example.slow5")?;
+    /// assert!(slow5.contains_read_id(b"r1"));
+    /// assert!(!slow5.contains_read_id(b"not a read"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn contains_read_id(&self, read_id: &[u8]) -> bool {
+        let index = unsafe { (*self.slow5_file).index };
+        let Ok(read_id) = to_cstring(read_id) else {
+            return false;
+        };
+        index_entry(index, &read_id).is_some()
+    }
+
+    /// Returns iterator over the entries of the loaded index, as the read
+    /// id, the offset of the record in the file, and the size of the record
+    /// in bytes. Entries are in the order the records are stored in the
+    /// file.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::FileReader;
+    /// # fn main() -> anyhow::Result<()> {
+    /// let slow5 = FileReader::open("examples/example3.blow5")?;
+    /// for (read_id, offset, size) in slow5.index_iter()? {
+    ///     println!("{}\t{offset}\t{size}", String::from_utf8_lossy(read_id));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`Slow5Error::NoIndex`] if no index has been loaded.
+    pub fn index_iter(&self) -> Result<IndexIter<'_>, Slow5Error> {
+        if !self.has_index() {
+            return Err(Slow5Error::NoIndex);
+        }
+        Ok(IndexIter::new(self))
+    }
+
+    /// Get a record as it is stored in the file, without decompressing or
+    /// parsing it. The [`RawRecord`] can be written to another file with the
+    /// same format and compression using [`FileWriter::add_raw_record`], which
+    /// is much faster than [`get_record`] followed by [`add_record`] when
+    /// merging or subsetting files.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::FileReader;
+    /// # fn main() -> anyhow::Result<()> {
+    /// let reader = FileReader::open("examples/example3.blow5")?;
+    /// # let read_id = reader.iter_read_ids()?.next().unwrap().to_vec();
+    /// let raw = reader.raw_record_bytes(read_id)?;
+    /// assert!(!raw.as_bytes().is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an Err if the read id isn't in the file or if the reader was
+    /// opened without an index.
+    ///
+    /// [`FileWriter::add_raw_record`]: crate::FileWriter::add_raw_record
+    /// [`get_record`]: crate::FileReader::get_record
+    /// [`add_record`]: crate::FileWriter::add_record
+    pub fn raw_record_bytes<B>(&self, read_id: B) -> Result<RawRecord, Slow5Error>
+    where
+        B: Into<Vec<u8>>,
+    {
+        if !self.has_index() {
+            return Err(Slow5Error::NoIndex);
+        }
+        let read_id = to_cstring(read_id)?;
+        let mut n = 0;
+        let _lvl = self.log_level_guard();
+        let mem = unsafe { slow5_get_mem(read_id.as_ptr(), &mut n, self.slow5_file) };
+        if mem.is_null() {
+            return Err(Slow5Error::GetRecordFailed);
+        }
+        let data = unsafe { std::slice::from_raw_parts(mem as *const u8, n) };
+        self.update_stats(|stats| stats.add_bytes(n as u64));
+        let binary = unsafe { (*self.slow5_file).format } == slow5_fmt_SLOW5_FORMAT_BINARY;
+        let mut bytes = Vec::with_capacity(n + size_of::<slow5_rec_size_t>());
+        if binary {
+            // slow5_get_mem strips the record size
+            bytes.extend_from_slice(&(n as slow5_rec_size_t).to_ne_bytes());
+            bytes.extend_from_slice(data);
+        } else {
+            // Newline at the end of the line is replaced with a nul byte
+            let line = match data.last() {
+                Some(b'\0' | b'\n') => &data[..n - 1],
+                _ => data,
+            };
+            bytes.extend_from_slice(line);
+            bytes.push(b'\n');
+        }
+        unsafe { libc::free(mem) };
+        Ok(RawRecord {
+            read_id: read_id.into_bytes(),
+            bytes,
+            binary,
+            rec_comp: self.record_compression(),
+            sig_comp: self.signal_compression(),
+        })
+    }
+
     /// Returns iterator over the labels for an enum auxiliary field. Useful for
     /// converting into an indexable collection
     /// and using [`crate::EnumField`] to get the value for an auxiliary field
@@ -217,7 +949,7 @@ impl FileReader {
         let label_ptr =
             unsafe { slow5_get_aux_enum_labels(self.header().header, field.as_ptr(), &mut n) };
         if label_ptr.is_null() {
-            Err(Slow5Error::Unknown)
+            Err(Slow5Error::from_errno(SLOW5_ERR_OTHER))
         } else {
             Ok(AuxEnumLabelIter::new(self, label_ptr, n))
         }
@@ -247,7 +979,7 @@ impl FileReader {
         let mut n = 0;
         let keys = unsafe { slow5_get_hdr_keys(self.header().header, &mut n) };
         if keys.is_null() {
-            Err(Slow5Error::Unknown)
+            Err(Slow5Error::from_errno(SLOW5_ERR_OTHER))
         } else {
             Ok(AttrKeysIter {
                 _reader: self,
@@ -267,12 +999,87 @@ impl HeaderExt for FileReader {
 
 impl Drop for FileReader {
     fn drop(&mut self) {
+        let _lvl = self.log_level_guard();
+        self.release_shared_index();
         unsafe {
             slow5lib_sys::slow5_close(self.slow5_file);
         }
     }
 }
 
+/// Options for opening a [`FileReader`], ie whether to load the index and
+/// the slow5lib log level for the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenOptions {
+    index: bool,
+    log_level: Option<LogLevel>,
+}
+
+impl Default for OpenOptions {
+    fn default() -> Self {
+        Self {
+            index: true,
+            log_level: None,
+        }
+    }
+}
+
+impl OpenOptions {
+    /// Load or create the index when the file is opened. By default, the
+    /// index is loaded, see [`FileReader::open_without_index`].
+    ///
+    /// [`FileReader::open_without_index`]: crate::FileReader::open_without_index
+    pub fn index(&mut self, index: bool) -> &mut Self {
+        self.index = index;
+        self
+    }
+
+    /// Set the slow5lib log level while the file is being read, instead of
+    /// using the log level of the whole process. Since slow5lib's log level
+    /// is a global, calls on files with their own log level are run one at a
+    /// time.
+    pub fn log_level(&mut self, lvl: LogLevel) -> &mut Self {
+        self.log_level = Some(lvl);
+        self
+    }
+
+    /// Open a SLOW5 file with these options, see [`FileReader::open_with`].
+    ///
+    /// [`FileReader::open_with`]: crate::FileReader::open_with
+    pub fn open<P: AsRef<Path>>(&self, file_path: P) -> Result<FileReader, Slow5Error> {
+        FileReader::open_with(file_path, self)
+    }
+}
+
+/// Format of a SLOW5 file
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum FileFormat {
+    /// ASCII, usually with the .slow5 extension
+    Slow5,
+    /// Binary, usually with the .blow5 extension
+    Blow5,
+}
+
+/// Record as it is stored in a SLOW5 or BLOW5 file, generated by
+/// [`raw_record_bytes`].
+///
+/// [`raw_record_bytes`]: crate::FileReader::raw_record_bytes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawRecord {
+    pub(crate) read_id: Vec<u8>,
+    bytes: Vec<u8>,
+    pub(crate) binary: bool,
+    pub(crate) rec_comp: RecordCompression,
+    pub(crate) sig_comp: SignalCompression,
+}
+
+impl RawRecord {
+    /// Bytes of the record, including the record size for BLOW5
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
 /// Iterator over all the read IDs in a SLOW5 file
 // TODO Is liftime fine, or should I just hold the &'a FileReader?
 pub struct ReadIdIter<'a> {
@@ -323,6 +1130,62 @@ impl<'a> Iterator for ReadIdIter<'a> {
     }
 }
 
+// Offset and size of a record from the index, None if it isn't in the index
+fn index_entry(index: *mut slow5_idx, read_id: &CStr) -> Option<(u64, u64)> {
+    if index.is_null() {
+        return None;
+    }
+    let mut rec_idx = slow5_rec_idx { offset: 0, size: 0 };
+    let ret = unsafe { slow5_idx_get(index, read_id.as_ptr(), &mut rec_idx) };
+    if ret < 0 {
+        None
+    } else {
+        Some((rec_idx.offset, rec_idx.size))
+    }
+}
+
+/// Iterator over the entries of the index of a SLOW5 file, generated by
+/// calling [`index_iter`].
+///
+/// [`index_iter`]: crate::FileReader::index_iter
+pub struct IndexIter<'a> {
+    index: *mut slow5_idx,
+    idx: u64,
+    _reader: PhantomData<&'a FileReader>,
+}
+
+impl<'a> std::fmt::Debug for IndexIter<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IndexIter").field("idx", &self.idx).finish()
+    }
+}
+
+impl<'a> IndexIter<'a> {
+    fn new(reader: &'a FileReader) -> Self {
+        IndexIter {
+            index: unsafe { (*reader.slow5_file).index },
+            idx: 0,
+            _reader: PhantomData,
+        }
+    }
+}
+
+impl<'a> Iterator for IndexIter<'a> {
+    type Item = (&'a [u8], u64, u64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let num_ids = unsafe { (*self.index).num_ids };
+        while self.idx < num_ids {
+            let read_id = unsafe { CStr::from_ptr(*(*self.index).ids.add(self.idx as usize)) };
+            self.idx += 1;
+            if let Some((offset, size)) = index_entry(self.index, read_id) {
+                return Some((read_id.to_bytes(), offset, size));
+            }
+        }
+        None
+    }
+}
+
 /// Iterator over labels for an auxiliary field enum
 pub struct AuxEnumLabelIter<'a> {
     _reader: &'a FileReader,
@@ -404,6 +1267,8 @@ impl<'a> Drop for AttrKeysIter<'a> {
 #[cfg(test)]
 mod test {
 
+    use assert_fs::{prelude::PathChild, TempDir};
+
     use super::*;
     use crate::RecordExt;
 
@@ -423,6 +1288,26 @@ mod test {
         assert!(!acc.is_empty());
     }
 
+    #[test]
+    fn test_index_iter() -> anyhow::Result<()> {
+        let reader = FileReader::open("examples/example.slow5")?;
+        let entries = reader.index_iter()?.collect::<Vec<_>>();
+        let read_ids = reader.iter_read_ids()?.collect::<Vec<_>>();
+        assert_eq!(entries.len(), read_ids.len());
+        for ((read_id, _, size), expected) in entries.iter().zip(read_ids) {
+            assert_eq!(*read_id, expected);
+            assert!(*size > 0);
+            assert!(reader.contains_read_id(read_id));
+        }
+        assert!(entries.windows(2).all(|w| w[0].1 < w[1].1));
+        assert!(!reader.contains_read_id(b"not a read"));
+
+        let reader = FileReader::open_without_index("examples/example.slow5")?;
+        assert!(matches!(reader.index_iter(), Err(Slow5Error::NoIndex)));
+        assert!(!reader.contains_read_id(b"r1"));
+        Ok(())
+    }
+
     #[test]
     fn test_bad_path() {
         let filename = "random_fileoufnseif";
@@ -430,6 +1315,23 @@ mod test {
         assert!(matches!(reader, Err(Slow5Error::IncorrectPath(_))));
     }
 
+    #[test]
+    fn test_open_without_index() -> anyhow::Result<()> {
+        let filename = "examples/example.slow5";
+        let mut reader = FileReader::open_without_index(filename)?;
+        assert!(!reader.has_index());
+        assert!(matches!(reader.get_record("r3"), Err(Slow5Error::NoIndex)));
+        assert_eq!(reader.records().count(), 5);
+
+        let index_path = reader.index_path();
+        assert_eq!(index_path, PathBuf::from("examples/example.slow5.idx"));
+        reader.load_index_from(index_path)?;
+        assert!(reader.has_index());
+        let rec = reader.get_record("r3")?;
+        assert_eq!(rec.read_id(), b"r3");
+        Ok(())
+    }
+
     #[test]
     fn test_no_compression() {
         let filename = "examples/example.slow5";
@@ -437,4 +1339,166 @@ mod test {
         assert_eq!(reader.record_compression(), RecordCompression::None);
         assert_eq!(reader.signal_compression(), SignalCompression::None);
     }
+
+    #[test]
+    fn test_format_version() -> anyhow::Result<()> {
+        let reader = FileReader::open("examples/example.slow5")?;
+        assert_eq!(reader.format(), FileFormat::Slow5);
+        assert!(reader.slow5_version() >= (0, 1, 0));
+
+        let reader = FileReader::open("examples/example3.blow5")?;
+        assert_eq!(reader.format(), FileFormat::Blow5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_open_with() -> anyhow::Result<()> {
+        let reader = OpenOptions::default()
+            .index(false)
+            .open("examples/example.slow5")?;
+        assert!(!reader.has_index());
+
+        let mut opts = OpenOptions::default();
+        opts.log_level(LogLevel::Off);
+        let mut reader = FileReader::open_with("examples/example.slow5", &opts)?;
+        assert!(reader.get_record("r3").is_ok());
+        assert!(reader.get_record("not_a_read").is_err());
+        assert_eq!(reader.records().count(), 5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_records_recover() -> anyhow::Result<()> {
+        let mut reader = FileReader::open("examples/example3.blow5")?;
+        let expected = reader.records().count();
+        let mut reader = FileReader::open("examples/example3.blow5")?;
+        assert_eq!(
+            reader.records_recover().filter(|r| r.is_ok()).count(),
+            expected
+        );
+
+        // Cut the file in the middle of the last record
+        let tmp_dir = TempDir::new()?;
+        let file_path = tmp_dir.child("truncated.blow5");
+        let bytes = std::fs::read("examples/example3.blow5")?;
+        let last = reader
+            .index_iter()?
+            .map(|(_, offset, _)| offset)
+            .max()
+            .unwrap();
+        std::fs::write(&file_path, &bytes[..last as usize + 16])?;
+        let mut reader = FileReader::open_without_index(&file_path)?;
+        let records = reader.records_recover().collect::<Vec<_>>();
+        assert!(records.last().unwrap().is_err());
+        assert_eq!(records.iter().filter(|r| r.is_ok()).count(), expected - 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_records_range() -> anyhow::Result<()> {
+        let mut reader = FileReader::open("examples/example3.blow5")?;
+        let read_ids = reader
+            .records()
+            .map(|rec| rec.map(|rec| rec.read_id().to_vec()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let page = reader
+            .records_range(1, 2)?
+            .map(|rec| rec.map(|rec| rec.read_id().to_vec()))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(page, read_ids[1..3]);
+        assert_eq!(reader.records_range(read_ids.len(), 2)?.count(), 0);
+
+        let mut reader = FileReader::open_without_index("examples/example3.blow5")?;
+        assert!(matches!(
+            reader.records_range(0, 1),
+            Err(Slow5Error::NoIndex)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_records_chunked() -> anyhow::Result<()> {
+        let mut reader = FileReader::open("examples/example.slow5")?;
+        let chunks = reader.records_chunked(2).collect::<Result<Vec<_>, _>>()?;
+        let sizes = chunks.iter().map(Vec::len).collect::<Vec<_>>();
+        assert_eq!(sizes, [2, 2, 1]);
+        let read_ids = chunks
+            .iter()
+            .flatten()
+            .map(|rec| rec.read_id().to_vec())
+            .collect::<Vec<_>>();
+        assert_eq!(read_ids, [b"r1", b"r2", b"r3", b"r4", b"r5"]);
+        assert!(reader.records_chunked(2).next().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_for_each_record() -> anyhow::Result<()> {
+        let mut reader = FileReader::open("examples/example3.blow5")?;
+        let expected = reader.records().collect::<Result<Vec<_>, _>>()?;
+
+        let mut reader = FileReader::open("examples/example3.blow5")?;
+        let mut copies = Vec::new();
+        reader.for_each_record(|rec| copies.push(rec.to_record()))?;
+        assert_eq!(copies.len(), expected.len());
+        for (copy, rec) in copies.iter().zip(&expected) {
+            assert_eq!(copy.read_id(), rec.read_id());
+            assert!(copy.raw_signal_iter().eq(rec.raw_signal_iter()));
+        }
+        assert_eq!(reader.stats().records(), expected.len() as u64);
+        Ok(())
+    }
+
+    #[test]
+    fn test_records_into() -> anyhow::Result<()> {
+        // Buffer from another reader, so reading it doesn't move this one
+        let mut rec = FileReader::open("examples/example.slow5")?.get_record("r5")?;
+        let mut reader = FileReader::open("examples/example.slow5")?;
+        let mut read_ids = Vec::new();
+        let mut records = reader.records_into(&mut rec);
+        while let Some(res) = records.next() {
+            res?;
+            read_ids.push(records.record().read_id().to_vec());
+        }
+        assert_eq!(read_ids, [b"r1", b"r2", b"r3", b"r4", b"r5"]);
+        assert_eq!(rec.read_id(), b"r5");
+        Ok(())
+    }
+
+    #[test]
+    fn test_records_shard() -> anyhow::Result<()> {
+        let reader = FileReader::open("examples/example3.blow5")?;
+        let mut expected = reader
+            .iter_read_ids()?
+            .map(|read_id| read_id.to_vec())
+            .collect::<Vec<_>>();
+        let clone = reader.try_clone()?;
+        drop(reader);
+        let mut read_ids = Vec::new();
+        for i in 0..3 {
+            for rec in clone.records_shard(i, 3)? {
+                read_ids.push(rec?.read_id().to_vec());
+            }
+        }
+        read_ids.sort();
+        expected.sort();
+        assert_eq!(read_ids, expected);
+        assert!(clone.records_shard(3, 3).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_reader() -> anyhow::Result<()> {
+        for file_path in ["examples/example.slow5", "examples/example3.blow5"] {
+            let mut reader = FileReader::open(file_path)?;
+            let expected = reader.records().count();
+            let bytes = std::fs::read(file_path)?;
+            let mut reader = FileReader::from_reader(std::io::Cursor::new(bytes))?;
+            assert_eq!(reader.format(), FileReader::open(file_path)?.format());
+            assert!(!reader.has_index());
+            let records = reader.records().collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(records.len(), expected);
+        }
+        Ok(())
+    }
 }
@@ -4,28 +4,74 @@ use std::{
     marker::PhantomData,
     mem::size_of,
     ptr::null_mut,
+    time::Instant,
 };
 
+use cstr::cstr;
 use libc::{c_char, c_void};
-use slow5lib_sys::{slow5_rec_free, slow5_rec_t};
+use slow5lib_sys::{
+    kh_slow5_s2a_t, khint32_t, slow5_aux_array_set, slow5_aux_set, slow5_aux_set_string,
+    slow5_aux_type_SLOW5_ENUM, slow5_fmt_SLOW5_FORMAT_ASCII,
+    slow5_press_method_SLOW5_COMPRESS_NONE, slow5_rec_aux_data, slow5_rec_free, slow5_rec_parse,
+    slow5_rec_t, slow5_rec_to_mem, SLOW5_ERR_EOF,
+};
 use thiserror::Error;
 
 use crate::{
-    auxiliary::{AuxField, AuxFieldSetExt},
+    auxiliary::{parse_aux_field_set_error, AuxField, AuxFieldSetExt},
+    calibration::{to_picoamps, to_raw_signal},
     error::Slow5Error,
-    to_cstring, FileReader, FileWriter,
+    header::aux_enum_labels,
+    signal::{Fnv64, Normalization, NormalizedSignalIter, SignalStats},
+    stats::Stats,
+    to_cstring, EnumField, FieldType, FileReader, FileWriter, HeaderExt,
 };
 
+/// Errors from building a [`Record`] with a [`RecordBuilder`]
 #[derive(Error, Debug)]
 pub enum BuilderError {
+    /// A required primary field wasn't set
     #[error("Field not set {0}")]
     RequiredValueUnset(&'static str),
+    /// Failed to convert a value into the type expected by slow5lib
     #[error("Conversion error")]
     ConversionError,
+    /// Failed to allocate memory for the Record
     #[error("Failed to allocate memory")]
     AllocationError,
+    /// Read ID contains an interior nul byte
     #[error("Read ID Error, interior nul?")]
     ReadIDError,
+    /// A primary field has a value that isn't physically possible
+    #[error("Invalid {field}: {reason}")]
+    InvalidValue {
+        /// Name of the field
+        field: &'static str,
+        /// Why the value is invalid
+        reason: &'static str,
+    },
+}
+
+// Object safe version of AuxFieldSetExt, so values of different types can be
+// staged in the RecordBuilder
+trait StagedAuxField {
+    fn set_staged(
+        &self,
+        rec: &mut Record,
+        field: &[u8],
+        writer: &mut FileWriter,
+    ) -> Result<(), Slow5Error>;
+}
+
+impl<T: AuxFieldSetExt> StagedAuxField for T {
+    fn set_staged(
+        &self,
+        rec: &mut Record,
+        field: &[u8],
+        writer: &mut FileWriter,
+    ) -> Result<(), Slow5Error> {
+        self.aux_set(rec, field, writer)
+    }
 }
 
 /// Builder to create a Record, call methods to set parameters and build to
@@ -55,13 +101,41 @@ pub struct RecordBuilder {
     offset: Option<f64>,
     range: Option<f64>,
     sampling_rate: Option<f64>,
-    raw_signal: Option<Vec<i16>>,
-    // TODO use aux_fields attribute to allow for setting auxiliary fields from the builder
-    #[allow(dead_code)]
-    aux_fields: Option<HashMap<String, Box<dyn AuxFieldSetExt>>>,
+    raw_signal: Option<StagedSignal>,
+    aux_fields: HashMap<Vec<u8>, Box<dyn StagedAuxField>>,
+    aux_map: Option<AuxMap>,
+}
+
+// Signal staged in the RecordBuilder. Picoamps are converted to raw values
+// when the Record is built, so the calibration can be set in any order.
+#[derive(Debug)]
+enum StagedSignal {
+    Raw(Vec<i16>),
+    Picoamps(Vec<f64>),
+}
+
+impl StagedSignal {
+    fn len(&self) -> usize {
+        match self {
+            Self::Raw(raw_signal) => raw_signal.len(),
+            Self::Picoamps(picoamps) => picoamps.len(),
+        }
+    }
+}
+
+// Auxiliary values copied from a Record by RecordBuilder::from_record, set on
+// every Record built before the staged values, with the names they are keyed by
+struct AuxMap {
+    map: *mut kh_slow5_s2a_t,
+    _keys: Vec<CString>,
+}
+
+impl Drop for AuxMap {
+    fn drop(&mut self) {
+        unsafe { free_aux_map(self.map, (*self.map).n_buckets as usize) }
+    }
 }
 
-// TODO eventually add aux_fields to debug
 impl std::fmt::Debug for RecordBuilder {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("RecordBuilder")
@@ -72,11 +146,88 @@ impl std::fmt::Debug for RecordBuilder {
             .field("range", &self.range)
             .field("sampling_rate", &self.sampling_rate)
             .field("raw_signal", &self.raw_signal)
+            .field("aux_fields", &self.aux_fields.keys().collect::<Vec<_>>())
+            .field("aux_map", &self.aux_map.is_some())
             .finish()
     }
 }
 
 impl RecordBuilder {
+    /// Builder with every primary field set except the read id and signal.
+    /// The read group is 0, the sampling rate is 4000 Hz, and the
+    /// digitisation, offset, and range are set so the signal in picoamps is
+    /// the same as the raw signal. Useful for simulated or test records.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{RecordBuilder, RecordExt};
+    /// # fn main() -> anyhow::Result<()> {
+    /// let rec = RecordBuilder::with_defaults()
+    ///     .read_id("simulated")
+    ///     .raw_signal(&[100, 101, 102])
+    ///     .build()?;
+    /// assert_eq!(rec.picoamps_signal_iter().next(), Some(100.0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_defaults() -> Self {
+        Self {
+            read_group: Some(0),
+            digitisation: Some(1.0),
+            offset: Some(0.0),
+            range: Some(1.0),
+            sampling_rate: Some(4000.0),
+            ..Self::default()
+        }
+    }
+
+    /// Builder with the primary fields and auxiliary values of rec, ie to
+    /// change one field of a Record and build it again. Auxiliary values
+    /// staged with [`aux_field`] replace the ones from rec.
+    ///
+    /// [`aux_field`]: crate::RecordBuilder::aux_field
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{FileReader, RecordBuilder, RecordExt};
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut reader = FileReader::open("examples/example3.blow5")?;
+    /// let rec = reader.records().next().unwrap()?;
+    /// let fixed = RecordBuilder::from_record(&rec)
+    ///     .sampling_rate(5000.0)
+    ///     .build()?;
+    /// assert_eq!(fixed.read_id(), rec.read_id());
+    /// assert_eq!(fixed.sampling_rate(), 5000.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if unable to allocate memory for the auxiliary values
+    pub fn from_record(rec: &Record) -> Self {
+        let aux_map = unsafe { (*rec.slow5_rec).aux_map };
+        let aux_map = (!aux_map.is_null()).then(|| {
+            let mut aux_keys = Vec::new();
+            let map = unsafe { clone_aux_map(aux_map, &mut aux_keys) }
+                .expect("Failed to allocate memory for auxiliary fields");
+            AuxMap {
+                map,
+                _keys: aux_keys,
+            }
+        });
+        Self {
+            read_id: Some(rec.read_id().to_vec()),
+            read_group: Some(rec.read_group()),
+            digitisation: Some(rec.digitisation()),
+            offset: Some(rec.offset()),
+            range: Some(rec.range()),
+            sampling_rate: Some(rec.sampling_rate()),
+            raw_signal: Some(StagedSignal::Raw(rec.raw_signal_iter().collect())),
+            aux_fields: HashMap::new(),
+            aux_map,
+        }
+    }
+
     /// Set the read id of the Record
     pub fn read_id<B: Into<Vec<u8>>>(&mut self, read_id: B) -> &mut Self {
         let read_id = read_id.into();
@@ -117,11 +268,133 @@ impl RecordBuilder {
     /// Set the signal of the Record using raw values
     pub fn raw_signal(&mut self, raw_signal: &[i16]) -> &mut Self {
         let raw_signal = raw_signal.to_vec();
-        self.raw_signal = Some(raw_signal);
+        self.raw_signal = Some(StagedSignal::Raw(raw_signal));
+        self
+    }
+
+    /// Set the signal of the Record using raw values from an iterator,
+    /// without collecting them into a slice first
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{RecordBuilder, RecordExt};
+    /// # fn main() -> anyhow::Result<()> {
+    /// let rec = RecordBuilder::default()
+    ///     .read_id("test_id")
+    ///     .read_group(0)
+    ///     .digitisation(4096.0)
+    ///     .offset(4.0)
+    ///     .range(12.0)
+    ///     .sampling_rate(4000.0)
+    ///     .raw_signal_from_iter((0..100).map(|x| x * 2))
+    ///     .build()?;
+    /// assert_eq!(rec.len_signal(), 100);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn raw_signal_from_iter<I>(&mut self, raw_signal: I) -> &mut Self
+    where
+        I: ExactSizeIterator<Item = i16>,
+    {
+        let mut buf = Vec::with_capacity(raw_signal.len());
+        buf.extend(raw_signal);
+        self.raw_signal = Some(StagedSignal::Raw(buf));
+        self
+    }
+
+    /// Set the signal of the Record using values in picoamps. They are
+    /// converted to raw values, rounded to the nearest integer, with the
+    /// digitisation, offset, and range of the Record when it is built.
+    pub fn picoamps_signal_from_iter<I>(&mut self, picoamps: I) -> &mut Self
+    where
+        I: ExactSizeIterator<Item = f64>,
+    {
+        let mut buf = Vec::with_capacity(picoamps.len());
+        buf.extend(picoamps);
+        self.raw_signal = Some(StagedSignal::Picoamps(buf));
         self
     }
 
-    /// Convert into a Record.
+    /// Stage a value for an auxiliary field. Staged values are only set when
+    /// the Record is built with [`build_with_writer`], since the auxiliary
+    /// fields are declared in the header of the [`FileWriter`]. If the same
+    /// name is used multiple times, the last value will be used.
+    ///
+    /// [`build_with_writer`]: crate::RecordBuilder::build_with_writer
+    pub fn aux_field<B, T>(&mut self, name: B, value: T) -> &mut Self
+    where
+        B: Into<Vec<u8>>,
+        T: AuxFieldSetExt + 'static,
+    {
+        self.aux_fields.insert(name.into(), Box::new(value));
+        self
+    }
+
+    /// Check the primary fields are set and physically possible, before
+    /// calling [`build`]. Values that are possible but likely a mistake, ie
+    /// an empty read id, are logged as warnings.
+    ///
+    /// [`build`]: crate::RecordBuilder::build
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::RecordBuilder;
+    /// let mut builder = RecordBuilder::with_defaults();
+    /// builder.read_id("test_id").raw_signal(&[]);
+    /// assert!(builder.validate().is_err());
+    /// builder.raw_signal(&[0, 1, 2]);
+    /// assert!(builder.validate().is_ok());
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`BuilderError::RequiredValueUnset`] if a field isn't set, or
+    /// [`BuilderError::InvalidValue`] if the digitisation or range is zero or
+    /// not finite, the offset isn't finite, the sampling rate isn't positive,
+    /// the signal is empty, or the read id is longer than u16::MAX or has a
+    /// nul byte.
+    pub fn validate(&self) -> Result<(), BuilderError> {
+        let invalid = |field, reason| Err(BuilderError::InvalidValue { field, reason });
+        let required = BuilderError::RequiredValueUnset;
+
+        let read_id = self.read_id.as_ref().ok_or(required("read_id"))?;
+        if read_id.len() > usize::from(u16::MAX) {
+            return invalid("read_id", "longer than u16::MAX");
+        }
+        if read_id.contains(&0) {
+            return invalid("read_id", "contains a nul byte");
+        }
+        if read_id.is_empty() {
+            log::warn!("Read id is empty");
+        }
+        self.read_group.ok_or(required("read_group"))?;
+        let digitisation = self.digitisation.ok_or(required("digitisation"))?;
+        if digitisation == 0.0 || !digitisation.is_finite() {
+            return invalid("digitisation", "must be finite and not zero");
+        }
+        let offset = self.offset.ok_or(required("offset"))?;
+        if !offset.is_finite() {
+            return invalid("offset", "must be finite");
+        }
+        let range = self.range.ok_or(required("range"))?;
+        if range == 0.0 || !range.is_finite() {
+            return invalid("range", "must be finite and not zero");
+        }
+        let sampling_rate = self.sampling_rate.ok_or(required("sampling_rate"))?;
+        if sampling_rate <= 0.0 || !sampling_rate.is_finite() {
+            return invalid("sampling_rate", "must be finite and positive");
+        }
+        let raw_signal = self.raw_signal.as_ref().ok_or(required("raw_signal"))?;
+        if raw_signal.len() == 0 {
+            return invalid("raw_signal", "is empty");
+        }
+        Ok(())
+    }
+
+    /// Convert into a Record. Auxiliary fields staged with [`aux_field`] are
+    /// ignored, use [`build_with_writer`] to set them.
+    ///
+    /// [`aux_field`]: crate::RecordBuilder::aux_field
+    /// [`build_with_writer`]: crate::RecordBuilder::build_with_writer
     ///
     /// # Errors
     /// Builder will fail if
@@ -173,13 +446,82 @@ impl RecordBuilder {
                 e
             })? as *mut i16;
 
-            for (idx, &signal) in raw_signal.iter().enumerate() {
-                *raw_signal_ptr.add(idx) = signal;
+            match raw_signal {
+                StagedSignal::Raw(raw_signal) => {
+                    std::ptr::copy_nonoverlapping(
+                        raw_signal.as_ptr(),
+                        raw_signal_ptr,
+                        raw_signal.len(),
+                    );
+                }
+                StagedSignal::Picoamps(picoamps) => {
+                    for (idx, &pa) in picoamps.iter().enumerate() {
+                        let raw = to_raw_signal(pa, digitisation, offset, range).round();
+                        *raw_signal_ptr.add(idx) = raw as i16;
+                    }
+                }
             }
             (*record).raw_signal = raw_signal_ptr;
 
-            Ok(Record::new(record))
+            let mut aux_keys = Vec::new();
+            if let Some(aux_map) = &self.aux_map {
+                (*record).aux_map = clone_aux_map(aux_map.map, &mut aux_keys).map_err(|e| {
+                    slow5_rec_free(record);
+                    e
+                })?;
+            }
+
+            Ok(Record {
+                slow5_rec: record,
+                _aux_keys: aux_keys,
+            })
+        }
+    }
+
+    /// Convert into a Record and set all the auxiliary fields staged with
+    /// [`aux_field`], using the header of the [`FileWriter`].
+    ///
+    /// [`aux_field`]: crate::RecordBuilder::aux_field
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{FieldType, FileWriter, RecordBuilder};
+    /// # use assert_fs::TempDir;
+    /// # use assert_fs::fixture::PathChild;
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp_dir = TempDir::new()?;
+    /// # let path = tmp_dir.child("new.slow5");
+    /// let mut writer = FileWriter::options()
+    ///     .aux("median", FieldType::Float)
+    ///     .aux("string", FieldType::Str)
+    ///     .create(path)?;
+    /// let rec = RecordBuilder::default()
+    ///     .read_id("test_id")
+    ///     .read_group(0)
+    ///     .digitisation(4096.0)
+    ///     .offset(4.0)
+    ///     .range(12.0)
+    ///     .sampling_rate(4000.0)
+    ///     .raw_signal(&[0, 1, 2, 3])
+    ///     .aux_field("median", 10.0f32)
+    ///     .aux_field("string", "here")
+    ///     .build_with_writer(&mut writer)?;
+    /// writer.add_record(&rec)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an Err if the Record fails to build (see [`build`]) or if any
+    /// of the auxiliary fields fail to be set.
+    ///
+    /// [`build`]: crate::RecordBuilder::build
+    pub fn build_with_writer(&self, writer: &mut FileWriter) -> Result<Record, Slow5Error> {
+        let mut record = self.build()?;
+        for (name, value) in self.aux_fields.iter() {
+            value.set_staged(&mut record, name, writer)?;
         }
+        Ok(record)
     }
 }
 
@@ -193,9 +535,189 @@ unsafe fn allocate(size: usize) -> Result<*mut c_void, BuilderError> {
     }
 }
 
+// Deep copy a slow5_rec_t into a Record. On error, everything allocated so far
+// is freed.
+unsafe fn clone_rec(src: *const slow5_rec_t) -> Result<Record, BuilderError> {
+    let dst = libc::calloc(1, size_of::<slow5_rec_t>()) as *mut slow5_rec_t;
+    if dst.is_null() {
+        return Err(BuilderError::AllocationError);
+    }
+
+    // Copy the primary fields, pointers are replaced below so that
+    // slow5_rec_free is always safe to call on dst
+    std::ptr::copy_nonoverlapping(src, dst, 1);
+    (*dst).read_id = null_mut();
+    (*dst).raw_signal = null_mut();
+    (*dst).aux_map = null_mut();
+
+    if !(*src).read_id.is_null() {
+        (*dst).read_id = libc::strdup((*src).read_id);
+        if (*dst).read_id.is_null() {
+            slow5_rec_free(dst);
+            return Err(BuilderError::AllocationError);
+        }
+    }
+
+    let len_raw_signal = (*src).len_raw_signal as usize;
+    if !(*src).raw_signal.is_null() && len_raw_signal > 0 {
+        let raw_signal_ptr = allocate(size_of::<i16>() * len_raw_signal).map_err(|e| {
+            slow5_rec_free(dst);
+            e
+        })? as *mut i16;
+        std::ptr::copy_nonoverlapping((*src).raw_signal, raw_signal_ptr, len_raw_signal);
+        (*dst).raw_signal = raw_signal_ptr;
+    }
+
+    let mut aux_keys = Vec::new();
+    if !(*src).aux_map.is_null() {
+        (*dst).aux_map = clone_aux_map((*src).aux_map, &mut aux_keys).map_err(|e| {
+            slow5_rec_free(dst);
+            e
+        })?;
+    }
+    Ok(Record {
+        slow5_rec: dst,
+        _aux_keys: aux_keys,
+    })
+}
+
+// Deep copy the khash map holding the auxiliary values of a record. The keys
+// of the original point to field names owned by the header of a reader or
+// writer, so they are copied into aux_keys, which has to live as long as the
+// copy.
+unsafe fn clone_aux_map(
+    src: *const kh_slow5_s2a_t,
+    aux_keys: &mut Vec<CString>,
+) -> Result<*mut kh_slow5_s2a_t, BuilderError> {
+    let dst = libc::calloc(1, size_of::<kh_slow5_s2a_t>()) as *mut kh_slow5_s2a_t;
+    if dst.is_null() {
+        return Err(BuilderError::AllocationError);
+    }
+    let n_buckets = (*src).n_buckets as usize;
+    if n_buckets == 0 {
+        return Ok(dst);
+    }
+
+    // Same sizes khash uses when resizing the flags, keys and values
+    let n_flags = if n_buckets < 16 { 1 } else { n_buckets >> 4 };
+    let flags = libc::calloc(n_flags, size_of::<khint32_t>()) as *mut khint32_t;
+    let keys = libc::calloc(n_buckets, size_of::<*const c_char>()) as *mut *const c_char;
+    let vals = libc::calloc(n_buckets, size_of::<slow5_rec_aux_data>()) as *mut slow5_rec_aux_data;
+    (*dst).flags = flags;
+    (*dst).keys = keys;
+    (*dst).vals = vals;
+    if flags.is_null() || keys.is_null() || vals.is_null() {
+        free_aux_map(dst, n_buckets);
+        return Err(BuilderError::AllocationError);
+    }
+    std::ptr::copy_nonoverlapping((*src).flags, flags, n_flags);
+
+    for i in 0..n_buckets {
+        // Equivalent to kh_exist
+        let is_empty_or_deleted = (*flags.add(i >> 4) >> ((i & 0xf) << 1)) & 3;
+        if is_empty_or_deleted != 0 {
+            continue;
+        }
+        // The buffer of a CString doesn't move when the CString does
+        let key = CStr::from_ptr(*(*src).keys.add(i)).to_owned();
+        *keys.add(i) = key.as_ptr();
+        aux_keys.push(key);
+        let src_val = &*(*src).vals.add(i);
+        let dst_val = &mut *vals.add(i);
+        dst_val.len = src_val.len;
+        dst_val.bytes = src_val.bytes;
+        dst_val.type_ = src_val.type_;
+        if !src_val.data.is_null() {
+            // Extra zeroed byte in case the value is a string
+            let bytes = src_val.bytes as usize;
+            let data = libc::calloc(bytes + 1, 1) as *mut u8;
+            if data.is_null() {
+                free_aux_map(dst, n_buckets);
+                return Err(BuilderError::AllocationError);
+            }
+            std::ptr::copy_nonoverlapping(src_val.data, data, bytes);
+            dst_val.data = data;
+        }
+    }
+
+    (*dst).n_buckets = (*src).n_buckets;
+    (*dst).size = (*src).size;
+    (*dst).n_occupied = (*src).n_occupied;
+    (*dst).upper_bound = (*src).upper_bound;
+    Ok(dst)
+}
+
+// Auxiliary values in the map whose size in bytes doesn't match their length
+// and type, as the field name, expected size, and size
+unsafe fn aux_size_mismatches(map: *const kh_slow5_s2a_t) -> Vec<(String, u64, u64)> {
+    let mut mismatches = Vec::new();
+    if map.is_null() || (*map).n_buckets == 0 {
+        return mismatches;
+    }
+    for i in 0..(*map).n_buckets as usize {
+        // Equivalent to kh_exist
+        let is_empty_or_deleted = (*(*map).flags.add(i >> 4) >> ((i & 0xf) << 1)) & 3;
+        if is_empty_or_deleted != 0 {
+            continue;
+        }
+        let val = &*(*map).vals.add(i);
+        let elem_size = if val.type_ == slow5_aux_type_SLOW5_ENUM {
+            Some(1)
+        } else {
+            FieldType::from_slow5_t(val.type_).and_then(|ty| ty.elem_size())
+        };
+        let Some(elem_size) = elem_size else {
+            continue;
+        };
+        let expected = val.len * elem_size;
+        if val.bytes != expected || (val.len > 0 && val.data.is_null()) {
+            let name = CStr::from_ptr(*(*map).keys.add(i));
+            mismatches.push((name.to_string_lossy().into_owned(), expected, val.bytes));
+        }
+    }
+    mismatches
+}
+
+// Free a partially cloned auxiliary map, values that weren't copied are null
+unsafe fn free_aux_map(map: *mut kh_slow5_s2a_t, n_buckets: usize) {
+    if !(*map).vals.is_null() {
+        for i in 0..n_buckets {
+            libc::free((*(*map).vals.add(i)).data as *mut c_void);
+        }
+    }
+    libc::free((*map).vals as *mut c_void);
+    libc::free((*map).keys as *mut c_void);
+    libc::free((*map).flags as *mut c_void);
+    libc::free(map as *mut c_void);
+}
+
 /// Represents a SLOW5 record.
+///
+/// Cloning a Record makes a deep copy of the read id, signal, and auxiliary
+/// fields, including the names of the auxiliary fields, so the copy can
+/// outlive the [`FileReader`], [`FileWriter`], or iterator it came from.
+///
+/// # Example
+/// ```
+/// # use slow5::FileReader;
+/// use slow5::RecordExt;
+/// # fn main() -> anyhow::Result<()> {
+/// let mut reader = FileReader::open("examples/example.slow5")?;
+/// let mut stash = Vec::new();
+/// for rec in reader.records() {
+///     let rec = rec?;
+///     stash.push(rec.clone());
+/// }
+/// assert_eq!(stash[0].read_id(), b"r1");
+/// # Ok(())
+/// # }
+/// ```
 pub struct Record {
     pub(crate) slow5_rec: *mut slow5_rec_t,
+    // Names the auxiliary values are keyed by if they were copied from another
+    // record, otherwise the names are owned by a header. Dropped after
+    // slow5_rec is freed.
+    _aux_keys: Vec<CString>,
 }
 
 unsafe impl Send for Record {}
@@ -222,9 +744,83 @@ impl serde::Serialize for Record {
     }
 }
 
+#[cfg(feature = "serde")]
+/// Only primary fields (no auxiliary fields) are deserialized, from the same
+/// map shape used for serializing
+impl<'de> serde::Deserialize<'de> for Record {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::{Error, MapAccess, Visitor};
+
+        const FIELDS: &[&str] = &[
+            "read_id",
+            "read_group",
+            "digitisation",
+            "offset",
+            "range",
+            "sampling_rate",
+            "raw_signal",
+        ];
+
+        struct RecordVisitor;
+
+        impl<'de> Visitor<'de> for RecordVisitor {
+            type Value = Record;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a map of the SLOW5 record primary fields")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut builder = RecordBuilder::default();
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "read_id" => builder.read_id(map.next_value::<String>()?),
+                        "read_group" => builder.read_group(map.next_value()?),
+                        "digitisation" => builder.digitisation(map.next_value()?),
+                        "offset" => builder.offset(map.next_value()?),
+                        "range" => builder.range(map.next_value()?),
+                        "sampling_rate" => builder.sampling_rate(map.next_value()?),
+                        "raw_signal" => builder.raw_signal(&map.next_value::<Vec<i16>>()?),
+                        _ => return Err(A::Error::unknown_field(&key, FIELDS)),
+                    };
+                }
+                builder.build().map_err(|e| match e {
+                    BuilderError::RequiredValueUnset(field) => A::Error::missing_field(field),
+                    e => A::Error::custom(e),
+                })
+            }
+        }
+
+        deserializer.deserialize_map(RecordVisitor)
+    }
+}
+
 impl Record {
     pub(crate) fn new(slow5_rec: *mut slow5_rec_t) -> Self {
-        Self { slow5_rec }
+        Self {
+            slow5_rec,
+            _aux_keys: Vec::new(),
+        }
+    }
+
+    /// Take ownership of a record read by slow5lib, used by slow5-typed
+    ///
+    /// # Safety
+    /// slow5_rec must be a valid record that nothing else frees.
+    #[doc(hidden)]
+    pub unsafe fn from_raw(slow5_rec: *mut slow5_rec_t) -> Self {
+        Self::new(slow5_rec)
+    }
+
+    // Auxiliary fields with a size that doesn't match their length and type
+    pub(crate) fn aux_size_mismatches(&self) -> Vec<(String, u64, u64)> {
+        unsafe { aux_size_mismatches((*self.slow5_rec).aux_map) }
     }
 
     /// Initialize builder to make new Record
@@ -232,6 +828,84 @@ impl Record {
         Default::default()
     }
 
+    /// Set the read id, replacing the old one
+    ///
+    /// # Errors
+    /// Returns an Err if read_id contains an interior nul byte, is longer than
+    /// u16::MAX, or memory for it fails to be allocated
+    pub fn set_read_id<B: Into<Vec<u8>>>(&mut self, read_id: B) -> Result<(), Slow5Error> {
+        let read_id = to_cstring(read_id)?;
+        let read_id_len = read_id
+            .as_bytes()
+            .len()
+            .try_into()
+            .map_err(|_| Slow5Error::Conversion)?;
+        unsafe {
+            let read_id_ptr = libc::strdup(read_id.as_ptr());
+            if read_id_ptr.is_null() {
+                return Err(Slow5Error::Allocation);
+            }
+            libc::free((*self.slow5_rec).read_id as *mut c_void);
+            (*self.slow5_rec).read_id = read_id_ptr;
+            (*self.slow5_rec).read_id_len = read_id_len;
+        }
+        Ok(())
+    }
+
+    /// Set the offset, used to convert the raw signal to picoamps
+    pub fn set_offset(&mut self, offset: f64) {
+        unsafe { (*self.slow5_rec).offset = offset }
+    }
+
+    /// Set the range, used to convert the raw signal to picoamps
+    pub fn set_range(&mut self, range: f64) {
+        unsafe { (*self.slow5_rec).range = range }
+    }
+
+    /// Set the sampling rate
+    pub fn set_sampling_rate(&mut self, sampling_rate: f64) {
+        unsafe { (*self.slow5_rec).sampling_rate = sampling_rate }
+    }
+
+    /// Set the raw signal, replacing the old one. The length of the signal is
+    /// updated to match.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{FileReader, RecordExt};
+    /// # fn main() -> anyhow::Result<()> {
+    /// let reader = FileReader::open("examples/example.slow5")?;
+    /// let mut rec = reader.get_record("r1")?;
+    /// let trimmed = rec.raw_signal_iter().skip(10).collect::<Vec<_>>();
+    /// rec.set_raw_signal(&trimmed)?;
+    /// assert_eq!(rec.len_signal(), trimmed.len() as u64);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an Err if memory for the signal fails to be allocated, the
+    /// Record is unchanged if so
+    pub fn set_raw_signal(&mut self, raw_signal: &[i16]) -> Result<(), Slow5Error> {
+        let len_raw_signal = raw_signal
+            .len()
+            .try_into()
+            .map_err(|_| Slow5Error::Conversion)?;
+        unsafe {
+            // realloc with a size of 0 may free the signal and return null
+            let size = size_of::<i16>() * raw_signal.len().max(1);
+            let raw_signal_ptr =
+                libc::realloc((*self.slow5_rec).raw_signal as *mut c_void, size) as *mut i16;
+            if raw_signal_ptr.is_null() {
+                return Err(Slow5Error::Allocation);
+            }
+            std::ptr::copy_nonoverlapping(raw_signal.as_ptr(), raw_signal_ptr, raw_signal.len());
+            (*self.slow5_rec).raw_signal = raw_signal_ptr;
+            (*self.slow5_rec).len_raw_signal = len_raw_signal;
+        }
+        Ok(())
+    }
+
     /// Set the value for an auxiliary field of a record. Not all auxiliary
     /// fields need to be set, however, calling [`get_aux_field`] will
     /// return an Err if its called on an unset auxiliary field.
@@ -279,43 +953,381 @@ impl Record {
         value.aux_set(self, field_name, writer)
     }
 
-    /// Get data for an auxiliary field of a record.
+    /// Set the value of an auxiliary enum field by its label instead of its
+    /// index.
+    ///
     /// # Example
     /// ```
-    /// # use anyhow::Result;
-    /// # use slow5::FileReader;
+    /// # use slow5::{RecordBuilder, WriteOptions};
     /// # use assert_fs::TempDir;
     /// # use assert_fs::fixture::PathChild;
-    /// # fn main() -> Result<()> {
-    /// let path = "examples/example2.slow5";
-    /// let slow5 = FileReader::open(path)?;
-    /// let rec = slow5.get_record("r0")?;
-    /// let channel_number: i32 = rec.get_aux_field("read_number")?;
-    /// // Alt turbofish syntax
-    /// let channel_number = rec.get_aux_field::<i32>("read_number")?;
-    /// assert_eq!(channel_number, 4019);
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp_dir = TempDir::new()?;
+    /// # let path = tmp_dir.child("test.slow5");
+    /// let mut opts = WriteOptions::default();
+    /// opts.aux("end_reason", vec!["unknown", "signal_positive"]);
+    /// let mut slow5 = opts.create(path)?;
+    /// let mut rec = RecordBuilder::default()
+    ///     .read_id("test_id")
+    ///     .read_group(0)
+    ///     .digitisation(4096.0)
+    ///     .offset(4.0)
+    ///     .range(12.0)
+    ///     .sampling_rate(4000.0)
+    ///     .raw_signal(&[0, 1, 2, 3])
+    ///     .build()?;
+    /// rec.set_aux_enum(&mut slow5, "end_reason", "signal_positive")?;
+    /// assert_eq!(rec.get_aux_enum_label(&slow5, "end_reason")?, "signal_positive");
     /// # Ok(())
     /// # }
     /// ```
     ///
-    /// # Note
-    /// You may get a compiler error complaining about trait bounds
-    /// ```ignore_test
-    /// error[E0277]: the trait bound `(): AuxField` is not satisfied
-    /// or
-    /// error[E0277]: can't compare `()` with SomeType
-    /// ```
-    /// In this case use turbofish syntax (as shown in the example) to help the
-    /// compiler figure out the type.
-    ///
     /// # Errors
-    /// Returns an Err if auxiliary field wasn't set for that record.
-    pub fn get_aux_field<T>(&self, name: impl Into<Vec<u8>>) -> Result<T, Slow5Error>
-    where
+    /// Returns an Err if the field is not an auxiliary enum field in the
+    /// header, or if the label is not one of the field's labels.
+    pub fn set_aux_enum<B, L>(
+        &mut self,
+        writer: &mut FileWriter,
+        field_name: B,
+        label: L,
+    ) -> Result<(), Slow5Error>
+    where
+        B: Into<Vec<u8>>,
+        L: Into<Vec<u8>>,
+    {
+        let field_name = field_name.into();
+        let label = label.into();
+        let idx = aux_enum_labels(&writer.header(), field_name.clone())?
+            .iter()
+            .position(|l| l.to_bytes() == label.as_slice());
+        match idx {
+            Some(idx) => self.set_aux_field(writer, field_name, EnumField(idx)),
+            None => Err(Slow5Error::UnknownEnumLabel(
+                String::from_utf8_lossy(&label).into_owned(),
+            )),
+        }
+    }
+
+    /// Set the value of an auxiliary field from its bytes, ie to copy values
+    /// between files without knowing their types at compile time. Values are
+    /// in native byte order, arrays are their elements one after another, and
+    /// strings are their bytes without a nul terminator.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{FieldType, RecordBuilder, WriteOptions};
+    /// # use assert_fs::TempDir;
+    /// # use assert_fs::fixture::PathChild;
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp_dir = TempDir::new()?;
+    /// # let path = tmp_dir.child("test.slow5");
+    /// let mut writer = WriteOptions::default()
+    ///     .aux("read_number", FieldType::Int32)
+    ///     .create(path)?;
+    /// let mut rec = RecordBuilder::with_defaults()
+    ///     .read_id("test_id")
+    ///     .raw_signal(&[0, 1, 2, 3])
+    ///     .build()?;
+    /// let bytes = 42i32.to_ne_bytes();
+    /// rec.set_aux_field_bytes(&mut writer, "read_number", &bytes, FieldType::Int32)?;
+    /// assert_eq!(rec.get_aux_field::<i32>("read_number")?, 42);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`Slow5Error::SchemaMismatch`] if the field isn't in the header
+    /// with field_type, [`Slow5Error::AuxTypeMismatch`] if the number of
+    /// bytes doesn't fit field_type, or an Err if the value fails to be set.
+    pub fn set_aux_field_bytes<B>(
+        &mut self,
+        writer: &mut FileWriter,
+        field_name: B,
+        bytes: &[u8],
+        field_type: FieldType,
+    ) -> Result<(), Slow5Error>
+    where
+        B: Into<Vec<u8>>,
+    {
+        let field_name = field_name.into();
+        let found = writer.aux_field_type(field_name.clone()).ok();
+        // Labels of enums are checked by slow5lib, through the index
+        let matches = match (&found, &field_type) {
+            (Some(FieldType::Enum(_)), FieldType::Enum(_)) => true,
+            (Some(found), expected) => found == expected,
+            (None, _) => false,
+        };
+        if !matches {
+            return Err(Slow5Error::SchemaMismatch {
+                field: String::from_utf8_lossy(&field_name).into_owned(),
+                expected: field_type,
+                found,
+            });
+        }
+
+        let header = writer.header().header;
+        let name = writer.aux_name(field_name)?;
+        let ret = match field_type.elem_size() {
+            None => {
+                let value = to_cstring(bytes)?;
+                unsafe { slow5_aux_set_string(self.slow5_rec, name, value.as_ptr(), header) }
+            }
+            Some(size) if field_type.is_array() => {
+                let size = size as usize;
+                if bytes.len() % size != 0 {
+                    return Err(Slow5Error::AuxTypeMismatch);
+                }
+                let value_ptr = bytes.as_ptr() as *const c_void;
+                let len = bytes.len() / size;
+                unsafe { slow5_aux_array_set(self.slow5_rec, name, value_ptr, len, header) }
+            }
+            Some(size) => {
+                if bytes.len() as u64 != size {
+                    return Err(Slow5Error::AuxTypeMismatch);
+                }
+                let value_ptr = bytes.as_ptr() as *const c_void;
+                unsafe { slow5_aux_set(self.slow5_rec, name, value_ptr, header) }
+            }
+        };
+        if ret < 0 {
+            Err(parse_aux_field_set_error(ret))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Get the label of an auxiliary enum field's value, using the labels
+    /// stored in header.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::FileReader;
+    /// # fn main() -> anyhow::Result<()> {
+    /// let reader = FileReader::open("examples/example3.blow5")?;
+    /// let rec = reader.get_record("0035aaf9-a746-4bbd-97c4-390ddc27c756")?;
+    /// assert_eq!(rec.get_aux_enum_label(&reader, "end_reason")?, "unknown");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an Err if the field is not an auxiliary enum field, the value
+    /// isn't set, or the label isn't valid UTF-8.
+    pub fn get_aux_enum_label<'h, H, B>(
+        &self,
+        header: &'h H,
+        field_name: B,
+    ) -> Result<&'h str, Slow5Error>
+    where
+        H: HeaderExt,
+        B: Into<Vec<u8>>,
+    {
+        let field_name = field_name.into();
+        let EnumField(idx) = self.get_aux_field(field_name.clone())?;
+        let labels = aux_enum_labels(&header.header(), field_name)?;
+        let label = labels.get(idx).ok_or(Slow5Error::EnumOutOfRange)?;
+        Ok(label.to_str()?)
+    }
+
+    /// Get data for an auxiliary field of a record.
+    /// # Example
+    /// ```
+    /// # use anyhow::Result;
+    /// # use slow5::FileReader;
+    /// # use assert_fs::TempDir;
+    /// # use assert_fs::fixture::PathChild;
+    /// # fn main() -> Result<()> {
+    /// let path = "examples/example2.slow5";
+    /// let slow5 = FileReader::open(path)?;
+    /// let rec = slow5.get_record("r0")?;
+    /// let channel_number: i32 = rec.get_aux_field("read_number")?;
+    /// // Alt turbofish syntax
+    /// let channel_number = rec.get_aux_field::<i32>("read_number")?;
+    /// assert_eq!(channel_number, 4019);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Note
+    /// You may get a compiler error complaining about trait bounds
+    /// ```ignore_test
+    /// error[E0277]: the trait bound `(): AuxField` is not satisfied
+    /// or
+    /// error[E0277]: can't compare `()` with SomeType
+    /// ```
+    /// In this case use turbofish syntax (as shown in the example) to help the
+    /// compiler figure out the type.
+    ///
+    /// Slices and `&str` borrow from the record and must not outlive it. Use
+    /// `Vec<T>` or `String` to get an owned copy of the value instead.
+    ///
+    /// # Errors
+    /// Returns an Err if auxiliary field wasn't set for that record.
+    pub fn get_aux_field<T>(&self, name: impl Into<Vec<u8>>) -> Result<T, Slow5Error>
+    where
         T: AuxField,
     {
         T::aux_get(self, name)
     }
+
+    /// Get data for an auxiliary field of a record, returning `Ok(None)` if
+    /// the field is declared in the header but has no value for this record.
+    ///
+    /// # Example
+    /// ```
+    /// # use anyhow::Result;
+    /// # use slow5::FileReader;
+    /// # fn main() -> Result<()> {
+    /// let slow5 = FileReader::open("examples/example2.slow5")?;
+    /// let rec = slow5.get_record("r0")?;
+    /// let read_number = rec.get_aux_field_opt::<i32>("read_number")?;
+    /// assert_eq!(read_number, Some(4019));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Note
+    /// slow5lib marks missing primitive values with a sentinel (the maximum
+    /// value for integers, NaN for floating point, `'\0'` for chars), these
+    /// are returned as `None`. For a record built in memory, `None` is only
+    /// returned if no auxiliary fields have been set on the record.
+    ///
+    /// # Errors
+    /// Returns an Err if the field doesn't exist or the type doesn't match
+    /// the type of the field.
+    pub fn get_aux_field_opt<T>(&self, name: impl Into<Vec<u8>>) -> Result<Option<T>, Slow5Error>
+    where
+        T: AuxField,
+    {
+        T::aux_get_opt(self, name)
+    }
+
+    /// Get data for an auxiliary field of a record, like [`get_aux_field`],
+    /// with the name already converted to a C string. Converting the name
+    /// once, instead of on every call, avoids an allocation per field per
+    /// record when reading many records.
+    ///
+    /// # Example
+    /// ```
+    /// # use anyhow::Result;
+    /// # use slow5::FileReader;
+    /// use std::ffi::CString;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let mut slow5 = FileReader::open("examples/example2.slow5")?;
+    /// let read_number = CString::new("read_number")?;
+    /// for rec in slow5.records() {
+    ///     let rec = rec?;
+    ///     let _ = rec.get_aux_field_cstr::<i32>(&read_number)?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an Err if auxiliary field wasn't set for that record.
+    ///
+    /// [`get_aux_field`]: crate::Record::get_aux_field
+    pub fn get_aux_field_cstr<T>(&self, name: &CStr) -> Result<T, Slow5Error>
+    where
+        T: AuxField,
+    {
+        T::aux_get_cstr(self, name)
+    }
+
+    /// Format the record as a line of a SLOW5 file, without the trailing
+    /// newline. The header is used for the auxiliary fields.
+    ///
+    /// # Example
+    /// ```
+    /// # use anyhow::Result;
+    /// # use slow5::FileReader;
+    /// # fn main() -> Result<()> {
+    /// let slow5 = FileReader::open("examples/example2.slow5")?;
+    /// let rec = slow5.get_record("r0")?;
+    /// let line = rec.to_slow5_line(&slow5)?;
+    /// assert!(line.starts_with("r0\t"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an Err if slow5lib fails to format the record, or the line
+    /// isn't valid UTF-8.
+    pub fn to_slow5_line<H: HeaderExt>(&self, header: &H) -> Result<String, Slow5Error> {
+        let mut n = 0;
+        let line = unsafe {
+            let aux_meta = (*header.header().header).aux_meta;
+            let mem = slow5_rec_to_mem(
+                self.slow5_rec,
+                aux_meta,
+                slow5_fmt_SLOW5_FORMAT_ASCII,
+                null_mut(),
+                &mut n,
+            ) as *mut u8;
+            if mem.is_null() {
+                return Err(Slow5Error::Allocation);
+            }
+            let line = std::slice::from_raw_parts(mem, n).to_vec();
+            libc::free(mem as *mut c_void);
+            line
+        };
+        let mut line = String::from_utf8(line).map_err(|e| e.utf8_error())?;
+        while line.ends_with(['\n', '\0']) {
+            line.pop();
+        }
+        Ok(line)
+    }
+
+    /// Parse a line of a SLOW5 file into a Record. The header is used for the
+    /// auxiliary fields, so the Record must not outlive it.
+    ///
+    /// # Example
+    /// ```
+    /// # use anyhow::Result;
+    /// # use slow5::{FileReader, Record, RecordExt};
+    /// # fn main() -> Result<()> {
+    /// let slow5 = FileReader::open("examples/example2.slow5")?;
+    /// let line = slow5.get_record("r0")?.to_slow5_line(&slow5)?;
+    /// let rec = Record::parse_slow5_line(&slow5, &line)?;
+    /// assert_eq!(rec.read_id(), b"r0");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`Slow5Error::RecordParse`] if the line isn't a valid record
+    /// for the header.
+    pub fn parse_slow5_line<H: HeaderExt>(header: &H, line: &str) -> Result<Self, Slow5Error> {
+        let line = line.trim_end_matches(['\n', '\r']);
+        let len = line.len();
+        // slow5lib tokenizes the line in place, so it needs its own copy
+        let line = to_cstring(line)?.into_raw();
+        let rec = unsafe { libc::calloc(1, size_of::<slow5_rec_t>()) as *mut slow5_rec_t };
+        if rec.is_null() {
+            let _ = unsafe { CString::from_raw(line) };
+            return Err(Slow5Error::Allocation);
+        }
+        let ret = unsafe {
+            let aux_meta = (*header.header().header).aux_meta;
+            slow5_rec_parse(
+                line,
+                len,
+                std::ptr::null(),
+                rec,
+                slow5_fmt_SLOW5_FORMAT_ASCII,
+                aux_meta,
+                slow5_press_method_SLOW5_COMPRESS_NONE,
+            )
+        };
+        let _ = unsafe { CString::from_raw(line) };
+        if ret < 0 {
+            unsafe { slow5_rec_free(rec) };
+            Err(Slow5Error::RecordParse)
+        } else {
+            Ok(Record::new(rec))
+        }
+    }
 }
 
 impl Drop for Record {
@@ -326,6 +1338,14 @@ impl Drop for Record {
     }
 }
 
+impl Clone for Record {
+    /// # Panics
+    /// Panics if unable to allocate memory for the copy
+    fn clone(&self) -> Self {
+        unsafe { clone_rec(self.slow5_rec) }.expect("Failed to allocate memory for Record")
+    }
+}
+
 impl std::fmt::Debug for Record {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Record")
@@ -368,102 +1388,635 @@ pub trait RecordExt: RecPtr {
         unsafe { (*self.ptr().ptr).range }
     }
 
-    /// Group of the read.
-    fn read_group(&self) -> u32 {
-        unsafe { (*self.ptr().ptr).read_group }
+    /// Group of the read.
+    fn read_group(&self) -> u32 {
+        unsafe { (*self.ptr().ptr).read_group }
+    }
+
+    /// Number of signal measurements
+    fn len_signal(&self) -> u64 {
+        unsafe { (*self.ptr().ptr).len_raw_signal }
+    }
+
+    /// Sampling frequency of the ADC, i.e., the number of data points collected
+    /// per second (in Hertz).
+    fn sampling_rate(&self) -> f64 {
+        unsafe { (*self.ptr().ptr).sampling_rate }
+    }
+
+    /// Return iterator over signal in terms of picoamps
+    fn picoamps_signal_iter(&self) -> PicoAmpsSignalIter<'_> {
+        PicoAmpsSignalIter::new(self.ptr().ptr)
+    }
+
+    /// Return iterator over raw signal measurements
+    fn raw_signal_iter(&self) -> RawSignalIter<'_> {
+        RawSignalIter::new(self.ptr().ptr)
+    }
+
+    /// Return iterator over signal in terms of picoamps, normalized with
+    /// method. See [`signal::normalize`].
+    ///
+    /// [`signal::normalize`]: crate::signal::normalize
+    fn normalized_signal_iter(&self, method: Normalization) -> NormalizedSignalIter {
+        NormalizedSignalIter::new(self.picoamps_signal_iter().collect(), method)
+    }
+
+    /// Min, max, mean, median, and standard deviation of the raw signal,
+    /// computed without copying or sorting the signal. Returns None if the
+    /// record has no signal.
+    fn signal_stats(&self) -> Option<SignalStats> {
+        SignalStats::new(raw_signal(self))
+    }
+
+    /// Hash of the raw signal and the values used to convert it to picoamps,
+    /// ie to find records with the same signal under different read ids. The
+    /// hash is 64-bit FNV-1a, so it is the same across platforms and versions
+    /// of this crate. See [`dedupe`].
+    ///
+    /// [`dedupe`]: crate::dedupe
+    fn signal_hash(&self) -> u64 {
+        let mut hasher = Fnv64::new();
+        for value in [
+            self.digitisation(),
+            self.offset(),
+            self.range(),
+            self.sampling_rate(),
+        ] {
+            hasher.write(&value.to_le_bytes());
+        }
+        for x in self.raw_signal_iter() {
+            hasher.write(&x.to_le_bytes());
+        }
+        hasher.finish()
+    }
+
+    /// Duration of the read in seconds, from the number of signal
+    /// measurements and the sampling rate
+    fn duration_secs(&self) -> f64 {
+        self.len_signal() as f64 / self.sampling_rate()
+    }
+
+    /// Raw signal measurement at secs seconds since the start of the
+    /// experiment, using the `start_time` auxiliary field, ie the number of
+    /// samples since the start of the experiment when the read started.
+    /// Returns `Ok(None)` if the read wasn't being sequenced at that time.
+    ///
+    /// # Errors
+    /// Returns an Err if the record doesn't have a `start_time` field
+    fn sample_at_time(&self, secs: f64) -> Result<Option<i16>, Slow5Error>
+    where
+        Self: Sized,
+    {
+        let idx = sample_index(self, secs)?;
+        let signal = raw_signal(self);
+        if idx < 0.0 || idx >= signal.len() as f64 {
+            return Ok(None);
+        }
+        Ok(Some(signal[idx as usize]))
+    }
+
+    /// Raw signal measured from start up to end seconds since the start of
+    /// the experiment, like [`sample_at_time`]. The times are clipped to the
+    /// read, so the slice is empty if the read wasn't being sequenced between
+    /// them.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{FileReader, RecordExt};
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut reader = FileReader::open("examples/example3.blow5")?;
+    /// let rec = reader.records().next().unwrap()?;
+    /// let start = rec.get_aux_field::<u64>("start_time")? as f64 / rec.sampling_rate();
+    /// let first_second = rec.signal_slice_by_time(start, start + 1.0)?;
+    /// assert!(first_second.len() as f64 <= rec.sampling_rate());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an Err if the record doesn't have a `start_time` field
+    ///
+    /// [`sample_at_time`]: RecordExt::sample_at_time
+    fn signal_slice_by_time(&self, start: f64, end: f64) -> Result<&[i16], Slow5Error>
+    where
+        Self: Sized,
+    {
+        let signal = raw_signal(self);
+        let clip = |idx: f64| idx.clamp(0.0, signal.len() as f64) as usize;
+        let start = clip(sample_index(self, start)?);
+        let end = clip(sample_index(self, end)?).max(start);
+        Ok(&signal[start..end])
+    }
+
+    /// Number of samples since the start of the experiment when the read
+    /// started, from the ONT `start_time` auxiliary field. Returns None if
+    /// the field is missing, isn't a uint64_t, or has no value.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{FileReader, RecordExt};
+    /// # fn main() -> anyhow::Result<()> {
+    /// let reader = FileReader::open("examples/example2.slow5")?;
+    /// let rec = reader.get_record("r0")?;
+    /// assert_eq!(rec.read_number(), Some(4019));
+    /// assert_eq!(rec.channel_number().as_deref(), Some("281"));
+    /// assert!(rec.start_time().is_some());
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn start_time(&self) -> Option<u64>
+    where
+        Self: Sized,
+    {
+        ont_aux_field(self, "start_time")
+    }
+
+    /// Number of the read on its channel, from the ONT `read_number`
+    /// auxiliary field. Returns None if the field is missing, isn't an
+    /// int32_t, or has no value.
+    fn read_number(&self) -> Option<i32>
+    where
+        Self: Sized,
+    {
+        ont_aux_field(self, "read_number")
+    }
+
+    /// Current level in picoamps before the read started, from the ONT
+    /// `median_before` auxiliary field. Returns None if the field is missing,
+    /// isn't a double, or has no value.
+    fn median_before(&self) -> Option<f64>
+    where
+        Self: Sized,
+    {
+        ont_aux_field(self, "median_before")
+    }
+
+    /// Mux, ie the well of the channel, at the start of the read, from the
+    /// ONT `start_mux` auxiliary field. Returns None if the field is missing,
+    /// isn't a uint8_t, or has no value.
+    fn start_mux(&self) -> Option<u8>
+    where
+        Self: Sized,
+    {
+        ont_aux_field(self, "start_mux")
+    }
+
+    /// Channel the read was sequenced on, from the ONT `channel_number`
+    /// auxiliary field. Returns None if the field is missing, isn't a string,
+    /// or has no value.
+    fn channel_number(&self) -> Option<String>
+    where
+        Self: Sized,
+    {
+        ont_aux_field(self, "channel_number")
+    }
+
+    /// Label of the reason the read ended, ie "signal_positive", from the ONT
+    /// `end_reason` auxiliary enum field and the labels stored in header.
+    /// Returns None if the field is missing, isn't an enum, or has no value.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{FileReader, RecordExt};
+    /// # fn main() -> anyhow::Result<()> {
+    /// let reader = FileReader::open("examples/example3.blow5")?;
+    /// let rec = reader.get_record("0035aaf9-a746-4bbd-97c4-390ddc27c756")?;
+    /// assert_eq!(rec.end_reason(&reader), Some("unknown"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn end_reason<'h, H>(&self, header: &'h H) -> Option<&'h str>
+    where
+        Self: Sized,
+        H: HeaderExt,
+    {
+        let EnumField(idx) = ont_aux_field(self, "end_reason")?;
+        let labels = aux_enum_labels(&header.header(), "end_reason").ok()?;
+        labels.get(idx)?.to_str().ok()
+    }
+}
+
+// Value of one of the auxiliary fields written by ONT's software, None if the
+// record doesn't have it with the usual type
+fn ont_aux_field<T: AuxField, R: RecordExt>(rec: &R, name: &str) -> Option<T> {
+    T::aux_get_opt(rec, name).ok().flatten()
+}
+
+// Raw signal of a record as a slice
+pub(crate) fn raw_signal<R: RecordExt + ?Sized>(rec: &R) -> &[i16] {
+    let rec = rec.ptr().ptr;
+    unsafe {
+        let len = (*rec).len_raw_signal as usize;
+        if len == 0 || (*rec).raw_signal.is_null() {
+            return &[];
+        }
+        std::slice::from_raw_parts((*rec).raw_signal, len)
+    }
+}
+
+// Index in the raw signal of the measurement taken secs seconds after the
+// start of the experiment, negative if it was before the read started
+fn sample_index<R: RecordExt>(rec: &R, secs: f64) -> Result<f64, Slow5Error> {
+    let start_time = u64::aux_get_cstr(rec, cstr!("start_time"))?;
+    let idx = secs * rec.sampling_rate() - start_time as f64;
+    // Allow for rounding error when secs was computed from a sample index
+    Ok((idx + 1e-6).floor())
+}
+
+impl RecordExt for Record {}
+
+/// Iterator over Records from a SLOW5 file.
+///
+/// If error occurs, iterator will produce Some(Err(_)) and then subsequent
+/// iterations will be None This struct is generated by calling [`records`] on a
+/// [`FileReader`].
+///
+/// [`records`]: crate::FileReader::records
+/// [`FileReader`]: crate::FileReader
+pub struct RecordIter<'a> {
+    reader: &'a mut FileReader,
+    errored: bool,
+}
+
+unsafe impl<'a> Send for RecordIter<'a> {}
+
+impl<'a> std::fmt::Debug for RecordIter<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RecordIter").finish()
+    }
+}
+
+impl<'a> RecordIter<'a> {
+    pub(crate) fn new(reader: &'a mut FileReader) -> Self {
+        Self {
+            reader,
+            errored: false,
+        }
+    }
+
+    // Stats of the reader, since it is borrowed by the iterator
+    pub(crate) fn stats(&self) -> Stats {
+        self.reader.stats()
+    }
+}
+
+impl<'a> Iterator for RecordIter<'a> {
+    type Item = Result<Record, Slow5Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut rec = null_mut() as *mut slow5_rec_t;
+        let _lvl = self.reader.log_level_guard();
+        let fp = unsafe { (*self.reader.slow5_file).fp.cast() };
+        let pos = unsafe { libc::ftello(fp) };
+        let start = Instant::now();
+        let ret = unsafe { slow5lib_sys::slow5_get_next(&mut rec, self.reader.slow5_file) };
+        let time = start.elapsed();
+        if self.errored {
+            None
+        } else if ret >= 0 {
+            let bytes = (unsafe { libc::ftello(fp) } - pos).max(0) as u64;
+            let len_raw_signal = unsafe { (*rec).len_raw_signal };
+            self.reader
+                .update_stats(|stats| stats.add_record(bytes, len_raw_signal, time));
+            Some(Ok(Record::new(rec)))
+        } else if ret == -1 {
+            None
+        } else if ret == -2 {
+            self.errored = true;
+            Some(Err(Slow5Error::Argument))
+        } else if ret == -4 {
+            self.errored = true;
+            Some(Err(Slow5Error::RecordParse))
+        } else {
+            // -5
+            // for now just put everything under this.
+            self.errored = true;
+            Some(Err(Slow5Error::IOError))
+        }
+    }
+}
+
+/// Iterator over batches of Records from a SLOW5 file, generated by
+/// [`records_chunked`].
+///
+/// Each batch has the same number of records, except the last one, which has
+/// the remaining records. If a record fails to be read, the error is returned
+/// instead of its batch and the iterator stops.
+///
+/// [`records_chunked`]: crate::FileReader::records_chunked
+#[derive(Debug)]
+pub struct RecordChunks<'a> {
+    records: RecordIter<'a>,
+    chunk_size: usize,
+}
+
+impl<'a> RecordChunks<'a> {
+    pub(crate) fn new(reader: &'a mut FileReader, chunk_size: usize) -> Self {
+        Self {
+            records: RecordIter::new(reader),
+            chunk_size: chunk_size.max(1),
+        }
+    }
+}
+
+impl<'a> Iterator for RecordChunks<'a> {
+    type Item = Result<Vec<Record>, Slow5Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut chunk = Vec::with_capacity(self.chunk_size);
+        for rec in self.records.by_ref().take(self.chunk_size) {
+            match rec {
+                Ok(rec) => chunk.push(rec),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        (!chunk.is_empty()).then_some(Ok(chunk))
+    }
+}
+
+/// Iterator that reads each record of a SLOW5 file into the same [`Record`],
+/// generated by [`records_into`]. Each iteration yields `Ok(())` once the
+/// record has been read, which can then be accessed with [`record`].
+///
+/// Like [`RecordIter`], the iterator stops after the first Err.
+///
+/// [`records_into`]: crate::FileReader::records_into
+/// [`record`]: crate::RecordsInto::record
+pub struct RecordsInto<'a> {
+    reader: &'a mut FileReader,
+    rec: &'a mut Record,
+    errored: bool,
+}
+
+impl<'a> std::fmt::Debug for RecordsInto<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RecordsInto")
+            .field("errored", &self.errored)
+            .finish()
+    }
+}
+
+impl<'a> RecordsInto<'a> {
+    pub(crate) fn new(reader: &'a mut FileReader, rec: &'a mut Record) -> Self {
+        Self {
+            reader,
+            rec,
+            errored: false,
+        }
+    }
+
+    /// The last record that was read, or the record that was passed to
+    /// [`records_into`] before the first iteration
+    ///
+    /// [`records_into`]: crate::FileReader::records_into
+    pub fn record(&self) -> &Record {
+        self.rec
+    }
+}
+
+impl<'a> Iterator for RecordsInto<'a> {
+    type Item = Result<(), Slow5Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.errored {
+            return None;
+        }
+        let _lvl = self.reader.log_level_guard();
+        let fp = unsafe { (*self.reader.slow5_file).fp.cast() };
+        let pos = unsafe { libc::ftello(fp) };
+        let start = Instant::now();
+        let ret = unsafe {
+            slow5lib_sys::slow5_get_next(&mut self.rec.slow5_rec, self.reader.slow5_file)
+        };
+        let time = start.elapsed();
+        if ret >= 0 {
+            let bytes = (unsafe { libc::ftello(fp) } - pos).max(0) as u64;
+            let len_raw_signal = unsafe { (*self.rec.slow5_rec).len_raw_signal };
+            self.reader
+                .update_stats(|stats| stats.add_record(bytes, len_raw_signal, time));
+            Some(Ok(()))
+        } else if ret == SLOW5_ERR_EOF {
+            None
+        } else {
+            self.errored = true;
+            Some(Err(Slow5Error::native(ret)))
+        }
+    }
+}
+
+/// Iterator over the records of a SLOW5 file that continues past records
+/// that fail to be read, generated by [`records_recover`].
+///
+/// [`records_recover`]: crate::FileReader::records_recover
+pub struct RecoverIter<'a> {
+    reader: &'a mut FileReader,
+    // Start of each record in the index, sorted, used to find the next record
+    // after one that failed to be read
+    offsets: Vec<u64>,
+    // Record allocated by slow5lib for a read that failed, reused for the next
+    rec: *mut slow5_rec_t,
+    done: bool,
+}
+
+unsafe impl<'a> Send for RecoverIter<'a> {}
+
+impl<'a> std::fmt::Debug for RecoverIter<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RecoverIter").finish()
     }
+}
 
-    /// Number of signal measurements
-    fn len_signal(&self) -> u64 {
-        unsafe { (*self.ptr().ptr).len_raw_signal }
+impl<'a> RecoverIter<'a> {
+    pub(crate) fn new(reader: &'a mut FileReader) -> Self {
+        let mut offsets = reader
+            .index_iter()
+            .map(|index| index.map(|(_, offset, _)| offset).collect::<Vec<_>>())
+            .unwrap_or_default();
+        offsets.sort_unstable();
+        Self {
+            reader,
+            offsets,
+            rec: null_mut(),
+            done: false,
+        }
     }
+}
 
-    /// Sampling frequency of the ADC, i.e., the number of data points collected
-    /// per second (in Hertz).
-    fn sampling_rate(&self) -> f64 {
-        unsafe { (*self.ptr().ptr).sampling_rate }
-    }
+impl<'a> Iterator for RecoverIter<'a> {
+    type Item = Result<Record, Slow5Error>;
 
-    /// Return iterator over signal in terms of picoamps
-    fn picoamps_signal_iter(&self) -> PicoAmpsSignalIter<'_> {
-        PicoAmpsSignalIter::new(self.ptr().ptr)
-    }
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let _lvl = self.reader.log_level_guard();
+        let fp = unsafe { (*self.reader.slow5_file).fp.cast() };
+        let pos = unsafe { libc::ftello(fp) };
+        let start = Instant::now();
+        let ret = unsafe { slow5lib_sys::slow5_get_next(&mut self.rec, self.reader.slow5_file) };
+        let time = start.elapsed();
+        if ret >= 0 {
+            let rec = std::mem::replace(&mut self.rec, null_mut());
+            let bytes = (unsafe { libc::ftello(fp) } - pos).max(0) as u64;
+            let len_raw_signal = unsafe { (*rec).len_raw_signal };
+            self.reader
+                .update_stats(|stats| stats.add_record(bytes, len_raw_signal, time));
+            return Some(Ok(Record::new(rec)));
+        }
+        if ret == SLOW5_ERR_EOF {
+            self.done = true;
+            return None;
+        }
 
-    /// Return iterator over raw signal measurements
-    fn raw_signal_iter(&self) -> RawSignalIter<'_> {
-        RawSignalIter::new(self.ptr().ptr)
+        // Resume at the next record in the index after the one that failed.
+        // Without an index, slow5lib has already read past the record, unless
+        // the file ended.
+        let next = self.offsets.iter().find(|&&offset| offset as i64 > pos);
+        match next {
+            Some(&offset) => {
+                if unsafe { libc::fseeko(fp, offset as libc::off_t, libc::SEEK_SET) } != 0 {
+                    self.done = true;
+                }
+            }
+            None => {
+                let new_pos = unsafe { libc::ftello(fp) };
+                let at_end = unsafe { libc::feof(fp) } != 0;
+                if new_pos <= pos || at_end || !self.offsets.is_empty() {
+                    self.done = true;
+                }
+            }
+        }
+        Some(Err(Slow5Error::native(ret)))
     }
 }
 
-impl RecordExt for Record {}
+impl<'a> Drop for RecoverIter<'a> {
+    fn drop(&mut self) {
+        if !self.rec.is_null() {
+            unsafe { slow5_rec_free(self.rec) };
+        }
+    }
+}
 
-/// Iterator over Records from a SLOW5 file.
-///
-/// If error occurs, iterator will produce Some(Err(_)) and then subsequent
-/// iterations will be None This struct is generated by calling [`records`] on a
-/// [`FileReader`].
+/// Iterator over every nth record in the index, generated by
+/// [`records_shard`].
 ///
-/// [`records`]: crate::FileReader::records
-/// [`FileReader`]: crate::FileReader
-pub struct RecordIter<'a> {
-    reader: &'a mut FileReader,
-    errored: bool,
+/// [`records_shard`]: crate::FileReader::records_shard
+pub struct ShardIter<'a> {
+    reader: &'a FileReader,
+    read_ids: std::vec::IntoIter<Vec<u8>>,
 }
 
-unsafe impl<'a> Send for RecordIter<'a> {}
-
-impl<'a> std::fmt::Debug for RecordIter<'a> {
+impl<'a> std::fmt::Debug for ShardIter<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("RecordIter").finish()
+        f.debug_struct("ShardIter")
+            .field("remaining", &self.read_ids.len())
+            .finish()
     }
 }
 
-impl<'a> RecordIter<'a> {
-    pub(crate) fn new(reader: &'a mut FileReader) -> Self {
+impl<'a> ShardIter<'a> {
+    pub(crate) fn new(reader: &'a FileReader, read_ids: Vec<Vec<u8>>) -> Self {
         Self {
             reader,
-            errored: false,
+            read_ids: read_ids.into_iter(),
         }
     }
 }
 
-impl<'a> Iterator for RecordIter<'a> {
+impl<'a> Iterator for ShardIter<'a> {
     type Item = Result<Record, Slow5Error>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut rec = null_mut() as *mut slow5_rec_t;
-        let ret = unsafe { slow5lib_sys::slow5_get_next(&mut rec, self.reader.slow5_file) };
-        if self.errored {
-            None
-        } else if ret >= 0 {
-            Some(Ok(Record::new(rec)))
-        } else if ret == -1 {
-            None
-        } else if ret == -2 {
-            self.errored = true;
-            Some(Err(Slow5Error::Argument))
-        } else if ret == -4 {
-            self.errored = true;
-            Some(Err(Slow5Error::RecordParse))
-        } else {
-            // -5
-            // for now just put everything under this.
-            self.errored = true;
-            Some(Err(Slow5Error::IOError))
-        }
+        Some(self.reader.get_record(self.read_ids.next()?))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.read_ids.size_hint()
+    }
+}
+
+/// Record borrowed from a buffer that is reused for every record, passed to
+/// the closure of [`for_each_record`]. Use [`to_record`] to keep a copy.
+///
+/// [`for_each_record`]: crate::FileReader::for_each_record
+/// [`to_record`]: crate::RecordView::to_record
+pub struct RecordView<'a> {
+    slow5_rec: *mut slow5_rec_t,
+    _lifetime: PhantomData<&'a ()>,
+}
+
+impl<'a> std::fmt::Debug for RecordView<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RecordView")
+            .field("digitisation", &self.digitisation())
+            .field("len_signal", &self.len_signal())
+            .finish()
     }
 }
 
-/// Convert raw signal into a picoamps measurement
-pub fn to_picoamps(raw_signal: f64, digitisation: f64, offset: f64, range: f64) -> f64 {
-    ((raw_signal) + offset) * (range / digitisation)
+impl<'a> RecordView<'a> {
+    pub(crate) fn new(slow5_rec: *mut slow5_rec_t) -> Self {
+        Self {
+            slow5_rec,
+            _lifetime: PhantomData,
+        }
+    }
+
+    /// Get data for an auxiliary field, like [`Record::get_aux_field`]
+    ///
+    /// # Errors
+    /// Returns an Err if auxiliary field wasn't set for that record.
+    pub fn get_aux_field<T>(&self, name: impl Into<Vec<u8>>) -> Result<T, Slow5Error>
+    where
+        T: AuxField,
+    {
+        T::aux_get(self, name)
+    }
+
+    /// Get data for an auxiliary field, returning `Ok(None)` if it has no
+    /// value for this record, like [`Record::get_aux_field_opt`]
+    ///
+    /// # Errors
+    /// Returns an Err if the field doesn't exist or the type doesn't match
+    /// the type of the field.
+    pub fn get_aux_field_opt<T>(&self, name: impl Into<Vec<u8>>) -> Result<Option<T>, Slow5Error>
+    where
+        T: AuxField,
+    {
+        T::aux_get_opt(self, name)
+    }
+
+    /// Get data for an auxiliary field, with the name already converted to a
+    /// C string, like [`Record::get_aux_field_cstr`]
+    ///
+    /// # Errors
+    /// Returns an Err if auxiliary field wasn't set for that record.
+    pub fn get_aux_field_cstr<T>(&self, name: &CStr) -> Result<T, Slow5Error>
+    where
+        T: AuxField,
+    {
+        T::aux_get_cstr(self, name)
+    }
+
+    /// Copy the record out of the buffer, like [`Record::clone`]
+    ///
+    /// # Panics
+    /// Panics if unable to allocate memory for the copy
+    pub fn to_record(&self) -> Record {
+        unsafe { clone_rec(self.slow5_rec) }.expect("Failed to allocate memory for Record")
+    }
 }
 
-/// Convert picoamps signal into the raw signal
-pub fn to_raw_signal(picoamps: f64, digitisation: f64, offset: f64, range: f64) -> f64 {
-    (picoamps / (range / digitisation)) - offset
+impl<'a> RecPtr for RecordView<'a> {
+    fn ptr(&self) -> RecordPointer {
+        RecordPointer::new(self.slow5_rec)
+    }
 }
 
+impl<'a> RecordExt for RecordView<'a> {}
+
 /// Iterator over signal in picoamps from Record.
 ///
 /// This struct is generally created by calling [`picoamps_signal_iter`] on a
@@ -514,8 +2067,15 @@ impl<'a> Iterator for PicoAmpsSignalIter<'a> {
             }
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = unsafe { ((*self.read).len_raw_signal - self.i) as usize };
+        (len, Some(len))
+    }
 }
 
+impl<'a> ExactSizeIterator for PicoAmpsSignalIter<'a> {}
+
 /// Iterator over signal in picoamps from Record.
 ///
 /// This struct is generally created by calling [`raw_signal_iter`] on a
@@ -558,20 +2118,30 @@ impl<'a> Iterator for RawSignalIter<'a> {
             }
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = unsafe { ((*self.read).len_raw_signal - self.i) as usize };
+        (len, Some(len))
+    }
 }
 
+impl<'a> ExactSizeIterator for RawSignalIter<'a> {}
+
 #[derive(Debug)]
+#[doc(hidden)]
 pub struct RecordPointer {
-    pub(crate) ptr: *mut slow5_rec_t,
+    pub ptr: *mut slow5_rec_t,
 }
 
 impl RecordPointer {
-    pub(crate) fn new(ptr: *mut slow5_rec_t) -> Self {
+    #[doc(hidden)]
+    pub fn new(ptr: *mut slow5_rec_t) -> Self {
         RecordPointer { ptr }
     }
 }
 
-// TODO Hide docs, since it isnt useful to have in the documented API
+// Hidden from the docs, only exported for slow5-typed to implement RecordExt
+#[doc(hidden)]
 pub trait RecPtr {
     fn ptr(&self) -> RecordPointer;
 }
@@ -587,7 +2157,7 @@ mod test {
     use assert_fs::{fixture::PathChild, TempDir};
 
     use super::*;
-    use crate::{auxiliary::FieldType, EnumField, FileReader, FileWriter};
+    use crate::{EnumField, FieldType, FileReader, FileWriter};
 
     #[test]
     fn test_aux() -> anyhow::Result<()> {
@@ -610,6 +2180,53 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_builder_aux() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let path = tmp_dir.child("new.blow5");
+        let mut writer = FileWriter::options()
+            .aux("median", FieldType::Float)
+            .aux("string", FieldType::Str)
+            .aux("end_reason", vec!["a", "b"])
+            .create(&path)?;
+        let rec = RecordBuilder::default()
+            .read_id("test_id")
+            .read_group(0)
+            .digitisation(4096.0)
+            .offset(4.0)
+            .range(12.0)
+            .sampling_rate(4000.0)
+            .raw_signal(&[0, 1, 2, 3])
+            .aux_field("median", 1.0f32)
+            .aux_field("median", 10.0f32)
+            .aux_field("string", String::from("here"))
+            .aux_field("end_reason", EnumField(1))
+            .build_with_writer(&mut writer)?;
+        writer.add_record(&rec)?;
+        writer.close()?;
+
+        let reader = FileReader::open(&path)?;
+        let rec = reader.get_record("test_id")?;
+        assert_eq!(rec.get_aux_field::<f32>("median")?, 10.0);
+        assert_eq!(rec.get_aux_field::<&str>("string")?, "here");
+        let EnumField(end_reason) = rec.get_aux_field("end_reason")?;
+        assert_eq!(end_reason, 1);
+
+        let mut writer = FileWriter::options().create(tmp_dir.child("other.slow5"))?;
+        let rec = RecordBuilder::default()
+            .read_id("test_id")
+            .read_group(0)
+            .digitisation(4096.0)
+            .offset(4.0)
+            .range(12.0)
+            .sampling_rate(4000.0)
+            .raw_signal(&[0, 1, 2, 3])
+            .aux_field("not declared", 10.0f32)
+            .build_with_writer(&mut writer);
+        assert!(rec.is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_get_aux_enum() {
         let fp = "examples/example3.blow5";
@@ -621,6 +2238,284 @@ mod test {
             .expect("Unable to get end_reason enum field");
     }
 
+    #[test]
+    fn test_aux_enum_label() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let mut writer = FileWriter::options()
+            .aux("end_reason", vec!["unknown", "signal_positive"])
+            .aux("median", FieldType::Float)
+            .create(tmp_dir.child("new.blow5"))?;
+        let mut rec = RecordBuilder::default()
+            .read_id("test_id")
+            .read_group(0)
+            .digitisation(4096.0)
+            .offset(4.0)
+            .range(12.0)
+            .sampling_rate(4000.0)
+            .raw_signal(&[0, 1, 2, 3])
+            .build()?;
+        rec.set_aux_enum(&mut writer, "end_reason", "signal_positive")?;
+        let EnumField(idx) = rec.get_aux_field("end_reason")?;
+        assert_eq!(idx, 1);
+        assert_eq!(
+            rec.get_aux_enum_label(&writer, "end_reason")?,
+            "signal_positive"
+        );
+        assert!(matches!(
+            rec.set_aux_enum(&mut writer, "end_reason", "not a label"),
+            Err(Slow5Error::UnknownEnumLabel(_))
+        ));
+        assert!(rec.set_aux_enum(&mut writer, "median", "unknown").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_ont_aux_fields() -> anyhow::Result<()> {
+        let reader = FileReader::open("examples/example2.slow5")?;
+        let rec = reader.get_record("r0")?;
+        assert_eq!(rec.read_number(), Some(4019));
+        assert_eq!(rec.channel_number().as_deref(), Some("281"));
+        assert_eq!(
+            rec.start_time(),
+            Some(rec.get_aux_field::<u64>("start_time")?)
+        );
+        assert_eq!(rec.start_mux(), Some(rec.get_aux_field::<u8>("start_mux")?));
+        assert!(rec.median_before().is_some());
+        assert_eq!(rec.end_reason(&reader), None);
+
+        let reader = FileReader::open("examples/example.slow5")?;
+        let rec = reader.get_record("r1")?;
+        assert_eq!(rec.read_number(), None);
+        assert_eq!(rec.channel_number(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_clone() -> anyhow::Result<()> {
+        let reader = FileReader::open("examples/example2.slow5")?;
+        let rec = reader.get_record("r0")?;
+        let copy = rec.clone();
+        drop(rec);
+        drop(reader);
+
+        assert_eq!(copy.read_id(), b"r0");
+        assert_eq!(copy.len_signal(), copy.raw_signal_iter().count() as u64);
+        assert_eq!(copy.get_aux_field::<i32>("read_number")?, 4019);
+        assert_eq!(copy.get_aux_field::<&str>("channel_number")?, "281");
+
+        let rec = RecordBuilder::default()
+            .read_id("test_id")
+            .read_group(0)
+            .digitisation(4096.0)
+            .offset(4.0)
+            .range(12.0)
+            .sampling_rate(4000.0)
+            .raw_signal(&[0, 1, 2, 3])
+            .build()?;
+        let copy = rec.clone();
+        assert_eq!(copy.read_id(), rec.read_id());
+        assert_eq!(
+            copy.raw_signal_iter().collect::<Vec<_>>(),
+            rec.raw_signal_iter().collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_copies_outlive_source() -> anyhow::Result<()> {
+        let mut reader = FileReader::open("examples/example2.slow5")?;
+        let rec = reader.get_record("r0")?;
+        let builder = RecordBuilder::from_record(&rec);
+        let mut views = Vec::new();
+        reader.for_each_record(|rec| views.push(rec.to_record()))?;
+        drop(rec);
+        drop(reader);
+
+        assert_eq!(views[0].get_aux_field::<i32>("read_number")?, 4019);
+        let rebuilt = builder.build()?;
+        drop(builder);
+        assert_eq!(rebuilt.get_aux_field::<&str>("channel_number")?, "281");
+
+        let tmp_dir = TempDir::new()?;
+        let mut writer = FileWriter::options()
+            .aux("median", FieldType::Float)
+            .create(tmp_dir.child("new.slow5"))?;
+        let rec = RecordBuilder::with_defaults()
+            .read_id("test_id")
+            .raw_signal(&[0, 1, 2, 3])
+            .aux_field("median", 10.0f32)
+            .build_with_writer(&mut writer)?;
+        let copy = rec.clone();
+        drop(rec);
+        writer.close()?;
+        assert_eq!(copy.get_aux_field::<f32>("median")?, 10.0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_record() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let path = tmp_dir.child("fixed.blow5");
+        let reader = FileReader::open("examples/example2.slow5")?;
+        let rec = reader.get_record("r0")?;
+        let mut writer = FileWriter::create_from(&reader, &path)?;
+        let fixed = RecordBuilder::from_record(&rec)
+            .sampling_rate(5000.0)
+            .aux_field("read_number", 1i32)
+            .build_with_writer(&mut writer)?;
+        assert_eq!(fixed.sampling_rate(), 5000.0);
+        assert_eq!(fixed.range(), rec.range());
+        assert_eq!(
+            fixed.raw_signal_iter().collect::<Vec<_>>(),
+            rec.raw_signal_iter().collect::<Vec<_>>()
+        );
+        assert_eq!(fixed.get_aux_field::<i32>("read_number")?, 1);
+        assert_eq!(fixed.get_aux_field::<&str>("channel_number")?, "281");
+        writer.add_record(&fixed)?;
+        writer.close()?;
+
+        let reader = FileReader::open(&path)?;
+        let rec = reader.get_record("r0")?;
+        assert_eq!(rec.sampling_rate(), 5000.0);
+        assert_eq!(rec.get_aux_field::<i32>("read_number")?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate() -> anyhow::Result<()> {
+        let mut builder = RecordBuilder::with_defaults();
+        assert!(matches!(
+            builder.validate(),
+            Err(BuilderError::RequiredValueUnset("read_id"))
+        ));
+        builder.read_id("test_id").raw_signal(&[0, 1, 2]);
+        builder.validate()?;
+        builder.build()?;
+
+        builder.digitisation(0.0);
+        assert!(matches!(
+            builder.validate(),
+            Err(BuilderError::InvalidValue {
+                field: "digitisation",
+                ..
+            })
+        ));
+        builder.digitisation(4096.0).read_id(vec![b'a'; 70_000]);
+        assert!(matches!(
+            builder.validate(),
+            Err(BuilderError::InvalidValue {
+                field: "read_id",
+                ..
+            })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_signal_from_iter() -> anyhow::Result<()> {
+        let reader = FileReader::open("examples/example.slow5")?;
+        let rec = reader.get_record("r1")?;
+        let raw = RecordBuilder::from_record(&rec)
+            .raw_signal_from_iter(rec.raw_signal_iter())
+            .build()?;
+        let picoamps = RecordBuilder::from_record(&rec)
+            .picoamps_signal_from_iter(rec.picoamps_signal_iter())
+            .build()?;
+        let expected = rec.raw_signal_iter().collect::<Vec<_>>();
+        assert_eq!(raw.raw_signal_iter().collect::<Vec<_>>(), expected);
+        assert_eq!(picoamps.raw_signal_iter().collect::<Vec<_>>(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_aux_field_bytes() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let mut writer = FileWriter::options()
+            .aux("channel_number", FieldType::Str)
+            .aux("samples", FieldType::Uint16Array)
+            .aux("end_reason", vec!["unknown", "signal_positive"])
+            .create(tmp_dir.child("bytes.slow5"))?;
+        let mut rec = RecordBuilder::with_defaults()
+            .read_id("test_id")
+            .raw_signal(&[0, 1, 2, 3])
+            .build()?;
+        rec.set_aux_field_bytes(&mut writer, "channel_number", b"281", FieldType::Str)?;
+        let samples = [1u16, 2, 3]
+            .iter()
+            .flat_map(|x| x.to_ne_bytes())
+            .collect::<Vec<_>>();
+        rec.set_aux_field_bytes(&mut writer, "samples", &samples, FieldType::Uint16Array)?;
+        let end_reason = FieldType::Enum(Vec::new());
+        rec.set_aux_field_bytes(&mut writer, "end_reason", &[1], end_reason)?;
+        assert_eq!(rec.get_aux_field::<&str>("channel_number")?, "281");
+        assert_eq!(rec.get_aux_field::<Vec<u16>>("samples")?, [1, 2, 3]);
+        assert_eq!(
+            rec.get_aux_enum_label(&writer, "end_reason")?,
+            "signal_positive"
+        );
+
+        let res = rec.set_aux_field_bytes(&mut writer, "samples", &[1], FieldType::Uint16Array);
+        assert!(matches!(res, Err(Slow5Error::AuxTypeMismatch)));
+        let res = rec.set_aux_field_bytes(&mut writer, "samples", &[1], FieldType::Uint8Array);
+        assert!(matches!(res, Err(Slow5Error::SchemaMismatch { .. })));
+        Ok(())
+    }
+
+    #[test]
+    fn test_setters() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let path = tmp_dir.child("fixed.blow5");
+        let reader = FileReader::open("examples/example2.slow5")?;
+        let mut rec = reader.get_record("r0")?;
+        rec.set_read_id("r0_fixed")?;
+        rec.set_offset(1.0);
+        rec.set_range(2.0);
+        rec.set_sampling_rate(5000.0);
+        rec.set_raw_signal(&[1, 2, 3])?;
+        assert!(rec.set_read_id("r0\0").is_err());
+        assert_eq!(rec.read_id(), b"r0_fixed");
+
+        let mut writer = FileWriter::create_from(&reader, &path)?;
+        writer.add_record(&rec)?;
+        writer.close()?;
+        let reader = FileReader::open(&path)?;
+        let rec = reader.get_record("r0_fixed")?;
+        assert_eq!(rec.offset(), 1.0);
+        assert_eq!(rec.range(), 2.0);
+        assert_eq!(rec.sampling_rate(), 5000.0);
+        assert_eq!(rec.raw_signal_iter().collect::<Vec<_>>(), [1, 2, 3]);
+
+        let mut rec = rec.clone();
+        rec.set_raw_signal(&[])?;
+        assert_eq!(rec.len_signal(), 0);
+        assert_eq!(rec.raw_signal_iter().count(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_slow5_line() -> anyhow::Result<()> {
+        let reader = FileReader::open("examples/example2.slow5")?;
+        let rec = reader.get_record("r0")?;
+        let line = rec.to_slow5_line(&reader)?;
+        assert!(line.starts_with("r0\t"));
+        assert!(!line.ends_with('\n'));
+
+        let parsed = Record::parse_slow5_line(&reader, &line)?;
+        assert_eq!(parsed.read_id(), rec.read_id());
+        assert_eq!(
+            parsed.raw_signal_iter().collect::<Vec<_>>(),
+            rec.raw_signal_iter().collect::<Vec<_>>()
+        );
+        assert_eq!(parsed.get_aux_field::<i32>("read_number")?, 4019);
+        assert_eq!(parsed.to_slow5_line(&reader)?, line);
+
+        assert!(matches!(
+            Record::parse_slow5_line(&reader, "r0\tnot a record"),
+            Err(Slow5Error::RecordParse)
+        ));
+        Ok(())
+    }
+
     #[cfg(feature = "serde")]
     #[test]
     fn test_serialize() -> anyhow::Result<()> {
@@ -662,4 +2557,59 @@ mod test {
         );
         Ok(())
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize() -> anyhow::Result<()> {
+        let rec = RecordBuilder::default()
+            .read_id("test_id")
+            .read_group(1)
+            .digitisation(4096.0)
+            .offset(4.0)
+            .range(12.0)
+            .sampling_rate(4000.0)
+            .raw_signal(&[0, 1, 2, 3])
+            .build()?;
+        let json = serde_json::to_string(&rec)?;
+        let de: Record = serde_json::from_str(&json)?;
+        assert_eq!(de.read_id(), b"test_id");
+        assert_eq!(de.read_group(), 1);
+        assert_eq!(de.digitisation(), 4096.0);
+        assert_eq!(de.offset(), 4.0);
+        assert_eq!(de.range(), 12.0);
+        assert_eq!(de.sampling_rate(), 4000.0);
+        assert_eq!(de.raw_signal_iter().collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+
+        let missing = r#"{"read_id": "test_id", "read_group": 0}"#;
+        let err = serde_json::from_str::<Record>(missing).unwrap_err();
+        assert!(err.to_string().contains("missing field"));
+
+        let unknown = r#"{"read_id": "test_id", "median": 0.5}"#;
+        assert!(serde_json::from_str::<Record>(unknown).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_time_accessors() -> anyhow::Result<()> {
+        let mut reader = FileReader::open("examples/example3.blow5")?;
+        let rec = reader.records().next().unwrap()?;
+        let rate = rec.sampling_rate();
+        assert_eq!(rec.duration_secs(), rec.len_signal() as f64 / rate);
+
+        let start = rec.get_aux_field::<u64>("start_time")? as f64 / rate;
+        let signal = rec.raw_signal_iter().collect::<Vec<_>>();
+        assert_eq!(rec.sample_at_time(start)?, Some(signal[0]));
+        assert_eq!(rec.sample_at_time(start - 1.0)?, None);
+        assert_eq!(rec.sample_at_time(start + rec.duration_secs())?, None);
+        assert_eq!(rec.signal_slice_by_time(0.0, start + 1e9)?, signal);
+        assert_eq!(
+            rec.signal_slice_by_time(start + 2.0 / rate, start + 5.0 / rate)?,
+            &signal[2..5]
+        );
+        assert!(rec.signal_slice_by_time(start, start - 1.0)?.is_empty());
+
+        let rec = FileReader::open("examples/example.slow5")?.get_record("r1")?;
+        assert!(rec.sample_at_time(0.0).is_err());
+        Ok(())
+    }
 }
@@ -0,0 +1,488 @@
+//! Fetch records from a SLOW5/BLOW5 file on a web server without
+//! downloading the whole file, similar to `slow5curl`.
+//!
+//! The index is downloaded when the file is opened, then the header and each
+//! record are fetched with HTTP range requests. With the `object_store`
+//! feature, files can also be fetched from S3 (`s3://`), Google Cloud Storage
+//! (`gs://`), and Azure (`az://`), with the credentials from the environment,
+//! ie `AWS_ACCESS_KEY_ID`.
+//!
+//! [`RemoteReader`] blocks until each request is done. The object store
+//! requests are run on a small tokio runtime owned by the reader, so it can be
+//! used outside of async code. Inside a tokio runtime, each request is waited
+//! on from a separate thread, since blocking the runtime's thread would
+//! panic, so prefer calling the reader with `spawn_blocking` there.
+//!
+//! # Example
+//! ```no_run
+//! use slow5::remote::RemoteReader;
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let reader = RemoteReader::open("https://example.com/reads.blow5")?;
+//! let rec = reader.get_record("r1")?;
+//! let batch = reader.get_record_batch(["r1", "r2", "r3"], 4)?;
+//! # Ok(())
+//! # }
+//! ```
+use std::{
+    collections::HashMap,
+    io::{Cursor, Read},
+    mem::size_of,
+    thread,
+};
+
+use libc::c_void;
+use slow5lib_sys::{
+    slow5_decode, slow5_file_t, slow5_fmt_SLOW5_FORMAT_BINARY, slow5_rec_size_t, slow5_rec_t,
+};
+
+use crate::{
+    error::Slow5Error,
+    header::{Header, HeaderExt},
+    reader::FileReader,
+    record::Record,
+};
+
+const INDEX_MAGIC: &[u8] = b"SLOW5IDX";
+const INDEX_EOF: &[u8] = b"XDI5WOLS";
+// Magic number, version, and padding before the first entry
+const INDEX_HEADER_LEN: usize = 64;
+
+fn remote_err<E: std::fmt::Display>(e: E) -> Slow5Error {
+    Slow5Error::Remote(e.to_string())
+}
+
+// Where the bytes of the file and its index come from
+pub(crate) trait Backend: Send + Sync {
+    // Bytes of the file from start to end, exclusive, or to the end of the file
+    // if end is None
+    fn get_range(&self, start: u64, end: Option<u64>) -> Result<Vec<u8>, Slow5Error>;
+
+    // The whole index
+    fn get_index(&self) -> Result<Vec<u8>, Slow5Error>;
+}
+
+struct Http {
+    agent: ureq::Agent,
+    url: String,
+    index_url: String,
+}
+
+impl Http {
+    fn get(&self, url: &str, range: Option<String>) -> Result<Vec<u8>, Slow5Error> {
+        let mut req = self.agent.get(url);
+        if let Some(range) = &range {
+            req = req.set("Range", range);
+        }
+        let resp = req.call().map_err(remote_err)?;
+        if range.is_some() && resp.status() != 206 {
+            return Err(remote_err(format!(
+                "{url} doesn't support range requests, status {}",
+                resp.status()
+            )));
+        }
+        let mut bytes = Vec::new();
+        resp.into_reader().read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+impl Backend for Http {
+    fn get_range(&self, start: u64, end: Option<u64>) -> Result<Vec<u8>, Slow5Error> {
+        let range = match end {
+            Some(end) => format!("bytes={start}-{}", end.saturating_sub(1)),
+            None => format!("bytes={start}-"),
+        };
+        self.get(&self.url, Some(range))
+    }
+
+    fn get_index(&self) -> Result<Vec<u8>, Slow5Error> {
+        self.get(&self.index_url, None)
+    }
+}
+
+// Cloud storage, with a runtime to wait on the async requests. The runtime is
+// only None while being dropped.
+#[cfg(feature = "object_store")]
+struct ObjectStore {
+    runtime: Option<tokio::runtime::Runtime>,
+    store: Box<dyn object_store::ObjectStore>,
+    path: object_store::path::Path,
+    index_path: object_store::path::Path,
+}
+
+#[cfg(feature = "object_store")]
+impl ObjectStore {
+    // Prefixes of the environment variables with credentials and settings
+    const ENV_PREFIXES: [&'static str; 3] = ["AWS_", "GOOGLE_", "AZURE_"];
+
+    fn new(url: &str, index_url: &str) -> Result<Self, Slow5Error> {
+        let url = url::Url::parse(url).map_err(remote_err)?;
+        let index_url = url::Url::parse(index_url).map_err(remote_err)?;
+        let opts = std::env::vars()
+            .filter(|(key, _)| Self::ENV_PREFIXES.iter().any(|p| key.starts_with(p)))
+            .map(|(key, value)| (key.to_ascii_lowercase(), value));
+        let (store, path) = object_store::parse_url_opts(&url, opts).map_err(remote_err)?;
+        let (_, index_path) = object_store::parse_url(&index_url).map_err(remote_err)?;
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .enable_all()
+            .build()?;
+        Ok(Self {
+            runtime: Some(runtime),
+            store,
+            path,
+            index_path,
+        })
+    }
+
+    // Wait for fut on the runtime. Runtime::block_on panics on a thread that
+    // is already running a runtime, ie in async code, so fut is waited on from
+    // another thread there.
+    fn block_on<F>(&self, fut: F) -> F::Output
+    where
+        F: std::future::Future + Send,
+        F::Output: Send,
+    {
+        let runtime = self
+            .runtime
+            .as_ref()
+            .expect("runtime is only taken on drop");
+        if tokio::runtime::Handle::try_current().is_err() {
+            return runtime.block_on(fut);
+        }
+        thread::scope(|s| s.spawn(|| runtime.block_on(fut)).join())
+            .unwrap_or_else(|payload| std::panic::resume_unwind(payload))
+    }
+}
+
+#[cfg(feature = "object_store")]
+impl Drop for ObjectStore {
+    fn drop(&mut self) {
+        // Dropping a runtime also panics in async code, where it has to be shut
+        // down in the background instead
+        if let Some(runtime) = self.runtime.take() {
+            if tokio::runtime::Handle::try_current().is_ok() {
+                runtime.shutdown_background();
+            }
+        }
+    }
+}
+
+#[cfg(feature = "object_store")]
+impl Backend for ObjectStore {
+    fn get_range(&self, start: u64, end: Option<u64>) -> Result<Vec<u8>, Slow5Error> {
+        self.block_on(async {
+            let end = match end {
+                Some(end) => end,
+                None => self.store.head(&self.path).await.map_err(remote_err)?.size as u64,
+            };
+            let bytes = self
+                .store
+                .get_range(&self.path, start as usize..end as usize)
+                .await
+                .map_err(remote_err)?;
+            Ok(bytes.to_vec())
+        })
+    }
+
+    fn get_index(&self) -> Result<Vec<u8>, Slow5Error> {
+        self.block_on(async {
+            let result = self.store.get(&self.index_path).await.map_err(remote_err)?;
+            let bytes = result.bytes().await.map_err(remote_err)?;
+            Ok(bytes.to_vec())
+        })
+    }
+}
+
+// Parse a SLOW5 index into the read id, offset, and size of each record
+pub(crate) fn parse_index(bytes: &[u8]) -> Result<Vec<(Vec<u8>, u64, u64)>, Slow5Error> {
+    if !bytes.starts_with(INDEX_MAGIC) || bytes.len() < INDEX_HEADER_LEN {
+        return Err(remote_err("invalid index, magic number not found"));
+    }
+    let truncated = || remote_err("invalid index, truncated");
+    let mut entries = Vec::new();
+    let mut pos = INDEX_HEADER_LEN;
+    while !bytes[pos..].starts_with(INDEX_EOF) {
+        let len = bytes.get(pos..pos + 2).ok_or_else(truncated)?;
+        let len = u16::from_ne_bytes([len[0], len[1]]) as usize;
+        pos += 2;
+        let read_id = bytes.get(pos..pos + len).ok_or_else(truncated)?.to_vec();
+        pos += len;
+        let mut fields = [0u64; 2];
+        for field in fields.iter_mut() {
+            let value = bytes.get(pos..pos + 8).ok_or_else(truncated)?;
+            *field = u64::from_ne_bytes(value.try_into().map_err(|_| truncated())?);
+            pos += 8;
+        }
+        entries.push((read_id, fields[0], fields[1]));
+    }
+    Ok(entries)
+}
+
+// Pointer to the file with the parsed header, only used to decode records,
+// which doesn't modify it
+#[derive(Clone, Copy)]
+struct FilePtr(*mut slow5_file_t);
+
+unsafe impl Send for FilePtr {}
+unsafe impl Sync for FilePtr {}
+
+impl FilePtr {
+    // Decode a record as it is stored in the file, ie from the index offset
+    // and size
+    fn decode(self, mut bytes: Vec<u8>) -> Result<Record, Slow5Error> {
+        let binary = unsafe { (*self.0).format } == slow5_fmt_SLOW5_FORMAT_BINARY;
+        if binary {
+            // slow5_decode expects the record without its size
+            bytes.drain(..size_of::<slow5_rec_size_t>().min(bytes.len()));
+        } else if let Some(last) = bytes.last_mut() {
+            // and the line without the newline
+            *last = b'\0';
+        }
+        let mut n = bytes.len();
+        let mut mem = unsafe { libc::malloc(n.max(1)) };
+        if mem.is_null() {
+            return Err(Slow5Error::Allocation);
+        }
+        unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), mem as *mut u8, n) };
+        let mut rec = unsafe { libc::calloc(1, size_of::<slow5_rec_t>()) as *mut slow5_rec_t };
+        let ret = unsafe { slow5_decode(&mut mem, &mut n, &mut rec, self.0) };
+        unsafe { libc::free(mem) };
+        if ret < 0 {
+            unsafe { libc::free(rec as *mut c_void) };
+            Err(Slow5Error::from_errno(ret))
+        } else {
+            Ok(Record::new(rec))
+        }
+    }
+}
+
+/// Read records from a SLOW5/BLOW5 file on a web server, or in cloud storage
+/// with the `object_store` feature, fetching only the records requested with
+/// range requests. The server must support range requests, and the index must
+/// be available, ie at the URL of the file followed by ".idx".
+pub struct RemoteReader {
+    // Header parsed from the start of the file, used to decode records
+    reader: FileReader,
+    backend: Box<dyn Backend>,
+    entries: Vec<(Vec<u8>, u64, u64)>,
+    index: HashMap<Vec<u8>, usize>,
+}
+
+impl std::fmt::Debug for RemoteReader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RemoteReader")
+            .field("records", &self.entries.len())
+            .finish()
+    }
+}
+
+impl RemoteReader {
+    /// Open a remote file by URL, downloading the index from the URL followed
+    /// by ".idx" and the header.
+    ///
+    /// # Errors
+    /// Returns an Err if the index or the header fail to be downloaded or
+    /// parsed, or the server doesn't support range requests.
+    pub fn open(url: &str) -> Result<Self, Slow5Error> {
+        Self::open_with_index(url, &format!("{url}.idx"))
+    }
+
+    /// Open a remote file by URL with the index at index_url
+    pub fn open_with_index(url: &str, index_url: &str) -> Result<Self, Slow5Error> {
+        #[cfg(feature = "object_store")]
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            let backend = ObjectStore::new(url, index_url)?;
+            return Self::with_backend(Box::new(backend));
+        }
+        let backend = Http {
+            agent: ureq::AgentBuilder::new().build(),
+            url: url.to_owned(),
+            index_url: index_url.to_owned(),
+        };
+        Self::with_backend(Box::new(backend))
+    }
+
+    pub(crate) fn with_backend(backend: Box<dyn Backend>) -> Result<Self, Slow5Error> {
+        let entries = parse_index(&backend.get_index()?)?;
+        // Records start after the header
+        let header_end = entries.iter().map(|(_, offset, _)| *offset).min();
+        let header = backend.get_range(0, header_end)?;
+        let reader = FileReader::from_reader(Cursor::new(header))?;
+        let index = entries
+            .iter()
+            .enumerate()
+            .map(|(i, (read_id, _, _))| (read_id.clone(), i))
+            .collect();
+        Ok(Self {
+            reader,
+            backend,
+            entries,
+            index,
+        })
+    }
+
+    /// Header of the remote file
+    pub fn header(&self) -> Header<'_> {
+        self.reader.header()
+    }
+
+    /// Returns iterator over the read ids in the index, in the order the
+    /// records are stored in the file
+    pub fn iter_read_ids(&self) -> impl Iterator<Item = &[u8]> + '_ {
+        self.entries
+            .iter()
+            .map(|(read_id, _, _)| read_id.as_slice())
+    }
+
+    /// Returns true if the read id is in the index
+    pub fn contains_read_id(&self, read_id: &[u8]) -> bool {
+        self.index.contains_key(read_id)
+    }
+
+    fn file_ptr(&self) -> FilePtr {
+        FilePtr(self.reader.slow5_file)
+    }
+
+    // Fetch the bytes of the record with one range request
+    fn fetch(&self, read_id: &[u8]) -> Result<Vec<u8>, Slow5Error> {
+        let &i = self.index.get(read_id).ok_or(Slow5Error::GetRecordFailed)?;
+        let (_, offset, size) = self.entries[i];
+        self.backend.get_range(offset, Some(offset + size))
+    }
+
+    /// Fetch a single record with a range request
+    ///
+    /// # Errors
+    /// Returns an Err if the read id isn't in the index, the request fails,
+    /// or the record fails to be decoded.
+    pub fn get_record<B: Into<Vec<u8>>>(&self, read_id: B) -> Result<Record, Slow5Error> {
+        let bytes = self.fetch(&read_id.into())?;
+        self.file_ptr().decode(bytes)
+    }
+
+    /// Fetch multiple records, with requests split between threads. Records
+    /// are returned in the same order as the read ids.
+    ///
+    /// # Errors
+    /// Returns the first Err from [`get_record`] for any of the read ids.
+    ///
+    /// [`get_record`]: crate::remote::RemoteReader::get_record
+    pub fn get_record_batch<I, B>(
+        &self,
+        read_ids: I,
+        threads: usize,
+    ) -> Result<Vec<Record>, Slow5Error>
+    where
+        I: IntoIterator<Item = B>,
+        B: Into<Vec<u8>>,
+    {
+        let read_ids = read_ids.into_iter().map(Into::into).collect::<Vec<_>>();
+        if read_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let threads = threads.max(1);
+        let chunk_size = (read_ids.len() + threads - 1) / threads;
+        let file = self.file_ptr();
+        let backend = &self.backend;
+        let index = &self.index;
+        let entries = &self.entries;
+        let chunks = thread::scope(|s| {
+            let handles = read_ids
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    s.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|read_id| {
+                                let &i = index.get(read_id).ok_or(Slow5Error::GetRecordFailed)?;
+                                let (_, offset, size) = entries[i];
+                                let bytes = backend.get_range(offset, Some(offset + size))?;
+                                file.decode(bytes)
+                            })
+                            .collect::<Result<Vec<_>, _>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|h| h.join().expect("remote worker thread panicked"))
+                .collect::<Result<Vec<_>, _>>()
+        })?;
+        Ok(chunks.into_iter().flatten().collect())
+    }
+}
+
+impl HeaderExt for RemoteReader {
+    fn header(&self) -> Header<'_> {
+        self.header()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::RecordExt;
+
+    // Serves a local file as if it were remote
+    struct Local(Vec<u8>, Vec<u8>);
+
+    impl Backend for Local {
+        fn get_range(&self, start: u64, end: Option<u64>) -> Result<Vec<u8>, Slow5Error> {
+            let end = end.map_or(self.0.len(), |end| end as usize);
+            Ok(self.0[start as usize..end].to_vec())
+        }
+
+        fn get_index(&self) -> Result<Vec<u8>, Slow5Error> {
+            Ok(self.1.clone())
+        }
+    }
+
+    #[test]
+    fn test_parse_index() -> anyhow::Result<()> {
+        let entries = parse_index(&std::fs::read("examples/example.slow5.idx")?)?;
+        let read_ids = entries
+            .iter()
+            .map(|(id, _, _)| id.as_slice())
+            .collect::<Vec<_>>();
+        assert_eq!(read_ids, [b"r1", b"r2", b"r3", b"r4", b"r5"]);
+        assert!(parse_index(b"not an index").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_remote_reader() -> anyhow::Result<()> {
+        for file_path in ["examples/example.slow5", "examples/example3.blow5"] {
+            let backend = Local(
+                std::fs::read(file_path)?,
+                std::fs::read(format!("{file_path}.idx"))?,
+            );
+            let remote = RemoteReader::with_backend(Box::new(backend))?;
+            let local = FileReader::open(file_path)?;
+            let read_ids = remote
+                .iter_read_ids()
+                .map(|id| id.to_vec())
+                .collect::<Vec<_>>();
+            let batch = remote.get_record_batch(read_ids.clone(), 3)?;
+            for (read_id, rec) in read_ids.iter().zip(batch) {
+                let expected = local.get_record(read_id.as_slice())?;
+                assert_eq!(rec.read_id(), read_id.as_slice());
+                assert!(rec.raw_signal_iter().eq(expected.raw_signal_iter()));
+            }
+            assert!(remote.get_record("missing").is_err());
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "object_store")]
+    #[tokio::test]
+    async fn test_object_store_in_runtime() -> anyhow::Result<()> {
+        let file_path = std::fs::canonicalize("examples/example.slow5")?;
+        let remote = RemoteReader::open(&format!("file://{}", file_path.display()))?;
+        let rec = remote.get_record("r1")?;
+        assert_eq!(rec.read_id(), b"r1");
+        drop(remote);
+        Ok(())
+    }
+}
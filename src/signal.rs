@@ -0,0 +1,417 @@
+//! Processing of the signal of records.
+//!
+//! The level and spread of nanopore signal differs between reads, pores and
+//! runs, so most tools scale the signal of each read to a common level before
+//! segmenting or aligning it.
+use std::vec;
+
+use crate::record::RecordExt;
+
+pub mod events;
+
+/// Method used to compute the shift and scale of a signal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Normalization {
+    /// Shift by the median and scale by the median absolute deviation (MAD).
+    /// The MAD is multiplied by 1.4826 so it matches the standard deviation
+    /// of normally distributed signal. Less affected by spikes in the signal
+    /// than [`MeanStdev`].
+    ///
+    /// [`MeanStdev`]: Normalization::MeanStdev
+    #[default]
+    MedianMad,
+    /// Shift by the mean and scale by the standard deviation
+    MeanStdev,
+}
+
+// Factor to make the MAD consistent with the standard deviation
+const MAD_FACTOR: f64 = 1.4826;
+
+/// Shift and scale of a signal, used to normalize it with `(x - shift) /
+/// scale`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Scale {
+    shift: f64,
+    scale: f64,
+}
+
+impl Scale {
+    /// Compute the shift and scale of the signal with method. If the signal
+    /// has no spread, ie every measurement is the same, the scale is 1.0 so the
+    /// signal is only shifted.
+    pub fn new(signal: &[f64], method: Normalization) -> Self {
+        let (shift, scale) = match method {
+            Normalization::MedianMad => {
+                let mut values = signal.to_vec();
+                let shift = median(&mut values);
+                for x in values.iter_mut() {
+                    *x = (*x - shift).abs();
+                }
+                (shift, median(&mut values) * MAD_FACTOR)
+            }
+            Normalization::MeanStdev => {
+                let n = signal.len() as f64;
+                let mean = signal.iter().sum::<f64>() / n;
+                let var = signal.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+                (mean, var.sqrt())
+            }
+        };
+        let scale = if scale.is_normal() { scale } else { 1.0 };
+        Self { shift, scale }
+    }
+
+    /// Value subtracted from each measurement
+    pub fn shift(&self) -> f64 {
+        self.shift
+    }
+
+    /// Value each shifted measurement is divided by
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// Normalize a measurement
+    pub fn apply(&self, x: f64) -> f64 {
+        (x - self.shift) / self.scale
+    }
+}
+
+// Median of values, reordering them
+pub(crate) fn median(values: &mut [f64]) -> f64 {
+    let n = values.len();
+    if n == 0 {
+        return f64::NAN;
+    }
+    let (lower, mid, _) = values.select_nth_unstable_by(n / 2, f64::total_cmp);
+    let mid = *mid;
+    if n % 2 == 1 {
+        mid
+    } else {
+        let below = lower.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        (below + mid) / 2.0
+    }
+}
+
+/// Normalize the signal with method
+///
+/// # Example
+/// ```
+/// use slow5::signal::{normalize, Normalization};
+///
+/// let signal = normalize(&[1.0, 2.0, 3.0], Normalization::MeanStdev);
+/// assert_eq!(signal[1], 0.0);
+/// ```
+pub fn normalize(signal: &[f64], method: Normalization) -> Vec<f64> {
+    let scale = Scale::new(signal, method);
+    signal.iter().map(|&x| scale.apply(x)).collect()
+}
+
+/// Reduce the signal of the record in picoamps to one measurement for every
+/// factor measurements, ie for plotting long reads. See [`decimate_signal`].
+///
+/// # Example
+/// ```
+/// # use slow5::{FileReader, RecordExt};
+/// # fn main() -> anyhow::Result<()> {
+/// let reader = FileReader::open("examples/example.slow5")?;
+/// let rec = reader.get_record("r1")?;
+/// let signal = slow5::signal::decimate(&rec, 10);
+/// assert_eq!(signal.len() as u64, (rec.len_signal() + 9) / 10);
+/// # Ok(())
+/// # }
+/// ```
+pub fn decimate<R: RecordExt>(record: &R, factor: usize) -> Vec<f64> {
+    let signal = record.picoamps_signal_iter().collect::<Vec<_>>();
+    decimate_signal(&signal, factor)
+}
+
+/// Reduce the signal to one measurement for every factor measurements, by
+/// averaging each block of factor measurements so the signal is smoothed
+/// before it is downsampled. The last block may be shorter. A factor of 0 or
+/// 1 returns the signal unchanged.
+pub fn decimate_signal(signal: &[f64], factor: usize) -> Vec<f64> {
+    signal
+        .chunks(factor.max(1))
+        .map(|block| block.iter().sum::<f64>() / block.len() as f64)
+        .collect()
+}
+
+/// Smooth the signal with a moving average over window measurements,
+/// centered on each measurement. The window is shortened at the ends of the
+/// signal, and a window of 0 or 1 returns the signal unchanged.
+///
+/// # Example
+/// ```
+/// let signal = slow5::signal::moving_average(&[0.0, 3.0, 0.0, 3.0], 3);
+/// assert_eq!(signal, [1.5, 1.0, 2.0, 1.5]);
+/// ```
+pub fn moving_average(signal: &[f64], window: usize) -> Vec<f64> {
+    let window = window.max(1);
+    let mut sums = Vec::with_capacity(signal.len() + 1);
+    sums.push(0.0);
+    for (i, &x) in signal.iter().enumerate() {
+        sums.push(sums[i] + x);
+    }
+    (0..signal.len())
+        .map(|i| {
+            let start = i.saturating_sub(window / 2);
+            let end = (i + window - window / 2).min(signal.len());
+            (sums[end] - sums[start]) / (end - start) as f64
+        })
+        .collect()
+}
+
+/// Summary statistics of the raw signal of a record, computed with
+/// [`signal_stats`]. Convert them to picoamps with [`to_picoamps`], except
+/// for the standard deviation which is only multiplied by range /
+/// digitisation.
+///
+/// [`signal_stats`]: crate::RecordExt::signal_stats
+/// [`to_picoamps`]: crate::to_picoamps
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SignalStats {
+    min: i16,
+    max: i16,
+    mean: f64,
+    median: f64,
+    stdev: f64,
+}
+
+impl SignalStats {
+    // Compute the statistics without sorting the signal. The first pass finds
+    // the range of the signal, then the median is found by counting each
+    // value in a table only as large as that range.
+    pub(crate) fn new(signal: &[i16]) -> Option<Self> {
+        let n = signal.len() as u64;
+        let (mut sum, mut sumsq) = (0i128, 0i128);
+        let (mut min, mut max) = (i16::MAX, i16::MIN);
+        for &x in signal {
+            sum += x as i128;
+            sumsq += (x as i128) * (x as i128);
+            min = min.min(x);
+            max = max.max(x);
+        }
+        if n == 0 {
+            return None;
+        }
+
+        let key = |x: i16| (x as i32 - min as i32) as usize;
+        let mut counts = vec![0u64; key(max) + 1];
+        for &x in signal {
+            counts[key(x)] += 1;
+        }
+
+        // Ranks of the middle values, the same if n is odd
+        let (lo, hi) = ((n - 1) / 2, n / 2);
+        let mut seen = 0;
+        let mut median_lo = None;
+        let mut median = 0.0;
+        for (k, count) in counts.iter().enumerate() {
+            seen += count;
+            let value = (k as i32 + min as i32) as f64;
+            if median_lo.is_none() && seen > lo {
+                median_lo = Some(value);
+            }
+            if seen > hi {
+                median = (median_lo.unwrap_or(value) + value) / 2.0;
+                break;
+            }
+        }
+
+        let nf = n as f64;
+        let mean = sum as f64 / nf;
+        let var = (sumsq as f64 - (sum as f64) * mean) / nf;
+        Some(Self {
+            min,
+            max,
+            mean,
+            median,
+            stdev: var.max(0.0).sqrt(),
+        })
+    }
+
+    /// Smallest measurement
+    pub fn min(&self) -> i16 {
+        self.min
+    }
+
+    /// Largest measurement
+    pub fn max(&self) -> i16 {
+        self.max
+    }
+
+    /// Mean of the measurements
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Median of the measurements
+    pub fn median(&self) -> f64 {
+        self.median
+    }
+
+    /// Standard deviation of the measurements
+    pub fn stdev(&self) -> f64 {
+        self.stdev
+    }
+}
+
+// 64-bit FNV-1a, which doesn't change between Rust versions or platforms
+// unlike the hashers in std
+pub(crate) struct Fnv64(u64);
+
+impl Fnv64 {
+    pub(crate) fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    pub(crate) fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 = (self.0 ^ b as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    pub(crate) fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Iterator over the normalized signal of a record in picoamps.
+///
+/// This struct is generally created by calling [`normalized_signal_iter`] on
+/// a record type.
+///
+/// [`normalized_signal_iter`]: crate::RecordExt::normalized_signal_iter
+#[derive(Debug)]
+pub struct NormalizedSignalIter {
+    signal: vec::IntoIter<f64>,
+    scale: Scale,
+}
+
+impl NormalizedSignalIter {
+    pub(crate) fn new(signal: Vec<f64>, method: Normalization) -> Self {
+        let scale = Scale::new(&signal, method);
+        Self {
+            signal: signal.into_iter(),
+            scale,
+        }
+    }
+
+    /// Shift and scale computed from the signal of the record
+    pub fn scale(&self) -> Scale {
+        self.scale
+    }
+}
+
+impl Iterator for NormalizedSignalIter {
+    type Item = f64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.signal.next().map(|x| self.scale.apply(x))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.signal.size_hint()
+    }
+}
+
+impl ExactSizeIterator for NormalizedSignalIter {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{FileReader, RecordBuilder};
+
+    #[test]
+    fn test_normalize() {
+        let signal = [1.0, 2.0, 3.0, 4.0, 100.0];
+        let scale = Scale::new(&signal, Normalization::MedianMad);
+        assert_eq!(scale.shift(), 3.0);
+        assert_eq!(scale.scale(), MAD_FACTOR);
+
+        let scale = Scale::new(&[1.0, 3.0], Normalization::MeanStdev);
+        assert_eq!((scale.shift(), scale.scale()), (2.0, 1.0));
+        assert_eq!(
+            normalize(&[1.0, 3.0], Normalization::MeanStdev),
+            [-1.0, 1.0]
+        );
+
+        // No spread, only shifted
+        assert_eq!(normalize(&[5.0, 5.0], Normalization::MedianMad), [0.0, 0.0]);
+        assert!(normalize(&[], Normalization::MedianMad).is_empty());
+    }
+
+    #[test]
+    fn test_normalized_signal_iter() -> anyhow::Result<()> {
+        let reader = FileReader::open("examples/example.slow5")?;
+        let rec = reader.get_record("r1")?;
+        let picoamps = rec.picoamps_signal_iter().collect::<Vec<_>>();
+        let iter = rec.normalized_signal_iter(Normalization::MedianMad);
+        assert_eq!(iter.len(), picoamps.len());
+        assert_eq!(
+            iter.collect::<Vec<_>>(),
+            normalize(&picoamps, Normalization::MedianMad)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_signal_stats() -> anyhow::Result<()> {
+        let stats = SignalStats::new(&[3, -1, 2, 10]).unwrap();
+        assert_eq!((stats.min(), stats.max()), (-1, 10));
+        assert_eq!(stats.mean(), 3.5);
+        assert_eq!(stats.median(), 2.5);
+        assert_eq!(stats.stdev(), 16.25f64.sqrt());
+        assert_eq!(SignalStats::new(&[7, i16::MIN, 7]).unwrap().median(), 7.0);
+        let full_range = SignalStats::new(&[i16::MAX, i16::MIN]).unwrap();
+        assert_eq!(full_range.median(), -0.5);
+        assert!(SignalStats::new(&[]).is_none());
+
+        let reader = FileReader::open("examples/example.slow5")?;
+        let rec = reader.get_record("r1")?;
+        let mut signal = rec.raw_signal_iter().map(f64::from).collect::<Vec<_>>();
+        let stats = rec.signal_stats().unwrap();
+        assert_eq!(stats.median(), median(&mut signal));
+        Ok(())
+    }
+
+    #[test]
+    fn test_signal_hash() -> anyhow::Result<()> {
+        let mut hasher = Fnv64::new();
+        hasher.write(b"a");
+        assert_eq!(hasher.finish(), 0xaf63_dc4c_8601_ec8c);
+
+        let mut builder = RecordBuilder::default();
+        builder
+            .read_id("a")
+            .read_group(0)
+            .digitisation(4096.0)
+            .offset(4.0)
+            .range(12.0)
+            .sampling_rate(4000.0)
+            .raw_signal(&[1, 2, 3]);
+        let a = builder.build()?;
+        let b = builder.read_id("b").build()?;
+        assert_eq!(a.signal_hash(), b.signal_hash());
+        let c = builder.raw_signal(&[1, 2, 4]).build()?;
+        assert_ne!(a.signal_hash(), c.signal_hash());
+        let d = builder.raw_signal(&[1, 2, 3]).offset(5.0).build()?;
+        assert_ne!(a.signal_hash(), d.signal_hash());
+        Ok(())
+    }
+
+    #[test]
+    fn test_decimate() -> anyhow::Result<()> {
+        let signal = [1.0, 3.0, 5.0, 7.0, 9.0];
+        assert_eq!(decimate_signal(&signal, 2), [2.0, 6.0, 9.0]);
+        assert_eq!(decimate_signal(&signal, 0), signal);
+        assert_eq!(moving_average(&signal, 1), signal);
+        assert_eq!(moving_average(&signal, 2), [1.0, 2.0, 4.0, 6.0, 8.0]);
+
+        let reader = FileReader::open("examples/example.slow5")?;
+        let rec = reader.get_record("r1")?;
+        let picoamps = rec.picoamps_signal_iter().collect::<Vec<_>>();
+        assert_eq!(decimate(&rec, 4), decimate_signal(&picoamps, 4));
+        Ok(())
+    }
+}
@@ -0,0 +1,288 @@
+//! Segment signal into events, ie stretches of signal with a similar level,
+//! using the t-test based event detection of scrappie and nanopolish.
+//!
+//! Two t-tests, over a short and a long window on each side of every
+//! measurement, compare the signal before and after it. Peaks in either
+//! t-statistic are boundaries between events.
+use crate::record::RecordExt;
+
+/// Parameters for [`detect`]. The default is the parameters used for R9.4
+/// reads by nanopolish.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EventDetectionParams {
+    window_length1: usize,
+    window_length2: usize,
+    threshold1: f64,
+    threshold2: f64,
+    peak_height: f64,
+}
+
+impl Default for EventDetectionParams {
+    fn default() -> Self {
+        Self {
+            window_length1: 3,
+            window_length2: 6,
+            threshold1: 1.4,
+            threshold2: 9.0,
+            peak_height: 0.2,
+        }
+    }
+}
+
+impl EventDetectionParams {
+    /// Set the length of the short window, at least 2
+    pub fn window_length1(&mut self, len: usize) -> &mut Self {
+        self.window_length1 = len;
+        self
+    }
+
+    /// Set the length of the long window, at least 2
+    pub fn window_length2(&mut self, len: usize) -> &mut Self {
+        self.window_length2 = len;
+        self
+    }
+
+    /// Set the t-statistic a peak over the short window must reach
+    pub fn threshold1(&mut self, threshold: f64) -> &mut Self {
+        self.threshold1 = threshold;
+        self
+    }
+
+    /// Set the t-statistic a peak over the long window must reach
+    pub fn threshold2(&mut self, threshold: f64) -> &mut Self {
+        self.threshold2 = threshold;
+        self
+    }
+
+    /// Set how far the t-statistic must rise and fall around a peak
+    pub fn peak_height(&mut self, height: f64) -> &mut Self {
+        self.peak_height = height;
+        self
+    }
+}
+
+/// Stretch of signal with a similar level, found by [`detect`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Event {
+    start: u64,
+    length: u64,
+    mean: f64,
+    stdv: f64,
+}
+
+impl Event {
+    /// Index of the first measurement of the event in the signal
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    /// Number of measurements in the event
+    pub fn length(&self) -> u64 {
+        self.length
+    }
+
+    /// Mean of the measurements
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Standard deviation of the measurements
+    pub fn stdv(&self) -> f64 {
+        self.stdv
+    }
+}
+
+/// Segment the signal of the record in picoamps into events
+///
+/// # Example
+/// ```
+/// # use slow5::FileReader;
+/// use slow5::signal::events::{detect, EventDetectionParams};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let reader = FileReader::open("examples/example.slow5")?;
+/// let rec = reader.get_record("r1")?;
+/// for event in detect(&rec, &EventDetectionParams::default()) {
+///     println!("{}\t{}\t{:.2}", event.start(), event.length(), event.mean());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn detect<R: RecordExt>(record: &R, params: &EventDetectionParams) -> Vec<Event> {
+    let signal = record.picoamps_signal_iter().collect::<Vec<_>>();
+    detect_signal(&signal, params)
+}
+
+/// Segment the signal into events, like [`detect`]
+pub fn detect_signal(signal: &[f64], params: &EventDetectionParams) -> Vec<Event> {
+    let n = signal.len();
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut sums = Vec::with_capacity(n + 1);
+    let mut sumsqs = Vec::with_capacity(n + 1);
+    sums.push(0.0);
+    sumsqs.push(0.0);
+    for (i, &x) in signal.iter().enumerate() {
+        sums.push(sums[i] + x);
+        sumsqs.push(sumsqs[i] + x * x);
+    }
+
+    let tstat1 = tstat(&sums, &sumsqs, params.window_length1);
+    let tstat2 = tstat(&sums, &sumsqs, params.window_length2);
+    let mut short = Detector::new(&tstat1, params.threshold1, params.window_length1);
+    let mut long = Detector::new(&tstat2, params.threshold2, params.window_length2);
+    let mut peaks = Vec::new();
+    for i in 0..n {
+        if let Some(masked_to) = short.step(i, params.peak_height, &mut peaks) {
+            // A peak over the short window takes precedence over the long one
+            long.masked_to = masked_to;
+            long.reset(f64::MAX);
+        }
+        long.step(i, params.peak_height, &mut peaks);
+    }
+    peaks.sort_unstable();
+    peaks.dedup();
+
+    let bounds = std::iter::once(0)
+        .chain(peaks.into_iter().filter(|&peak| peak > 0 && peak < n))
+        .chain(std::iter::once(n))
+        .collect::<Vec<_>>();
+    bounds
+        .windows(2)
+        .map(|w| {
+            let (start, end) = (w[0], w[1]);
+            let len = (end - start) as f64;
+            let mean = (sums[end] - sums[start]) / len;
+            let var = (sumsqs[end] - sumsqs[start]) / len - mean * mean;
+            Event {
+                start: start as u64,
+                length: (end - start) as u64,
+                mean,
+                stdv: var.max(0.0).sqrt(),
+            }
+        })
+        .collect()
+}
+
+// t-statistic comparing the w measurements before and after each position,
+// zero where either window is past the end of the signal
+fn tstat(sums: &[f64], sumsqs: &[f64], w: usize) -> Vec<f64> {
+    let n = sums.len() - 1;
+    let mut tstat = vec![0.0; n];
+    if w < 2 || n < 2 * w {
+        return tstat;
+    }
+    let wf = w as f64;
+    for (i, t) in tstat.iter_mut().enumerate().take(n - w).skip(w) {
+        let mean1 = (sums[i] - sums[i - w]) / wf;
+        let mean2 = (sums[i + w] - sums[i]) / wf;
+        let var1 = (sumsqs[i] - sumsqs[i - w]) / wf - mean1 * mean1;
+        let var2 = (sumsqs[i + w] - sumsqs[i]) / wf - mean2 * mean2;
+        let combined_var = (var1 + var2).max(1e-100);
+        *t = (mean2 - mean1).abs() / (combined_var / wf).sqrt();
+    }
+    tstat
+}
+
+// Peak detector over one t-statistic
+struct Detector<'a> {
+    tstat: &'a [f64],
+    threshold: f64,
+    window_length: usize,
+    masked_to: usize,
+    peak_pos: Option<usize>,
+    peak_value: f64,
+    valid_peak: bool,
+}
+
+impl<'a> Detector<'a> {
+    fn new(tstat: &'a [f64], threshold: f64, window_length: usize) -> Self {
+        Self {
+            tstat,
+            threshold,
+            window_length,
+            masked_to: 0,
+            peak_pos: None,
+            peak_value: f64::MAX,
+            valid_peak: false,
+        }
+    }
+
+    fn reset(&mut self, value: f64) {
+        self.peak_pos = None;
+        self.peak_value = value;
+        self.valid_peak = false;
+    }
+
+    // Look at the t-statistic at i, adding a peak once one has been passed.
+    // Returns the position the other detectors should be masked to if the
+    // current peak is above the threshold.
+    fn step(&mut self, i: usize, peak_height: f64, peaks: &mut Vec<usize>) -> Option<usize> {
+        if self.masked_to >= i {
+            return None;
+        }
+        let value = self.tstat[i];
+        let Some(mut peak_pos) = self.peak_pos else {
+            // Look for the t-statistic to rise from a minimum
+            if value < self.peak_value {
+                self.peak_value = value;
+            } else if value - self.peak_value > peak_height {
+                self.peak_value = value;
+                self.peak_pos = Some(i);
+            }
+            return None;
+        };
+        if value > self.peak_value {
+            self.peak_value = value;
+            self.peak_pos = Some(i);
+            peak_pos = i;
+        }
+        let masked_to = (self.peak_value > self.threshold).then_some(peak_pos + self.window_length);
+        if self.peak_value - value > peak_height && self.peak_value > self.threshold {
+            self.valid_peak = true;
+        }
+        if self.valid_peak && i - peak_pos > self.window_length / 2 {
+            peaks.push(peak_pos);
+            self.reset(value);
+        }
+        masked_to
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::FileReader;
+
+    #[test]
+    fn test_detect_signal() {
+        let mut signal = vec![80.0; 50];
+        signal.extend([100.0; 50]);
+        signal.extend([90.0; 50]);
+        // Add some noise so the windows have a variance
+        for (i, x) in signal.iter_mut().enumerate() {
+            *x += [0.5, -0.5, 0.25, -0.25][i % 4];
+        }
+        let events = detect_signal(&signal, &EventDetectionParams::default());
+        let starts = events.iter().map(|e| e.start()).collect::<Vec<_>>();
+        assert_eq!(starts, [0, 50, 100]);
+        assert_eq!(events.iter().map(|e| e.length()).sum::<u64>(), 150);
+        assert!((events[1].mean() - 100.0).abs() < 0.1);
+        assert!(detect_signal(&[], &EventDetectionParams::default()).is_empty());
+    }
+
+    #[test]
+    fn test_detect() -> anyhow::Result<()> {
+        let reader = FileReader::open("examples/example.slow5")?;
+        let rec = reader.get_record("r1")?;
+        let events = detect(&rec, &EventDetectionParams::default());
+        assert!(!events.is_empty());
+        assert_eq!(events[0].start(), 0);
+        assert_eq!(
+            events.iter().map(|e| e.length()).sum::<u64>(),
+            rec.len_signal()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,338 @@
+//! Sort the records of a SLOW5 file into a new file
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+    fs,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering as AtomicOrdering},
+};
+
+use crate::{
+    error::Slow5Error,
+    header::HeaderExt,
+    reader::FileReader,
+    record::{Record, RecordExt},
+    writer::{FileWriter, WriteOptions},
+    EnumField, FieldType,
+};
+
+// Distinguishes the temporary files of sorts running at the same time
+static RUN_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// What records are sorted by with [`sort`]. Records with the same key are
+/// sorted by read id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey<'a> {
+    /// Sort by read id
+    ReadId,
+    /// Sort by the value of a numeric, string, char, or enum auxiliary field.
+    /// Records without a value are sorted last.
+    Aux(&'a str),
+}
+
+/// Options for sorting records, ie how many records are sorted in memory at
+/// once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortOptions {
+    buffer_records: usize,
+    tmp_dir: Option<PathBuf>,
+}
+
+impl Default for SortOptions {
+    fn default() -> Self {
+        Self {
+            buffer_records: 100_000,
+            tmp_dir: None,
+        }
+    }
+}
+
+impl SortOptions {
+    /// Set the number of records held in memory. If the input has more
+    /// records, they are sorted in batches written to temporary files, which
+    /// are then merged into the output.
+    pub fn buffer_records(&mut self, n: usize) -> &mut Self {
+        self.buffer_records = n.max(1);
+        self
+    }
+
+    /// Set the directory for the temporary files, by default
+    /// [`std::env::temp_dir`]
+    pub fn tmp_dir<P: AsRef<Path>>(&mut self, dir: P) -> &mut Self {
+        self.tmp_dir = Some(dir.as_ref().to_owned());
+        self
+    }
+
+    /// Write the records of reader to writer sorted by key. See [`sort`] for
+    /// more information.
+    pub fn sort(
+        &self,
+        reader: &mut FileReader,
+        writer: &mut FileWriter,
+        key: SortKey,
+    ) -> Result<(), Slow5Error> {
+        let field_type = match key {
+            SortKey::ReadId => None,
+            SortKey::Aux(name) => Some(reader.aux_field_type(name)?),
+        };
+        let key_of = |rec: &Record| sort_key(rec, key, field_type.as_ref());
+        let tmp_dir = self.tmp_dir.clone().unwrap_or_else(std::env::temp_dir);
+        let run_opts = WriteOptions::from_reader(reader)?;
+        let mut runs = Runs(Vec::new());
+        let mut buffer = Vec::new();
+        for rec in reader.records() {
+            let rec = rec?;
+            buffer.push((key_of(&rec)?, rec));
+            if buffer.len() == self.buffer_records {
+                let path = tmp_dir.join(format!(
+                    "slow5-sort-{}-{}.blow5",
+                    std::process::id(),
+                    RUN_ID.fetch_add(1, AtomicOrdering::Relaxed)
+                ));
+                runs.0.push(path.clone());
+                let mut run = run_opts.create(&path)?;
+                write_sorted(&mut run, &mut buffer)?;
+                run.close()?;
+            }
+        }
+        if runs.0.is_empty() {
+            return write_sorted(writer, &mut buffer);
+        }
+
+        // Merge the sorted runs with the records left in the buffer
+        buffer.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut readers = runs
+            .0
+            .iter()
+            .map(FileReader::open_without_index)
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut heads = Vec::with_capacity(readers.len() + 1);
+        let mut heap = BinaryHeap::new();
+        for (idx, reader) in readers.iter_mut().enumerate() {
+            let rec = reader.records().next().transpose()?;
+            if let Some(rec) = &rec {
+                heap.push(Reverse((key_of(rec)?, idx)));
+            }
+            heads.push(rec);
+        }
+        let mut buffer = buffer.into_iter();
+        let buffer_idx = readers.len();
+        if let Some((key, rec)) = buffer.next() {
+            heap.push(Reverse((key, buffer_idx)));
+            heads.push(Some(rec));
+        }
+        while let Some(Reverse((_, idx))) = heap.pop() {
+            let Some(rec) = heads[idx].take() else {
+                continue;
+            };
+            writer.add_record(&rec)?;
+            let next = if idx == buffer_idx {
+                buffer.next().map(|(_, rec)| rec)
+            } else {
+                readers[idx].records().next().transpose()?
+            };
+            if let Some(rec) = next {
+                heap.push(Reverse((key_of(&rec)?, idx)));
+                heads[idx] = Some(rec);
+            }
+        }
+        Ok(())
+    }
+}
+
+// Temporary files, removed once the sort is done or fails
+struct Runs(Vec<PathBuf>);
+
+impl Drop for Runs {
+    fn drop(&mut self) {
+        for path in self.0.iter() {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+fn write_sorted(
+    writer: &mut FileWriter,
+    buffer: &mut Vec<(Key, Record)>,
+) -> Result<(), Slow5Error> {
+    buffer.sort_by(|a, b| a.0.cmp(&b.0));
+    for (_, rec) in buffer.drain(..) {
+        writer.add_record(&rec)?;
+    }
+    Ok(())
+}
+
+// Value of the sort key, compared before the read id
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Int(i128),
+    Float(f64),
+    Bytes(Vec<u8>),
+    // Sorted after every value
+    Missing,
+}
+
+impl Value {
+    fn compare(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => a.cmp(b),
+            (Value::Float(a), Value::Float(b)) => a.total_cmp(b),
+            (Value::Bytes(a), Value::Bytes(b)) => a.cmp(b),
+            (Value::Missing, Value::Missing) => Ordering::Equal,
+            (Value::Missing, _) => Ordering::Greater,
+            (_, Value::Missing) => Ordering::Less,
+            // Every value comes from the same field, so has the same type
+            _ => Ordering::Equal,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Key {
+    value: Value,
+    read_id: Vec<u8>,
+}
+
+impl Eq for Key {}
+
+impl PartialOrd for Key {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Key {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value
+            .compare(&other.value)
+            .then_with(|| self.read_id.cmp(&other.read_id))
+    }
+}
+
+macro_rules! aux_value {
+    ($rec:expr, $name:expr, $field_type:expr, ints: $($int:ident => $itype:ty),*; floats: $($float:ident => $ftype:ty),*) => {
+        match $field_type {
+            $(FieldType::$int => $rec.get_aux_field_opt::<$itype>($name)?.map(|v| Value::Int(v as i128)),)*
+            $(FieldType::$float => $rec.get_aux_field_opt::<$ftype>($name)?.map(|v| Value::Float(v as f64)),)*
+            FieldType::Char => $rec.get_aux_field_opt::<char>($name)?.map(|v| Value::Int(v as i128)),
+            FieldType::Str => $rec.get_aux_field_opt::<String>($name)?.map(|v| Value::Bytes(v.into_bytes())),
+            FieldType::Enum(_) => $rec
+                .get_aux_field_opt::<EnumField>($name)?
+                .map(|EnumField(idx)| Value::Int(idx as i128)),
+            _ => return Err(Slow5Error::AuxTypeMismatch),
+        }
+    };
+}
+
+fn sort_key(rec: &Record, key: SortKey, field_type: Option<&FieldType>) -> Result<Key, Slow5Error> {
+    let value = match (key, field_type) {
+        (SortKey::Aux(name), Some(field_type)) => aux_value!(
+            rec, name, field_type,
+            ints: Int8 => i8, Int16 => i16, Int32 => i32, Int64 => i64,
+                Uint8 => u8, Uint16 => u16, Uint32 => u32, Uint64 => u64;
+            floats: Float => f32, Double => f64
+        )
+        .unwrap_or(Value::Missing),
+        // Only sorted by read id
+        _ => Value::Missing,
+    };
+    Ok(Key {
+        value,
+        read_id: rec.read_id().to_vec(),
+    })
+}
+
+/// Write the records of reader to writer, sorted by key. Records are sorted
+/// in memory in batches, and if there is more than one batch they are written
+/// to temporary files which are merged, so files larger than memory can be
+/// sorted. Use [`SortOptions`] to set the batch size and where the temporary
+/// files are written.
+///
+/// The writer should have the same auxiliary fields as the reader, ie created
+/// with [`FileWriter::create_from`].
+///
+/// # Example
+/// ```
+/// # use assert_fs::TempDir;
+/// # use assert_fs::fixture::PathChild;
+/// use slow5::{sort, FileReader, FileWriter, SortKey};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// # let tmp_dir = TempDir::new()?;
+/// # let file_path = tmp_dir.child("sorted.blow5");
+/// let mut reader = FileReader::open("examples/example3.blow5")?;
+/// let mut writer = FileWriter::create_from(&reader, file_path)?;
+/// sort(&mut reader, &mut writer, SortKey::Aux("start_time"))?;
+/// writer.close()?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// Returns an Err if the auxiliary field isn't in the header or is an array,
+/// or if a record or temporary file fails to be read or written.
+pub fn sort(
+    reader: &mut FileReader,
+    writer: &mut FileWriter,
+    key: SortKey,
+) -> Result<(), Slow5Error> {
+    SortOptions::default().sort(reader, writer, key)
+}
+
+#[cfg(test)]
+mod test {
+    use assert_fs::{fixture::PathChild, TempDir};
+
+    use super::*;
+
+    fn sorted_by(key: SortKey, buffer_records: usize) -> anyhow::Result<Vec<Record>> {
+        let tmp_dir = TempDir::new()?;
+        let file_path = tmp_dir.child("sorted.blow5");
+        let mut reader = FileReader::open("examples/example3.blow5")?;
+        let mut writer = FileWriter::create_from(&reader, &file_path)?;
+        SortOptions::default()
+            .buffer_records(buffer_records)
+            .tmp_dir(tmp_dir.path())
+            .sort(&mut reader, &mut writer, key)?;
+        writer.close()?;
+        let records = FileReader::open(&file_path)?
+            .records()
+            .collect::<Result<Vec<_>, _>>()?;
+        // Temporary files are removed
+        for entry in std::fs::read_dir(tmp_dir.path())? {
+            assert!(!entry?
+                .file_name()
+                .to_string_lossy()
+                .starts_with("slow5-sort"));
+        }
+        Ok(records)
+    }
+
+    #[test]
+    fn test_sort() -> anyhow::Result<()> {
+        let reader = FileReader::open("examples/example3.blow5")?;
+        let mut read_ids = reader
+            .iter_read_ids()?
+            .map(|read_id| read_id.to_vec())
+            .collect::<Vec<_>>();
+        read_ids.sort();
+
+        for buffer_records in [1, 2, 1000] {
+            let records = sorted_by(SortKey::ReadId, buffer_records)?;
+            let sorted = records
+                .iter()
+                .map(|rec| rec.read_id().to_vec())
+                .collect::<Vec<_>>();
+            assert_eq!(sorted, read_ids);
+
+            let records = sorted_by(SortKey::Aux("start_time"), buffer_records)?;
+            let start_times = records
+                .iter()
+                .map(|rec| rec.get_aux_field::<u64>("start_time"))
+                .collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(start_times.len(), read_ids.len());
+            assert!(start_times.windows(2).all(|w| w[0] <= w[1]));
+        }
+        Ok(())
+    }
+}
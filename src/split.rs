@@ -0,0 +1,205 @@
+//! Split a SLOW5 file into multiple files
+use std::path::{Path, PathBuf};
+
+use crate::{
+    error::Slow5Error, header::HeaderExt, reader::FileReader, writer::FileWriter, WriteOptions,
+};
+
+/// How records are divided between the output files of [`split`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitMode {
+    /// One file per read group, each with only the attributes of that read
+    /// group. Records are moved to read group 0 of their output file.
+    ByReadGroup,
+    /// New file every n records, the last file may have fewer records
+    ByCount(usize),
+    /// Divide records evenly into n files. Requires an index.
+    ByFileCount(usize),
+}
+
+// Header for the output, if a read group is given only its attributes are kept
+// and it becomes read group 0
+pub(crate) fn split_options(
+    reader: &FileReader,
+    read_group: Option<u32>,
+) -> Result<WriteOptions, Slow5Error> {
+    let mut opts = match read_group {
+        None => WriteOptions::from_reader(reader)?,
+        Some(rg) => {
+            let mut opts = WriteOptions::default();
+            for key in reader.iter_attr_keys()? {
+                if let Ok(value) = reader.get_attribute(key, rg) {
+                    opts.attr(key, value, 0);
+                }
+            }
+            for (name, field_type) in reader.aux_fields_iter() {
+                opts.aux(name, field_type);
+            }
+            opts
+        }
+    };
+    opts.record_compression(reader.record_compression())
+        .signal_compression(reader.signal_compression());
+    Ok(opts)
+}
+
+// File name of the input without and with only its extension, which output
+// file names are made from
+pub(crate) fn stem_and_extension(reader: &FileReader) -> (String, String) {
+    let stem = reader
+        .file_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let ext = reader
+        .file_path
+        .extension()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    (stem, ext)
+}
+
+/// Split the records of reader into multiple files in out_dir. Output files
+/// have the same file type and compression as the input and are named after
+/// it, ie "example_0.slow5", "example_1.slow5", etc. for "example.slow5".
+///
+/// Returns the paths of the files that were written, in order.
+///
+/// # Example
+/// ```
+/// # use assert_fs::TempDir;
+/// use slow5::{split, FileReader, SplitMode};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// # let tmp_dir = TempDir::new()?;
+/// # let out_dir = tmp_dir.path();
+/// let mut reader = FileReader::open("examples/example.slow5")?;
+/// let files = split(&mut reader, out_dir, SplitMode::ByCount(2))?;
+/// assert_eq!(files.len(), 3);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// Returns an Err if n is 0 for [`SplitMode::ByCount`] or
+/// [`SplitMode::ByFileCount`], if the reader doesn't have an index for
+/// [`SplitMode::ByFileCount`], or if any of the output files fail to be
+/// written.
+pub fn split<P>(
+    reader: &mut FileReader,
+    out_dir: P,
+    mode: SplitMode,
+) -> Result<Vec<PathBuf>, Slow5Error>
+where
+    P: AsRef<Path>,
+{
+    let out_dir = out_dir.as_ref();
+    let (stem, ext) = stem_and_extension(reader);
+    let out_path = |idx: usize| out_dir.join(format!("{stem}_{idx}.{ext}"));
+
+    let mut paths = Vec::new();
+    match mode {
+        SplitMode::ByReadGroup => {
+            let mut writers = Vec::new();
+            for rg in 0..reader.num_read_groups() {
+                let path = out_path(rg as usize);
+                writers.push(split_options(reader, Some(rg))?.create(&path)?);
+                paths.push(path);
+            }
+            for rec in reader.records() {
+                let rec = rec?;
+                let rg = unsafe { (*rec.slow5_rec).read_group };
+                let writer = writers
+                    .get_mut(rg as usize)
+                    .ok_or(Slow5Error::AttributeError)?;
+                unsafe { (*rec.slow5_rec).read_group = 0 };
+                writer.add_record(&rec)?;
+            }
+            for writer in writers {
+                writer.close()?;
+            }
+        }
+        SplitMode::ByCount(n) | SplitMode::ByFileCount(n) if n == 0 => {
+            return Err(Slow5Error::Argument);
+        }
+        SplitMode::ByCount(n) => {
+            let opts = split_options(reader, None)?;
+            let mut writer: Option<FileWriter> = None;
+            for (idx, rec) in reader.records().enumerate() {
+                let rec = rec?;
+                if idx % n == 0 {
+                    let path = out_path(idx / n);
+                    if let Some(full) = writer.replace(opts.create(&path)?) {
+                        full.close()?;
+                    }
+                    paths.push(path);
+                }
+                if let Some(writer) = writer.as_mut() {
+                    writer.add_record(&rec)?;
+                }
+            }
+            if let Some(writer) = writer {
+                writer.close()?;
+            }
+        }
+        SplitMode::ByFileCount(n) => {
+            if !reader.has_index() {
+                return Err(Slow5Error::NoIndex);
+            }
+            let total = reader.iter_read_ids()?.count();
+            let opts = split_options(reader, None)?;
+            let mut writers = Vec::new();
+            for idx in 0..n {
+                let path = out_path(idx);
+                writers.push(opts.create(&path)?);
+                paths.push(path);
+            }
+            for (idx, rec) in reader.records().enumerate() {
+                writers[idx * n / total].add_record(&rec?)?;
+            }
+            for writer in writers {
+                writer.close()?;
+            }
+        }
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod test {
+    use assert_fs::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_split_by_file_count() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let mut reader = FileReader::open("examples/example2.slow5")?;
+        let files = split(&mut reader, tmp_dir.path(), SplitMode::ByFileCount(2))?;
+        assert_eq!(files.len(), 2);
+        let mut total = 0;
+        for file in files {
+            let mut split_reader = FileReader::open(file)?;
+            assert_eq!(split_reader.aux_fields_iter().count(), 5);
+            total += split_reader.records().count();
+        }
+        assert_eq!(total, reader.iter_read_ids()?.count());
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_by_read_group() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let mut reader = FileReader::open("examples/example.slow5")?;
+        let files = split(&mut reader, tmp_dir.path(), SplitMode::ByReadGroup)?;
+        assert_eq!(files.len(), 1);
+        let split_reader = FileReader::open(&files[0])?;
+        assert_eq!(split_reader.num_read_groups(), 1);
+        assert_eq!(
+            split_reader.get_attribute("asic_id", 0)?,
+            reader.get_attribute("asic_id", 0)?
+        );
+        assert!(split(&mut reader, tmp_dir.path(), SplitMode::ByCount(0)).is_err());
+        Ok(())
+    }
+}
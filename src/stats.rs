@@ -0,0 +1,568 @@
+//! Statistics about the records read from or written to a SLOW5 file
+use std::{thread, time::Duration};
+
+use crate::{
+    error::Slow5Error,
+    header::HeaderOwned,
+    progress::{report_progress, ProgressSink},
+    reader::{FileFormat, FileReader},
+    record::RecordExt,
+    RecordCompression, SignalCompression,
+};
+
+/// Records and bytes processed by a [`FileReader`] or [`FileWriter`], from
+/// `stats()`. Useful for reporting throughput and compression ratios.
+///
+/// # Example
+/// ```
+/// # use slow5::FileReader;
+/// # fn main() -> anyhow::Result<()> {
+/// let mut reader = FileReader::open("examples/example.slow5")?;
+/// for rec in reader.records() {
+///     let _ = rec?;
+/// }
+/// let stats = reader.stats();
+/// assert_eq!(stats.records(), 5);
+/// assert!(stats.compression_ratio().unwrap() > 0.0);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`FileReader`]: crate::FileReader
+/// [`FileWriter`]: crate::FileWriter
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    records: u64,
+    bytes: u64,
+    signal_bytes: u64,
+    codec_time: Duration,
+}
+
+impl Stats {
+    // Count a record of the given size in the file, and length of its raw
+    // signal, which took time to decode or encode
+    pub(crate) fn add_record(&mut self, bytes: u64, len_raw_signal: u64, time: Duration) {
+        self.records += 1;
+        self.bytes += bytes;
+        self.signal_bytes += len_raw_signal * 2;
+        self.codec_time += time;
+    }
+
+    // Count bytes that aren't part of a decoded record, ie the header or raw
+    // records
+    pub(crate) fn add_bytes(&mut self, bytes: u64) {
+        self.bytes += bytes;
+    }
+
+    pub(crate) fn merge(&mut self, other: &Stats) {
+        self.records += other.records;
+        self.bytes += other.bytes;
+        self.signal_bytes += other.signal_bytes;
+        self.codec_time += other.codec_time;
+    }
+
+    /// Number of records read or written
+    pub fn records(&self) -> u64 {
+        self.records
+    }
+
+    /// Number of bytes read from or written to the file, including the header
+    /// when writing
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    /// Size of the raw signal of the records in memory, ie 2 bytes per sample
+    pub fn signal_bytes(&self) -> u64 {
+        self.signal_bytes
+    }
+
+    /// Time spent in slow5lib decoding or encoding records. Without
+    /// [`FileWriter::with_threads`], this includes reading or writing the
+    /// records.
+    ///
+    /// [`FileWriter::with_threads`]: crate::FileWriter::with_threads
+    pub fn codec_time(&self) -> Duration {
+        self.codec_time
+    }
+
+    /// Size of the raw signal in memory relative to the bytes in the file,
+    /// None if nothing has been read or written
+    pub fn compression_ratio(&self) -> Option<f64> {
+        (self.bytes > 0).then(|| self.signal_bytes as f64 / self.bytes as f64)
+    }
+}
+
+/// Summary of the records and header of a SLOW5 file, similar to
+/// `slow5tools stats`, from [`summarize`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileStats {
+    format: FileFormat,
+    version: (u8, u8, u8),
+    record_compression: RecordCompression,
+    signal_compression: SignalCompression,
+    records: u64,
+    samples: u64,
+    read_groups: Vec<u64>,
+    min_signal_len: Option<u64>,
+    max_signal_len: Option<u64>,
+}
+
+impl FileStats {
+    fn new(reader: &FileReader) -> Self {
+        Self {
+            format: reader.format(),
+            version: reader.slow5_version(),
+            record_compression: reader.record_compression(),
+            signal_compression: reader.signal_compression(),
+            records: 0,
+            samples: 0,
+            read_groups: vec![0; reader.header().num_read_groups() as usize],
+            min_signal_len: None,
+            max_signal_len: None,
+        }
+    }
+
+    fn add<R: RecordExt>(&mut self, rec: &R) {
+        let len = rec.len_signal();
+        self.records += 1;
+        self.samples += len;
+        let rg = rec.read_group() as usize;
+        if rg >= self.read_groups.len() {
+            self.read_groups.resize(rg + 1, 0);
+        }
+        self.read_groups[rg] += 1;
+        self.min_signal_len = Some(self.min_signal_len.map_or(len, |min| min.min(len)));
+        self.max_signal_len = Some(self.max_signal_len.map_or(len, |max| max.max(len)));
+    }
+
+    fn merge(&mut self, other: FileStats) {
+        self.records += other.records;
+        self.samples += other.samples;
+        if other.read_groups.len() > self.read_groups.len() {
+            self.read_groups.resize(other.read_groups.len(), 0);
+        }
+        for (count, other) in self.read_groups.iter_mut().zip(other.read_groups) {
+            *count += other;
+        }
+        self.min_signal_len = match (self.min_signal_len, other.min_signal_len) {
+            (Some(min), Some(other)) => Some(min.min(other)),
+            (min, other) => min.or(other),
+        };
+        self.max_signal_len = self.max_signal_len.max(other.max_signal_len);
+    }
+
+    /// Format of the file
+    pub fn format(&self) -> FileFormat {
+        self.format
+    }
+
+    /// SLOW5 version in the header, as (major, minor, patch)
+    pub fn version(&self) -> (u8, u8, u8) {
+        self.version
+    }
+
+    /// Record compression of the file
+    pub fn record_compression(&self) -> RecordCompression {
+        self.record_compression
+    }
+
+    /// Signal compression of the file
+    pub fn signal_compression(&self) -> SignalCompression {
+        self.signal_compression
+    }
+
+    /// Number of records
+    pub fn records(&self) -> u64 {
+        self.records
+    }
+
+    /// Total number of raw signal samples over all the records
+    pub fn total_samples(&self) -> u64 {
+        self.samples
+    }
+
+    /// Number of records in each read group, indexed by read group
+    pub fn read_group_counts(&self) -> &[u64] {
+        &self.read_groups
+    }
+
+    /// Shortest raw signal, None if there are no records
+    pub fn min_signal_len(&self) -> Option<u64> {
+        self.min_signal_len
+    }
+
+    /// Longest raw signal, None if there are no records
+    pub fn max_signal_len(&self) -> Option<u64> {
+        self.max_signal_len
+    }
+
+    /// Mean length of the raw signal, None if there are no records
+    pub fn mean_signal_len(&self) -> Option<f64> {
+        (self.records > 0).then(|| self.samples as f64 / self.records as f64)
+    }
+}
+
+/// Summarize the records read from reader, ie the number of records and
+/// length of their signal, along with the header version and compression.
+/// Only the records that haven't been read yet with [`records`] are
+/// counted, so use a newly opened reader.
+///
+/// # Example
+/// ```
+/// # use slow5::FileReader;
+/// # fn main() -> anyhow::Result<()> {
+/// let mut reader = FileReader::open("examples/example.slow5")?;
+/// let stats = slow5::stats::summarize(&mut reader)?;
+/// assert_eq!(stats.records(), 5);
+/// assert_eq!(stats.read_group_counts().iter().sum::<u64>(), 5);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`records`]: crate::FileReader::records
+pub fn summarize(reader: &mut FileReader) -> Result<FileStats, Slow5Error> {
+    summarize_with_progress(reader, &mut ())
+}
+
+/// Same as [`summarize`], with the number of records and bytes read from
+/// reader reported to progress after each record.
+///
+/// # Example
+/// ```
+/// # use slow5::FileReader;
+/// # fn main() -> anyhow::Result<()> {
+/// let mut reader = FileReader::open("examples/example.slow5")?;
+/// let mut read = 0;
+/// let mut progress = |records: u64, _bytes: u64| read = records;
+/// let stats = slow5::stats::summarize_with_progress(&mut reader, &mut progress)?;
+/// assert_eq!(read, stats.records());
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// Returns an Err if a record fails to be read, or
+/// [`Slow5Error::Cancelled`] if progress cancels it.
+pub fn summarize_with_progress(
+    reader: &mut FileReader,
+    progress: &mut dyn ProgressSink,
+) -> Result<FileStats, Slow5Error> {
+    let mut stats = FileStats::new(reader);
+    let start_bytes = reader.stats().bytes();
+    let mut records = reader.records();
+    while let Some(rec) = records.next() {
+        stats.add(&rec?);
+        report_progress(
+            progress,
+            stats.records,
+            records.stats().bytes() - start_bytes,
+        )?;
+    }
+    Ok(stats)
+}
+
+/// Same as [`summarize`], but the records are split between threads, each
+/// with its own handle to the file. Every record in the file is counted,
+/// regardless of what has been read from reader.
+///
+/// # Example
+/// ```
+/// # use slow5::FileReader;
+/// # fn main() -> anyhow::Result<()> {
+/// let reader = FileReader::open("examples/example2.slow5")?;
+/// let stats = slow5::stats::summarize_parallel(&reader, 4)?;
+/// println!("Mean signal length: {:?}", stats.mean_signal_len());
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// Returns an Err if the reader doesn't have an index, or a record fails to be
+/// read.
+pub fn summarize_parallel(reader: &FileReader, threads: usize) -> Result<FileStats, Slow5Error> {
+    let read_ids = reader
+        .iter_read_ids()?
+        .map(|read_id| read_id.to_vec())
+        .collect::<Vec<_>>();
+    let mut stats = FileStats::new(reader);
+    if read_ids.is_empty() {
+        return Ok(stats);
+    }
+    let threads = threads.max(1);
+    let chunk_size = (read_ids.len() + threads - 1) / threads;
+    let file_path = &reader.file_path;
+    let partial = thread::scope(|s| {
+        let handles = read_ids
+            .chunks(chunk_size)
+            .map(|chunk| {
+                s.spawn(move || {
+                    let reader = FileReader::open(file_path)?;
+                    let mut stats = FileStats::new(&reader);
+                    for read_id in chunk {
+                        stats.add(&reader.get_record(read_id.as_slice())?);
+                    }
+                    Ok::<_, Slow5Error>(stats)
+                })
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .map(|h| h.join().expect("stats worker thread panicked"))
+            .collect::<Result<Vec<_>, _>>()
+    })?;
+    for other in partial {
+        stats.merge(other);
+    }
+    Ok(stats)
+}
+
+/// Everything about a SLOW5 file in one place: its format, compression, and
+/// header, along with the number of records, total samples, and estimated
+/// length of the run, from the index.
+///
+/// # Example
+/// ```
+/// # use slow5::FileReader;
+/// use slow5::stats::FileSummary;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let reader = FileReader::open("examples/example3.blow5")?;
+/// let summary = FileSummary::from(&reader)?;
+/// println!(
+///     "{} records over {:?}",
+///     summary.records(),
+///     summary.run_duration()
+/// );
+/// # Ok(())
+/// # }
+/// ```
+///
+/// With the `serde` feature, it can be serialized, ie to JSON, with the
+/// header serialized as a [`HeaderOwned`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSummary {
+    format: FileFormat,
+    record_compression: RecordCompression,
+    signal_compression: SignalCompression,
+    header: HeaderOwned,
+    records: u64,
+    samples: u64,
+    run_duration: Option<Duration>,
+}
+
+impl FileSummary {
+    /// Format of the file
+    pub fn format(&self) -> FileFormat {
+        self.format
+    }
+
+    /// SLOW5 version in the header, as (major, minor, patch)
+    pub fn version(&self) -> (u8, u8, u8) {
+        self.header.version()
+    }
+
+    /// Record compression of the file
+    pub fn record_compression(&self) -> RecordCompression {
+        self.record_compression
+    }
+
+    /// Signal compression of the file
+    pub fn signal_compression(&self) -> SignalCompression {
+        self.signal_compression
+    }
+
+    /// Header of the file, with the attributes of each read group
+    pub fn header(&self) -> &HeaderOwned {
+        &self.header
+    }
+
+    /// Number of records
+    pub fn records(&self) -> u64 {
+        self.records
+    }
+
+    /// Total number of raw signal samples over all the records
+    pub fn total_samples(&self) -> u64 {
+        self.samples
+    }
+
+    /// Time from the start of the first read to the end of the last read,
+    /// using the `start_time` auxiliary field. None if no record has a
+    /// `start_time`.
+    pub fn run_duration(&self) -> Option<Duration> {
+        self.run_duration
+    }
+
+    /// Summarize the file of reader with one pass over its records, using
+    /// another handle to the file so what has been read from reader doesn't
+    /// matter. Records whose start or end time isn't a finite, non-negative
+    /// number of seconds, ie with a `sampling_rate` of 0, are left out of
+    /// the run duration.
+    ///
+    /// # Errors
+    /// Returns an Err if the file fails to be opened again, ie for readers
+    /// that aren't reading a file, or a record fails to be read.
+    pub fn from(reader: &FileReader) -> Result<Self, Slow5Error> {
+        let mut records = 0;
+        let mut samples = 0;
+        let mut run: Option<(f64, f64)> = None;
+        let mut clone = reader.try_clone()?;
+        for rec in clone.records() {
+            let rec = rec?;
+            records += 1;
+            samples += rec.len_signal();
+            if let Some(start_time) = rec.start_time() {
+                let start = start_time as f64 / rec.sampling_rate();
+                let end = start + rec.duration_secs();
+                if !(start.is_finite() && end.is_finite() && start >= 0.0) {
+                    continue;
+                }
+                run = Some(run.map_or((start, end), |(first, last)| {
+                    (first.min(start), last.max(end))
+                }));
+            }
+        }
+        Ok(Self {
+            format: reader.format(),
+            record_compression: reader.record_compression(),
+            signal_compression: reader.signal_compression(),
+            header: reader.header_snapshot(),
+            records,
+            samples,
+            run_duration: run
+                .and_then(|(first, last)| Duration::try_from_secs_f64(last - first).ok()),
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for FileSummary {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeMap;
+
+        let format = match self.format {
+            FileFormat::Slow5 => "slow5",
+            FileFormat::Blow5 => "blow5",
+        };
+        let mut state = serializer.serialize_map(Some(5))?;
+        state.serialize_entry("format", format)?;
+        state.serialize_entry("records", &self.records)?;
+        state.serialize_entry("total_samples", &self.samples)?;
+        state.serialize_entry(
+            "run_duration_secs",
+            &self.run_duration.map(|duration| duration.as_secs_f64()),
+        )?;
+        state.serialize_entry("header", &self.header)?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use assert_fs::{fixture::PathChild, TempDir};
+
+    use super::*;
+    use crate::{FieldType, RecordBuilder, WriteOptions};
+
+    #[test]
+    fn test_summarize() -> anyhow::Result<()> {
+        let mut reader = FileReader::open("examples/example3.blow5")?;
+        let stats = summarize(&mut reader)?;
+        assert_eq!(stats.format(), FileFormat::Blow5);
+        assert!(stats.records() > 0);
+        let min = stats.min_signal_len().unwrap();
+        let max = stats.max_signal_len().unwrap();
+        let mean = stats.mean_signal_len().unwrap();
+        assert!(min as f64 <= mean && mean <= max as f64);
+
+        let reader = FileReader::open("examples/example3.blow5")?;
+        assert_eq!(summarize_parallel(&reader, 3)?, stats);
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_summary() -> anyhow::Result<()> {
+        let mut reader = FileReader::open("examples/example3.blow5")?;
+        let summary = FileSummary::from(&reader)?;
+        let stats = summarize(&mut reader)?;
+        assert_eq!(summary.format(), FileFormat::Blow5);
+        assert_eq!(summary.records(), stats.records());
+        assert_eq!(summary.total_samples(), stats.total_samples());
+        assert_eq!(summary.header(), &reader.header_snapshot());
+        assert!(summary.run_duration().is_some());
+
+        let reader = FileReader::open("examples/example.slow5")?;
+        let summary = FileSummary::from(&reader)?;
+        assert_eq!(summary.records(), 5);
+        assert_eq!(summary.run_duration(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_summary_bad_sampling_rate() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let file_path = tmp_dir.child("test.blow5");
+        let mut writer = WriteOptions::default()
+            .aux("start_time", FieldType::Uint64)
+            .create(&file_path)?;
+        for (read_id, sampling_rate) in [("read_0", 4000.0), ("read_1", 0.0)] {
+            let mut rec = RecordBuilder::default()
+                .read_id(read_id)
+                .read_group(0)
+                .digitisation(4096.0)
+                .offset(4.0)
+                .range(12.0)
+                .sampling_rate(4000.0)
+                .raw_signal(&[0; 4000])
+                .build()?;
+            rec.set_sampling_rate(sampling_rate);
+            rec.set_aux_field(&mut writer, "start_time", 4000u64)?;
+            writer.add_record(&rec)?;
+        }
+        writer.close()?;
+
+        let reader = FileReader::open(&file_path)?;
+        let summary = FileSummary::from(&reader)?;
+        assert_eq!(summary.records(), 2);
+        assert_eq!(summary.run_duration(), Some(Duration::from_secs(1)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_summarize_cancelled() -> anyhow::Result<()> {
+        struct StopAfter(u64, u64);
+        impl ProgressSink for StopAfter {
+            fn update(&mut self, records: u64, _bytes: u64) {
+                self.0 = records;
+            }
+            fn is_cancelled(&self) -> bool {
+                self.0 >= self.1
+            }
+        }
+
+        let mut reader = FileReader::open("examples/example.slow5")?;
+        let mut progress = StopAfter(0, 2);
+        let res = summarize_with_progress(&mut reader, &mut progress);
+        assert!(matches!(res, Err(Slow5Error::Cancelled)));
+        assert_eq!(progress.0, 2);
+        assert_eq!(reader.stats().records(), 2);
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize_file_summary() -> anyhow::Result<()> {
+        let reader = FileReader::open("examples/example.slow5")?;
+        let summary = FileSummary::from(&reader)?;
+        let json = serde_json::to_value(&summary)?;
+        assert_eq!(json["format"], "slow5");
+        assert_eq!(json["records"], 5);
+        assert!(json["run_duration_secs"].is_null());
+        assert_eq!(json["header"]["read_groups"].as_array().unwrap().len(), 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,244 @@
+//! C FILE streams backed by Rust readers and writers, so slow5lib can parse
+//! and write SLOW5 and BLOW5 data that isn't in a file on disk.
+//!
+//! glibc creates custom streams with fopencookie, macOS and the BSDs with
+//! funopen. The cookie is a boxed [`Io`], which is dropped when slow5lib
+//! closes the stream.
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+use libc::{c_char, c_int, c_void};
+
+use crate::{error::Slow5Error, reader::FileFormat};
+
+// Operations slow5lib may call on the stream, unsupported by default
+pub(crate) trait Io: Send {
+    fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    fn seek(&mut self, _pos: SeekFrom) -> io::Result<u64> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    // Called once when the stream is closed
+    fn close(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+pub(crate) struct Seekable<R>(pub(crate) R);
+
+impl<R: Read + Seek + Send> Io for Seekable<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.0.seek(pos)
+    }
+}
+
+// Writer that can't seek, only the current position can be queried, ie with
+// ftello
+pub(crate) struct Sequential<W> {
+    inner: W,
+    pos: u64,
+}
+
+impl<W> Sequential<W> {
+    pub(crate) fn new(inner: W) -> Self {
+        Self { inner, pos: 0 }
+    }
+}
+
+impl<W: Write + Send> Io for Sequential<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write_all(buf)?;
+        self.pos += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match pos {
+            SeekFrom::Current(0) => Ok(self.pos),
+            _ => Err(io::ErrorKind::Unsupported.into()),
+        }
+    }
+
+    fn close(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+// Guess the format from the first bytes, ie the BLOW5 magic number, then
+// rewind
+pub(crate) fn detect_format<R: Read + Seek>(reader: &mut R) -> io::Result<FileFormat> {
+    let start = reader.stream_position()?;
+    let mut magic = [0; 5];
+    let mut n = 0;
+    while n < magic.len() {
+        match reader.read(&mut magic[n..])? {
+            0 => break,
+            read => n += read,
+        }
+    }
+    reader.seek(SeekFrom::Start(start))?;
+    if &magic[..n] == b"BLOW5" {
+        Ok(FileFormat::Blow5)
+    } else {
+        Ok(FileFormat::Slow5)
+    }
+}
+
+type Cookie = Box<dyn Io>;
+
+// Open a stream over io with mode, ie "r" or "w"
+pub(crate) fn open(io: Box<dyn Io>, mode: &std::ffi::CStr) -> Result<*mut libc::FILE, Slow5Error> {
+    let cookie = Box::into_raw(Box::new(io)) as *mut c_void;
+    let fp = unsafe { sys::open(cookie, mode) };
+    if fp.is_null() {
+        drop(unsafe { Box::from_raw(cookie as *mut Cookie) });
+        Err(Slow5Error::IOError)
+    } else {
+        Ok(fp)
+    }
+}
+
+fn read(cookie: *mut c_void, buf: *mut c_char, size: usize) -> isize {
+    let io = unsafe { &mut *(cookie as *mut Cookie) };
+    let buf = unsafe { std::slice::from_raw_parts_mut(buf as *mut u8, size) };
+    loop {
+        match io.read(buf) {
+            Ok(n) => return n as isize,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(_) => return -1,
+        }
+    }
+}
+
+// Returns 0 on error, which stdio treats as a failed write
+fn write(cookie: *mut c_void, buf: *const c_char, size: usize) -> usize {
+    let io = unsafe { &mut *(cookie as *mut Cookie) };
+    let buf = unsafe { std::slice::from_raw_parts(buf as *const u8, size) };
+    io.write(buf).unwrap_or(0)
+}
+
+fn seek(cookie: *mut c_void, offset: i64, whence: c_int) -> Option<u64> {
+    let io = unsafe { &mut *(cookie as *mut Cookie) };
+    let pos = match whence {
+        libc::SEEK_SET => SeekFrom::Start(u64::try_from(offset).ok()?),
+        libc::SEEK_CUR => SeekFrom::Current(offset),
+        libc::SEEK_END => SeekFrom::End(offset),
+        _ => return None,
+    };
+    io.seek(pos).ok()
+}
+
+fn close(cookie: *mut c_void) -> c_int {
+    let mut io = unsafe { Box::from_raw(cookie as *mut Cookie) };
+    match io.close() {
+        Ok(()) => 0,
+        Err(_) => -1,
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod sys {
+    use libc::{c_char, c_int, c_void, off64_t, size_t, ssize_t};
+
+    #[repr(C)]
+    struct CookieIoFunctions {
+        read: Option<unsafe extern "C" fn(*mut c_void, *mut c_char, size_t) -> ssize_t>,
+        write: Option<unsafe extern "C" fn(*mut c_void, *const c_char, size_t) -> ssize_t>,
+        seek: Option<unsafe extern "C" fn(*mut c_void, *mut off64_t, c_int) -> c_int>,
+        close: Option<unsafe extern "C" fn(*mut c_void) -> c_int>,
+    }
+
+    extern "C" {
+        fn fopencookie(
+            cookie: *mut c_void,
+            mode: *const c_char,
+            io_funcs: CookieIoFunctions,
+        ) -> *mut libc::FILE;
+    }
+
+    unsafe extern "C" fn read(cookie: *mut c_void, buf: *mut c_char, size: size_t) -> ssize_t {
+        super::read(cookie, buf, size)
+    }
+
+    unsafe extern "C" fn write(cookie: *mut c_void, buf: *const c_char, size: size_t) -> ssize_t {
+        super::write(cookie, buf, size) as ssize_t
+    }
+
+    unsafe extern "C" fn seek(cookie: *mut c_void, offset: *mut off64_t, whence: c_int) -> c_int {
+        match super::seek(cookie, *offset, whence) {
+            Some(pos) => {
+                *offset = pos as off64_t;
+                0
+            }
+            None => -1,
+        }
+    }
+
+    unsafe extern "C" fn close(cookie: *mut c_void) -> c_int {
+        super::close(cookie)
+    }
+
+    pub(super) unsafe fn open(cookie: *mut c_void, mode: &std::ffi::CStr) -> *mut libc::FILE {
+        let funcs = CookieIoFunctions {
+            read: Some(read),
+            write: Some(write),
+            seek: Some(seek),
+            close: Some(close),
+        };
+        fopencookie(cookie, mode.as_ptr(), funcs)
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+mod sys {
+    use libc::{c_char, c_int, c_void};
+
+    type Fpos = i64;
+
+    extern "C" {
+        fn funopen(
+            cookie: *const c_void,
+            readfn: Option<unsafe extern "C" fn(*mut c_void, *mut c_char, c_int) -> c_int>,
+            writefn: Option<unsafe extern "C" fn(*mut c_void, *const c_char, c_int) -> c_int>,
+            seekfn: Option<unsafe extern "C" fn(*mut c_void, Fpos, c_int) -> Fpos>,
+            closefn: Option<unsafe extern "C" fn(*mut c_void) -> c_int>,
+        ) -> *mut libc::FILE;
+    }
+
+    unsafe extern "C" fn read(cookie: *mut c_void, buf: *mut c_char, size: c_int) -> c_int {
+        super::read(cookie, buf, size.max(0) as usize) as c_int
+    }
+
+    unsafe extern "C" fn write(cookie: *mut c_void, buf: *const c_char, size: c_int) -> c_int {
+        match super::write(cookie, buf, size.max(0) as usize) {
+            0 if size > 0 => -1,
+            n => n as c_int,
+        }
+    }
+
+    unsafe extern "C" fn seek(cookie: *mut c_void, offset: Fpos, whence: c_int) -> Fpos {
+        super::seek(cookie, offset, whence).map_or(-1, |pos| pos as Fpos)
+    }
+
+    unsafe extern "C" fn close(cookie: *mut c_void) -> c_int {
+        super::close(cookie)
+    }
+
+    pub(super) unsafe fn open(cookie: *mut c_void, mode: &std::ffi::CStr) -> *mut libc::FILE {
+        if mode.to_bytes().starts_with(b"r") {
+            funopen(cookie, Some(read), None, Some(seek), Some(close))
+        } else {
+            funopen(cookie, None, Some(write), Some(seek), Some(close))
+        }
+    }
+}
@@ -0,0 +1,250 @@
+//! Copy a subset of records from one SLOW5 file into another
+use std::thread;
+
+use crate::{
+    error::Slow5Error,
+    progress::{report_progress, ProgressSink},
+    reader::FileReader,
+    record::Record,
+    writer::FileWriter,
+};
+
+/// Options for copying a subset of records, ie number of threads used to
+/// fetch records.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubsetOptions {
+    threads: usize,
+    batch_size: usize,
+}
+
+impl Default for SubsetOptions {
+    fn default() -> Self {
+        Self {
+            threads: 1,
+            batch_size: 1024,
+        }
+    }
+}
+
+impl SubsetOptions {
+    /// Set the number of threads used to fetch records. Each thread opens its
+    /// own handle to the input file, sharing the index of the reader. Records
+    /// are always written in the order of the read ids. By default, a single
+    /// thread is used.
+    pub fn threads(&mut self, n: usize) -> &mut Self {
+        self.threads = n.max(1);
+        self
+    }
+
+    /// Set the number of records fetched before they are written to the
+    /// output.
+    pub fn batch_size(&mut self, n: usize) -> &mut Self {
+        self.batch_size = n.max(1);
+        self
+    }
+
+    /// Copy the records with the given read ids from reader into writer. See
+    /// [`subset`] for more information.
+    pub fn subset<I, B>(
+        &self,
+        reader: &FileReader,
+        writer: &mut FileWriter,
+        read_ids: I,
+    ) -> Result<SubsetReport, Slow5Error>
+    where
+        I: IntoIterator<Item = B>,
+        B: Into<Vec<u8>>,
+    {
+        self.subset_with_progress(reader, writer, read_ids, &mut ())
+    }
+
+    /// Same as [`subset`], with the number of read ids looked up and bytes
+    /// read from the input reported to progress. With multiple threads,
+    /// progress is reported after each batch. Returns
+    /// [`Slow5Error::Cancelled`] if progress cancels it.
+    ///
+    /// [`subset`]: SubsetOptions::subset
+    pub fn subset_with_progress<I, B>(
+        &self,
+        reader: &FileReader,
+        writer: &mut FileWriter,
+        read_ids: I,
+        progress: &mut dyn ProgressSink,
+    ) -> Result<SubsetReport, Slow5Error>
+    where
+        I: IntoIterator<Item = B>,
+        B: Into<Vec<u8>>,
+    {
+        let read_ids = read_ids.into_iter().map(Into::into).collect::<Vec<_>>();
+        let mut report = SubsetReport::default();
+        let mut n = 0;
+        if self.threads == 1 {
+            let start_bytes = reader.stats().bytes();
+            for read_id in read_ids {
+                let rec = fetch(reader, &read_id)?;
+                report.write(writer, read_id, rec)?;
+                n += 1;
+                report_progress(progress, n, reader.stats().bytes() - start_bytes)?;
+            }
+        } else {
+            let mut readers = (0..self.threads)
+                .map(|_| reader.try_clone())
+                .collect::<Result<Vec<_>, _>>()?;
+            let chunk_size = (self.batch_size + self.threads - 1) / self.threads;
+            for batch in read_ids.chunks(self.batch_size) {
+                let fetched = thread::scope(|s| {
+                    let handles = readers
+                        .iter_mut()
+                        .zip(batch.chunks(chunk_size))
+                        .map(|(reader, chunk)| {
+                            s.spawn(move || {
+                                chunk
+                                    .iter()
+                                    .map(|read_id| fetch(reader, read_id))
+                                    .collect::<Result<Vec<_>, _>>()
+                            })
+                        })
+                        .collect::<Vec<_>>();
+                    handles
+                        .into_iter()
+                        .map(|h| h.join().expect("subset worker thread panicked"))
+                        .collect::<Result<Vec<_>, _>>()
+                })?;
+                for (read_id, rec) in batch.iter().zip(fetched.into_iter().flatten()) {
+                    report.write(writer, read_id.clone(), rec)?;
+                }
+                n += batch.len() as u64;
+                let bytes = readers.iter().map(|reader| reader.stats().bytes()).sum();
+                report_progress(progress, n, bytes)?;
+            }
+        }
+        Ok(report)
+    }
+}
+
+// Get a record, returning None if the read id isn't in the file
+fn fetch(reader: &FileReader, read_id: &[u8]) -> Result<Option<Record>, Slow5Error> {
+    match reader.get_record(read_id) {
+        Ok(rec) => Ok(Some(rec)),
+        Err(Slow5Error::GetRecordFailed) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Summary of the records copied by [`subset`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SubsetReport {
+    written: usize,
+    missing: Vec<Vec<u8>>,
+}
+
+impl SubsetReport {
+    fn write(
+        &mut self,
+        writer: &mut FileWriter,
+        read_id: Vec<u8>,
+        rec: Option<Record>,
+    ) -> Result<(), Slow5Error> {
+        match rec {
+            Some(rec) => {
+                writer.add_record(&rec)?;
+                self.written += 1;
+            }
+            None => self.missing.push(read_id),
+        }
+        Ok(())
+    }
+
+    /// Number of records written to the output
+    pub fn written(&self) -> usize {
+        self.written
+    }
+
+    /// Read ids that were not found in the input, in the order they were
+    /// given
+    pub fn missing(&self) -> &[Vec<u8>] {
+        &self.missing
+    }
+}
+
+/// Copy the records with the given read ids, including their auxiliary
+/// fields, from reader into writer. Read ids that aren't in the input are
+/// skipped and reported in the returned [`SubsetReport`].
+///
+/// The writer should have the same auxiliary fields as the reader, ie created
+/// with [`FileWriter::create_from`]. Use [`SubsetOptions`] to fetch records
+/// with multiple threads.
+///
+/// # Example
+/// ```
+/// # use assert_fs::TempDir;
+/// # use assert_fs::fixture::PathChild;
+/// use slow5::{subset, FileReader, FileWriter};
+///
+/// # fn main() -> anyhow::Result<()> {
+/// # let tmp_dir = TempDir::new()?;
+/// # let file_path = tmp_dir.child("subset.slow5");
+/// let reader = FileReader::open("examples/example.slow5")?;
+/// let mut writer = FileWriter::create_from(&reader, file_path)?;
+/// let report = subset(&reader, &mut writer, ["r1", "r3", "not_a_read"])?;
+/// assert_eq!(report.written(), 2);
+/// assert_eq!(report.missing(), [b"not_a_read".to_vec()]);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+/// Returns an Err if the reader doesn't have an index, or a record fails to be
+/// written.
+pub fn subset<I, B>(
+    reader: &FileReader,
+    writer: &mut FileWriter,
+    read_ids: I,
+) -> Result<SubsetReport, Slow5Error>
+where
+    I: IntoIterator<Item = B>,
+    B: Into<Vec<u8>>,
+{
+    SubsetOptions::default().subset(reader, writer, read_ids)
+}
+
+#[cfg(test)]
+mod test {
+    use assert_fs::{fixture::PathChild, TempDir};
+
+    use super::*;
+    use crate::RecordExt;
+
+    #[test]
+    fn test_subset_threads() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let file_path = tmp_dir.child("subset.blow5");
+        let reader = FileReader::open("examples/example2.slow5")?;
+        let mut writer = FileWriter::create_from(&reader, &file_path)?;
+        let mut read_ids = reader
+            .iter_read_ids()?
+            .map(|rid| rid.to_vec())
+            .collect::<Vec<_>>();
+        read_ids.reverse();
+        let mut opts = SubsetOptions::default();
+        opts.threads(3).batch_size(2);
+        let report = opts.subset(&reader, &mut writer, read_ids.clone())?;
+        writer.close()?;
+        assert_eq!(report.written(), read_ids.len());
+        assert!(report.missing().is_empty());
+
+        let mut copy = FileReader::open(&file_path)?;
+        let copied = copy
+            .records()
+            .map(|rec| rec.map(|rec| rec.read_id().to_vec()))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(copied, read_ids);
+        let rec = copy.get_record(read_ids[0].clone())?;
+        let orig = reader.get_record(read_ids[0].clone())?;
+        assert_eq!(
+            rec.get_aux_field::<i32>("read_number")?,
+            orig.get_aux_field::<i32>("read_number")?
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,245 @@
+//! Check the integrity of a SLOW5 file, ie after it was transferred
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+};
+
+use crate::{
+    error::Slow5Error,
+    reader::{FileFormat, FileReader},
+    record::RecordExt,
+};
+
+// Marker at the end of every BLOW5 file
+const BLOW5_EOF: &[u8; 5] = b"5WOLB";
+
+/// How thoroughly to check a file with [`FileReader::verify`]
+///
+/// [`FileReader::verify`]: crate::FileReader::verify
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyLevel {
+    /// Check the end of file marker of BLOW5 files, and that the records in
+    /// the index are within the file
+    Quick,
+    /// Same as Quick, and also parse every record, checking it is in the index
+    /// and the size of its auxiliary fields
+    Full,
+}
+
+/// Problem found by [`FileReader::verify`]
+///
+/// [`FileReader::verify`]: crate::FileReader::verify
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum VerifyIssue {
+    /// BLOW5 file doesn't end with the end of file marker, so it may be
+    /// truncated
+    MissingEofMarker,
+    /// No index is loaded, so the index wasn't checked
+    NoIndex,
+    /// Record in the index extends past the end of the file
+    IndexOutOfBounds {
+        /// Read id of the record
+        read_id: String,
+        /// Offset of the record in the file
+        offset: u64,
+        /// Size of the record in bytes
+        size: u64,
+    },
+    /// Record failed to be read or parsed, no records after it were checked
+    RecordParse {
+        /// Position of the record in the file, starting from 0
+        record: u64,
+        /// Error from reading the record
+        reason: String,
+    },
+    /// Record in the file isn't in the index
+    NotInIndex(String),
+    /// Number of records in the file doesn't match the index
+    RecordCount {
+        /// Number of records in the index
+        index: u64,
+        /// Number of records in the file
+        file: u64,
+    },
+    /// Size of an auxiliary field doesn't match its length and type
+    AuxSize {
+        /// Read id of the record
+        read_id: String,
+        /// Name of the auxiliary field
+        field: String,
+        /// Expected size in bytes
+        expected: u64,
+        /// Size in bytes
+        found: u64,
+    },
+}
+
+/// Result of [`FileReader::verify`]
+///
+/// [`FileReader::verify`]: crate::FileReader::verify
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyReport {
+    level: VerifyLevel,
+    records: Option<u64>,
+    issues: Vec<VerifyIssue>,
+}
+
+impl VerifyReport {
+    /// Level the file was checked at
+    pub fn level(&self) -> VerifyLevel {
+        self.level
+    }
+
+    /// Number of records parsed, None for [`VerifyLevel::Quick`]
+    pub fn records(&self) -> Option<u64> {
+        self.records
+    }
+
+    /// Problems found in the file
+    pub fn issues(&self) -> &[VerifyIssue] {
+        &self.issues
+    }
+
+    /// Returns true if no problems were found, other than a missing index
+    pub fn is_ok(&self) -> bool {
+        self.issues
+            .iter()
+            .all(|issue| matches!(issue, VerifyIssue::NoIndex))
+    }
+}
+
+impl FileReader {
+    /// Check the integrity of the file, ie after it has been transferred.
+    /// Problems found in the file are returned in the [`VerifyReport`]
+    /// instead of as an Err.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{FileReader, VerifyLevel};
+    /// # fn main() -> anyhow::Result<()> {
+    /// let reader = FileReader::open("examples/example3.blow5")?;
+    /// let report = reader.verify(VerifyLevel::Full)?;
+    /// assert!(report.is_ok(), "{:?}", report.issues());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an Err if the file can't be opened or read.
+    pub fn verify(&self, level: VerifyLevel) -> Result<VerifyReport, Slow5Error> {
+        let mut file = File::open(&self.file_path)?;
+        let file_len = file.metadata()?.len();
+        let mut issues = Vec::new();
+
+        if self.format() == FileFormat::Blow5 {
+            let mut eof = [0; BLOW5_EOF.len()];
+            let has_eof = file_len >= eof.len() as u64 && {
+                file.seek(SeekFrom::End(-(eof.len() as i64)))?;
+                file.read_exact(&mut eof)?;
+                &eof == BLOW5_EOF
+            };
+            if !has_eof {
+                issues.push(VerifyIssue::MissingEofMarker);
+            }
+        }
+
+        let mut index_len = None;
+        match self.index_iter() {
+            Ok(index) => {
+                let mut n = 0;
+                for (read_id, offset, size) in index {
+                    n += 1;
+                    if offset.saturating_add(size) > file_len {
+                        issues.push(VerifyIssue::IndexOutOfBounds {
+                            read_id: String::from_utf8_lossy(read_id).into_owned(),
+                            offset,
+                            size,
+                        });
+                    }
+                }
+                index_len = Some(n);
+            }
+            Err(Slow5Error::NoIndex) => issues.push(VerifyIssue::NoIndex),
+            Err(e) => return Err(e),
+        }
+
+        let mut records = None;
+        if level == VerifyLevel::Full {
+            // Separate handle, so the position of this reader doesn't change
+            let mut reader = FileReader::open_without_index(&self.file_path)?;
+            let mut n = 0;
+            for rec in reader.records() {
+                let rec = match rec {
+                    Ok(rec) => rec,
+                    Err(e) => {
+                        issues.push(VerifyIssue::RecordParse {
+                            record: n,
+                            reason: e.to_string(),
+                        });
+                        break;
+                    }
+                };
+                n += 1;
+                let read_id = String::from_utf8_lossy(rec.read_id()).into_owned();
+                if index_len.is_some() && !self.contains_read_id(rec.read_id()) {
+                    issues.push(VerifyIssue::NotInIndex(read_id.clone()));
+                }
+                for (field, expected, found) in rec.aux_size_mismatches() {
+                    issues.push(VerifyIssue::AuxSize {
+                        read_id: read_id.clone(),
+                        field,
+                        expected,
+                        found,
+                    });
+                }
+            }
+            if let Some(index_len) = index_len {
+                if index_len != n {
+                    issues.push(VerifyIssue::RecordCount {
+                        index: index_len,
+                        file: n,
+                    });
+                }
+            }
+            records = Some(n);
+        }
+
+        Ok(VerifyReport {
+            level,
+            records,
+            issues,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use assert_fs::{fixture::PathChild, TempDir};
+
+    use super::*;
+
+    #[test]
+    fn test_verify() -> anyhow::Result<()> {
+        for file_path in ["examples/example.slow5", "examples/example3.blow5"] {
+            let reader = FileReader::open(file_path)?;
+            let report = reader.verify(VerifyLevel::Full)?;
+            assert!(report.is_ok(), "{:?}", report.issues());
+            assert!(report.records().unwrap() > 0);
+        }
+
+        // Drop the end of file marker
+        let tmp_dir = TempDir::new()?;
+        let file_path = tmp_dir.child("truncated.blow5");
+        let bytes = std::fs::read("examples/example3.blow5")?;
+        std::fs::write(&file_path, &bytes[..bytes.len() - BLOW5_EOF.len()])?;
+        let reader = FileReader::open_without_index(&file_path)?;
+        let report = reader.verify(VerifyLevel::Quick)?;
+        assert!(!report.is_ok());
+        assert_eq!(
+            report.issues(),
+            [VerifyIssue::MissingEofMarker, VerifyIssue::NoIndex]
+        );
+        Ok(())
+    }
+}
@@ -2,43 +2,76 @@ use std::{
     collections::{HashMap, HashSet},
     ffi::{CStr, CString},
     fmt,
+    fs::OpenOptions,
+    io::{self, Write},
     os::unix::prelude::OsStrExt,
-    path::Path,
+    path::{Path, PathBuf},
+    ptr::null_mut,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
 };
 
 use cstr::cstr;
+use libc::{c_char, c_void};
 use slow5lib_sys::{
-    slow5_file, slow5_hdr_add_rg, slow5_hdr_write, slow5_open, slow5_set_press, slow5_write,
+    slow5_file, slow5_fmt_SLOW5_FORMAT_ASCII, slow5_fmt_SLOW5_FORMAT_BINARY, slow5_hdr_add_rg,
+    slow5_hdr_write, slow5_init_empty, slow5_open_with, slow5_set_press, slow5_write,
+    slow5_write_bytes,
 };
 
 use crate::{
-    header::{Header, HeaderExt},
-    record::Record,
-    to_cstring, FieldType, RecordCompression, SignalCompression, Slow5Error,
+    encode::EncoderPool,
+    header::{Header, HeaderExt, HeaderMut},
+    log::{silence_slow5lib_logs, LogLevel, LogLevelGuard},
+    reader::{FileFormat, FileReader, RawRecord, STREAM_PATH},
+    record::{Record, RecordExt},
+    stats::Stats,
+    stream, to_cstring, FieldType, RecordCompression, SignalCompression, Slow5Error,
 };
 
-#[derive(Debug)]
-enum FileType {
-    Slow5,
-    Blow5,
-}
+// Labels of the end_reason enum set by slow5tools when converting from FAST5
+pub(crate) const ONT_END_REASONS: [&str; 7] = [
+    "unknown",
+    "partial",
+    "mux_change",
+    "unblock_mux_change",
+    "data_service_unblock_mux_change",
+    "signal_positive",
+    "signal_negative",
+];
 
 // Check the file extension, return Err if it isn't blow5 or slow5
-fn check_file_ext<P>(file_path: P) -> Result<FileType, Slow5Error>
+pub(crate) fn check_file_ext<P>(file_path: P) -> Result<FileFormat, Slow5Error>
 where
     P: AsRef<Path>,
 {
     let file_path = file_path.as_ref();
     let Some(ext) = file_path.extension() else { return Err(Slow5Error::InvalidFilePath(String::from("No file extension found")) )};
     if ext == "blow5" {
-        Ok(FileType::Blow5)
+        Ok(FileFormat::Blow5)
     } else if ext == "slow5" {
-        Ok(FileType::Slow5)
+        Ok(FileFormat::Slow5)
     } else {
         Err(Slow5Error::InvalidFilePath(String::from("found ")))
     }
 }
 
+// Path of a hidden temporary file in the same directory as file_path, so it
+// can be renamed to file_path without copying
+fn temp_path(file_path: &Path) -> Result<PathBuf, Slow5Error> {
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+    let Some(file_name) = file_path.file_name() else {
+        return Err(Slow5Error::InvalidFilePath(file_path.display().to_string()));
+    };
+    let temp_name = format!(
+        ".{}.{}.{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id(),
+        COUNT.fetch_add(1, Ordering::Relaxed)
+    );
+    Ok(file_path.with_file_name(temp_name))
+}
+
 #[derive(Debug)]
 pub(crate) enum Mode {
     Write,
@@ -60,9 +93,15 @@ pub struct WriteOptions {
     pub(crate) rec_comp: RecordCompression,
     pub(crate) sig_comp: SignalCompression,
     pub(crate) num_read_groups: u32,
+    pub(crate) format: Option<FileFormat>,
+    atomic: bool,
+    check_duplicates: bool,
+    log_level: Option<LogLevel>,
     attributes: HashMap<(Vec<u8>, u32), Vec<u8>>,
     auxiliary_fields: HashMap<Vec<u8>, FieldType>,
     aux_enums: HashMap<Vec<u8>, Vec<Vec<u8>>>,
+    removed_aux: Vec<Vec<u8>>,
+    renamed_attrs: Vec<(Vec<u8>, Vec<u8>)>,
 }
 
 impl WriteOptions {
@@ -70,6 +109,7 @@ impl WriteOptions {
         rec_comp: RecordCompression,
         sig_comp: SignalCompression,
         num_read_groups: u32,
+        format: Option<FileFormat>,
         attributes: HashMap<(Vec<u8>, u32), Vec<u8>>,
         auxiliary_fields: HashMap<Vec<u8>, FieldType>,
         aux_enums: HashMap<Vec<u8>, Vec<Vec<u8>>>,
@@ -78,10 +118,139 @@ impl WriteOptions {
             rec_comp,
             sig_comp,
             num_read_groups,
+            format,
+            atomic: false,
+            check_duplicates: false,
+            log_level: None,
             attributes,
             auxiliary_fields,
             aux_enums,
+            removed_aux: Vec::new(),
+            renamed_attrs: Vec::new(),
+        }
+    }
+
+    /// Create WriteOptions with all the attributes, read groups, auxiliary
+    /// fields, and enum labels from the header of an existing file.
+    /// Compression is left as the default, since the output may be SLOW5.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{FileReader, WriteOptions};
+    /// # use assert_fs::TempDir;
+    /// # use assert_fs::fixture::PathChild;
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp_dir = TempDir::new()?;
+    /// # let file_path = tmp_dir.child("subset.blow5");
+    /// let reader = FileReader::open("examples/example3.blow5")?;
+    /// let writer = WriteOptions::from_reader(&reader)?.create(file_path)?;
+    /// assert_eq!(
+    ///     writer.header().aux_fields_iter().count(),
+    ///     reader.header().aux_fields_iter().count()
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_reader(reader: &FileReader) -> Result<Self, Slow5Error> {
+        let mut opts = WriteOptions::default();
+        let header = reader.header();
+        for key in reader.iter_attr_keys()? {
+            for (rg, value) in header.attr_values(key)?.enumerate() {
+                if let Some(value) = value {
+                    opts.attr(key, value, rg as u32);
+                }
+            }
+        }
+        opts.num_read_groups(header.num_read_groups().saturating_sub(1))?;
+        for (name, field_type) in header.aux_fields_iter() {
+            opts.aux(name, field_type);
+        }
+        Ok(opts)
+    }
+
+    // Add the attributes, read groups, auxiliary fields, compression, format,
+    // atomic writes, duplicate checks, and log level of other, overwriting any
+    // that are already set. Auxiliary fields removed and attributes renamed in
+    // other are removed and renamed here too.
+    pub(crate) fn extend_from(&mut self, other: &WriteOptions) -> &mut Self {
+        self.rec_comp = other.rec_comp;
+        self.sig_comp = other.sig_comp;
+        self.num_read_groups = self.num_read_groups.max(other.num_read_groups);
+        self.format = other.format.or(self.format);
+        self.atomic |= other.atomic;
+        self.check_duplicates |= other.check_duplicates;
+        self.log_level = other.log_level.or(self.log_level);
+        self.attributes.extend(other.attributes.clone());
+        self.auxiliary_fields.extend(other.auxiliary_fields.clone());
+        self.aux_enums.extend(other.aux_enums.clone());
+        for name in other.removed_aux.iter() {
+            self.remove_aux(name.clone());
+        }
+        for (old, new) in other.renamed_attrs.iter() {
+            self.rename_attr(old.clone(), new.clone());
+        }
+        self
+    }
+
+    /// Remove an auxiliary field, ie one copied from a file with
+    /// [`from_reader`], so records are written without it. When these options
+    /// are added to the header of another file, ie with [`convert`], the
+    /// field is removed from that header too.
+    ///
+    /// [`from_reader`]: crate::WriteOptions::from_reader
+    /// [`convert`]: crate::convert
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{FileReader, HeaderExt, WriteOptions};
+    /// # use assert_fs::TempDir;
+    /// # use assert_fs::fixture::PathChild;
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp_dir = TempDir::new()?;
+    /// # let file_path = tmp_dir.child("stripped.blow5");
+    /// let reader = FileReader::open("examples/example2.slow5")?;
+    /// let writer = WriteOptions::from_reader(&reader)?
+    ///     .remove_aux("channel_number")
+    ///     .create(file_path)?;
+    /// assert!(writer.aux_field_type("channel_number").is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn remove_aux<B>(&mut self, name: B) -> &mut Self
+    where
+        B: Into<Vec<u8>>,
+    {
+        let name = name.into();
+        self.auxiliary_fields.remove(&name);
+        self.aux_enums.remove(&name);
+        if !self.removed_aux.contains(&name) {
+            self.removed_aux.push(name);
         }
+        self
+    }
+
+    /// Rename an attribute in every read group, ie one copied from a file
+    /// with [`from_reader`]. When these options are added to the header of
+    /// another file, ie with [`convert`], the attribute is renamed in that
+    /// header too.
+    ///
+    /// [`from_reader`]: crate::WriteOptions::from_reader
+    /// [`convert`]: crate::convert
+    pub fn rename_attr<B, C>(&mut self, old: B, new: C) -> &mut Self
+    where
+        B: Into<Vec<u8>>,
+        C: Into<Vec<u8>>,
+    {
+        let (old, new) = (old.into(), new.into());
+        self.attributes = std::mem::take(&mut self.attributes)
+            .into_iter()
+            .map(|((key, rg), value)| {
+                let key = if key == old { new.clone() } else { key };
+                ((key, rg), value)
+            })
+            .collect();
+        self.renamed_attrs.push((old, new));
+        self
     }
 
     /// Set attribute for header.
@@ -133,11 +302,9 @@ impl WriteOptions {
     /// If the same name is used multiple times, the last FieldType will be used
     /// in the header.
     ///
-    /// # Warning
-    /// While you can add auxiliary fields of any [`FieldType`] variant, only
-    /// primitive types, strings, and enums (if they implement
-    /// [`AuxFieldSetExt`]) can have values set. In the future this
-    /// restriction may be lifted.
+    /// Values for the auxiliary fields are set with types implementing
+    /// [`AuxFieldSetExt`], which includes primitive types, strings, enums,
+    /// and arrays of primitive types.
     ///
     /// [`AuxFieldSetExt`]: crate::AuxFieldSetExt
     ///
@@ -169,6 +336,42 @@ impl WriteOptions {
         self
     }
 
+    /// Add the auxiliary fields ONT reads usually have, with the types and
+    /// end_reason labels used by slow5tools when converting from FAST5, so
+    /// the file can be used by tools expecting them:
+    ///
+    /// | Name           | Type       |
+    /// |----------------|------------|
+    /// | channel_number | char*      |
+    /// | median_before  | double     |
+    /// | read_number    | int32_t    |
+    /// | start_mux      | uint8_t    |
+    /// | start_time     | uint64_t   |
+    /// | end_reason     | enum       |
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{FieldType, WriteOptions};
+    /// # use assert_fs::TempDir;
+    /// # use assert_fs::fixture::PathChild;
+    /// use slow5::HeaderExt;
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp_dir = TempDir::new()?;
+    /// # let path = tmp_dir.child("ont.blow5");
+    /// let writer = WriteOptions::default().ont_standard_aux().create(path)?;
+    /// assert_eq!(writer.aux_field_type("read_number")?, FieldType::Int32);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn ont_standard_aux(&mut self) -> &mut Self {
+        self.aux("channel_number", FieldType::Str)
+            .aux("median_before", FieldType::Double)
+            .aux("read_number", FieldType::Int32)
+            .aux("start_mux", FieldType::Uint8)
+            .aux("start_time", FieldType::Uint64)
+            .aux("end_reason", ONT_END_REASONS.to_vec())
+    }
+
     /// Set compression of the SLOW5 records. By default no compression is used.
     ///
     /// # Example
@@ -219,8 +422,121 @@ impl WriteOptions {
         }
     }
 
+    /// Explicitly set the format of the file, instead of inferring it from
+    /// the file extension. Useful for temporary files or paths without a
+    /// .slow5 or .blow5 extension.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{FileFormat, FileReader, WriteOptions};
+    /// # use assert_fs::TempDir;
+    /// # use assert_fs::fixture::PathChild;
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp_dir = TempDir::new()?;
+    /// let file_path = "reads.tmp";
+    /// # let file_path = tmp_dir.child(file_path);
+    /// let writer = WriteOptions::default()
+    ///     .format(FileFormat::Blow5)
+    ///     .create(&file_path)?;
+    /// # writer.close()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn format(&mut self, format: FileFormat) -> &mut Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Write to a temporary file in the same directory, which is renamed to
+    /// the file path when the FileWriter is closed with [`close`]. If the
+    /// FileWriter is dropped without being closed, or fails to close, the
+    /// temporary file is removed instead. This avoids leaving a partially
+    /// written file if a program crashes. Ignored by [`append`].
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::WriteOptions;
+    /// # use assert_fs::TempDir;
+    /// # use assert_fs::fixture::PathChild;
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp_dir = TempDir::new()?;
+    /// # let file_path = tmp_dir.child("test.blow5");
+    /// let writer = WriteOptions::default().atomic(true).create(&file_path)?;
+    /// assert!(!file_path.exists());
+    /// writer.close()?;
+    /// assert!(file_path.exists());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`close`]: crate::FileWriter::close
+    /// [`append`]: crate::FileWriter::append
+    pub fn atomic(&mut self, atomic: bool) -> &mut Self {
+        self.atomic = atomic;
+        self
+    }
+
+    /// Keep track of the read IDs written, so adding a record with a read ID
+    /// that was already added returns [`Slow5Error::DuplicateReadId`] instead
+    /// of writing it. Read IDs are kept in memory, so this is off by default.
+    /// When appending, only the records added by the FileWriter are checked.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{FileReader, Slow5Error, WriteOptions};
+    /// # use assert_fs::TempDir;
+    /// # use assert_fs::fixture::PathChild;
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp_dir = TempDir::new()?;
+    /// # let file_path = tmp_dir.child("test.blow5");
+    /// let mut reader = FileReader::open("examples/example3.blow5")?;
+    /// let rec = reader.records().next().unwrap()?;
+    /// let mut writer = WriteOptions::from_reader(&reader)?
+    ///     .check_duplicates(true)
+    ///     .create(&file_path)?;
+    /// writer.add_record(&rec)?;
+    /// assert!(matches!(
+    ///     writer.add_record(&rec),
+    ///     Err(Slow5Error::DuplicateReadId(_))
+    /// ));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn check_duplicates(&mut self, check: bool) -> &mut Self {
+        self.check_duplicates = check;
+        self
+    }
+
+    /// Set the slow5lib log level while the file is being written, instead of
+    /// using the log level of the whole process. Since slow5lib's log level
+    /// is a global, calls on files with their own log level are run one at a
+    /// time.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{LogLevel, WriteOptions};
+    /// # use assert_fs::TempDir;
+    /// # use assert_fs::fixture::PathChild;
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp_dir = TempDir::new()?;
+    /// # let file_path = tmp_dir.child("test.blow5");
+    /// let writer = WriteOptions::default()
+    ///     .log_level(LogLevel::Error)
+    ///     .create(&file_path)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn log_level(&mut self, lvl: LogLevel) -> &mut Self {
+        self.log_level = Some(lvl);
+        self
+    }
+
     /// Create new file with the given options. File type will be SLOW5 or BLOW5
-    /// based on the file extension. # Example
+    /// based on the file extension, unless it is set with [`format`].
+    ///
+    /// [`format`]: crate::WriteOptions::format
+    ///
+    /// # Example
     /// ```
     /// # use slow5::WriteOptions;
     /// # use assert_fs::TempDir;
@@ -239,11 +555,129 @@ impl WriteOptions {
     /// function will return an Err. Since SLOW5 is ascii, no compression is
     /// allowed. If you do want compression create a BLOW5 file.
     ///
-    /// File path must end in ".blow5" or ".slow5" otherwise, function will
-    /// return an Err.
+    /// If the format isn't set, the file path must end in ".blow5" or ".slow5"
+    /// otherwise, function will return an Err.
     pub fn create<P: AsRef<Path>>(&self, file_path: P) -> Result<FileWriter, Slow5Error> {
         FileWriter::with_options(file_path, self, Mode::Write)
     }
+
+    /// Create a new file with the given options, like [`create`], but fail if
+    /// the file already exists instead of overwriting it.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{Slow5Error, WriteOptions};
+    /// # use assert_fs::TempDir;
+    /// # use assert_fs::fixture::PathChild;
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp_dir = TempDir::new()?;
+    /// # let file_path = tmp_dir.child("test.blow5");
+    /// let opts = WriteOptions::default();
+    /// opts.create_new(&file_path)?.close()?;
+    /// let res = opts.create_new(&file_path);
+    /// assert!(matches!(res, Err(Slow5Error::FileExists(_))));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`create`]: crate::WriteOptions::create
+    ///
+    /// # Errors
+    /// Returns [`Slow5Error::FileExists`] if the file already exists, or the
+    /// same errors as [`create`].
+    pub fn create_new<P: AsRef<Path>>(&self, file_path: P) -> Result<FileWriter, Slow5Error> {
+        let file_path = file_path.as_ref();
+        if self.atomic {
+            // The file is only created once it is renamed on close
+            if file_path.exists() {
+                return Err(Slow5Error::FileExists(file_path.to_owned()));
+            }
+            return self.create(file_path);
+        }
+
+        // Reserve the path, so it can't be created by someone else before
+        // slow5lib opens it
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(file_path)
+        {
+            Ok(_) => (),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                return Err(Slow5Error::FileExists(file_path.to_owned()));
+            }
+            Err(e) => return Err(e.into()),
+        }
+        self.create(file_path).map_err(|e| {
+            let _ = std::fs::remove_file(file_path);
+            e
+        })
+    }
+
+    /// Append to an existing file. The compression of the file is used
+    /// instead of the compression set in the options, and its auxiliary
+    /// fields can be set on records with [`set_aux_field`]. Attributes and
+    /// read groups in the options are ignored, since the header has already
+    /// been written.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{FieldType, RecordCompression, WriteOptions};
+    /// # use assert_fs::TempDir;
+    /// # use assert_fs::fixture::PathChild;
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp_dir = TempDir::new()?;
+    /// # let file_path = tmp_dir.child("test.blow5");
+    /// WriteOptions::default()
+    ///     .record_compression(RecordCompression::Zlib)
+    ///     .aux("median", FieldType::Float)
+    ///     .create(&file_path)?
+    ///     .close()?;
+    /// let writer = WriteOptions::default()
+    ///     .aux("median", FieldType::Float)
+    ///     .append(&file_path)?;
+    /// assert_eq!(writer.record_compression(), RecordCompression::Zlib);
+    /// # writer.close()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`Slow5Error::SchemaMismatch`] if an auxiliary field set in the
+    /// options is missing from the file or has a different type, including
+    /// enum fields with different labels.
+    ///
+    /// [`set_aux_field`]: crate::Record::set_aux_field
+    pub fn append<P: AsRef<Path>>(&self, file_path: P) -> Result<FileWriter, Slow5Error> {
+        FileWriter::with_options(file_path, self, Mode::Append)
+    }
+
+    // Check the auxiliary fields in the options are in the header, with the
+    // same type
+    fn check_aux_fields(&self, header: &Header) -> Result<(), Slow5Error> {
+        let enums = self
+            .aux_enums
+            .iter()
+            .map(|(name, labels)| (name, FieldType::Enum(labels.clone())));
+        let fields = self
+            .auxiliary_fields
+            .iter()
+            .map(|(name, field_type)| (name, field_type.clone()));
+        for (name, expected) in fields.chain(enums) {
+            let found = header
+                .aux_fields_iter()
+                .find(|(aux_name, _)| aux_name == name)
+                .map(|(_, field_type)| field_type);
+            if found.as_ref() != Some(&expected) {
+                return Err(Slow5Error::SchemaMismatch {
+                    field: String::from_utf8_lossy(name).into_owned(),
+                    expected,
+                    found,
+                });
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Default for WriteOptions {
@@ -252,6 +686,7 @@ impl Default for WriteOptions {
             RecordCompression::None,
             SignalCompression::None,
             0,
+            None,
             Default::default(),
             Default::default(),
             Default::default(),
@@ -265,10 +700,131 @@ pub struct FileWriter {
 
     // This stores CStrings used in slow5_aux_set and extends the lifetime of the CString until it
     // gets dropped. slow5_aux_get doesn't allocate so we must manually extend the lifetime.
+    // Each name is only converted once, see aux_name.
     // TODO Replace using this with getting a pointer to the auxiliary field already allocated in
     // the header
-    // TODO Replace with HashSet?
-    pub(crate) auxiliary_fields: Vec<CString>,
+    auxiliary_fields: HashMap<Vec<u8>, CString>,
+
+    // Header is written lazily, before the first record or when the file is
+    // closed, so it can be modified with header_mut until then
+    header_written: bool,
+
+    // Encodes records on worker threads, set with with_threads
+    pool: Option<EncoderPool>,
+
+    // Temporary file being written and the path it is renamed to on close,
+    // set with WriteOptions::atomic
+    atomic: Option<(PathBuf, PathBuf)>,
+
+    // Read IDs added so far, set with WriteOptions::check_duplicates
+    read_ids: Option<HashSet<Vec<u8>>>,
+
+    // slow5lib log level for this file, set with WriteOptions::log_level
+    log_level: Option<LogLevel>,
+
+    // Records written on this thread, records written by the pool are
+    // counted by the pool until it is stopped
+    stats: Stats,
+}
+
+unsafe impl Send for FileWriter {}
+
+// Add the read ID to the read IDs already added, if they are being tracked.
+// Takes the field instead of the FileWriter so the pool can be borrowed at the
+// same time.
+fn check_duplicate(
+    read_ids: &mut Option<HashSet<Vec<u8>>>,
+    read_id: &[u8],
+) -> Result<(), Slow5Error> {
+    if let Some(read_ids) = read_ids {
+        if !read_ids.insert(read_id.to_vec()) {
+            let read_id = String::from_utf8_lossy(read_id).into_owned();
+            return Err(Slow5Error::DuplicateReadId(read_id));
+        }
+    }
+    Ok(())
+}
+
+// Encode and write the record on this thread, counting it in stats
+fn write_record(
+    slow5_file: *mut slow5_file,
+    stats: &mut Stats,
+    record: &Record,
+) -> Result<(), Slow5Error> {
+    let start = Instant::now();
+    let ret = unsafe { slow5_write(record.slow5_rec, slow5_file) };
+    if ret > 0 {
+        let len_raw_signal = unsafe { (*record.slow5_rec).len_raw_signal };
+        stats.add_record(ret as u64, len_raw_signal, start.elapsed());
+        Ok(())
+    } else {
+        Err(Slow5Error::from_errno(ret))
+    }
+}
+
+// Check if compression is being used on a SLOW5, if so error out
+fn check_compression(opts: &WriteOptions, file_ext: FileFormat) -> Result<(), Slow5Error> {
+    let has_rec_comp = !matches!(opts.rec_comp, RecordCompression::None);
+    let has_sig_comp = !matches!(opts.sig_comp, SignalCompression::None);
+    if matches!(file_ext, FileFormat::Slow5) && (has_rec_comp || has_sig_comp) {
+        Err(Slow5Error::Slow5CompressionError)
+    } else {
+        Ok(())
+    }
+}
+
+// Set the compression, read groups, attributes, and auxiliary fields of a
+// file opened for writing
+unsafe fn init_file(
+    slow5_file: *mut slow5_file,
+    opts: &WriteOptions,
+    file_ext: FileFormat,
+) -> Result<(), Slow5Error> {
+    if matches!(file_ext, FileFormat::Blow5) {
+        // Compression
+        let comp_ret = slow5_set_press(
+            slow5_file,
+            opts.rec_comp.to_slow5_rep(),
+            opts.sig_comp.to_slow5_rep(),
+        );
+        if comp_ret < 0 {
+            return Err(Slow5Error::CompressionError);
+        }
+    } else {
+        log::info!("Not a BLOW5 file, skipping compression");
+    }
+
+    // Add read groups
+    let header_ptr = (*slow5_file).header;
+    for rg in 0..opts.num_read_groups {
+        let ret = slow5_hdr_add_rg(header_ptr);
+        if ret < 0 {
+            return Err(Slow5Error::FailedAddReadGroup(rg));
+        }
+    }
+    // (*header_ptr).num_read_groups = opts.num_read_groups + 1;
+
+    // Initialize all attributes and auxiliary fields
+    let mut header = Header::new(header_ptr);
+    let mut added_attr: HashSet<Vec<u8>> = HashSet::new();
+    for ((name, rg), value) in opts.attributes.iter() {
+        if !added_attr.contains(name) {
+            added_attr.insert(name.clone());
+            header.add_attribute(name.clone())?;
+        }
+        header.set_attribute(name.clone(), value.clone(), *rg)?;
+    }
+
+    // Auxiliary fields
+    for (name, fty) in opts.auxiliary_fields.iter() {
+        header.add_aux_field(name.clone(), fty.clone())?;
+    }
+
+    // Auxiliary enum fields
+    for (name, labels) in opts.aux_enums.iter() {
+        header.add_aux_enum_field(name.clone(), labels.clone())?;
+    }
+    Ok(())
 }
 
 impl fmt::Debug for FileWriter {
@@ -284,10 +840,82 @@ impl fmt::Debug for FileWriter {
 }
 
 impl FileWriter {
-    fn new(slow5_file: *mut slow5_file) -> Self {
+    fn new(slow5_file: *mut slow5_file, header_written: bool) -> Self {
         Self {
             slow5_file,
-            auxiliary_fields: Vec::new(),
+            auxiliary_fields: HashMap::new(),
+            header_written,
+            pool: None,
+            atomic: None,
+            read_ids: None,
+            log_level: None,
+            stats: Stats::default(),
+        }
+    }
+
+    // Name of an auxiliary field to give to slow5_aux_set, converted to a
+    // CString the first time it is used, so setting the same fields on every
+    // record doesn't allocate a new name each time
+    pub(crate) fn aux_name(&mut self, name: Vec<u8>) -> Result<*const c_char, Slow5Error> {
+        if let Some(name) = self.auxiliary_fields.get(&name) {
+            return Ok(name.as_ptr());
+        }
+        let c_name = to_cstring(name.clone())?;
+        let ptr = c_name.as_ptr();
+        self.auxiliary_fields.insert(name, c_name);
+        Ok(ptr)
+    }
+
+    /// Encode, ie compress, records on a pool of n worker threads. Records are
+    /// still written in the order they are added. With n of 0 or 1, records
+    /// are encoded on the calling thread.
+    ///
+    /// Since records are written in the background, an error from
+    /// encoding or writing a record may be returned by a later call to
+    /// [`add_record`].
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{FileReader, FileWriter};
+    /// # use assert_fs::TempDir;
+    /// # use assert_fs::fixture::PathChild;
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp_dir = TempDir::new()?;
+    /// # let file_path = tmp_dir.child("test.blow5");
+    /// let mut reader = FileReader::open("examples/example3.blow5")?;
+    /// let mut writer = FileWriter::create_from(&reader, &file_path)?.with_threads(4)?;
+    /// for rec in reader.records() {
+    ///     writer.add_record(&rec?)?;
+    /// }
+    /// writer.close()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an Err if records added before switching to the pool fail to
+    /// be written.
+    ///
+    /// [`add_record`]: crate::FileWriter::add_record
+    pub fn with_threads(mut self, n: usize) -> Result<Self, Slow5Error> {
+        self.write_pending()?;
+        self.stop_pool();
+        self.pool = (n > 1).then(|| EncoderPool::new(self.slow5_file, n));
+        Ok(self)
+    }
+
+    // Stop the workers, keeping the stats of the records they wrote
+    fn stop_pool(&mut self) {
+        if let Some(pool) = self.pool.take() {
+            self.stats.merge(&pool.stats);
+        }
+    }
+
+    // Wait for records being encoded by the pool to be written
+    fn write_pending(&mut self) -> Result<(), Slow5Error> {
+        match self.pool.as_mut() {
+            Some(pool) => pool.write_ready_first_err(self.slow5_file, true),
+            None => Ok(()),
         }
     }
 
@@ -322,7 +950,36 @@ impl FileWriter {
         Self::with_options(file_path, &Default::default(), Mode::Write)
     }
 
-    /// Append to a previously created file.
+    /// Create a new SLOW5 or BLOW5 file with the same header as an existing
+    /// file. See [`WriteOptions::from_reader`] for details.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{FileReader, FileWriter};
+    /// # use assert_fs::TempDir;
+    /// # use assert_fs::fixture::PathChild;
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp_dir = TempDir::new()?;
+    /// # let file_path = tmp_dir.child("subset.slow5");
+    /// let mut reader = FileReader::open("examples/example2.slow5")?;
+    /// let mut writer = FileWriter::create_from(&reader, file_path)?;
+    /// for rec in reader.records().take(2) {
+    ///     writer.add_record(&rec?)?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn create_from<P>(reader: &FileReader, file_path: P) -> Result<Self, Slow5Error>
+    where
+        P: AsRef<Path>,
+    {
+        WriteOptions::from_reader(reader)?.create(file_path)
+    }
+
+    /// Append to a previously created file. To check the auxiliary fields of
+    /// the file, see [`WriteOptions::append`].
+    ///
+    /// [`WriteOptions::append`]: crate::WriteOptions::append
     ///
     /// # Example
     /// ```
@@ -336,7 +993,7 @@ impl FileWriter {
     /// # let _ = std::fs::copy(&file_path, &tmp_file)?;
     /// # let file_path = tmp_file;
     /// let writer = FileWriter::append(&file_path)?;
-    /// # writer.close();
+    /// # writer.close()?;
     /// # Ok(())
     /// # }
     /// ```
@@ -362,88 +1019,183 @@ impl FileWriter {
     where
         P: AsRef<Path>,
     {
-        // If we aren't testing or running in debug mode, silence slow5lib logs
-        #[cfg(any(not(test), not(debug_assertions)))]
-        unsafe {
-            slow5lib_sys::slow5_set_log_level(slow5lib_sys::slow5_log_level_opt_SLOW5_LOG_OFF);
-        }
+        let _lvl = LogLevelGuard::new(opts.log_level);
+        silence_slow5lib_logs();
 
-        let file_ext = check_file_ext(&file_path)?;
+        let file_ext = match opts.format {
+            Some(format) => format,
+            None => check_file_ext(&file_path)?,
+        };
 
-        // Check if compression is being used on a SLOW5, if so error out
-        let has_rec_comp = !matches!(opts.rec_comp, RecordCompression::None);
-        let has_sig_comp = !matches!(opts.sig_comp, SignalCompression::None);
-        if matches!(file_ext, FileType::Slow5) && (has_rec_comp || has_sig_comp) {
-            return Err(Slow5Error::Slow5CompressionError);
+        // When appending, the compression of the file is used instead
+        if matches!(mode, Mode::Write) {
+            check_compression(opts, file_ext)?;
         }
 
-        let file_path = file_path.as_ref().as_os_str().as_bytes();
-        let file_path = to_cstring(file_path)?;
+        let atomic = match mode {
+            Mode::Write if opts.atomic => {
+                let file_path = file_path.as_ref().to_owned();
+                Some((temp_path(&file_path)?, file_path))
+            }
+            _ => None,
+        };
+        let file_path = match &atomic {
+            Some((temp_path, _)) => temp_path.as_path(),
+            None => file_path.as_ref(),
+        };
+        let file_path = to_cstring(file_path.as_os_str().as_bytes())?;
         let mode_str = mode.to_c_mode();
 
-        let slow5_file = unsafe { slow5_open(file_path.as_ptr(), mode_str.as_ptr()) };
-        if matches!(mode, Mode::Append) {
-            return Ok(Self::new(slow5_file));
-        }
-
+        let format = match file_ext {
+            FileFormat::Slow5 => slow5_fmt_SLOW5_FORMAT_ASCII,
+            FileFormat::Blow5 => slow5_fmt_SLOW5_FORMAT_BINARY,
+        };
+        let slow5_file = unsafe { slow5_open_with(file_path.as_ptr(), mode_str.as_ptr(), format) };
         if slow5_file.is_null() {
             return Err(Slow5Error::Allocation);
         }
 
-        unsafe {
-            if matches!(file_ext, FileType::Blow5) {
-                // Compression
-                let comp_ret = slow5_set_press(
-                    slow5_file,
-                    opts.rec_comp.to_slow5_rep(),
-                    opts.sig_comp.to_slow5_rep(),
-                );
-                if comp_ret < 0 {
-                    return Err(Slow5Error::CompressionError);
-                }
-            } else {
-                log::info!("Not a BLOW5 file, skipping compression");
-            }
+        // slow5lib reads the header and compression of the existing file, so
+        // only the auxiliary fields need to be checked
+        if matches!(mode, Mode::Append) {
+            let mut writer = Self::new(slow5_file, true);
+            writer.log_level = opts.log_level;
+            opts.check_aux_fields(&writer.header())?;
+            writer.read_ids = opts.check_duplicates.then(HashSet::new);
+            return Ok(writer);
+        }
 
-            // Add read groups
-            let header_ptr = (*slow5_file).header;
-            for rg in 0..opts.num_read_groups {
-                let ret = slow5_hdr_add_rg(header_ptr);
-                if ret < 0 {
-                    return Err(Slow5Error::FailedAddReadGroup(rg));
-                }
-            }
-            // (*header_ptr).num_read_groups = opts.num_read_groups + 1;
-
-            // Initialize all attributes and auxiliary fields
-            let mut header = Header::new(header_ptr);
-            let mut added_attr: HashSet<Vec<u8>> = HashSet::new();
-            for ((name, rg), value) in opts.attributes.iter() {
-                if !added_attr.contains(name) {
-                    added_attr.insert(name.clone());
-                    header.add_attribute(name.clone())?;
-                }
-                header.set_attribute(name.clone(), value.clone(), *rg)?;
-            }
+        unsafe { init_file(slow5_file, opts, file_ext)? };
 
-            // Auxiliary fields
-            for (name, fty) in opts.auxiliary_fields.iter() {
-                header.add_aux_field(name.clone(), fty.clone())?;
-            }
+        let mut writer = Self::new(slow5_file, false);
+        writer.atomic = atomic;
+        writer.read_ids = opts.check_duplicates.then(HashSet::new);
+        writer.log_level = opts.log_level;
+        Ok(writer)
+    }
 
-            // Auxiliary enum fields
-            for (name, labels) in opts.aux_enums.iter() {
-                header.add_aux_enum_field(name.clone(), labels.clone())?;
-            }
+    /// Write SLOW5 or BLOW5 data to any sink, ie a compression wrapper or an
+    /// upload to object storage, without a file on disk. Records are written
+    /// sequentially, so the writer doesn't need to seek. The format is taken
+    /// from [`WriteOptions::format`], or BLOW5 if it isn't set.
+    ///
+    /// The writer is flushed when the FileWriter is closed.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{FileReader, FileWriter, RecordCompression, WriteOptions};
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut reader = FileReader::open("examples/example3.blow5")?;
+    /// let mut opts = WriteOptions::from_reader(&reader)?;
+    /// opts.record_compression(RecordCompression::Zlib);
+    /// let mut writer = FileWriter::from_writer(Vec::new(), &opts)?;
+    /// for rec in reader.records() {
+    ///     writer.add_record(&rec?)?;
+    /// }
+    /// writer.close()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an Err if compression is set for a SLOW5 file, or the header
+    /// fails to be initialized.
+    ///
+    /// [`WriteOptions::format`]: crate::WriteOptions::format
+    pub fn from_writer<W>(writer: W, opts: &WriteOptions) -> Result<Self, Slow5Error>
+    where
+        W: Write + Send + 'static,
+    {
+        let _lvl = LogLevelGuard::new(opts.log_level);
+        silence_slow5lib_logs();
+        let file_ext = opts.format.unwrap_or(FileFormat::Blow5);
+        check_compression(opts, file_ext)?;
+        let fp = stream::open(Box::new(stream::Sequential::new(writer)), cstr!("w"))?;
+        Self::from_stream(fp, opts, file_ext)
+    }
 
-            // Header
-            let hdr_ret = slow5_hdr_write(slow5_file);
+    /// Write SLOW5 or BLOW5 data to stdout, so programs can be used in
+    /// pipelines like `slow5tools`. Like [`from_writer`], the format is taken
+    /// from [`WriteOptions::format`], or BLOW5 if it isn't set.
+    ///
+    /// Anything else written to stdout, ie with `println!`, will corrupt the
+    /// output.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use slow5::{FileFormat, FileReader, FileWriter, WriteOptions};
+    /// # fn main() -> anyhow::Result<()> {
+    /// let mut reader = FileReader::stdin()?;
+    /// let mut opts = WriteOptions::from_reader(&reader)?;
+    /// opts.format(FileFormat::Slow5);
+    /// let mut writer = FileWriter::stdout(&opts)?;
+    /// for rec in reader.records() {
+    ///     writer.add_record(&rec?)?;
+    /// }
+    /// writer.close()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`from_writer`]: crate::FileWriter::from_writer
+    /// [`WriteOptions::format`]: crate::WriteOptions::format
+    pub fn stdout(opts: &WriteOptions) -> Result<Self, Slow5Error> {
+        let _lvl = LogLevelGuard::new(opts.log_level);
+        silence_slow5lib_logs();
+        let file_ext = opts.format.unwrap_or(FileFormat::Blow5);
+        check_compression(opts, file_ext)?;
+        // Anything Rust has buffered must come before the header
+        io::stdout().flush()?;
+        // Duplicate stdout so closing the writer leaves it open
+        let fd = unsafe { libc::dup(libc::STDOUT_FILENO) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        let fp = unsafe { libc::fdopen(fd, cstr!("w").as_ptr()) };
+        if fp.is_null() {
+            unsafe { libc::close(fd) };
+            return Err(io::Error::last_os_error().into());
+        }
+        Self::from_stream(fp, opts, file_ext)
+    }
+
+    // Initialize a file for writing to a stream, ie stdout or one created with
+    // the stream module
+    fn from_stream(
+        fp: *mut libc::FILE,
+        opts: &WriteOptions,
+        file_ext: FileFormat,
+    ) -> Result<Self, Slow5Error> {
+        let format = match file_ext {
+            FileFormat::Slow5 => slow5_fmt_SLOW5_FORMAT_ASCII,
+            FileFormat::Blow5 => slow5_fmt_SLOW5_FORMAT_BINARY,
+        };
+        let slow5_file = unsafe { slow5_init_empty(fp.cast(), STREAM_PATH.as_ptr(), format) };
+        if slow5_file.is_null() {
+            unsafe { libc::fclose(fp) };
+            return Err(Slow5Error::Allocation);
+        }
+        if let Err(e) = unsafe { init_file(slow5_file, opts, file_ext) } {
+            unsafe { slow5lib_sys::slow5_close(slow5_file) };
+            return Err(e);
+        }
+        let mut writer = Self::new(slow5_file, false);
+        writer.read_ids = opts.check_duplicates.then(HashSet::new);
+        writer.log_level = opts.log_level;
+        Ok(writer)
+    }
+
+    // Write the header to the file if it hasn't been written yet
+    fn write_header(&mut self) -> Result<(), Slow5Error> {
+        if !self.header_written {
+            let hdr_ret = unsafe { slow5_hdr_write(self.slow5_file) };
             if hdr_ret == -1 {
                 return Err(Slow5Error::HeaderWriteFailed);
             }
+            self.stats.add_bytes(hdr_ret.max(0) as u64);
+            self.header_written = true;
         }
-
-        Ok(Self::new(slow5_file))
+        Ok(())
     }
 
     /// Get file's record compression
@@ -466,6 +1218,16 @@ impl FileWriter {
         SignalCompression::from_u32(signal_press)
     }
 
+    /// Get file's format
+    pub fn format(&self) -> FileFormat {
+        let format = unsafe { (*self.slow5_file).format };
+        if format == slow5_fmt_SLOW5_FORMAT_BINARY {
+            FileFormat::Blow5
+        } else {
+            FileFormat::Slow5
+        }
+    }
+
     /// Add [`Record`] to SLOW5 file, not thread safe.
     ///
     /// # Example
@@ -491,7 +1253,7 @@ impl FileWriter {
     ///     .raw_signal(&[0, 1, 2, 3])
     ///     .build()?;
     /// writer.add_record(&rec)?;
-    /// # writer.close();
+    /// # writer.close()?;
     /// # assert!(file_path.exists());
     /// # let reader = FileReader::open(&file_path)?;
     /// # let rec = reader.get_record("test")?;
@@ -500,16 +1262,196 @@ impl FileWriter {
     /// ```
     ///
     /// Attempting to add a record with a read ID already in the SLOW5 file will
-    /// result in an error.
+    /// result in an error. To know which read ID it was, see
+    /// [`WriteOptions::check_duplicates`].
+    ///
+    /// [`WriteOptions::check_duplicates`]: crate::WriteOptions::check_duplicates
     pub fn add_record(&mut self, record: &Record) -> Result<(), Slow5Error> {
-        let ret = unsafe { slow5_write(record.slow5_rec, self.slow5_file) };
-        if ret > 0 {
-            Ok(())
+        let _lvl = LogLevelGuard::new(self.log_level);
+        self.write_header()?;
+        check_duplicate(&mut self.read_ids, record.read_id())?;
+        if let Some(pool) = self.pool.as_mut() {
+            pool.submit(record.clone())?;
+            return pool.write_ready_first_err(self.slow5_file, false);
+        }
+        write_record(self.slow5_file, &mut self.stats, record)
+    }
+
+    /// Add a batch of records to the file, returning the index in the batch
+    /// and the error for each record that failed to be written. The rest of
+    /// the batch is still written. With [`with_threads`], the records are
+    /// encoded in parallel and every record has been written once this
+    /// returns.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{FileReader, FileWriter};
+    /// # use assert_fs::TempDir;
+    /// # use assert_fs::fixture::PathChild;
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp_dir = TempDir::new()?;
+    /// # let file_path = tmp_dir.child("test.blow5");
+    /// let mut reader = FileReader::open("examples/example3.blow5")?;
+    /// let mut writer = FileWriter::create_from(&reader, &file_path)?;
+    /// let records = reader.records().collect::<Result<Vec<_>, _>>()?;
+    /// let errors = writer.add_records(records)?;
+    /// assert!(errors.is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`with_threads`]: crate::FileWriter::with_threads
+    ///
+    /// # Errors
+    /// Returns an Err, without writing any of the batch, if the header fails
+    /// to be written or a record added before the batch failed to be written.
+    pub fn add_records<I>(&mut self, records: I) -> Result<Vec<(usize, Slow5Error)>, Slow5Error>
+    where
+        I: IntoIterator<Item = Record>,
+    {
+        let _lvl = LogLevelGuard::new(self.log_level);
+        self.write_header()?;
+        self.write_pending()?;
+        // Records with a duplicate read ID are skipped
+        let mut duplicates = Vec::new();
+        let read_ids = &mut self.read_ids;
+        let records =
+            records.into_iter().enumerate().filter(|(idx, record)| {
+                match check_duplicate(read_ids, record.read_id()) {
+                    Ok(()) => true,
+                    Err(e) => {
+                        duplicates.push((*idx, e));
+                        false
+                    }
+                }
+            });
+        let mut errors = Vec::new();
+        match self.pool.as_mut() {
+            None => {
+                for (idx, record) in records {
+                    if let Err(e) = write_record(self.slow5_file, &mut self.stats, &record) {
+                        errors.push((idx, e));
+                    }
+                }
+            }
+            Some(pool) => {
+                // Position of the first record of the batch in the pool, and
+                // the index in the batch of each record submitted
+                let start = pool.submitted();
+                let mut submitted = Vec::new();
+                let mut failed = Vec::new();
+                let mut records = records;
+                let mut unsubmitted = None;
+                for (idx, record) in records.by_ref() {
+                    if let Err(e) = pool.submit(record) {
+                        unsubmitted = Some((idx, e));
+                        break;
+                    }
+                    submitted.push(idx);
+                    pool.write_ready(self.slow5_file, false, |seq, e| failed.push((seq, e)));
+                }
+                pool.write_ready(self.slow5_file, true, |seq, e| failed.push((seq, e)));
+
+                errors.extend(
+                    failed
+                        .into_iter()
+                        .map(|(seq, e)| (submitted[(seq - start) as usize], e)),
+                );
+                // Submitting only fails once the workers have stopped, so none
+                // of the remaining records can be written
+                if let Some((idx, e)) = unsubmitted {
+                    errors.push((idx, e));
+                    errors.extend(records.map(|(idx, _)| (idx, Slow5Error::Unknown)));
+                }
+            }
+        }
+        errors.append(&mut duplicates);
+        errors.sort_by_key(|(idx, _)| *idx);
+        Ok(errors)
+    }
+
+    /// Add a [`RawRecord`] to the file without decompressing it. The record
+    /// must come from a file with the same format and compression as this
+    /// one, and the same auxiliary fields, ie with [`create_from`].
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{FileReader, FileWriter};
+    /// # use assert_fs::TempDir;
+    /// # use assert_fs::fixture::PathChild;
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp_dir = TempDir::new()?;
+    /// # let file_path = tmp_dir.child("copy.blow5");
+    /// let reader = FileReader::open("examples/example3.blow5")?;
+    /// let mut writer = FileWriter::create_from(&reader, &file_path)?;
+    /// for read_id in reader.iter_read_ids()? {
+    ///     writer.add_raw_record(&reader.raw_record_bytes(read_id)?)?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`Slow5Error::RawRecordMismatch`] if the record has a different
+    /// format or compression than this file.
+    ///
+    /// [`create_from`]: crate::FileWriter::create_from
+    pub fn add_raw_record(&mut self, record: &RawRecord) -> Result<(), Slow5Error> {
+        let binary = unsafe { (*self.slow5_file).format } == slow5_fmt_SLOW5_FORMAT_BINARY;
+        if record.binary != binary
+            || record.rec_comp != self.record_compression()
+            || record.sig_comp != self.signal_compression()
+        {
+            return Err(Slow5Error::RawRecordMismatch);
+        }
+        let _lvl = LogLevelGuard::new(self.log_level);
+        self.write_header()?;
+        self.write_pending()?;
+        check_duplicate(&mut self.read_ids, &record.read_id)?;
+        let bytes = record.as_bytes();
+        let ret = unsafe {
+            slow5_write_bytes(bytes.as_ptr() as *mut c_void, bytes.len(), self.slow5_file)
+        };
+        if ret < 0 {
+            Err(Slow5Error::IOError)
         } else {
-            Err(Slow5Error::Unknown)
+            self.stats.add_bytes(bytes.len() as u64);
+            Ok(())
         }
     }
 
+    /// Records and bytes written so far, including the header, and the time
+    /// spent encoding the records. With [`with_threads`], records are
+    /// counted once they are written. Raw records are counted as bytes, but
+    /// not as records.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{FileReader, FileWriter};
+    /// # use assert_fs::TempDir;
+    /// # use assert_fs::fixture::PathChild;
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp_dir = TempDir::new()?;
+    /// # let file_path = tmp_dir.child("test.slow5");
+    /// let mut reader = FileReader::open("examples/example.slow5")?;
+    /// let mut writer = FileWriter::create_from(&reader, &file_path)?;
+    /// for rec in reader.records() {
+    ///     writer.add_record(&rec?)?;
+    /// }
+    /// assert_eq!(writer.stats().records(), 5);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`with_threads`]: crate::FileWriter::with_threads
+    pub fn stats(&self) -> Stats {
+        let mut stats = self.stats;
+        if let Some(pool) = &self.pool {
+            stats.merge(&pool.stats);
+        }
+        stats
+    }
+
     /// Access header of FileWriter
     /// # Example
     /// ```
@@ -534,9 +1476,117 @@ impl FileWriter {
         Header::new(h)
     }
 
-    /// Close the SLOW5 file.
-    pub fn close(self) {
-        drop(self)
+    /// Mutable access to the header of the FileWriter, to add attributes and
+    /// read groups after the file is created.
+    ///
+    /// The header is written to the file before the first record is added, so
+    /// this is only available until then.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::FileWriter;
+    /// # use assert_fs::TempDir;
+    /// # use assert_fs::fixture::PathChild;
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp_dir = TempDir::new()?;
+    /// let file_path = "test.slow5";
+    /// # let file_path = tmp_dir.child(file_path);
+    /// let mut writer = FileWriter::create(&file_path)?;
+    /// let run_id = "computed after creation";
+    /// let mut header = writer.header_mut()?;
+    /// header.add_attribute("run_id")?;
+    /// header.set_attribute("run_id", run_id, 0)?;
+    /// assert_eq!(writer.header().get_attribute("run_id", 0)?, run_id.as_bytes());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`Slow5Error::HeaderAlreadyWritten`] if a record has already
+    /// been added, or if the FileWriter was opened with [`append`].
+    ///
+    /// [`append`]: crate::FileWriter::append
+    pub fn header_mut(&mut self) -> Result<HeaderMut<'_>, Slow5Error> {
+        if self.header_written {
+            Err(Slow5Error::HeaderAlreadyWritten)
+        } else {
+            let h = unsafe { (*self.slow5_file).header };
+            Ok(HeaderMut::new(h))
+        }
+    }
+
+    /// Write the header, if it hasn't been written yet, and any records that
+    /// are buffered or being encoded to the file.
+    ///
+    /// # Example
+    /// ```
+    /// # use slow5::{FileReader, FileWriter};
+    /// # use assert_fs::TempDir;
+    /// # use assert_fs::fixture::PathChild;
+    /// # fn main() -> anyhow::Result<()> {
+    /// # let tmp_dir = TempDir::new()?;
+    /// # let file_path = tmp_dir.child("test.blow5");
+    /// let mut reader = FileReader::open("examples/example3.blow5")?;
+    /// let mut writer = FileWriter::create_from(&reader, &file_path)?;
+    /// for rec in reader.records() {
+    ///     writer.add_record(&rec?)?;
+    /// }
+    /// writer.flush()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an Err if the header or a record fails to be written, or the
+    /// file fails to be flushed, ie if the disk is full.
+    pub fn flush(&mut self) -> Result<(), Slow5Error> {
+        let _lvl = LogLevelGuard::new(self.log_level);
+        self.write_header()?;
+        self.write_pending()?;
+        let ret = unsafe { libc::fflush((*self.slow5_file).fp.cast()) };
+        if ret != 0 {
+            Err(std::io::Error::last_os_error().into())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Close the SLOW5 file. Dropping the FileWriter also closes the file,
+    /// but any errors are only logged.
+    ///
+    /// # Errors
+    /// Returns an Err if the file fails to be flushed or closed, see
+    /// [`flush`].
+    ///
+    /// [`flush`]: crate::FileWriter::flush
+    pub fn close(mut self) -> Result<(), Slow5Error> {
+        self.finish(true)
+    }
+
+    // Flush and close the file, the file is closed even if flushing fails. For
+    // atomic writes, the temporary file is renamed if commit is true and
+    // there were no errors, otherwise it is removed.
+    fn finish(&mut self, commit: bool) -> Result<(), Slow5Error> {
+        let _lvl = LogLevelGuard::new(self.log_level);
+        let flushed = self.flush();
+        // Workers must be stopped before the file is closed
+        self.stop_pool();
+        let ret = unsafe { slow5lib_sys::slow5_close(self.slow5_file) };
+        self.slow5_file = null_mut();
+        let closed = if ret != 0 {
+            Err(std::io::Error::last_os_error().into())
+        } else {
+            Ok(())
+        };
+        let res = flushed.and(closed);
+        if let Some((temp_path, file_path)) = self.atomic.take() {
+            if commit && res.is_ok() {
+                std::fs::rename(temp_path, file_path)?;
+            } else {
+                let _ = std::fs::remove_file(temp_path);
+            }
+        }
+        res
     }
 }
 
@@ -548,8 +1598,12 @@ impl HeaderExt for FileWriter {
 
 impl Drop for FileWriter {
     fn drop(&mut self) {
-        unsafe {
-            slow5lib_sys::slow5_close(self.slow5_file);
+        // File was already closed with close
+        if self.slow5_file.is_null() {
+            return;
+        }
+        if let Err(e) = self.finish(false) {
+            log::error!("{e}");
         }
     }
 }
@@ -561,7 +1615,64 @@ mod test {
     use assert_fs::{fixture::PathChild, TempDir};
 
     use super::*;
-    use crate::{FileReader, RecordExt};
+    use crate::{FileReader, RecordBuilder, RecordExt};
+
+    #[test]
+    fn test_remove_aux_rename_attr() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let file_path = tmp_dir.child("stripped.slow5");
+        let mut opts = WriteOptions::default();
+        opts.remove_aux("channel_number");
+        crate::convert("examples/example2.slow5", &file_path, &opts)?;
+        let reader = FileReader::open(&file_path)?;
+        assert_eq!(reader.aux_names_iter().count(), 4);
+        assert!(reader.aux_field_type("channel_number").is_err());
+        let rec = reader.get_record("r0")?;
+        assert_eq!(rec.get_aux_field::<i32>("read_number")?, 4019);
+
+        let file_path = tmp_dir.child("renamed.slow5");
+        let original = FileReader::open("examples/example.slow5")?;
+        let writer = WriteOptions::from_reader(&original)?
+            .rename_attr("asic_id", "anonymous_id")
+            .create(&file_path)?;
+        writer.close()?;
+        let reader = FileReader::open(&file_path)?;
+        assert_eq!(
+            reader.get_attribute("anonymous_id", 0)?,
+            original.get_attribute("asic_id", 0)?
+        );
+        assert!(reader.get_attribute("asic_id", 0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_ont_standard_aux() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let file_path = tmp_dir.child("ont.blow5");
+        let mut writer = WriteOptions::default()
+            .ont_standard_aux()
+            .create(&file_path)?;
+        let rec = RecordBuilder::with_defaults()
+            .read_id("test")
+            .raw_signal(&[1, 2, 3])
+            .aux_field("channel_number", "123")
+            .aux_field("read_number", 7i32)
+            .build_with_writer(&mut writer)?;
+        writer.add_record(&rec)?;
+        writer.close()?;
+
+        let reader = FileReader::open(&file_path)?;
+        assert_eq!(reader.aux_names_iter().count(), 6);
+        assert_eq!(reader.aux_field_type("start_time")?, FieldType::Uint64);
+        let labels = reader
+            .iter_aux_enum_labels("end_reason")?
+            .collect::<Vec<_>>();
+        assert_eq!(labels.len(), ONT_END_REASONS.len());
+        assert_eq!(labels[5], b"signal_positive");
+        let rec = reader.get_record("test")?;
+        assert_eq!(rec.get_aux_field::<i32>("read_number")?, 7);
+        Ok(())
+    }
 
     #[test]
     fn test_writer() -> Result<()> {
@@ -580,7 +1691,7 @@ mod test {
             .raw_signal(&[0, 1, 2, 3])
             .build()?;
         writer.add_record(&rec)?;
-        writer.close();
+        writer.close()?;
         assert!(file_path.exists());
 
         let reader = FileReader::open(&file_path)?;
@@ -623,9 +1734,178 @@ mod test {
             .signal_compression(SignalCompression::StreamVByte)
             .create(&file_path)
             .unwrap();
-        writer.close();
+        writer.close().unwrap();
         let appender = FileWriter::append(&file_path).unwrap();
-        appender.close();
+        appender.close().unwrap();
+    }
+
+    #[test]
+    fn test_append_options() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let file_path = tmp_dir.child("test.blow5");
+        let mut opts = WriteOptions::default();
+        opts.record_compression(RecordCompression::Zlib)
+            .aux("read_number", FieldType::Uint32)
+            .aux("end_reason", vec!["unknown", "signal_positive"]);
+        let mut writer = opts.create(&file_path)?;
+        let rec = RecordBuilder::default()
+            .read_id("read_0")
+            .read_group(0)
+            .digitisation(4096.0)
+            .offset(4.0)
+            .range(12.0)
+            .sampling_rate(4000.0)
+            .raw_signal(&[0, 1, 2, 3])
+            .build()?;
+        writer.add_record(&rec)?;
+        writer.close()?;
+
+        // Compression is inherited from the file, not the options
+        let mut writer = WriteOptions::default()
+            .aux("read_number", FieldType::Uint32)
+            .append(&file_path)?;
+        assert_eq!(writer.record_compression(), RecordCompression::Zlib);
+        let mut rec = RecordBuilder::default()
+            .read_id("read_1")
+            .read_group(0)
+            .digitisation(4096.0)
+            .offset(4.0)
+            .range(12.0)
+            .sampling_rate(4000.0)
+            .raw_signal(&[0, 1, 2, 3])
+            .build()?;
+        rec.set_aux_field(&mut writer, "read_number", 10u32)?;
+        writer.add_record(&rec)?;
+        writer.close()?;
+        let reader = FileReader::open(&file_path)?;
+        let rec = reader.get_record("read_1")?;
+        assert_eq!(rec.get_aux_field::<u32>("read_number")?, 10);
+
+        let res = WriteOptions::default()
+            .aux("read_number", FieldType::Uint8)
+            .append(&file_path);
+        assert!(matches!(
+            res,
+            Err(Slow5Error::SchemaMismatch {
+                found: Some(FieldType::Uint32),
+                ..
+            })
+        ));
+        let res = WriteOptions::default()
+            .aux("end_reason", vec!["unknown"])
+            .append(&file_path);
+        assert!(matches!(
+            res,
+            Err(Slow5Error::SchemaMismatch {
+                found: Some(FieldType::Enum(_)),
+                ..
+            })
+        ));
+        let res = WriteOptions::default()
+            .aux("median", FieldType::Float)
+            .append(&file_path);
+        assert!(matches!(
+            res,
+            Err(Slow5Error::SchemaMismatch { found: None, .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_header_mut() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let file_path = tmp_dir.child("test.blow5");
+        let mut writer = FileWriter::create(&file_path)?;
+        let mut header = writer.header_mut()?;
+        header.add_attribute("run_id")?;
+        header.set_attribute("run_id", "run0", 0)?;
+        let rg = header.add_read_group()?;
+        assert_eq!(rg, 1);
+        header.set_attribute("run_id", "run1", rg)?;
+
+        let rec = Record::builder()
+            .read_id("test")
+            .read_group(0)
+            .digitisation(4096.0)
+            .offset(4.0)
+            .range(12.0)
+            .sampling_rate(4000.0)
+            .raw_signal(&[0, 1, 2, 3])
+            .build()?;
+        writer.add_record(&rec)?;
+        assert!(matches!(
+            writer.header_mut(),
+            Err(Slow5Error::HeaderAlreadyWritten)
+        ));
+        writer.close()?;
+
+        let reader = FileReader::open(&file_path)?;
+        assert_eq!(reader.header().num_read_groups(), 2);
+        assert_eq!(reader.header().get_attribute("run_id", 1)?, b"run1");
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_from() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let file_path = tmp_dir.child("test.blow5");
+        let mut reader = FileReader::open("examples/example3.blow5")?;
+        let mut writer = FileWriter::create_from(&reader, &file_path)?;
+        for rec in reader.records() {
+            writer.add_record(&rec?)?;
+        }
+        writer.close()?;
+
+        let copy = FileReader::open(&file_path)?;
+        assert_eq!(copy.num_read_groups(), reader.num_read_groups());
+        assert_eq!(
+            copy.aux_fields_iter().count(),
+            reader.aux_fields_iter().count()
+        );
+        for (name, field_type) in reader.aux_fields_iter() {
+            assert_eq!(copy.aux_field_type(name)?, field_type);
+        }
+        for key in reader.iter_attr_keys()? {
+            assert_eq!(
+                copy.header().get_attribute(key, 0)?,
+                reader.header().get_attribute(key, 0)?
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_raw_record() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let reader = FileReader::open("examples/example2.slow5")?;
+        let file_path = tmp_dir.child("test.slow5");
+        let mut writer = FileWriter::create_from(&reader, &file_path)?;
+        let read_ids = reader
+            .iter_read_ids()?
+            .map(|rid| rid.to_vec())
+            .collect::<Vec<_>>();
+        for read_id in read_ids.iter() {
+            writer.add_raw_record(&reader.raw_record_bytes(read_id.clone())?)?;
+        }
+        writer.close()?;
+
+        let copy = FileReader::open(&file_path)?;
+        for read_id in read_ids.iter() {
+            let rec = copy.get_record(read_id.clone())?;
+            let orig = reader.get_record(read_id.clone())?;
+            assert_eq!(
+                rec.raw_signal_iter().collect::<Vec<_>>(),
+                orig.raw_signal_iter().collect::<Vec<_>>()
+            );
+        }
+
+        let mut blow5 = FileWriter::create_from(&reader, tmp_dir.child("test.blow5"))?;
+        let raw = reader.raw_record_bytes(read_ids[0].clone())?;
+        assert!(matches!(
+            blow5.add_raw_record(&raw),
+            Err(Slow5Error::RawRecordMismatch)
+        ));
+        Ok(())
     }
 
     #[test]
@@ -636,6 +1916,246 @@ mod test {
         assert!(writer.is_err());
     }
 
+    #[test]
+    fn test_with_threads() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let file_path = tmp_dir.child("test.blow5");
+        let mut reader = FileReader::open("examples/example3.blow5")?;
+        let mut writer = WriteOptions::from_reader(&reader)?
+            .record_compression(RecordCompression::Zlib)
+            .signal_compression(SignalCompression::StreamVByte)
+            .create(&file_path)?
+            .with_threads(4)?;
+        let mut read_ids = Vec::new();
+        for rec in reader.records() {
+            let rec = rec?;
+            read_ids.push(rec.read_id().to_vec());
+            writer.add_record(&rec)?;
+        }
+        writer.close()?;
+
+        let mut written = FileReader::open(&file_path)?;
+        let mut reader = FileReader::open("examples/example3.blow5")?;
+        let mut written_ids = Vec::new();
+        for (rec, expected) in written.records().zip(reader.records()) {
+            let (rec, expected) = (rec?, expected?);
+            written_ids.push(rec.read_id().to_vec());
+            assert_eq!(
+                rec.raw_signal_iter().collect::<Vec<_>>(),
+                expected.raw_signal_iter().collect::<Vec<_>>()
+            );
+        }
+        assert_eq!(written_ids, read_ids);
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_records() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        for (threads, name) in [(1, "single.blow5"), (4, "threaded.blow5")] {
+            let file_path = tmp_dir.child(name);
+            let mut reader = FileReader::open("examples/example3.blow5")?;
+            let mut writer = FileWriter::create_from(&reader, &file_path)?.with_threads(threads)?;
+            let records = reader.records().collect::<Result<Vec<_>, _>>()?;
+            let n_records = records.len();
+            let errors = writer.add_records(records)?;
+            assert!(errors.is_empty());
+            writer.close()?;
+
+            let mut written = FileReader::open(&file_path)?;
+            assert_eq!(written.records().count(), n_records);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_aux_name() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let file_path = tmp_dir.child("test.blow5");
+        let mut writer = WriteOptions::default()
+            .aux("read_number", FieldType::Int32)
+            .create(&file_path)?;
+        for (i, read_id) in ["a", "b", "c"].into_iter().enumerate() {
+            let mut rec = RecordBuilder::default()
+                .digitisation(0.123)
+                .offset(0.456)
+                .range(0.999)
+                .read_group(0)
+                .read_id(read_id)
+                .sampling_rate(0.777)
+                .raw_signal(&[1, 2, 3])
+                .build()?;
+            rec.set_aux_field(&mut writer, "read_number", i as i32)?;
+            writer.add_record(&rec)?;
+        }
+        // The name is only converted once for every record
+        assert_eq!(writer.auxiliary_fields.len(), 1);
+        writer.close()?;
+
+        let reader = FileReader::open(&file_path)?;
+        let read_number = CString::new("read_number")?;
+        let rec = reader.get_record("c")?;
+        assert_eq!(rec.get_aux_field_cstr::<i32>(&read_number)?, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_flush_close() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let file_path = tmp_dir.child("test.blow5");
+        let mut reader = FileReader::open("examples/example3.blow5")?;
+        let mut writer = FileWriter::create_from(&reader, &file_path)?.with_threads(2)?;
+        for rec in reader.records() {
+            writer.add_record(&rec?)?;
+        }
+        writer.flush()?;
+        // Header and records are on disk before the file is closed
+        assert!(std::fs::metadata(&file_path)?.len() > 0);
+        writer.close()?;
+
+        let mut written = FileReader::open(&file_path)?;
+        let mut reader = FileReader::open("examples/example3.blow5")?;
+        assert_eq!(written.records().count(), reader.records().count());
+        Ok(())
+    }
+
+    #[test]
+    fn test_atomic() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let file_path = tmp_dir.child("test.blow5");
+        let mut reader = FileReader::open("examples/example3.blow5")?;
+        let mut writer = WriteOptions::from_reader(&reader)?
+            .atomic(true)
+            .create(&file_path)?;
+        for rec in reader.records() {
+            writer.add_record(&rec?)?;
+        }
+        assert!(!file_path.exists());
+        writer.close()?;
+        assert!(file_path.exists());
+        assert_eq!(std::fs::read_dir(&tmp_dir)?.count(), 1);
+
+        let file_path = tmp_dir.child("dropped.blow5");
+        let writer = FileWriter::options().atomic(true).create(&file_path)?;
+        drop(writer);
+        assert!(!file_path.exists());
+        assert_eq!(std::fs::read_dir(&tmp_dir)?.count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_new() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let file_path = tmp_dir.child("test.blow5");
+        std::fs::write(&file_path, b"not a blow5")?;
+        for atomic in [false, true] {
+            let res = WriteOptions::default()
+                .atomic(atomic)
+                .create_new(&file_path);
+            assert!(matches!(res, Err(Slow5Error::FileExists(_))));
+        }
+        assert_eq!(std::fs::read(&file_path)?, b"not a blow5");
+
+        // Failing to create the file doesn't leave an empty file behind
+        let file_path = tmp_dir.child("test.slow5");
+        let res = WriteOptions::default()
+            .record_compression(RecordCompression::Zlib)
+            .create_new(&file_path);
+        assert!(matches!(res, Err(Slow5Error::Slow5CompressionError)));
+        assert!(!file_path.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_duplicates() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        for (threads, name) in [(1, "single.blow5"), (4, "threaded.blow5")] {
+            let file_path = tmp_dir.child(name);
+            let mut reader = FileReader::open("examples/example3.blow5")?;
+            let mut writer = WriteOptions::from_reader(&reader)?
+                .check_duplicates(true)
+                .create(&file_path)?
+                .with_threads(threads)?;
+            let records = reader.records().collect::<Result<Vec<_>, _>>()?;
+            let n_records = records.len();
+            let read_id = records[0].read_id().to_vec();
+            writer.add_record(&records[0])?;
+            let res = writer.add_record(&records[0]);
+            assert!(
+                matches!(res, Err(Slow5Error::DuplicateReadId(id)) if id.as_bytes() == read_id)
+            );
+
+            let errors = writer.add_records(records)?;
+            assert_eq!(errors.len(), 1);
+            assert!(matches!(errors[0], (0, Slow5Error::DuplicateReadId(_))));
+            writer.close()?;
+
+            let mut written = FileReader::open(&file_path)?;
+            assert_eq!(written.records().count(), n_records);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_stats() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        for (threads, name) in [(1, "single.blow5"), (4, "threaded.blow5")] {
+            let file_path = tmp_dir.child(name);
+            let mut reader = FileReader::open("examples/example3.blow5")?;
+            let mut writer = FileWriter::create_from(&reader, &file_path)?.with_threads(threads)?;
+            let records = reader.records().collect::<Result<Vec<_>, _>>()?;
+            let n_records = records.len() as u64;
+            assert_eq!(reader.stats().records(), n_records);
+            writer.add_records(records)?;
+            let stats = writer.stats();
+            assert_eq!(stats.records(), n_records);
+            assert_eq!(stats.signal_bytes(), reader.stats().signal_bytes());
+            writer.close()?;
+
+            let written = FileReader::open(&file_path)?;
+            let read_id = written.iter_read_ids()?.next().unwrap().to_vec();
+            written.get_record(read_id)?;
+            assert_eq!(written.stats().records(), 1);
+            assert!(written.stats().bytes() > 0);
+            assert!(stats.bytes() > written.stats().bytes());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_explicit_format() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let file_path = tmp_dir.child("test.tmp");
+        let mut writer = WriteOptions::default()
+            .format(FileFormat::Blow5)
+            .signal_compression(SignalCompression::StreamVByte)
+            .create(&file_path)?;
+        let rec = RecordBuilder::default()
+            .read_id("read_0")
+            .read_group(0)
+            .digitisation(4096.0)
+            .offset(4.0)
+            .range(12.0)
+            .sampling_rate(4000.0)
+            .raw_signal(&[0, 1, 2, 3])
+            .build()?;
+        writer.add_record(&rec)?;
+        writer.close()?;
+
+        let blow5_path = tmp_dir.child("test.blow5");
+        std::fs::rename(&file_path, &blow5_path)?;
+        let reader = FileReader::open(&blow5_path)?;
+        assert_eq!(reader.format(), FileFormat::Blow5);
+        assert!(reader.get_record("read_0").is_ok());
+
+        let res = WriteOptions::default()
+            .format(FileFormat::Slow5)
+            .record_compression(RecordCompression::Zlib)
+            .create(tmp_dir.child("test.blow5"));
+        assert!(matches!(res, Err(Slow5Error::Slow5CompressionError)));
+        Ok(())
+    }
+
     #[test]
     fn test_compression_getter() -> anyhow::Result<()> {
         let tmp_dir = TempDir::new().unwrap();
@@ -666,4 +2186,25 @@ mod test {
         assert_eq!(signal_press, writer.signal_compression());
         Ok(())
     }
+
+    #[test]
+    fn test_from_writer() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        for file_path in ["test.slow5", "test.blow5"] {
+            let file_path = tmp_dir.child(file_path);
+            let mut reader = FileReader::open("examples/example.slow5")?;
+            let mut opts = WriteOptions::from_reader(&reader)?;
+            opts.format(check_file_ext(&file_path)?);
+            let file = std::fs::File::create(&file_path)?;
+            let mut writer = FileWriter::from_writer(io::BufWriter::new(file), &opts)?;
+            for rec in reader.records() {
+                writer.add_record(&rec?)?;
+            }
+            writer.close()?;
+
+            let reader = FileReader::open(&file_path)?;
+            assert_eq!(reader.get_record("r3")?.read_id(), b"r3");
+        }
+        Ok(())
+    }
 }
@@ -56,7 +56,7 @@ fn test_compression() {
                 rec.set_aux_field(&mut writer, "string", "here").unwrap();
                 writer.add_record(&rec).unwrap();
             }
-            writer.close();
+            writer.close().unwrap();
 
             let reader = FileReader::open(&file_path).unwrap();
             assert_eq!(reader.record_compression(), *rec_comp);
@@ -45,7 +45,7 @@ fn main() -> anyhow::Result<()> {
         rec.set_aux_field(&mut writer, "string", "here")?;
         writer.add_record(&rec)?;
     }
-    writer.close();
+    writer.close()?;
 
     let mut writer = FileWriter::append(&file_path)?;
     let mut rec = Record::builder()
@@ -62,7 +62,7 @@ fn main() -> anyhow::Result<()> {
     rec.set_aux_field(&mut writer, "string", String::from("i am"))?;
     rec.set_aux_field(&mut writer, "not set", 123i16)?;
     writer.add_record(&rec)?;
-    writer.close();
+    writer.close()?;
 
     let reader = FileReader::open(&file_path)?;
     assert_eq!(reader.record_compression(), RecordCompression::Zlib);
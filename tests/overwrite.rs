@@ -44,7 +44,7 @@ fn write_test_file(
         rec.set_aux_field(&mut writer, "string", "here")?;
         writer.add_record(&rec).unwrap();
     }
-    writer.close();
+    writer.close()?;
     Ok(())
 }
 